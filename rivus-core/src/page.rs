@@ -1,4 +1,23 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 默认的每页条数上限
+pub const DEFAULT_MAX_PAGE_SIZE: u64 = 1000;
+
+// 每页条数与跳过条数不超过 i64::MAX，保证拼接到 SQL 中仍是合法的整数
+const MAX_SQL_INT: u64 = i64::MAX as u64;
+
+static MAX_PAGE_SIZE: AtomicU64 = AtomicU64::new(DEFAULT_MAX_PAGE_SIZE);
+
+/// 设置每页条数上限（默认 [`DEFAULT_MAX_PAGE_SIZE`]），超出时按上限处理
+pub fn set_max_page_size(max: u64) {
+    MAX_PAGE_SIZE.store(max.clamp(1, MAX_SQL_INT), Ordering::Relaxed);
+}
+
+/// 当前的每页条数上限
+pub fn max_page_size() -> u64 {
+    MAX_PAGE_SIZE.load(Ordering::Relaxed)
+}
 
 #[derive(Serialize)]
 pub struct Page<T: Serialize> {
@@ -11,3 +30,45 @@ impl<T: Serialize> Page<T> {
         Self { total, items }
     }
 }
+
+/// 分页请求，页码从 1 开始
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageRequest {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_size")]
+    pub size: u64,
+}
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_size() -> u64 {
+    20
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            page: default_page(),
+            size: default_size(),
+        }
+    }
+}
+
+impl PageRequest {
+    pub fn new(page: u64, size: u64) -> Self {
+        Self { page, size }
+    }
+
+    /// 每页条数，至少为 1，至多为 [`max_page_size`]
+    pub fn limit(&self) -> u64 {
+        self.size.clamp(1, max_page_size())
+    }
+
+    /// 跳过的条数，页码小于 1 时按第 1 页处理；页码过大时饱和到 `i64::MAX`
+    pub fn offset(&self) -> u64 {
+        (self.page.max(1) - 1).saturating_mul(self.limit()).min(MAX_SQL_INT)
+    }
+}
@@ -0,0 +1,27 @@
+use rivus_core::page::{DEFAULT_MAX_PAGE_SIZE, PageRequest, max_page_size, set_max_page_size};
+
+#[test]
+fn test_page_request_bounds() {
+    assert_eq!(PageRequest::new(3, 20).offset(), 40);
+    assert_eq!(PageRequest::new(0, 0).limit(), 1);
+    assert_eq!(PageRequest::new(0, 10).offset(), 0);
+
+    // 超大页码与页大小不会溢出，也不会超出 SQL 整数范围
+    assert_eq!(PageRequest::new(u64::MAX, 20).offset(), i64::MAX as u64);
+    assert_eq!(
+        PageRequest::new(2, 1_000_000_000).limit(),
+        DEFAULT_MAX_PAGE_SIZE
+    );
+    assert_eq!(
+        PageRequest::new(2, 1_000_000_000).offset(),
+        DEFAULT_MAX_PAGE_SIZE
+    );
+
+    set_max_page_size(50);
+    assert_eq!(max_page_size(), 50);
+    assert_eq!(PageRequest::new(1, 100).limit(), 50);
+    set_max_page_size(u64::MAX);
+    assert_eq!(max_page_size(), i64::MAX as u64);
+    assert_eq!(PageRequest::new(1, u64::MAX).limit(), i64::MAX as u64);
+    set_max_page_size(DEFAULT_MAX_PAGE_SIZE);
+}
@@ -0,0 +1,33 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use std::path::PathBuf;
+use syn::{parse_macro_input, LitStr};
+
+// embed_mappers!("./mappers")：相对路径按调用方 crate 的 CARGO_MANIFEST_DIR 解析
+pub fn embed_mappers_impl(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as LitStr);
+    let mut path = PathBuf::from(lit.value());
+    if path.is_relative()
+        && let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR")
+    {
+        path = PathBuf::from(manifest_dir).join(path);
+    }
+
+    if !path.is_dir() {
+        return syn::Error::new(
+            lit.span(),
+            format!("mapper directory not found: {}", path.display()),
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let path = path.to_string_lossy().into_owned();
+    TokenStream::from(quote! {
+        {
+            use ::rivus_sqlx::include_dir;
+            static MAPPER_DIR: include_dir::Dir<'static> = include_dir::include_dir!(#path);
+            ::rivus_sqlx::mapper_store::Mappers::from_embedded(&MAPPER_DIR)
+        }
+    })
+}
@@ -0,0 +1,210 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Type};
+
+/// 映射到列的字段
+struct Column {
+    /// 字段名，即模板参数名与反序列化时的键
+    field: String,
+    column: String,
+    ty: Type,
+}
+
+impl Column {
+    /// SELECT 列表中的一项，列名与字段名不同时使用别名
+    fn select_item(&self) -> String {
+        if self.column == self.field {
+            self.column.clone()
+        } else {
+            format!("{} AS {}", self.column, self.field)
+        }
+    }
+}
+
+// #[derive(Entity)] #[entity(table = "users", id = "id")]：按表生成基本的增删改查
+pub fn entity_impl(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut table = None;
+    let mut id = "id".to_string();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("entity")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                table = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("id") {
+                id = meta.value()?.parse::<LitStr>()?.value();
+            } else {
+                return Err(meta.error("expected `table` or `id`"));
+            }
+            Ok(())
+        })?;
+    }
+    let Some(table) = table else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "missing #[entity(table = \"...\")]",
+        ));
+    };
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(Entity)] only supports structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(Entity)] requires named fields",
+        ));
+    };
+
+    let mut columns = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+        let mut column = name.clone();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("entity")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("column") {
+                    column = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `column`"))
+                }
+            })?;
+        }
+        columns.push(Column {
+            field: name,
+            column,
+            ty: field.ty.clone(),
+        });
+    }
+
+    let Some(id_col) = columns.iter().find(|c| c.field == id) else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("id field `{}` not found", id),
+        ));
+    };
+    let others: Vec<&Column> = columns.iter().filter(|c| c.field != id).collect();
+    if others.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "entity needs at least one column besides the id",
+        ));
+    }
+
+    let select = columns
+        .iter()
+        .map(Column::select_item)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let where_id = format!("{} = #{{{}}}", id_col.column, id_col.field);
+    let insert_sql = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table,
+        columns
+            .iter()
+            .map(|c| c.column.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        columns
+            .iter()
+            .map(|c| format!("#{{{}}}", c.field))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    let update_sql = format!(
+        "UPDATE {} SET {} WHERE {}",
+        table,
+        others
+            .iter()
+            .map(|c| format!("{} = #{{{}}}", c.column, c.field))
+            .collect::<Vec<_>>()
+            .join(", "),
+        where_id,
+    );
+    let delete_sql = format!("DELETE FROM {} WHERE {}", table, where_id);
+    let find_sql = format!("SELECT {} FROM {} WHERE {}", select, table, where_id);
+    let list_sql = format!("SELECT {} FROM {}", select, table);
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let id_field = &id_col.field;
+    let id_ty = &id_col.ty;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let key = |op: &str| quote! { concat!(module_path!(), "::", #name_str, ".", #op) };
+    let (insert_key, update_key, delete_key, find_key, list_key) = (
+        key("insert"),
+        key("update_by_id"),
+        key("delete_by_id"),
+        key("find_by_id"),
+        key("list"),
+    );
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub const TABLE: &'static str = #table;
+
+            /// 插入所有列，返回影响的行数
+            pub async fn insert(&self, pool: &::rivus_sqlx::db_pool::DbPool) -> ::core::result::Result<u64, ::rivus_sqlx::error::DbError> {
+                ::rivus_sqlx::orm::statement::Statement::template(
+                    #insert_key,
+                    #insert_sql,
+                    ::rivus_sqlx::orm::statement::Params::new().arg("entity", self),
+                )?
+                .execute(pool)
+                .await
+            }
+
+            /// 按主键更新其余所有列，返回影响的行数
+            pub async fn update_by_id(&self, pool: &::rivus_sqlx::db_pool::DbPool) -> ::core::result::Result<u64, ::rivus_sqlx::error::DbError> {
+                ::rivus_sqlx::orm::statement::Statement::template(
+                    #update_key,
+                    #update_sql,
+                    ::rivus_sqlx::orm::statement::Params::new().arg("entity", self),
+                )?
+                .execute(pool)
+                .await
+            }
+
+            /// 按主键删除，返回影响的行数
+            pub async fn delete_by_id(pool: &::rivus_sqlx::db_pool::DbPool, id: #id_ty) -> ::core::result::Result<u64, ::rivus_sqlx::error::DbError> {
+                ::rivus_sqlx::orm::statement::Statement::template(
+                    #delete_key,
+                    #delete_sql,
+                    ::rivus_sqlx::orm::statement::Params::new().arg(#id_field, &id),
+                )?
+                .execute(pool)
+                .await
+            }
+
+            pub async fn find_by_id(pool: &::rivus_sqlx::db_pool::DbPool, id: #id_ty) -> ::core::result::Result<::core::option::Option<Self>, ::rivus_sqlx::error::DbError> {
+                ::rivus_sqlx::orm::statement::Statement::template(
+                    #find_key,
+                    #find_sql,
+                    ::rivus_sqlx::orm::statement::Params::new().arg(#id_field, &id),
+                )?
+                .get::<Self>(pool)
+                .await
+            }
+
+            pub async fn list(pool: &::rivus_sqlx::db_pool::DbPool) -> ::core::result::Result<::std::vec::Vec<Self>, ::rivus_sqlx::error::DbError> {
+                ::rivus_sqlx::orm::statement::Statement::template(
+                    #list_key,
+                    #list_sql,
+                    ::rivus_sqlx::orm::statement::Params::new(),
+                )?
+                .list::<Self>(pool)
+                .await
+            }
+        }
+    })
+}
@@ -0,0 +1,83 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+// #[derive(FromRowDe)]：具名字段按列名（或 #[row_de(column = "...")]）取列下标，元组结构体按位置取列
+pub fn from_row_impl(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(FromRowDe)] only supports structs",
+        ));
+    };
+
+    let row_de = quote! { ::rivus_sqlx::orm::row_de };
+    let (indices, build) = match &data.fields {
+        Fields::Named(fields) => {
+            let mut columns = Vec::new();
+            let mut reads = Vec::new();
+            for (i, field) in fields.named.iter().enumerate() {
+                let ident = field.ident.as_ref().expect("named field");
+                let mut column = ident.to_string().trim_start_matches("r#").to_string();
+                for attr in field.attrs.iter().filter(|a| a.path().is_ident("row_de")) {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("column") {
+                            column = meta.value()?.parse::<LitStr>()?.value();
+                            Ok(())
+                        } else {
+                            Err(meta.error("expected `column`"))
+                        }
+                    })?;
+                }
+                reads.push(quote! { #ident: #row_de::read_column(row, indices[#i], #column)? });
+                columns.push(column);
+            }
+            (
+                quote! { #row_de::column_indices(row, &[#(#columns),*]) },
+                quote! { Self { #(#reads),* } },
+            )
+        }
+        Fields::Unnamed(fields) => {
+            let count = fields.unnamed.len();
+            let reads = (0..count).map(|i| {
+                let column = i.to_string();
+                quote! { #row_de::read_column(row, indices[#i], #column)? }
+            });
+            (
+                quote! { (0..#count).map(|i| (i < row.column_count()).then_some(i)).collect() },
+                quote! { Self(#(#reads),*) },
+            )
+        }
+        Fields::Unit => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(FromRowDe)] requires fields",
+            ));
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #row_de::FromRowDe for #name #ty_generics #where_clause {
+            fn column_indices<R: #row_de::RowReader>(row: &R) -> ::std::vec::Vec<::core::option::Option<usize>> {
+                #indices
+            }
+
+            fn from_row_at<R: #row_de::RowReader>(
+                row: &R,
+                indices: &[::core::option::Option<usize>],
+            ) -> ::core::result::Result<Self, ::rivus_sqlx::error::DbError> {
+                ::core::result::Result::Ok(#build)
+            }
+        }
+    })
+}
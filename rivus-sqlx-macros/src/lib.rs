@@ -1,8 +1,119 @@
 use proc_macro::TokenStream;
 
 mod sql_macro;
+mod embed_macro;
+mod entity_macro;
+mod from_row_macro;
+mod mapper_check;
+mod transactional_macro;
 
+/// 执行 mapper 中的语句：`#[sql("id")]` / `#[sql("Namespace.id")]` 标注的 async 方法
+///
+/// 以函数参数渲染语句，在 `default` 或 `pool = "name"` 指定的连接池上执行，按返回类型 `Result<X, E>` 决定执行方式：
+/// `Vec<T>` 多行、`Option<T>` 单行、`u64` 返回影响行数、`()` 仅执行，其余类型要求恰好一行；`E` 需实现 `From<DbError>`。
+/// 函数体为空时直接执行，否则由函数体中的 `exec!()` 执行。命名空间可写在 struct 或 impl 块的 `#[sql("Namespace")]` 上，
+/// impl 块上的 `pool` 作用于块内未指定连接池的方法。
+/// 语句来自 `Mappers::install` / `MapperStore::install` 安装的 mapper。
+///
+/// 通过 `#[sql("id", mappers = "src/mappers")]` 或编译期环境变量 `RIVUS_MAPPER_DIR` 指定 mapper 目录时，
+/// 在编译期校验语句是否存在，以及 `#{...}`、`test` 等表达式引用的是否为函数参数（只有一个参数时为其字段）。
 #[proc_macro_attribute]
 pub fn sql(args: TokenStream, input: TokenStream) -> TokenStream {
     sql_macro::sql_impl(args, input)
 }
+
+/// 执行写在函数上的查询语句，语法与 mapper 模板相同，执行方式同 [`macro@sql`]
+///
+/// ```ignore
+/// #[select("SELECT * FROM users WHERE id = #{id}")]
+/// async fn get_user(id: i64) -> Result<Option<User>, DbError> {}
+/// ```
+///
+/// 同样可用 `pool = "name"` 指定连接池。编译期校验语句中引用的变量：须为函数参数，只有一个结构体参数时也可以是其字段。
+#[proc_macro_attribute]
+pub fn select(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql_macro::inline_impl(args, input)
+}
+
+/// 执行写在函数上的插入语句，同 [`macro@select`]
+#[proc_macro_attribute]
+pub fn insert(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql_macro::inline_impl(args, input)
+}
+
+/// 执行写在函数上的更新语句，同 [`macro@select`]
+#[proc_macro_attribute]
+pub fn update(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql_macro::inline_impl(args, input)
+}
+
+/// 执行写在函数上的删除语句，同 [`macro@select`]
+#[proc_macro_attribute]
+pub fn delete(args: TokenStream, input: TokenStream) -> TokenStream {
+    sql_macro::inline_impl(args, input)
+}
+
+/// 在事务中执行 async 函数：返回 `Ok` 时提交，返回 `Err` 或 panic 时回滚
+///
+/// ```ignore
+/// #[transactional(pool = "orders")]
+/// async fn place_order(order: &Order) -> Result<(), DbError> {
+///     OrderDao::insert(order).await?;
+///     StockDao::decrease(order.item_id, order.qty).await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// `pool` 默认为 `default`。函数需返回 `Result<T, E>` 且 `E: From<DbError>`；
+/// 函数体内对该连接池的操作（包括 `#[sql]` 方法）使用同一事务连接，已处于该连接池的事务中时加入外层事务。
+#[proc_macro_attribute]
+pub fn transactional(args: TokenStream, input: TokenStream) -> TokenStream {
+    transactional_macro::transactional_impl(args, input)
+}
+
+/// 编译期嵌入 mapper 目录，返回 `anyhow::Result<Mappers>`
+#[proc_macro]
+pub fn embed_mappers(input: TokenStream) -> TokenStream {
+    embed_macro::embed_mappers_impl(input)
+}
+
+/// 按表生成基本的增删改查：`insert` / `update_by_id` / `delete_by_id` / `find_by_id` / `list`
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize, Entity)]
+/// #[entity(table = "users", id = "id")]
+/// struct User {
+///     id: i64,
+///     #[entity(column = "user_name")]
+///     name: String,
+/// }
+///
+/// let user = User::find_by_id(&pool, 1).await?;
+/// ```
+///
+/// `id` 默认为 `id`；列名默认与字段名相同，可用 `#[entity(column = "...")]` 指定。结构体需实现 `Serialize` 与 `Deserialize`。
+#[proc_macro_derive(Entity, attributes(entity))]
+pub fn entity(input: TokenStream) -> TokenStream {
+    entity_macro::entity_impl(input)
+}
+
+/// 按列下标直接映射行，配合 `DbPool::list_as` / `DbPool::get_as` 使用，跳过 serde 逐字段按名称匹配
+///
+/// ```ignore
+/// #[derive(FromRowDe)]
+/// struct User {
+///     id: i64,
+///     #[row_de(column = "user_name")]
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// let users: Vec<User> = pool.list_as("SELECT id, user_name, email FROM users", vec![]).await?;
+/// ```
+///
+/// 具名字段按列名查找，列下标按结果集的第一行计算一次；元组结构体按位置对应列。
+/// 字段类型需实现 `FromColumn`；`Option<T>` 字段在列为 NULL 或不存在时为 `None`。
+#[proc_macro_derive(FromRowDe, attributes(row_de))]
+pub fn from_row_de(input: TokenStream) -> TokenStream {
+    from_row_macro::from_row_impl(input)
+}
@@ -0,0 +1,248 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// 未在属性上指定目录时读取的环境变量，可在 `.cargo/config.toml` 的 `[env]` 中设置
+pub(crate) const MAPPER_DIR_ENV: &str = "RIVUS_MAPPER_DIR";
+
+/// mapper 中的一条语句
+pub(crate) struct Statement {
+    pub text: String,
+    pub file: PathBuf,
+}
+
+/// 编译期读取的 mapper 目录，按 `(namespace, id)` 索引语句
+pub(crate) struct MapperIndex {
+    pub dir: PathBuf,
+    statements: HashMap<(String, String), Statement>,
+}
+
+impl MapperIndex {
+    /// 属性上的目录优先，其次是环境变量；相对路径按调用方 crate 的 CARGO_MANIFEST_DIR 解析。均未指定时返回 `None`
+    pub fn locate(attr: Option<&str>) -> Option<PathBuf> {
+        let dir = match attr {
+            Some(dir) => dir.to_string(),
+            None => std::env::var(MAPPER_DIR_ENV)
+                .ok()
+                .filter(|d| !d.is_empty())?,
+        };
+        let mut path = PathBuf::from(dir);
+        if path.is_relative()
+            && let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR")
+        {
+            path = PathBuf::from(manifest_dir).join(path);
+        }
+        Some(path)
+    }
+
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        if !dir.is_dir() {
+            return Err(format!("mapper directory not found: {}", dir.display()));
+        }
+        let mut index = Self {
+            dir: dir.to_path_buf(),
+            statements: HashMap::new(),
+        };
+        index.scan(dir)?;
+        Ok(index)
+    }
+
+    fn scan(&mut self, dir: &Path) -> Result<(), String> {
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_dir() {
+                self.scan(&path)?;
+            } else if path.extension().is_some_and(|ext| ext == "xml") {
+                let xml = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                self.parse(&xml, &path)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(&mut self, xml: &str, file: &Path) -> Result<(), quick_xml::Error> {
+        let mut reader = Reader::from_str(xml);
+        let mut namespace = None;
+        // 当前语句的 id 与内容
+        let mut current: Option<(String, String)> = None;
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => match e.name().as_ref() {
+                    b"mapper" => namespace = attr(&e, b"namespace"),
+                    b"sql" | b"select" | b"insert" | b"update" | b"delete" => {
+                        current = attr(&e, b"id").map(|id| (id, String::new()));
+                    }
+                    _ => {}
+                },
+                Event::Text(t) => {
+                    if let Some((_, text)) = &mut current {
+                        text.push_str(&t.decode().map_err(quick_xml::Error::from)?);
+                    }
+                }
+                Event::CData(t) => {
+                    if let Some((_, text)) = &mut current {
+                        text.push_str(&String::from_utf8_lossy(&t));
+                    }
+                }
+                Event::End(e)
+                    if matches!(
+                        e.name().as_ref(),
+                        b"sql" | b"select" | b"insert" | b"update" | b"delete"
+                    ) =>
+                {
+                    if let (Some(ns), Some((id, text))) = (&namespace, current.take()) {
+                        let statement = Statement {
+                            text,
+                            file: file.to_path_buf(),
+                        };
+                        self.statements.insert((ns.clone(), id), statement);
+                    }
+                }
+                Event::Eof => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn get(&self, namespace: &str, id: &str) -> Option<&Statement> {
+        self.statements
+            .get(&(namespace.to_string(), id.to_string()))
+    }
+
+    /// 各命名空间中 id 为 `id` 的语句
+    pub fn find_id(&self, id: &str) -> Vec<(&str, &Statement)> {
+        self.statements
+            .iter()
+            .filter(|((_, sid), _)| sid == id)
+            .map(|((ns, _), s)| (ns.as_str(), s))
+            .collect()
+    }
+}
+
+fn attr(e: &quick_xml::events::BytesStart<'_>, name: &[u8]) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
+
+/// 模板中 `#{...}`、`<if>` / `<when>` 的 test、`<bind>` 的 value 与 `<for collection>` 引用的变量路径（如 `user.name`），
+/// 去除 `<for>` / `<bind>` 定义的局部变量，去重
+pub(crate) fn referenced_paths(template: &str) -> Vec<String> {
+    let mut locals = HashSet::new();
+    for (tag, keys) in [("<for ", &["item", "index"][..]), ("<bind ", &["name"][..])] {
+        for tag_content in tags(template, tag) {
+            for key in keys {
+                if let Some(v) = extract_attr(tag_content, key) {
+                    locals.insert(v.to_string());
+                }
+            }
+        }
+    }
+
+    let mut refs = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("#{") {
+        let Some(end) = rest[start..].find('}') else { break };
+        refs.push(rest[start + 2..start + end].trim());
+        rest = &rest[start + end + 1..];
+    }
+    for tag in ["<if ", "<when "] {
+        for test in tags(template, tag).filter_map(|t| extract_attr(t, "test")) {
+            for atom in test.split(" or ").flat_map(|p| p.split(" and ")) {
+                refs.extend(atom.split("!=").flat_map(|p| p.split("==")).map(str::trim));
+            }
+        }
+    }
+    for value in tags(template, "<bind ").filter_map(|t| extract_attr(t, "value")) {
+        refs.extend(value.split('+').map(str::trim));
+    }
+    refs.extend(tags(template, "<for ").filter_map(|t| extract_attr(t, "collection")));
+
+    let mut paths = Vec::new();
+    for r in refs {
+        let root = r.split('.').next().unwrap_or_default();
+        if is_literal(r) || locals.contains(root) || paths.iter().any(|p| p == r) {
+            continue;
+        }
+        paths.push(r.to_string());
+    }
+    paths
+}
+
+// 以 `tag` 开头的标签内容（不含结尾的 `>`）
+fn tags<'a>(template: &'a str, tag: &'a str) -> impl Iterator<Item = &'a str> {
+    template
+        .match_indices(tag)
+        .map(|(start, _)| &template[start..template[start..].find('>').map_or(template.len(), |e| start + e)])
+}
+
+// test / bind 表达式中的字面量：null、布尔、数字与引号字符串
+fn is_literal(term: &str) -> bool {
+    matches!(term, "" | "null" | "true" | "false")
+        || term.starts_with(['\'', '"'])
+        || term.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+}
+
+fn extract_attr<'a>(tag_content: &'a str, key: &str) -> Option<&'a str> {
+    let key_eq = format!("{}=\"", key);
+    let start = tag_content.find(&key_eq)? + key_eq.len();
+    let end = tag_content[start..].find('"')?;
+    Some(&tag_content[start..start + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mapper() {
+        let xml = r#"<mapper namespace="UserDao">
+            <sql id="cols">id, name</sql>
+            <select id="list"><![CDATA[SELECT <include refid="cols"/> FROM users WHERE age > #{age}]]></select>
+            <delete id="remove">DELETE FROM users WHERE id = #{id}</delete>
+        </mapper>"#;
+        let mut index = MapperIndex {
+            dir: PathBuf::new(),
+            statements: HashMap::new(),
+        };
+        index.parse(xml, Path::new("UserDao.xml")).unwrap();
+        assert_eq!(index.get("UserDao", "cols").unwrap().text, "id, name");
+        assert!(
+            index
+                .get("UserDao", "list")
+                .unwrap()
+                .text
+                .contains("#{age}")
+        );
+        assert_eq!(index.find_id("remove").len(), 1);
+        assert!(index.get("UserDao", "missing").is_none());
+    }
+
+    #[test]
+    fn test_referenced_paths() {
+        let tpl = r#"SELECT * FROM users WHERE name = #{user.name} AND age > #{ age }
+            <bind name="pattern" value="'%' + user.nick + '%'"/> AND nick LIKE #{pattern}
+            AND id IN <for item="id" index="i" collection="ids" open="(" sep="," close=")">#{id}</for>
+            <if test="status != null and status != 'x' or vip == true">AND status = #{status}</if>
+            <choose><when test="kind == 1">AND kind = 1</when></choose>
+            AND name = #{user.name}"#;
+        assert_eq!(
+            referenced_paths(tpl),
+            vec![
+                "user.name",
+                "age",
+                "status",
+                "vip",
+                "kind",
+                "user.nick",
+                "ids"
+            ]
+        );
+    }
+}
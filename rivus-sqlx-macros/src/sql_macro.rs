@@ -1,132 +1,426 @@
-
 use proc_macro::TokenStream;
-use quote::{quote, ToTokens};
-use syn::{parse_macro_input, FnArg, ItemFn, ItemStruct, ReturnType};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use crate::mapper_check::{referenced_paths, MapperIndex};
+use std::path::PathBuf;
+use syn::{Block, FnArg, GenericArgument, Ident, ImplItem, Item, ItemFn, ItemImpl, ItemStruct, LitStr, Pat, PathArguments, ReturnType, Signature, Token, Type};
 
-// 1. 处理 Struct 上的 #[sql] - 目前主要是为了不报错，也可以用来做标记
-pub fn sql_impl(args: TokenStream, input: TokenStream) -> TokenStream {
-    // 尝试解析为函数
-    let input_clone = input.clone();
-    if let Ok(item_fn) = syn::parse::<ItemFn>(input_clone) {
-        return handle_fn(args, item_fn);
-    }
+/// `#[sql(...)]` 的参数：语句 id 或命名空间，以及可选的 `mappers = "dir"`、`pool = "name"`
+struct SqlArgs {
+    value: LitStr,
+    /// 编译期校验使用的 mapper 目录
+    mappers: Option<LitStr>,
+    /// 执行语句的连接池，未指定时使用 impl 块上的设置或 `default`
+    pool: Option<LitStr>,
+}
 
-    // 尝试解析为 Struct
-    let input_clone = input.clone();
-    if let Ok(item_struct) = syn::parse::<ItemStruct>(input_clone) {
-        // 对于 struct 上的 sql，我们直接原样返回，不做修改
-        // 如果需要获取 namespace 参数，可以在这里解析 args
-        return TokenStream::from(quote! { #item_struct });
+impl Parse for SqlArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let value: LitStr = input.parse()?;
+        let (mut mappers, mut pool) = (None, None);
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let lit: LitStr = input.parse()?;
+            match key.to_string().as_str() {
+                "mappers" => mappers = Some(lit),
+                "pool" => pool = Some(lit),
+                _ => return Err(syn::Error::new_spanned(key, "expected `mappers` or `pool`")),
+            }
+        }
+        Ok(SqlArgs {
+            value,
+            mappers,
+            pool,
+        })
     }
+}
 
-    // 如果都不是，报错
-    syn::Error::new_spanned(
-        proc_macro2::TokenStream::from(input),
-        "#[sql] must be applied to a struct or function",
-    )
-        .to_compile_error()
-        .into()
+// #[sql("Namespace")] struct / impl：声明命名空间；#[sql("id")] / #[sql("Namespace.id")] fn：执行 mapper 语句
+pub fn sql_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(input as Item);
+    let result = syn::parse::<SqlArgs>(args).and_then(|args| match item {
+        Item::Fn(mut func) => {
+            expand_fn(&func.sig, &mut func.block, &args, None, None)?;
+            Ok(quote! { #func })
+        }
+        Item::Struct(item_struct) => Ok(expand_struct(&args, item_struct)),
+        Item::Impl(item_impl) => expand_impl(&args, item_impl),
+        other => Err(syn::Error::new_spanned(
+            other,
+            "#[sql] must be applied to a struct, impl block or function",
+        )),
+    });
+    result.unwrap_or_else(|e| e.to_compile_error()).into()
 }
 
-// 2. 核心逻辑：处理函数的 #[sql]
-fn handle_fn(args: TokenStream, mut func: ItemFn) -> TokenStream {
-    // 解析宏的参数，例如 #[sql("list_user")] 中的 "list_user"
-    struct SqlArgs {
-        id: String,
-    }
+// struct 上的命名空间以关联常量提供给方法上的 #[sql("id")]
+fn expand_struct(args: &SqlArgs, item: ItemStruct) -> TokenStream2 {
+    let name = &item.ident;
+    let namespace = &args.value;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    quote! {
+        #item
 
-    impl syn::parse::Parse for SqlArgs {
-        fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-            let vars = syn::punctuated::Punctuated::<syn::Lit, syn::Token![,]>::parse_terminated(input)?;
-            let id = if let Some(syn::Lit::Str(lit)) = vars.first() {
-                lit.value()
-            } else {
-                "Unknown".to_string()
-            };
-            Ok(SqlArgs { id })
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc(hidden)]
+            pub const SQL_NAMESPACE: &'static str = #namespace;
         }
     }
+}
+
+// impl 块上的命名空间与连接池直接用于块内带 #[sql("id")] 的方法
+fn expand_impl(args: &SqlArgs, mut item: ItemImpl) -> syn::Result<TokenStream2> {
+    let namespace = args.value.value();
+    let pool = args.pool.as_ref().map(LitStr::value);
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else { continue };
+        let Some(pos) = method.attrs.iter().position(|a| a.path().is_ident("sql")) else {
+            continue;
+        };
+        let attr = method.attrs.remove(pos);
+        let method_args: SqlArgs = attr.parse_args()?;
+        expand_fn(
+            &method.sig,
+            &mut method.block,
+            &method_args,
+            Some(&namespace),
+            pool.as_deref(),
+        )?;
+    }
+    Ok(quote! { #item })
+}
 
-    let args = parse_macro_input!(args as SqlArgs);
-    let sql_id = args.id;
-
-    let fn_name_str = func.sig.ident.to_string();
-
-    // 收集参数信息的代码片段
-    let mut print_stmts = Vec::new();
-
-    // 遍历函数参数
-    for arg in &func.sig.inputs {
-        if let FnArg::Typed(pat_type) = arg {
-            // 获取参数名 (例如 person, sex)
-            let pat = &pat_type.pat;
-            let arg_name_str = pat.to_token_stream().to_string();
-
-            // 获取参数类型 (例如 Person, i32)
-            let ty = &pat_type.ty;
-
-            // 生成打印语句：名称、值、类型
-            // 注意：这里使用 stringify! 也就是把类型编译期转字符串，或者使用 type_name
-            print_stmts.push(quote! {
-                println!(
-                    "  [Param] Name: {}, Value: {:?}, Type: {}",
-                    #arg_name_str,
-                    #pat, // 这里直接引用变量，前提是变量实现了 Debug
-                    std::any::type_name::<#ty>()
-                );
-            });
-        }
+/// 函数参数的名称与类型
+type FnParam = (String, Type);
+
+/// 返回值对应的执行方式
+enum Exec {
+    List(Type),
+    Get(Type),
+    One(Type),
+    Execute,
+    Unit,
+}
+
+fn expand_fn(
+    sig: &Signature,
+    block: &mut Block,
+    args: &SqlArgs,
+    namespace: Option<&str>,
+    pool: Option<&str>,
+) -> syn::Result<()> {
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "#[sql] requires an async fn",
+        ));
     }
 
-    // 模拟返回值逻辑
-    // 根据函数签名的返回类型，我们需要构造一个默认的返回值
-    // 题目中是 Result<Vec<...>>，我们构造 Ok(vec![])
-    let default_return = match &func.sig.output {
-        ReturnType::Type(_, _) => quote! { Ok(vec![]) },
-        ReturnType::Default => quote! { () },
+    // "Namespace.id" 优先，其次 impl 块上的命名空间，最后是 struct 上声明的 `Self::SQL_NAMESPACE`
+    let value = args.value.value();
+    let (known_namespace, id) = match value.rsplit_once('.') {
+        Some((ns, id)) => (Some(ns.to_string()), id.to_string()),
+        None => (namespace.map(str::to_string), value),
+    };
+    let namespace = match &known_namespace {
+        Some(ns) => quote! { #ns },
+        None => quote! { Self::SQL_NAMESPACE },
+    };
+
+    let (arg_calls, params) = fn_params(sig)?;
+
+    // 指定了 mapper 目录时校验语句是否存在及其参数，并在 mapper 文件变化时重新编译
+    let tracked = check_statement(args, known_namespace.as_deref(), &id, sig, &params)?.map(
+        |(file, checks)| {
+            let file = file.to_string_lossy().into_owned();
+            quote! {
+                const _: &[u8] = include_bytes!(#file);
+                #checks
+            }
+        },
+    );
+    let stmt = quote! {
+        #tracked
+        let __stmt = ::rivus_sqlx::orm::statement::Statement::render(
+            #namespace,
+            #id,
+            ::rivus_sqlx::orm::statement::Params::new() #(#arg_calls)*,
+        );
     };
+    let pool = args
+        .pool
+        .as_ref()
+        .map(LitStr::value)
+        .or(pool.map(str::to_string));
+    expand_body(sig, block, stmt, pool.as_deref().unwrap_or("default"))
+}
 
-    // 获取原始函数体（里面包含了 exec!() 调用）
-    let stmts = &func.block.stmts;
+// #[select("SELECT ... #{id}")] 等：语句直接写在函数上，以函数路径与行号作为模板缓存的键
+pub fn inline_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as SqlArgs);
+    let mut func = syn::parse_macro_input!(input as ItemFn);
+    let result = expand_inline(&func.sig, &mut func.block, &args).map(|_| quote! { #func });
+    result.unwrap_or_else(|e| e.to_compile_error()).into()
+}
 
-    // 策略：我们在新函数体开头定义一个局部宏 exec!，然后保留用户的函数体（或者直接忽略用户的函数体由我们完全接管）
-    // 鉴于题目代码里写了 exec!()，最优雅的方式是让 exec! 展开为我们的打印逻辑。
+fn expand_inline(sig: &Signature, block: &mut Block, args: &SqlArgs) -> syn::Result<()> {
+    if let Some(mappers) = &args.mappers {
+        return Err(syn::Error::new_spanned(
+            mappers,
+            "`mappers` only applies to #[sql]",
+        ));
+    }
+    let template = &args.value;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "SQL attribute macros require an async fn",
+        ));
+    }
+    let (arg_calls, params) = fn_params(sig)?;
+    let checks = check_references(&template.value(), template, sig, &params)?;
+    let name = sig.ident.to_string();
+    let stmt = quote! {
+        #checks
+        let __stmt = ::rivus_sqlx::orm::statement::Statement::template(
+            concat!(module_path!(), "::", #name, ":", line!()),
+            #template,
+            ::rivus_sqlx::orm::statement::Params::new() #(#arg_calls)*,
+        );
+    };
+    let pool = args.pool.as_ref().map(LitStr::value);
+    expand_body(sig, block, stmt, pool.as_deref().unwrap_or("default"))
+}
 
-    let new_body = quote! {
+// 函数参数按名称传入模板，返回 `.arg(...)` 调用与参数名、类型
+fn fn_params(sig: &Signature) -> syn::Result<(Vec<TokenStream2>, Vec<FnParam>)> {
+    let mut args = Vec::new();
+    let mut params = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(pat_type) = input else { continue };
+        let Pat::Ident(pat) = &*pat_type.pat else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.pat,
+                "SQL function parameters must be plain identifiers",
+            ));
+        };
+        let ident = &pat.ident;
+        let name = ident.to_string();
+        args.push(quote! { .arg(#name, &#ident) });
+        params.push((name, (*pat_type.ty).clone()));
+    }
+    Ok((args, params))
+}
+
+// 以 `stmt` 定义的 `__stmt` 在 `pool` 上按返回类型执行，生成函数体
+fn expand_body(
+    sig: &Signature,
+    block: &mut Block,
+    stmt: TokenStream2,
+    pool: &str,
+) -> syn::Result<()> {
+    let ReturnType::Type(_, ret) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            &sig.output,
+            "#[sql] functions must return a Result",
+        ));
+    };
+    let run = match classify(&sig.output)? {
+        Exec::List(ty) => quote! { __stmt?.list::<#ty>(&__pool).await },
+        Exec::Get(ty) => quote! { __stmt?.get::<#ty>(&__pool).await },
+        Exec::One(ty) => quote! { __stmt?.one::<#ty>(&__pool).await },
+        Exec::Execute => quote! { __stmt?.execute(&__pool).await },
+        Exec::Unit => quote! { __stmt?.execute(&__pool).await.map(|_| ()) },
+    };
+    let exec = quote! {
         {
-            // 定义局部宏 exec!，它捕获了外部的变量（参数）
-            // 这种写法使得 exec! 只能在当前函数内部有效
-            macro_rules! exec {
-                () => {
-                    {
-                        println!("--------------------------------------------------");
-                        // 1. 打印 Struct 名称 + 方法名
-                        // 使用 std::any::type_name::<Self>() 获取当前 impl 块的结构体名称
-                        // 如果是普通函数，Self 可能会报错，这里假设是在 impl 块中使用，或者通过 trait 兼容
-                        // 为了兼容 standalone 函数，我们可以尝试用 Option 包装或者直接用 strict 模式
-                        // 这里演示标准 impl 块下的用法：
-                        let struct_name = std::any::type_name::<Self>();
-                        // 简单的字符串处理去掉详细路径
-                        let short_struct_name = struct_name.split("::").last().unwrap_or(struct_name);
-
-                        println!("Executing SQL: {}::{} (ID: {})", short_struct_name, #fn_name_str, #sql_id);
-
-                        // 2. 打印参数
-                        #(#print_stmts)*
-
-                        // 3. 返回模拟值
-                        #default_return
-                    }
-                };
+            #stmt
+            let __result: ::core::result::Result<_, ::rivus_sqlx::error::DbError> = async move {
+                let __pool = ::rivus_sqlx::orm::statement::pool(#pool)?;
+                #run
             }
+            .await;
+            // 标注为函数的返回类型，函数体中的 `exec!()?` 才能推断错误类型
+            let __ret: #ret = __result.map_err(::core::convert::Into::into);
+            __ret
+        }
+    };
 
-            // 执行原本的代码块，原本的代码块里写了 exec!()，现在会调用上面的宏
-            #(#stmts)*
+    // 空函数体直接执行；否则保留函数体，由其中的 exec!() 执行
+    let stmts = &block.stmts;
+    let body = if stmts.is_empty() {
+        quote! { { #exec } }
+    } else {
+        quote! {
+            {
+                #[allow(unused_macros)]
+                macro_rules! exec {
+                    () => { #exec };
+                }
+                #(#stmts)*
+            }
         }
     };
+    *block = syn::parse2(body)?;
+    Ok(())
+}
+
+/// 校验 mapper 中存在该语句且其引用的变量与函数参数一致，返回语句所在文件与字段检查；未指定 mapper 目录时跳过
+///
+/// 命名空间来自 `Self::SQL_NAMESPACE` 时按 id 在所有命名空间中查找。
+fn check_statement(
+    args: &SqlArgs,
+    namespace: Option<&str>,
+    id: &str,
+    sig: &Signature,
+    params: &[FnParam],
+) -> syn::Result<Option<(PathBuf, TokenStream2)>> {
+    let Some(dir) = MapperIndex::locate(args.mappers.as_ref().map(LitStr::value).as_deref()) else {
+        return Ok(None);
+    };
+    let span = args.mappers.as_ref().unwrap_or(&args.value);
+    let index = MapperIndex::load(&dir).map_err(|e| syn::Error::new_spanned(span, e))?;
+
+    let statement = match namespace {
+        Some(ns) => index.get(ns, id).ok_or_else(|| {
+            syn::Error::new_spanned(
+                &args.value,
+                format!(
+                    "SQL '{}.{}' not found in mappers under {}",
+                    ns,
+                    id,
+                    index.dir.display()
+                ),
+            )
+        })?,
+        None => match index.find_id(id).as_slice() {
+            [] => {
+                return Err(syn::Error::new_spanned(
+                    &args.value,
+                    format!(
+                        "SQL id '{}' not found in mappers under {}",
+                        id,
+                        index.dir.display()
+                    ),
+                ));
+            }
+            [(_, statement)] => statement,
+            // 多个命名空间中同名，无法确定具体语句
+            _ => return Ok(None),
+        },
+    };
 
-    // 替换函数体
-    func.block = syn::parse2(new_body).expect("Failed to parse new body");
+    let checks = check_references(&statement.text, &args.value, sig, params)?;
+    Ok(Some((statement.file.clone(), checks)))
+}
+
+/// 校验模板引用的变量：根变量须为函数参数；只有一个参数时也可以是该参数的字段
+///
+/// 字段通过生成的 `|p: &T| &p.field` 交由编译器检查，参数类型为 Option / Map / 泛型等无法确定字段的类型时跳过。
+fn check_references(
+    template: &str,
+    lit: &LitStr,
+    sig: &Signature,
+    params: &[FnParam],
+) -> syn::Result<TokenStream2> {
+    let mut checks = Vec::new();
+    for path in referenced_paths(template) {
+        let (root, field) = match path.split_once('.') {
+            Some((root, rest)) => (root, rest.split('.').next()),
+            None => (path.as_str(), None),
+        };
+        let (ty, field) = match params.iter().find(|(name, _)| name == root) {
+            Some((_, ty)) => (ty, field),
+            None => match params {
+                [(_, ty)] => (ty, Some(root)),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!(
+                            "`{}` referenced by SQL is not a parameter of `{}`",
+                            path, sig.ident
+                        ),
+                    ));
+                }
+            },
+        };
+        let Some(field) = field.and_then(|f| syn::parse_str::<Ident>(f).ok()) else { continue };
+        let Some(ty) = struct_type(ty, sig) else { continue };
+        let field = Ident::new(&field.to_string(), lit.span());
+        checks.push(quote! { let _ = |__p: &#ty| { let _ = &__p.#field; }; });
+    }
+    Ok(quote! { #(#checks)* })
+}
 
-    TokenStream::from(quote! { #func })
+// 去掉引用后可检查字段的类型
+fn struct_type<'a>(ty: &'a Type, sig: &Signature) -> Option<&'a Type> {
+    match ty {
+        Type::Reference(r) => struct_type(&r.elem, sig),
+        Type::Paren(p) => struct_type(&p.elem, sig),
+        Type::Path(path) if path.qself.is_none() => {
+            let last = path.path.segments.last()?;
+            let opaque = [
+                "Option", "HashMap", "BTreeMap", "IndexMap", "Map", "Value", "Vec", "Box", "Arc",
+                "Rc", "Cow",
+            ];
+            let generic = path.path.segments.len() == 1
+                && sig.generics.type_params().any(|p| p.ident == last.ident);
+            (!opaque.iter().any(|o| last.ident == o) && !generic).then_some(ty)
+        }
+        _ => None,
+    }
+}
+
+// 按 `Result<X, _>` 中的 X 决定执行方式：Vec<T> 多行、Option<T> 单行、u64 影响行数、() 仅执行，其余类型要求恰好返回一行
+fn classify(output: &ReturnType) -> syn::Result<Exec> {
+    let ReturnType::Type(_, ty) = output else {
+        return Err(syn::Error::new_spanned(
+            output,
+            "#[sql] functions must return a Result",
+        ));
+    };
+    let Some(inner) = first_type_arg(ty) else {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "#[sql] functions must return a Result<T, E>",
+        ));
+    };
+    if let Type::Tuple(tuple) = inner
+        && tuple.elems.is_empty()
+    {
+        return Ok(Exec::Unit);
+    }
+    if let Type::Path(path) = inner
+        && let Some(last) = path.path.segments.last()
+    {
+        if last.ident == "u64" && path.path.segments.len() == 1 {
+            return Ok(Exec::Execute);
+        }
+        if let Some(elem) = first_type_arg(inner) {
+            if last.ident == "Vec" {
+                return Ok(Exec::List(elem.clone()));
+            }
+            if last.ident == "Option" {
+                return Ok(Exec::Get(elem.clone()));
+            }
+        }
+    }
+    Ok(Exec::One(inner.clone()))
+}
+
+fn first_type_arg(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    let PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
 }
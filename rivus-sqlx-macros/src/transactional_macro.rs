@@ -0,0 +1,52 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{ItemFn, LitStr, ReturnType};
+
+// #[transactional] / #[transactional(pool = "orders")]：函数体在事务中执行，返回 Ok 时提交，Err 或 panic 时回滚
+pub fn transactional_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut pool = LitStr::new("default", proc_macro2::Span::call_site());
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("pool") {
+            pool = meta.value()?.parse()?;
+            Ok(())
+        } else {
+            Err(meta.error("expected `pool`"))
+        }
+    });
+    syn::parse_macro_input!(args with parser);
+
+    let mut func = syn::parse_macro_input!(input as ItemFn);
+    if func.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(func.sig.fn_token, "#[transactional] requires an async fn")
+            .to_compile_error()
+            .into();
+    }
+    let ReturnType::Type(_, ret) = &func.sig.output else {
+        return syn::Error::new_spanned(
+            &func.sig,
+            "#[transactional] functions must return a Result",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    // 以返回类型标注函数体，使其中的 `?` 能推断错误类型；已处于该连接池的事务中时加入外层事务
+    let block = &func.block;
+    let body = quote! {
+        {
+            let __pool = ::rivus_sqlx::orm::statement::pool(#pool)?;
+            __pool
+                .transaction(|_| async move {
+                    let __ret: #ret = #block;
+                    #[allow(unreachable_code)]
+                    __ret
+                })
+                .await
+        }
+    };
+    func.block = match syn::parse2(body) {
+        Ok(block) => Box::new(block),
+        Err(e) => return e.to_compile_error().into(),
+    };
+    TokenStream::from(quote! { #func })
+}
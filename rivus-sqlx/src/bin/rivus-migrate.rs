@@ -0,0 +1,131 @@
+//! 命令行执行 schema 迁移
+//!
+//! ```text
+//! rivus-migrate --url <database-url> [--dir migrations] [--dry-run] <up | down [steps] | status>
+//! ```
+
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::migrate::{MigrationState, Migrator};
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: rivus-migrate --url <database-url> [--dir migrations] [--dry-run] <up | down [steps] | status>";
+
+struct Args {
+    url: String,
+    dir: String,
+    dry_run: bool,
+    command: String,
+    steps: usize,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut url = None;
+    let mut dir = "migrations".to_string();
+    let mut dry_run = false;
+    let mut positional = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => url = args.next(),
+            "--dir" => dir = args.next().ok_or("--dir requires a value")?,
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => positional.push(arg),
+        }
+    }
+
+    let url = url
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .ok_or("--url or DATABASE_URL is required")?;
+    let command = positional.first().cloned().ok_or(USAGE)?;
+    let steps = match positional.get(1) {
+        Some(n) => n.parse().map_err(|_| format!("invalid steps '{}'", n))?,
+        None => 1,
+    };
+    Ok(Args {
+        url,
+        dir,
+        dry_run,
+        command,
+        steps,
+    })
+}
+
+/// 由连接串前缀推断数据库类型
+fn db_type(url: &str) -> Option<&'static str> {
+    let scheme = url.split(':').next()?;
+    match scheme {
+        "mysql" | "mariadb" => Some("mysql"),
+        "postgres" | "postgresql" => Some("postgres"),
+        "sqlite" => Some("sqlite"),
+        _ => None,
+    }
+}
+
+async fn run(args: Args) -> Result<(), String> {
+    let db_type =
+        db_type(&args.url).ok_or_else(|| format!("unsupported database url '{}'", args.url))?;
+    let config = DatabaseOptions::new(db_type.to_string(), args.url.clone());
+    let pool = DbPool::new("migrate", db_type, &config)
+        .await
+        .map_err(|e| e.to_string())?;
+    let migrator = Migrator::from_dir(&args.dir)
+        .await
+        .map_err(|e| e.to_string())?
+        .dry_run(args.dry_run);
+    let prefix = if args.dry_run { "[dry run] " } else { "" };
+
+    match args.command.as_str() {
+        "up" => {
+            let versions = migrator.up(&pool).await.map_err(|e| e.to_string())?;
+            println!(
+                "{}applied {} migration(s) {:?}",
+                prefix,
+                versions.len(),
+                versions
+            );
+        }
+        "down" => {
+            let versions = migrator
+                .down(&pool, args.steps)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!(
+                "{}reverted {} migration(s) {:?}",
+                prefix,
+                versions.len(),
+                versions
+            );
+        }
+        "status" => {
+            for s in migrator.status(&pool).await.map_err(|e| e.to_string())? {
+                let state = match s.state {
+                    MigrationState::Pending => "pending",
+                    MigrationState::Applied => "applied",
+                    MigrationState::Modified => "modified",
+                    MigrationState::Missing => "missing",
+                };
+                println!("{:>16}  {:<8}  {}", s.version, state, s.description);
+            }
+        }
+        other => return Err(format!("unknown command '{}'\n{}", other, USAGE)),
+    }
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let result = match parse_args() {
+        Ok(args) => run(args).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,22 +1,47 @@
+use crate::error::DbError;
+use crate::metrics;
 use crate::models::db_config::DatabaseOptions;
 use std::collections::HashMap;
 use std::sync::{OnceLock, RwLock};
-use crate::db_pool::DbPool;
+use std::time::Duration;
+use crate::db_pool::{DbPool, PoolStats};
+use crate::driver::DbDriver;
+use std::sync::Arc;
 
 static DBS: OnceLock<RwLock<HashMap<String, DbPool>>> = OnceLock::new();
+static DRIVERS: OnceLock<RwLock<HashMap<String, Arc<dyn DbDriver>>>> = OnceLock::new();
 
 pub struct ConnManager;
 impl ConnManager {
+    /// 打开并注册连接池
+    ///
+    /// URL 非法或数据库不可达时返回错误，不会注册连接池。
     pub async fn open(
         name: &str,
         r#type: &str,
         config: &DatabaseOptions,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), DbError> {
         let pool = DbPool::new(name, r#type, config).await?;
         Self::all().write().unwrap().insert(name.to_string(), pool);
         Ok(())
     }
 
+    /// 注册外部数据库驱动，之后以 `r#type` 打开的连接池经由该驱动执行；同名注册替换已有驱动
+    pub fn register_driver(r#type: &str, driver: Arc<dyn DbDriver>) {
+        Self::drivers()
+            .write()
+            .unwrap()
+            .insert(r#type.to_string(), driver);
+    }
+
+    pub(crate) fn driver(r#type: &str) -> Option<Arc<dyn DbDriver>> {
+        Self::drivers().read().unwrap().get(r#type).cloned()
+    }
+
+    fn drivers() -> &'static RwLock<HashMap<String, Arc<dyn DbDriver>>> {
+        DRIVERS.get_or_init(|| RwLock::new(HashMap::new()))
+    }
+
     fn all() -> &'static RwLock<HashMap<String, DbPool>> {
         DBS.get_or_init(|| RwLock::new(HashMap::new()))
     }
@@ -29,6 +54,30 @@ impl ConnManager {
         Self::all().read().unwrap().get("default").cloned()
     }
 
+    /// 所有已注册连接池的状态
+    pub fn stats() -> HashMap<String, PoolStats> {
+        Self::all()
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|(name, pool)| pool.stats().map(|s| (name.clone(), s)))
+            .collect()
+    }
+
+    /// 将所有连接池的状态上报给指标接收方，可由定时任务调用
+    pub fn report_metrics() {
+        for (name, stats) in Self::stats() {
+            metrics::record_pool_stats(&name, &stats);
+        }
+    }
+
+    /// 检查指定连接池的连通性，返回耗时
+    pub async fn ping(name: &str, timeout: Duration) -> Result<Duration, DbError> {
+        let pool = Self::by(name)
+            .ok_or_else(|| DbError::from(format!("Database '{}' not found", name)))?;
+        pool.ping(timeout).await
+    }
+
     pub async fn close(name: &str) -> bool {
         let pool_opt = {
             let dbs = Self::all();
@@ -43,4 +92,20 @@ impl ConnManager {
             false
         }
     }
+
+    /// 关闭并移除所有连接池，返回关闭的数量
+    ///
+    /// 等待使用中的连接归还后再关闭，可重复调用。
+    pub async fn close_all() -> usize {
+        let pools: Vec<DbPool> = {
+            let mut map = Self::all().write().unwrap();
+            map.drain().map(|(_, pool)| pool).collect()
+        };
+
+        let count = pools.len();
+        for pool in pools {
+            pool.close().await;
+        }
+        count
+    }
 }
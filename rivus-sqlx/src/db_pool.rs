@@ -1,19 +1,76 @@
+use crate::db_conn::ConnManager;
+use crate::driver::{DriverConnection, OtherPool};
 use crate::error::DbError;
 use crate::models::db_config::DatabaseOptions;
+use crate::orm::crud_traits::CrudRepository;
+use crate::orm::query_log::QueryTimer;
+use crate::orm::row_de::FromRowDe;
+use crate::orm::sqlx_impl::{self, SqlxRepository};
+use crate::tenant;
+use futures::FutureExt;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 use sqlx::pool::PoolConnection;
-use sqlx::{FromRow, MySql, Pool, Postgres, Sqlite, Transaction};
+use sqlx::{MySql, Pool, Postgres, Sqlite, Transaction};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
 #[derive(Clone, Debug)]
 pub struct DbPool {
     pub name: String,
     pub inner: DbPoolInner,
+    /// 慢查询阈值，`None` 表示不记录
+    pub slow_query_threshold: Option<Duration>,
+    /// 事务重试策略，`None` 表示不重试
+    pub retry_policy: Option<RetryPolicy>,
+    /// 单条语句的超时时间，`None` 表示不限制
+    pub query_timeout: Option<Duration>,
+    /// 是否将语句加入连接的预处理语句缓存
+    pub cache_statements: bool,
+}
+
+/// 事务重试策略：按指数退避重新执行整个事务
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 首次执行之外的最大重试次数
+    pub max_retries: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub base_delay: Duration,
+    /// 单次等待时间上限
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// 第 `retry` 次重试（从 1 开始）前的等待时间
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -21,19 +78,120 @@ pub enum DbPoolInner {
     MySql(Pool<MySql>),
     Sqlite(Pool<Sqlite>),
     Postgres(Pool<Postgres>),
-    Other(String),
+    /// 外部驱动，见 [`crate::driver::DbDriver`]
+    Other(OtherPool),
+}
+
+/// 连接池状态快照
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// 当前连接数（空闲 + 使用中）
+    pub size: u32,
+    /// 空闲连接数
+    pub idle: u32,
+    /// 使用中的连接数
+    pub in_use: u32,
+    /// 最大连接数
+    pub max_size: u32,
 }
 
 pub enum DbConnection {
     MySql(PoolConnection<MySql>),
-    Sqlite(PoolConnection<Sqlite>),
+    /// 第二项记录事务是否通过 `PRAGMA query_only` 设为只读，结束事务时据此恢复
+    Sqlite(PoolConnection<Sqlite>, bool),
     Postgres(PoolConnection<Postgres>),
+    Other(Box<dyn DriverConnection>),
 }
 
 tokio::task_local! {
     pub static TRANSACTION_CONTEXT: RefCell<HashMap<String, Arc<Mutex<DbConnection>>>>;
 }
 
+/// 事务隔离级别
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// 事务选项，未指定隔离级别时使用数据库默认值
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TxOptions {
+    pub isolation: Option<IsolationLevel>,
+    pub read_only: bool,
+}
+
+impl TxOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn isolation(mut self, level: IsolationLevel) -> Self {
+        self.isolation = Some(level);
+        self
+    }
+
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// MySQL 的 `SET TRANSACTION`、Postgres 的 `BEGIN` 后接的事务模式
+    fn transaction_modes(&self) -> Option<String> {
+        let mut modes = Vec::new();
+        if let Some(level) = self.isolation {
+            modes.push(format!("ISOLATION LEVEL {}", level.as_sql()));
+        }
+        if self.read_only {
+            modes.push("READ ONLY".to_string());
+        }
+        (!modes.is_empty()).then(|| modes.join(", "))
+    }
+}
+
+/// 单次调用的语句选项，见 [`DbPool::with_options`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryOptions {
+    pub timeout: Option<Duration>,
+    pub cache_statement: Option<bool>,
+}
+
+impl QueryOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 语句超时时间：超时后取消执行并返回 [`DbError::Timeout`]，MySQL / Postgres 同时终止服务端的语句
+    ///
+    /// 按单次仓库调用计时（`batch_create` 为整批），流式查询不受限制。在事务中超时后连接状态不确定，应回滚该事务。
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 是否缓存预处理语句，默认缓存（连接池 `statement_cache_size` 为 0 时除外）
+    ///
+    /// 动态生成的 SQL（如按参数个数展开的 `IN (...)`）文本各不相同，缓存只会挤占缓存并在服务端累积预处理语句，
+    /// 应关闭。
+    pub fn cache_statement(mut self, cache: bool) -> Self {
+        self.cache_statement = Some(cache);
+        self
+    }
+}
+
 pub enum DbTransaction<'c> {
     MySql(Transaction<'c, MySql>),
     Sqlite(Transaction<'c, Sqlite>),
@@ -60,8 +218,9 @@ impl<'c> DbTransaction<'c> {
     }
 }
 
+/// 按连接类型执行：`$body` 使用 sqlx 连接，`$other_body` 使用外部驱动的连接
 macro_rules! dispatch_db {
-    ($self:expr, $conn:ident, $body:expr) => {{
+    ($self:expr, $conn:ident, $body:expr, $other:ident => $other_body:expr) => {{
         let tx_conn = TRANSACTION_CONTEXT
             .try_with(|map| map.borrow().get(&$self.name).cloned())
             .ok()
@@ -74,7 +233,7 @@ macro_rules! dispatch_db {
                     let $conn = &mut **c;
                     $body
                 }
-                DbConnection::Sqlite(c) => {
+                DbConnection::Sqlite(c, _) => {
                     let $conn = &mut **c;
                     $body
                 }
@@ -82,13 +241,17 @@ macro_rules! dispatch_db {
                     let $conn = &mut **c;
                     $body
                 }
+                DbConnection::Other($other) => $other_body,
             }
         } else {
             match &$self.inner {
                 DbPoolInner::MySql($conn) => $body,
                 DbPoolInner::Sqlite($conn) => $body,
                 DbPoolInner::Postgres($conn) => $body,
-                DbPoolInner::Other(_) => panic!("Direct DbPool execution not supported for 'Other' database type. Use Repository."),
+                DbPoolInner::Other(p) => {
+                    let $other = &mut p.acquire().await?;
+                    $other_body
+                }
             }
         }
     }};
@@ -100,16 +263,54 @@ impl DbPool {
             "mysql" => Self::mysql(config).await?,
             "sqlite" => Self::sqlite(config).await?,
             "postgres" => Self::postgres(config).await?,
-            _ => DbPoolInner::Other(r#type.to_string()),
+            _ => match ConnManager::driver(r#type) {
+                Some(driver) => DbPoolInner::Other(OtherPool::new(r#type, driver.connect(config).await?)),
+                None => DbPoolInner::Other(OtherPool::unsupported(r#type)),
+            },
         };
         Ok(Self {
             name: name.to_string(),
             inner,
+            slow_query_threshold: (config.slow_query_threshold > 0)
+                .then(|| Duration::from_millis(config.slow_query_threshold)),
+            retry_policy: (config.tx_max_retries > 0).then(|| {
+                RetryPolicy::new(config.tx_max_retries)
+                    .base_delay(Duration::from_millis(config.tx_retry_backoff))
+            }),
+            query_timeout: (config.query_timeout > 0)
+                .then(|| Duration::from_millis(config.query_timeout)),
+            cache_statements: config.statement_cache_size > 0,
         })
     }
 
+    /// 设置事务重试策略
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// 返回应用了语句选项的连接池，作为仓库方法的连接参数即可对单次调用生效
+    ///
+    /// ```ignore
+    /// let user: Option<User> = pool
+    ///     .with_options(QueryOptions::new().timeout(Duration::from_secs(2)))
+    ///     .get("SELECT * FROM users WHERE id = ? FOR UPDATE", vec![1.into()])
+    ///     .await?;
+    /// ```
+    pub fn with_options(&self, options: QueryOptions) -> DbPool {
+        let mut pool = self.clone();
+        if let Some(timeout) = options.timeout {
+            pool.query_timeout = Some(timeout);
+        }
+        if let Some(cache) = options.cache_statement {
+            pool.cache_statements = cache;
+        }
+        pool
+    }
+
     async fn mysql(config: &DatabaseOptions) -> Result<DbPoolInner, DbError> {
-        let options = sqlx::mysql::MySqlConnectOptions::from_str(&config.url)?;
+        let options = sqlx::mysql::MySqlConnectOptions::from_str(&config.url)?
+            .statement_cache_capacity(config.statement_cache_size);
         let pool = sqlx::mysql::MySqlPoolOptions::new()
             .max_connections(config.max_open_conns as u32)
             .min_connections(config.max_idle_conns as u32)
@@ -122,7 +323,9 @@ impl DbPool {
 
     async fn sqlite(config: &DatabaseOptions) -> Result<DbPoolInner, DbError> {
         let options =
-            sqlx::sqlite::SqliteConnectOptions::from_str(&config.url)?.create_if_missing(true);
+            sqlx::sqlite::SqliteConnectOptions::from_str(&config.url)?
+                .create_if_missing(true)
+                .statement_cache_capacity(config.statement_cache_size);
         let pool = sqlx::sqlite::SqlitePoolOptions::new()
             .max_connections(config.max_open_conns as u32)
             .min_connections(config.max_idle_conns as u32)
@@ -134,7 +337,8 @@ impl DbPool {
     }
 
     async fn postgres(config: &DatabaseOptions) -> Result<DbPoolInner, DbError> {
-        let options = sqlx::postgres::PgConnectOptions::from_str(&config.url)?;
+        let options = sqlx::postgres::PgConnectOptions::from_str(&config.url)?
+            .statement_cache_capacity(config.statement_cache_size);
         let pool = sqlx::postgres::PgPoolOptions::new()
             .max_connections(config.max_open_conns as u32)
             .min_connections(config.max_idle_conns as u32)
@@ -150,40 +354,122 @@ impl DbPool {
             DbPoolInner::MySql(pool) => pool.close().await,
             DbPoolInner::Sqlite(pool) => pool.close().await,
             DbPoolInner::Postgres(pool) => pool.close().await,
-            DbPoolInner::Other(_) => {}
+            DbPoolInner::Other(pool) => pool.close().await,
         }
     }
 
+    /// 连接池状态，`Other` 类型返回 `None`
+    pub fn stats(&self) -> Option<PoolStats> {
+        macro_rules! stats_of {
+            ($p:expr) => {{
+                let size = $p.size();
+                let idle = $p.num_idle() as u32;
+                PoolStats {
+                    size,
+                    idle,
+                    in_use: size.saturating_sub(idle),
+                    max_size: $p.options().get_max_connections(),
+                }
+            }};
+        }
+        match &self.inner {
+            DbPoolInner::MySql(p) => Some(stats_of!(p)),
+            DbPoolInner::Sqlite(p) => Some(stats_of!(p)),
+            DbPoolInner::Postgres(p) => Some(stats_of!(p)),
+            DbPoolInner::Other(_) => None,
+        }
+    }
+
+    /// 执行 `SELECT 1` 检查连通性，返回耗时
+    pub async fn ping(&self, timeout: Duration) -> Result<Duration, DbError> {
+        let start = Instant::now();
+        let fut = async {
+            match &self.inner {
+                DbPoolInner::MySql(p) => sqlx::query("SELECT 1").execute(p).await.map(|_| ()),
+                DbPoolInner::Sqlite(p) => sqlx::query("SELECT 1").execute(p).await.map(|_| ()),
+                DbPoolInner::Postgres(p) => sqlx::query("SELECT 1").execute(p).await.map(|_| ()),
+                DbPoolInner::Other(p) => return Err(DbError::from(format!("Ping not supported for '{}'", p.kind))),
+            }
+            .map_err(DbError::from)
+        };
+        tokio::time::timeout(timeout, fut)
+            .await
+            .map_err(|_| DbError::Timeout(timeout))??;
+        Ok(start.elapsed())
+    }
+
     pub async fn start_transaction(&self) -> Result<(), DbError> {
-        let conn = match &self.inner {
+        self.start_transaction_with(&TxOptions::default()).await
+    }
+
+    /// 按指定隔离级别 / 只读模式开启事务
+    ///
+    /// SQLite 事务始终为可串行化，忽略隔离级别；只读通过 `PRAGMA query_only` 实现。
+    pub async fn start_transaction_with(&self, options: &TxOptions) -> Result<(), DbError> {
+        let pool = tenant::route(self)?;
+        let modes = options.transaction_modes();
+        let conn = match &pool.inner {
             DbPoolInner::MySql(p) => {
                 let mut c = p.acquire().await?;
-                sqlx::query("BEGIN").execute(&mut *c).await?;
+                // MySQL 的 SET TRANSACTION 作用于下一个事务，需在 BEGIN 之前执行
+                if let Some(modes) = &modes {
+                    sqlx::query(&format!("SET TRANSACTION {modes}"))
+                        .execute(&mut *c)
+                        .await?;
+                }
+                if let Err(e) = sqlx::query("BEGIN").execute(&mut *c).await {
+                    // 事务模式仍会作用于该连接的下一个事务，关闭连接而不是归还连接池
+                    if modes.is_some() {
+                        c.close_on_drop();
+                    }
+                    return Err(e.into());
+                }
                 DbConnection::MySql(c)
             }
             DbPoolInner::Sqlite(p) => {
                 let mut c = p.acquire().await?;
-                sqlx::query("BEGIN").execute(&mut *c).await?;
-                DbConnection::Sqlite(c)
+                if options.read_only {
+                    sqlx::query("PRAGMA query_only = ON")
+                        .execute(&mut *c)
+                        .await?;
+                }
+                if let Err(e) = sqlx::query("BEGIN").execute(&mut *c).await {
+                    if options.read_only {
+                        reset_query_only(&mut c).await?;
+                    }
+                    return Err(e.into());
+                }
+                DbConnection::Sqlite(c, options.read_only)
             }
             DbPoolInner::Postgres(p) => {
                 let mut c = p.acquire().await?;
-                sqlx::query("BEGIN").execute(&mut *c).await?;
+                // 事务模式随 BEGIN 一条语句设置，失败时不会留下未结束的事务
+                let begin = match &modes {
+                    Some(modes) => format!("BEGIN {modes}"),
+                    None => "BEGIN".to_string(),
+                };
+                sqlx::query(&begin).execute(&mut *c).await?;
                 DbConnection::Postgres(c)
             }
-            DbPoolInner::Other(_) => return Err(DbError::from("Transactions not supported for Other DB types")),
+            // 外部驱动忽略事务选项
+            DbPoolInner::Other(p) => {
+                let mut c = p.acquire().await?;
+                c.begin().await?;
+                DbConnection::Other(c)
+            }
         };
 
         TRANSACTION_CONTEXT.try_with(|map| {
-            map.borrow_mut().insert(self.name.clone(), Arc::new(Mutex::new(conn)));
+            map.borrow_mut().insert(pool.name.clone(), Arc::new(Mutex::new(conn)));
         }).map_err(|_| DbError::from("Transaction context not found. Ensure you are within a `TRANSACTION_CONTEXT.scope`."))?;
 
         Ok(())
     }
 
     pub async fn commit_transaction(&self) -> Result<(), DbError> {
+        let pool = tenant::route(self)?;
         let conn_arc = TRANSACTION_CONTEXT
-            .try_with(|map| map.borrow_mut().remove(&self.name))
+            .try_with(|map| map.borrow_mut().remove(&pool.name))
             .map_err(|_| DbError::from("Transaction context not found"))?
             .ok_or_else(|| DbError::from("No active transaction to commit"))?;
 
@@ -192,19 +478,25 @@ impl DbPool {
             DbConnection::MySql(c) => {
                 sqlx::query("COMMIT").execute(&mut **c).await?;
             }
-            DbConnection::Sqlite(c) => {
-                sqlx::query("COMMIT").execute(&mut **c).await?;
+            DbConnection::Sqlite(c, read_only) => {
+                let result = sqlx::query("COMMIT").execute(&mut **c).await;
+                if *read_only {
+                    reset_query_only(c).await?;
+                }
+                result?;
             }
             DbConnection::Postgres(c) => {
                 sqlx::query("COMMIT").execute(&mut **c).await?;
             }
+            DbConnection::Other(c) => c.commit().await?,
         }
         Ok(())
     }
 
     pub async fn rollback_transaction(&self) -> Result<(), DbError> {
+        let pool = tenant::route(self)?;
         let conn_arc = TRANSACTION_CONTEXT
-            .try_with(|map| map.borrow_mut().remove(&self.name))
+            .try_with(|map| map.borrow_mut().remove(&pool.name))
             .map_err(|_| DbError::from("Transaction context not found"))?
             .ok_or_else(|| DbError::from("No active transaction to rollback"))?;
 
@@ -213,83 +505,208 @@ impl DbPool {
             DbConnection::MySql(c) => {
                 sqlx::query("ROLLBACK").execute(&mut **c).await?;
             }
-            DbConnection::Sqlite(c) => {
-                sqlx::query("ROLLBACK").execute(&mut **c).await?;
+            DbConnection::Sqlite(c, read_only) => {
+                let result = sqlx::query("ROLLBACK").execute(&mut **c).await;
+                if *read_only {
+                    reset_query_only(c).await?;
+                }
+                result?;
             }
             DbConnection::Postgres(c) => {
                 sqlx::query("ROLLBACK").execute(&mut **c).await?;
             }
+            DbConnection::Other(c) => c.rollback().await?,
         }
         Ok(())
     }
 
+    /// 在事务中执行闭包：返回 `Ok` 时提交，返回 `Err` 或 panic 时回滚
+    ///
+    /// 自动建立 `TRANSACTION_CONTEXT` 作用域；已处于该连接池的事务中时直接加入外层事务。
+    pub async fn transaction<F, Fut, T, E>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(DbPool) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<DbError>,
+    {
+        self.transaction_with(TxOptions::default(), f).await
+    }
+
+    /// 同 [`DbPool::transaction`]，遇到死锁 / 串行化失败时按 `retry_policy` 重新执行闭包
+    ///
+    /// 闭包可能被执行多次，需保证可重入。未配置重试策略或已处于外层事务中时只执行一次。
+    pub async fn transaction_retry<F, Fut, T>(&self, mut f: F) -> Result<T, DbError>
+    where
+        F: FnMut(DbPool) -> Fut,
+        Fut: Future<Output = Result<T, DbError>>,
+    {
+        let policy = match self.retry_policy {
+            Some(policy) if !self.in_transaction() => policy,
+            _ => return self.transaction(f).await,
+        };
+
+        let mut retry = 0;
+        loop {
+            match self.transaction(&mut f).await {
+                Err(e) if e.is_retryable() && retry < policy.max_retries => {
+                    retry += 1;
+                    let delay = policy.delay(retry);
+                    tracing::warn!(
+                        "transaction on '{}' failed ({}), retry {} in {:?}",
+                        self.name,
+                        e,
+                        retry,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 当前任务是否处于该连接池（设置租户时为租户连接池）的事务中
+    pub fn in_transaction(&self) -> bool {
+        let Ok(pool) = tenant::route(self) else {
+            return false;
+        };
+        TRANSACTION_CONTEXT
+            .try_with(|map| map.borrow().contains_key(&pool.name))
+            .unwrap_or(false)
+    }
+
+    /// 同 [`DbPool::transaction`]，可指定事务选项；加入外层事务时选项被忽略
+    pub async fn transaction_with<F, Fut, T, E>(&self, options: TxOptions, f: F) -> Result<T, E>
+    where
+        F: FnOnce(DbPool) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<DbError>,
+    {
+        // 设置租户时事务开启在租户连接池上，事务上下文按租户连接池区分
+        let pool = tenant::route(self)?;
+        match TRANSACTION_CONTEXT.try_with(|map| map.borrow().contains_key(&pool.name)) {
+            Ok(true) => f(pool.into_owned()).await,
+            Ok(false) => pool.run_in_transaction(options, f).await,
+            Err(_) => {
+                TRANSACTION_CONTEXT
+                    .scope(
+                        RefCell::new(HashMap::new()),
+                        pool.run_in_transaction(options, f),
+                    )
+                    .await
+            }
+        }
+    }
+
+    async fn run_in_transaction<F, Fut, T, E>(&self, options: TxOptions, f: F) -> Result<T, E>
+    where
+        F: FnOnce(DbPool) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<DbError>,
+    {
+        self.start_transaction_with(&options).await?;
+        match AssertUnwindSafe(f(self.clone())).catch_unwind().await {
+            Ok(Ok(v)) => {
+                self.commit_transaction().await?;
+                Ok(v)
+            }
+            Ok(Err(e)) => {
+                if let Err(re) = self.rollback_transaction().await {
+                    tracing::warn!("transaction rollback failed on '{}': {}", self.name, re);
+                }
+                Err(e)
+            }
+            Err(panic) => {
+                if let Err(re) = self.rollback_transaction().await {
+                    tracing::warn!("transaction rollback failed on '{}': {}", self.name, re);
+                }
+                std::panic::resume_unwind(panic)
+            }
+        }
+    }
+
+    /// 绑定参数执行语句，返回影响的行数
+    ///
+    /// 参数通过驱动层绑定，避免拼接 SQL；处于事务上下文时使用事务连接。
+    pub async fn execute(&self, sql: &str, args: Vec<Value>) -> Result<u64, DbError> {
+        SqlxRepository.update(self, sql, args).await
+    }
+
     // Helper to execute query with potential transaction
     // This is a minimal example to support "insert/update" logic
     pub async fn execute_raw(&self, sql: &str) -> Result<u64, DbError> {
-        let rows_affected = dispatch_db!(self, conn, {
+        let pool = tenant::route(self)?;
+        let mut timer = QueryTimer::start(&pool, sql, &[]);
+        let rows_affected = dispatch_db!(pool, conn, {
             sqlx::query(sql).execute(conn).await?.rows_affected()
-        });
+        }, other => other.execute(sql, vec![]).await?);
+        timer.rows(rows_affected);
         Ok(rows_affected)
     }
 
     // --- CRUD with TLS support ---
 
-    pub async fn get<T, A>(&self, sql: &str, _args: A) -> Result<Option<T>, DbError>
+    /// 根据 SQL 和参数获取单个实体
+    pub async fn get<T>(&self, sql: &str, args: Vec<Value>) -> Result<Option<T>, DbError>
     where
-        T: DeserializeOwned + Send + Unpin,
-        T: for<'r> FromRow<'r, sqlx::mysql::MySqlRow>,
-        T: for<'r> FromRow<'r, sqlx::sqlite::SqliteRow>,
-        T: for<'r> FromRow<'r, sqlx::postgres::PgRow>,
-        A: Send + Sync,
+        T: DeserializeOwned + Send,
     {
-        let res = dispatch_db!(self, conn, {
-            sqlx::query_as::<_, T>(sql)
-                .fetch_optional(conn)
-                .await?
-        });
-        Ok(res)
+        SqlxRepository.get(self, sql, args).await
     }
 
     /// 根据 SQL 和参数获取实体列表
-    pub async fn list<T, A>(&self, _sql: &str, _args: A) -> Result<Vec<T>, DbError>
+    pub async fn list<T>(&self, sql: &str, args: Vec<Value>) -> Result<Vec<T>, DbError>
     where
         T: DeserializeOwned + Send,
-        A: Send + Sync,
     {
-        todo!()
+        SqlxRepository.list(self, sql, args).await
+    }
+
+    /// 同 [`DbPool::get`]，按 `#[derive(FromRowDe)]` 生成的列下标映射直接构造结果
+    pub async fn get_as<T>(&self, sql: &str, args: Vec<Value>) -> Result<Option<T>, DbError>
+    where
+        T: FromRowDe + Send,
+    {
+        sqlx_impl::get_as(self, sql, args).await
+    }
+
+    /// 同 [`DbPool::list`]，列下标按第一行计算一次，之后各行直接按下标读取，适合大结果集
+    pub async fn list_as<T>(&self, sql: &str, args: Vec<Value>) -> Result<Vec<T>, DbError>
+    where
+        T: FromRowDe + Send,
+    {
+        sqlx_impl::list_as(self, sql, args).await
     }
 
     /// 执行创建操作，并返回结果
-    pub async fn create<T, A>(&self, _sql: &str, _args: A) -> Result<T, DbError>
+    pub async fn create<T>(&self, sql: &str, args: Vec<Value>) -> Result<T, DbError>
     where
         T: DeserializeOwned + Send,
-        A: Send + Sync,
     {
-        todo!()
+        SqlxRepository.create(self, sql, args).await
     }
 
     /// 批量创建操作
-    pub async fn batch_create<T, A>(&self, _sql: &str, _args: Vec<A>) -> Result<Vec<T>, DbError>
+    pub async fn batch_create<T>(&self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<T>, DbError>
     where
         T: DeserializeOwned + Send,
-        A: Send + Sync,
     {
-        todo!()
+        SqlxRepository.batch_create(self, sql, args).await
     }
 
     /// 更新操作，返回影响的行数
-    pub async fn update<A>(&self, _sql: &str, _args: A) -> Result<u64, DbError>
-    where
-        A: Send + Sync,
-    {
-        todo!()
+    pub async fn update(&self, sql: &str, args: Vec<Value>) -> Result<u64, DbError> {
+        SqlxRepository.update(self, sql, args).await
     }
 
     /// 删除操作，返回影响的行数
-    pub async fn delete<A>(&self, _sql: &str, _args: A) -> Result<u64, DbError>
-    where
-        A: Send + Sync,
-    {
-        todo!()
+    pub async fn delete(&self, sql: &str, args: Vec<Value>) -> Result<u64, DbError> {
+        SqlxRepository.delete(self, sql, args).await
     }
 }
+
+/// 恢复只读事务设置的 query_only，避免连接归还后仍为只读
+async fn reset_query_only(conn: &mut sqlx::SqliteConnection) -> Result<(), DbError> {
+    sqlx::query("PRAGMA query_only = OFF").execute(conn).await?;
+    Ok(())
+}
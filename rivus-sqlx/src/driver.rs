@@ -0,0 +1,102 @@
+use crate::error::DbError;
+use crate::models::db_config::DatabaseOptions;
+use futures::future::BoxFuture;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::Arc;
+
+/// 外部数据库驱动（如 ClickHouse、MSSQL），通过 [`crate::db_conn::ConnManager::register_driver`] 按类型名注册
+///
+/// 注册后 `ConnManager::open(name, "<类型名>", &config)` 创建的连接池经由该驱动执行，
+/// 可直接用于 `SqlxRepository` / `DbPool` 的增删改查与事务。SQL 与参数原样传给驱动，占位符风格由驱动处理。
+pub trait DbDriver: Send + Sync {
+    /// 按配置建立连接池
+    fn connect<'a>(
+        &'a self,
+        config: &'a DatabaseOptions,
+    ) -> BoxFuture<'a, Result<Arc<dyn DriverPool>, DbError>>;
+}
+
+/// 驱动建立的连接池
+pub trait DriverPool: Send + Sync {
+    /// 获取一个连接，事务期间该连接由当前任务独占
+    fn acquire(&self) -> BoxFuture<'_, Result<Box<dyn DriverConnection>, DbError>>;
+
+    /// 关闭连接池
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// 驱动的单个连接
+pub trait DriverConnection: Send {
+    /// 执行语句，返回影响的行数
+    fn execute<'a>(
+        &'a mut self,
+        sql: &'a str,
+        args: Vec<Value>,
+    ) -> BoxFuture<'a, Result<u64, DbError>>;
+
+    /// 执行查询，每行为列名到值的映射
+    fn fetch<'a>(
+        &'a mut self,
+        sql: &'a str,
+        args: Vec<Value>,
+    ) -> BoxFuture<'a, Result<Vec<Map<String, Value>>, DbError>>;
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), DbError>>;
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), DbError>>;
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), DbError>>;
+}
+
+/// `DbPoolInner::Other` 的连接池：已注册驱动的连接池，或未注册类型的占位
+#[derive(Clone)]
+pub struct OtherPool {
+    /// 数据库类型名
+    pub kind: String,
+    pool: Option<Arc<dyn DriverPool>>,
+}
+
+impl fmt::Debug for OtherPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtherPool")
+            .field("kind", &self.kind)
+            .field("registered", &self.pool.is_some())
+            .finish()
+    }
+}
+
+impl OtherPool {
+    pub fn new(kind: &str, pool: Arc<dyn DriverPool>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            pool: Some(pool),
+        }
+    }
+
+    /// 未注册驱动的类型，所有操作返回错误
+    pub fn unsupported(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            pool: None,
+        }
+    }
+
+    pub(crate) async fn acquire(&self) -> Result<Box<dyn DriverConnection>, DbError> {
+        match &self.pool {
+            Some(pool) => pool.acquire().await,
+            None => Err(DbError::from(format!(
+                "Unsupported database type '{}'",
+                self.kind
+            ))),
+        }
+    }
+
+    pub(crate) async fn close(&self) {
+        if let Some(pool) = &self.pool {
+            pool.close().await;
+        }
+    }
+}
@@ -1,9 +1,37 @@
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum DbError {
     Sqlx(sqlx::Error),
     Config(String),
+    Timeout(Duration),
+    /// 乐观锁冲突：按期望版本号更新时没有命中任何行
+    StaleVersion(i64),
+    /// 迁移失败：校验和不一致、版本缺失或迁移脚本执行出错
+    Migrate(sqlx::migrate::MigrateError),
+}
+
+impl DbError {
+    /// 是否为可重试的并发冲突：死锁、串行化失败、锁等待超时或 SQLite 的 BUSY/LOCKED
+    pub fn is_retryable(&self) -> bool {
+        let DbError::Sqlx(sqlx::Error::Database(e)) = self else {
+            return false;
+        };
+        if let Some(my) = e.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+            // 1213: 死锁，1205: 锁等待超时
+            return matches!(my.number(), 1213 | 1205);
+        }
+        if e.try_downcast_ref::<sqlx::sqlite::SqliteError>().is_some() {
+            // 扩展错误码的低 8 位为主错误码，5: SQLITE_BUSY，6: SQLITE_LOCKED
+            return e
+                .code()
+                .and_then(|c| c.parse::<i32>().ok())
+                .is_some_and(|c| matches!(c & 0xff, 5 | 6));
+        }
+        // Postgres 等按 SQLSTATE 判断：40001 串行化失败，40P01 死锁
+        e.code().is_some_and(|c| c == "40001" || c == "40P01")
+    }
 }
 
 impl fmt::Display for DbError {
@@ -11,6 +39,13 @@ impl fmt::Display for DbError {
         match self {
             DbError::Sqlx(e) => write!(f, "Database error: {}", e),
             DbError::Config(e) => write!(f, "Configuration error: {}", e),
+            DbError::Timeout(d) => write!(f, "Timed out after {:?}", d),
+            DbError::StaleVersion(v) => write!(
+                f,
+                "Stale version: row was modified or removed (expected version {})",
+                v
+            ),
+            DbError::Migrate(e) => write!(f, "Migration error: {}", e),
         }
     }
 }
@@ -19,7 +54,8 @@ impl std::error::Error for DbError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             DbError::Sqlx(e) => Some(e),
-            DbError::Config(_) => None,
+            DbError::Migrate(e) => Some(e),
+            DbError::Config(_) | DbError::Timeout(_) | DbError::StaleVersion(_) => None,
         }
     }
 }
@@ -30,6 +66,12 @@ impl From<sqlx::Error> for DbError {
     }
 }
 
+impl From<sqlx::migrate::MigrateError> for DbError {
+    fn from(err: sqlx::migrate::MigrateError) -> Self {
+        DbError::Migrate(err)
+    }
+}
+
 impl From<String> for DbError {
     fn from(err: String) -> Self {
         DbError::Config(err)
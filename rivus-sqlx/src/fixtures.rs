@@ -0,0 +1,123 @@
+use crate::db_pool::{DbPool, TRANSACTION_CONTEXT};
+use crate::error::DbError;
+use futures::FutureExt;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+
+/// 测试数据文件：表名到行列表的映射，保持文件中的表顺序
+///
+/// ```yaml
+/// users:
+///   - { id: 1, name: alice }
+/// orders:
+///   - { id: 10, user_id: 1, total: 100 }
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Fixture {
+    pub tables: Vec<(String, Vec<Map<String, Value>>)>,
+}
+
+impl<'de> Deserialize<'de> for Fixture {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FixtureVisitor;
+
+        impl<'de> Visitor<'de> for FixtureVisitor {
+            type Value = Fixture;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of table names to row lists")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Fixture, A::Error> {
+                let mut tables = Vec::new();
+                while let Some((table, rows)) =
+                    map.next_entry::<String, Option<Vec<Map<String, Value>>>>()?
+                {
+                    tables.push((table, rows.unwrap_or_default()));
+                }
+                Ok(Fixture { tables })
+            }
+        }
+
+        deserializer.deserialize_map(FixtureVisitor)
+    }
+}
+
+/// 从 YAML / JSON / TOML 文件加载测试数据，返回插入的行数
+///
+/// 先按文件中的逆序清空涉及的表，再按顺序插入，整体在一个事务中执行；
+/// 父表写在子表之前即可满足外键约束。文件支持 rivus-yaml 的变量替换与 `!include`。
+pub async fn load(pool: &DbPool, path: impl AsRef<Path>) -> Result<u64, DbError> {
+    let path = path.as_ref();
+    let fixture: Fixture = rivus_yaml::load_from_file(path)
+        .map_err(|e| DbError::from(format!("Cannot load fixture {}: {}", path.display(), e)))?;
+    insert(pool, fixture).await
+}
+
+/// 清空并写入 [`Fixture`] 中的表，返回插入的行数
+pub async fn insert(pool: &DbPool, fixture: Fixture) -> Result<u64, DbError> {
+    pool.transaction(|tx| async move {
+        for (table, _) in fixture.tables.iter().rev() {
+            tx.execute(&format!("DELETE FROM {}", table), vec![])
+                .await?;
+        }
+        let mut inserted = 0;
+        for (table, rows) in fixture.tables {
+            for row in rows {
+                let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+                let sql = format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    table,
+                    columns.join(", "),
+                    vec!["?"; columns.len()].join(", ")
+                );
+                let args = row.values().cloned().collect();
+                inserted += tx.execute(&sql, args).await?;
+            }
+        }
+        Ok(inserted)
+    })
+    .await
+}
+
+/// 在事务中执行测试体，结束后（包括 panic）总是回滚，测试之间互不影响
+///
+/// ```ignore
+/// #[tokio::test]
+/// async fn test_orders() {
+///     fixtures::rollback(&pool, |tx| async move {
+///         fixtures::load(&tx, "tests/fixtures/users.yaml").await.unwrap();
+///         // ...
+///     })
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn rollback<F, Fut, T>(pool: &DbPool, f: F) -> Result<T, DbError>
+where
+    F: FnOnce(DbPool) -> Fut,
+    Fut: Future<Output = T>,
+{
+    if pool.in_transaction() {
+        return Err(DbError::from(
+            "fixtures::rollback cannot run inside an existing transaction",
+        ));
+    }
+    TRANSACTION_CONTEXT
+        .scope(RefCell::new(HashMap::new()), async {
+            pool.start_transaction().await?;
+            let result = AssertUnwindSafe(f(pool.clone())).catch_unwind().await;
+            let rolled_back = pool.rollback_transaction().await;
+            match result {
+                Ok(v) => rolled_back.map(|_| v),
+                Err(panic) => std::panic::resume_unwind(panic),
+            }
+        })
+        .await
+}
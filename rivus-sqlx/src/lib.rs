@@ -1,9 +1,20 @@
 pub mod models;
 pub mod sql_parser;
+pub mod mapper_store;
 pub mod db_conn;
 pub mod db_pool;
+pub mod driver;
 pub mod error;
+pub mod fixtures;
+pub mod metrics;
+pub mod migrate;
 pub mod orm;
+pub mod pg_notify;
+pub mod schema;
 pub mod sql_tpl;
+pub mod tenant;
 
-pub use rivus_sqlx_macros::sql;
+pub use rivus_sqlx_macros::{delete, embed_mappers, insert, select, sql, transactional, update, Entity, FromRowDe};
+
+#[doc(hidden)]
+pub use include_dir;
@@ -0,0 +1,173 @@
+use crate::sql_parser::{parse_mapper_str, parse_mappers_recursively, ContentMap, IdMapper, MapperMap};
+use crate::sql_tpl::engine::register_mappers;
+use anyhow::{Context, Result};
+use include_dir::{Dir, DirEntry};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// 一次完整解析得到的 mapper 快照
+#[derive(Debug, Default)]
+pub struct Mappers {
+    pub contents: ContentMap,
+    pub mappers: MapperMap,
+}
+
+impl Mappers {
+    /// 递归解析目录下所有 XML 文件
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut mappers = Self::default();
+        parse_mappers_recursively(dir, &mut mappers.contents, &mut mappers.mappers)?;
+        Ok(mappers)
+    }
+
+    /// 解析编译期嵌入的目录（见 `embed_mappers!`）
+    pub fn from_embedded(dir: &Dir<'_>) -> Result<Self> {
+        let mut mappers = Self::default();
+        mappers.parse_embedded(dir)?;
+        Ok(mappers)
+    }
+
+    fn parse_embedded(&mut self, dir: &Dir<'_>) -> Result<()> {
+        for entry in dir.entries() {
+            match entry {
+                DirEntry::Dir(sub) => self.parse_embedded(sub)?,
+                DirEntry::File(file) if file.path().extension().is_some_and(|ext| ext == "xml") => {
+                    let xml = file
+                        .contents_utf8()
+                        .with_context(|| format!("非 UTF-8 文件: {}", file.path().display()))?;
+                    parse_mapper_str(xml, file.path(), &mut self.contents, &mut self.mappers)?;
+                }
+                DirEntry::File(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// 将语句按 `namespace.id` 注册为模板，供 `<include>` 引用
+    pub fn register_templates(&self) {
+        register_mappers(&self.contents);
+    }
+
+    pub fn sql(&self, namespace: &str, id: &str) -> Option<&str> {
+        self.contents.get(namespace)?.get(id)?.as_deref()
+    }
+
+    pub fn mapper(&self, namespace: &str, id: &str) -> Option<&IdMapper> {
+        self.mappers.get(namespace)?.get(id)
+    }
+
+    /// 注册模板并设为 `#[sql]` 方法使用的 mapper，替换已安装的 mapper
+    pub fn install(self) {
+        self.register_templates();
+        *INSTALLED.write().unwrap() = Some(Installed::Fixed(Arc::new(self)));
+    }
+}
+
+/// `#[sql]` 方法使用的 mapper
+enum Installed {
+    Fixed(Arc<Mappers>),
+    Store(Arc<MapperStore>),
+}
+
+static INSTALLED: LazyLock<RwLock<Option<Installed>>> = LazyLock::new(|| RwLock::new(None));
+
+/// 当前安装的 mapper 快照，见 [`Mappers::install`] / [`MapperStore::install`]
+pub fn installed() -> Option<Arc<Mappers>> {
+    match INSTALLED.read().unwrap().as_ref()? {
+        Installed::Fixed(mappers) => Some(mappers.clone()),
+        Installed::Store(store) => Some(store.snapshot()),
+    }
+}
+
+/// 可热更新的 mapper 存储
+///
+/// 读取方通过 [`MapperStore::snapshot`] 获取当前快照；重新加载成功后整体替换，
+/// 失败时保留旧快照。
+pub struct MapperStore {
+    dir: PathBuf,
+    current: RwLock<Arc<Mappers>>,
+}
+
+impl MapperStore {
+    /// 解析目录并注册 `namespace.id` 模板
+    pub fn load(dir: impl Into<PathBuf>) -> Result<Arc<Self>> {
+        let dir = dir.into();
+        let mappers = Mappers::load(&dir)?;
+        mappers.register_templates();
+        Ok(Arc::new(Self {
+            dir,
+            current: RwLock::new(Arc::new(mappers)),
+        }))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn snapshot(&self) -> Arc<Mappers> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// 设为 `#[sql]` 方法使用的 mapper，重新加载后自动使用新快照
+    pub fn install(self: &Arc<Self>) {
+        *INSTALLED.write().unwrap() = Some(Installed::Store(self.clone()));
+    }
+
+    /// 重新解析目录，成功后原子替换当前快照
+    pub fn reload(&self) -> Result<()> {
+        let mappers = Mappers::load(&self.dir)?;
+        mappers.register_templates();
+        *self.current.write().unwrap() = Arc::new(mappers);
+        Ok(())
+    }
+
+    /// 开发模式：按间隔检查目录下 XML 文件的变化并重新加载
+    ///
+    /// 解析失败时记录警告并继续使用旧快照。需在 tokio 运行时内调用。
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let store = Arc::downgrade(self);
+        let mut last = fingerprint(&self.dir);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                // store 已释放时结束监听
+                let Some(store) = store.upgrade() else {
+                    break;
+                };
+                let current = fingerprint(&store.dir);
+                if current == last {
+                    continue;
+                }
+                match store.reload() {
+                    Ok(()) => tracing::info!("mapper reloaded: {}", store.dir.display()),
+                    Err(e) => tracing::warn!("mapper reload failed, keep previous: {:#}", e),
+                }
+                last = current;
+            }
+        })
+    }
+}
+
+/// 目录下 XML 文件路径、大小与修改时间的摘要
+fn fingerprint(dir: &Path) -> u64 {
+    let mut files: Vec<_> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && e.path().extension().is_some_and(|ext| ext == "xml"))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some((e.into_path(), meta.len(), meta.modified().ok()))
+        })
+        .collect();
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+    files.hash(&mut hasher);
+    hasher.finish()
+}
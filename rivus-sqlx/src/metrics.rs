@@ -0,0 +1,84 @@
+use crate::db_pool::{DbPool, PoolStats};
+use std::future::Future;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+tokio::task_local! {
+    /// 当前执行的 mapper 语句 id（如 `UserDao.list`）
+    static STATEMENT_ID: Arc<str>;
+}
+
+/// 一次语句执行的指标
+#[derive(Debug, Clone, Copy)]
+pub struct QueryEvent<'a> {
+    /// 连接池名称
+    pub pool: &'a str,
+    /// mapper 语句 id，未通过 [`with_statement_id`] 指定时为 `None`
+    pub statement: Option<&'a str>,
+    pub elapsed: Duration,
+    /// 返回或影响的行数，失败时为 `None`
+    pub rows: Option<u64>,
+    pub success: bool,
+}
+
+/// 指标接收方，可对接 `metrics` / Prometheus 等
+///
+/// 所有事件均带连接池名称，便于按连接池分组。
+pub trait MetricsRecorder: Send + Sync {
+    /// 语句执行完成（成功或失败）
+    fn query(&self, event: &QueryEvent<'_>);
+
+    /// 从连接池获取连接的耗时（事务内复用连接时不记录）
+    fn acquire(&self, _pool: &str, _elapsed: Duration) {}
+
+    /// 获取连接后的连接池状态
+    fn pool_stats(&self, _pool: &str, _stats: &PoolStats) {}
+}
+
+static RECORDER: LazyLock<RwLock<Option<Arc<dyn MetricsRecorder>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// 设置全局指标接收方，替换已有的设置
+pub fn set_recorder(recorder: Arc<dyn MetricsRecorder>) {
+    *RECORDER.write().unwrap() = Some(recorder);
+}
+
+/// 移除全局指标接收方
+pub fn clear_recorder() {
+    *RECORDER.write().unwrap() = None;
+}
+
+fn recorder() -> Option<Arc<dyn MetricsRecorder>> {
+    RECORDER.read().unwrap().clone()
+}
+
+/// 在指定语句 id 下执行，期间的查询指标与日志带上该 id
+pub async fn with_statement_id<F: Future>(id: impl Into<Arc<str>>, fut: F) -> F::Output {
+    STATEMENT_ID.scope(id.into(), fut).await
+}
+
+/// 当前任务的语句 id
+pub fn current_statement_id() -> Option<Arc<str>> {
+    STATEMENT_ID.try_with(Arc::clone).ok()
+}
+
+pub(crate) fn record_query(event: &QueryEvent<'_>) {
+    if let Some(r) = recorder() {
+        r.query(event);
+    }
+}
+
+pub(crate) fn record_acquire(pool: &DbPool, elapsed: Duration) {
+    if let Some(r) = recorder() {
+        r.acquire(&pool.name, elapsed);
+        if let Some(stats) = pool.stats() {
+            r.pool_stats(&pool.name, &stats);
+        }
+    }
+}
+
+pub(crate) fn record_pool_stats(pool: &str, stats: &PoolStats) {
+    if let Some(r) = recorder() {
+        r.pool_stats(pool, stats);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::db_pool::{DbPool, DbPoolInner};
+use crate::error::DbError;
+use sqlx::migrate::{Migrate, MigrateError};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 按连接池类型取出具体的 sqlx 连接池执行
+macro_rules! with_pool {
+    ($pool:expr, $p:ident, $body:expr) => {
+        match &$pool.inner {
+            DbPoolInner::MySql($p) => $body,
+            DbPoolInner::Postgres($p) => $body,
+            DbPoolInner::Sqlite($p) => $body,
+            DbPoolInner::Other(_) => return Err(DbError::from("Unsupported database type")),
+        }
+    };
+}
+
+/// 迁移的当前状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationState {
+    /// 尚未执行
+    Pending,
+    Applied,
+    /// 已执行，但脚本内容与执行时不一致
+    Modified,
+    /// 数据库中已执行，迁移目录中不存在
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+    /// 是否有对应的 down 脚本
+    pub reversible: bool,
+}
+
+/// 基于 sqlx 的 schema 迁移
+///
+/// 迁移目录中的文件命名为 `<version>_<description>.sql`，可回滚的迁移使用
+/// `<version>_<description>.up.sql` / `.down.sql` 成对出现。已执行的迁移记录在 `_sqlx_migrations` 表，
+/// 执行前校验已执行脚本的校验和；MySQL / Postgres 执行期间持有 advisory lock，多实例同时启动时依次执行。
+pub struct Migrator {
+    inner: sqlx::migrate::Migrator,
+    dry_run: bool,
+}
+
+impl Migrator {
+    /// 读取目录中的迁移脚本
+    pub async fn from_dir(dir: impl AsRef<Path>) -> Result<Self, DbError> {
+        let inner = sqlx::migrate::Migrator::new(dir.as_ref().to_path_buf()).await?;
+        Ok(Self {
+            inner,
+            dry_run: false,
+        })
+    }
+
+    /// 为 true 时只输出将要执行的迁移，不修改数据库
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// 是否在执行期间加锁，默认开启；数据库不支持 advisory lock 时关闭
+    pub fn locking(mut self, locking: bool) -> Self {
+        self.inner.set_locking(locking);
+        self
+    }
+
+    /// 各迁移的执行状态，按版本号排序
+    pub async fn status(&self, pool: &DbPool) -> Result<Vec<MigrationStatus>, DbError> {
+        let mut applied = applied_checksums(pool).await?;
+        let mut status: Vec<MigrationStatus> = self
+            .inner
+            .iter()
+            .filter(|m| m.migration_type.is_up_migration())
+            .map(|m| {
+                let state = match applied.remove(&m.version) {
+                    None => MigrationState::Pending,
+                    Some(checksum) if checksum == *m.checksum => MigrationState::Applied,
+                    Some(_) => MigrationState::Modified,
+                };
+                MigrationStatus {
+                    version: m.version,
+                    description: m.description.to_string(),
+                    state,
+                    reversible: self.has_down(m.version),
+                }
+            })
+            .collect();
+        status.extend(applied.into_keys().map(|version| MigrationStatus {
+            version,
+            description: String::new(),
+            state: MigrationState::Missing,
+            reversible: false,
+        }));
+        status.sort_by_key(|s| s.version);
+        Ok(status)
+    }
+
+    /// 执行全部未执行的迁移，返回本次执行（dry run 时为将要执行）的版本号
+    pub async fn up(&self, pool: &DbPool) -> Result<Vec<i64>, DbError> {
+        let status = self.status(pool).await?;
+        check_consistent(&status)?;
+        let pending: Vec<&MigrationStatus> = status
+            .iter()
+            .filter(|s| s.state == MigrationState::Pending)
+            .collect();
+        for m in &pending {
+            tracing::info!(
+                "{} migration {} ({}) on '{}'",
+                if self.dry_run { "pending" } else { "applying" },
+                m.version,
+                m.description,
+                pool.name
+            );
+        }
+        if !self.dry_run {
+            with_pool!(pool, p, self.inner.run(p).await?);
+        }
+        Ok(pending.iter().map(|m| m.version).collect())
+    }
+
+    /// 按版本倒序回滚最近执行的 `steps` 个迁移，返回回滚（dry run 时为将要回滚）的版本号
+    ///
+    /// 待回滚的迁移必须都有 down 脚本，否则不执行任何回滚。
+    pub async fn down(&self, pool: &DbPool, steps: usize) -> Result<Vec<i64>, DbError> {
+        let status = self.status(pool).await?;
+        check_consistent(&status)?;
+        let mut applied: Vec<&MigrationStatus> = status
+            .iter()
+            .filter(|s| s.state == MigrationState::Applied)
+            .collect();
+        applied.reverse();
+
+        let revert = &applied[..steps.min(applied.len())];
+        if let Some(m) = revert.iter().find(|m| !m.reversible) {
+            return Err(DbError::from(format!(
+                "migration {} ({}) has no down script",
+                m.version, m.description
+            )));
+        }
+        for m in revert {
+            tracing::info!(
+                "{} migration {} ({}) on '{}'",
+                if self.dry_run {
+                    "would revert"
+                } else {
+                    "reverting"
+                },
+                m.version,
+                m.description,
+                pool.name
+            );
+        }
+        if !self.dry_run && !revert.is_empty() {
+            // 回滚所有版本号大于 target 的已执行迁移
+            let target = applied.get(revert.len()).map_or(i64::MIN, |m| m.version);
+            with_pool!(pool, p, self.inner.undo(p, target).await?);
+        }
+        Ok(revert.iter().map(|m| m.version).collect())
+    }
+
+    fn has_down(&self, version: i64) -> bool {
+        self.inner
+            .iter()
+            .any(|m| m.version == version && m.migration_type.is_down_migration())
+    }
+}
+
+/// 已执行脚本被修改或缺失时拒绝执行
+fn check_consistent(status: &[MigrationStatus]) -> Result<(), DbError> {
+    for s in status {
+        match s.state {
+            MigrationState::Modified => return Err(MigrateError::VersionMismatch(s.version).into()),
+            MigrationState::Missing => return Err(MigrateError::VersionMissing(s.version).into()),
+            MigrationState::Pending | MigrationState::Applied => {}
+        }
+    }
+    Ok(())
+}
+
+/// 已执行迁移的版本号与校验和；迁移表不存在时创建
+async fn applied_checksums(pool: &DbPool) -> Result<HashMap<i64, Vec<u8>>, DbError> {
+    let applied = with_pool!(pool, p, {
+        let mut conn = p.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        if let Some(version) = conn.dirty_version().await? {
+            return Err(MigrateError::Dirty(version).into());
+        }
+        conn.list_applied_migrations().await?
+    });
+    Ok(applied
+        .into_iter()
+        .map(|m| (m.version, m.checksum.into_owned()))
+        .collect())
+}
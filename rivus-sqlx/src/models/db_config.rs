@@ -1,12 +1,68 @@
-use std::error::Error;
+use rivus_yaml::YamlLoaderError;
+use serde::Deserialize;
+use std::path::Path;
 
+const DEFAULT_MAX_OPEN_CONNS: u64 = 10;
+const DEFAULT_MAX_IDLE_CONNS: u64 = 2;
+const DEFAULT_MAX_LIFETIME: u64 = 30_60;
+const DEFAULT_TIMEOUT: u64 = 10;
+const DEFAULT_SLOW_QUERY_THRESHOLD: u64 = 1000;
+const DEFAULT_TX_RETRY_BACKOFF: u64 = 50;
+const DEFAULT_STATEMENT_CACHE_SIZE: usize = 100;
+
+/// 数据库连接池配置
+///
+/// 可直接从配置文件反序列化（如 rivus-yaml 的 `database` 段），未填写的字段使用默认值。
+#[derive(Clone, Debug, Deserialize)]
 pub struct DatabaseOptions {
     pub r#type: String,
     pub url: String,
+    #[serde(default = "default_max_open_conns")]
     pub max_open_conns: u64, // 设置池最大连接数
+    #[serde(default = "default_max_idle_conns")]
     pub max_idle_conns: u64, // 设置池最大空闲数
+    #[serde(default = "default_max_lifetime")]
     pub max_lifetime: u64,   // 设置连接最大生命周期
+    #[serde(default = "default_timeout", alias = "connection_timeout")]
     pub timeout: u64,        // 设置连接池获取连接的超时时间
+    #[serde(default = "default_slow_query_threshold")]
+    pub slow_query_threshold: u64, // 慢查询阈值（毫秒），0 表示关闭
+    #[serde(default)]
+    pub query_timeout: u64, // 单条语句的超时时间（毫秒），0 表示不限制
+    #[serde(default)]
+    pub tx_max_retries: u32, // 事务遇到死锁 / 串行化失败时的最大重试次数，0 表示不重试
+    #[serde(default = "default_tx_retry_backoff")]
+    pub tx_retry_backoff: u64, // 重试的初始退避时间（毫秒），之后按指数增长
+    #[serde(default = "default_statement_cache_size")]
+    pub statement_cache_size: usize, // 每个连接缓存的预处理语句数，0 表示不缓存
+}
+
+fn default_max_open_conns() -> u64 {
+    DEFAULT_MAX_OPEN_CONNS
+}
+
+fn default_max_idle_conns() -> u64 {
+    DEFAULT_MAX_IDLE_CONNS
+}
+
+fn default_max_lifetime() -> u64 {
+    DEFAULT_MAX_LIFETIME
+}
+
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT
+}
+
+fn default_slow_query_threshold() -> u64 {
+    DEFAULT_SLOW_QUERY_THRESHOLD
+}
+
+fn default_tx_retry_backoff() -> u64 {
+    DEFAULT_TX_RETRY_BACKOFF
+}
+
+fn default_statement_cache_size() -> usize {
+    DEFAULT_STATEMENT_CACHE_SIZE
 }
 
 impl DatabaseOptions {
@@ -14,12 +70,25 @@ impl DatabaseOptions {
         DatabaseOptions {
             r#type,
             url,
-            max_open_conns: 10,
-            max_idle_conns: 2,
-            max_lifetime: 30_60,
-            timeout: 10,
+            max_open_conns: DEFAULT_MAX_OPEN_CONNS,
+            max_idle_conns: DEFAULT_MAX_IDLE_CONNS,
+            max_lifetime: DEFAULT_MAX_LIFETIME,
+            timeout: DEFAULT_TIMEOUT,
+            slow_query_threshold: DEFAULT_SLOW_QUERY_THRESHOLD,
+            query_timeout: 0,
+            tx_max_retries: 0,
+            tx_retry_backoff: DEFAULT_TX_RETRY_BACKOFF,
+            statement_cache_size: DEFAULT_STATEMENT_CACHE_SIZE,
         }
     }
+
+    /// 从配置文件中读取一节，如 `DatabaseOptions::from_yaml("app.yaml", "database")`
+    ///
+    /// 通过 rivus-yaml 加载，支持 `${VAR}` 环境变量替换与 `!include`；`section` 可为 `app.database` 这样的键路径。
+    pub fn from_yaml<P: AsRef<Path>>(path: P, section: &str) -> Result<Self, YamlLoaderError> {
+        rivus_yaml::load_section_from_file(path, section)
+    }
+
     pub fn max_open_conns(mut self, max_open_conns: u64) -> Self {
         self.max_open_conns = max_open_conns;
         self
@@ -37,4 +106,21 @@ impl DatabaseOptions {
         self.timeout = timeout;
         self
     }
+    pub fn slow_query_threshold(mut self, slow_query_threshold: u64) -> Self {
+        self.slow_query_threshold = slow_query_threshold;
+        self
+    }
+    pub fn query_timeout(mut self, query_timeout: u64) -> Self {
+        self.query_timeout = query_timeout;
+        self
+    }
+    pub fn statement_cache_size(mut self, statement_cache_size: usize) -> Self {
+        self.statement_cache_size = statement_cache_size;
+        self
+    }
+    pub fn tx_retry(mut self, max_retries: u32, backoff: u64) -> Self {
+        self.tx_max_retries = max_retries;
+        self.tx_retry_backoff = backoff;
+        self
+    }
 }
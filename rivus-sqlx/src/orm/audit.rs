@@ -0,0 +1,290 @@
+use crate::orm::placeholder::{self, find_keyword};
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    /// 当前操作人，填充 `created_by` / `updated_by`
+    static AUDIT_USER: Arc<str>;
+}
+
+/// 在此范围内执行的审计写入以 `user` 作为操作人
+pub async fn with_user<F: Future>(user: impl Into<Arc<str>>, fut: F) -> F::Output {
+    AUDIT_USER.scope(user.into(), fut).await
+}
+
+/// 当前任务的操作人
+pub fn current_user() -> Option<Arc<str>> {
+    AUDIT_USER.try_with(Arc::clone).ok()
+}
+
+/// 审计列名
+///
+/// mapper 中 `audit="true"` 使用默认列名，也可按 `created_at,created_by,updated_at,updated_by` 的顺序指定。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditColumns {
+    pub created_at: String,
+    pub created_by: String,
+    pub updated_at: String,
+    pub updated_by: String,
+}
+
+impl Default for AuditColumns {
+    fn default() -> Self {
+        Self {
+            created_at: "created_at".to_string(),
+            created_by: "created_by".to_string(),
+            updated_at: "updated_at".to_string(),
+            updated_by: "updated_by".to_string(),
+        }
+    }
+}
+
+impl AuditColumns {
+    /// 解析 mapper 的 `audit` 属性，`false` 或空值表示不审计
+    pub fn parse(attr: &str) -> Option<Self> {
+        let attr = attr.trim();
+        if attr.is_empty() || attr.eq_ignore_ascii_case("false") {
+            return None;
+        }
+        if attr.eq_ignore_ascii_case("true") {
+            return Some(Self::default());
+        }
+        let names: Vec<&str> = attr.split(',').map(str::trim).collect();
+        match names[..] {
+            [created_at, created_by, updated_at, updated_by] => Some(Self {
+                created_at: created_at.to_string(),
+                created_by: created_by.to_string(),
+                updated_at: updated_at.to_string(),
+                updated_by: updated_by.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// 为单行 `INSERT INTO t (...) VALUES (...)` 补充 `created_at` / `created_by`
+    ///
+    /// 语句已包含的列不重复填充；没有操作人时不填充 `created_by`。
+    pub(crate) fn insert(&self, sql: &str, args: &mut Vec<Value>) -> Option<String> {
+        let sql = sql.trim_end().trim_end_matches(';');
+        if !sql
+            .trim_start()
+            .get(..6)
+            .is_some_and(|kw| kw.eq_ignore_ascii_case("INSERT"))
+        {
+            return None;
+        }
+        let into = find_keyword(sql, "INTO", 0)?;
+        let values = find_keyword(sql, "VALUES", into)?;
+        let columns_open = into + sql[into..values].find('(')?;
+        let columns_close = columns_open + sql[columns_open..values].rfind(')')?;
+        let tuple_open = values + sql[values..].find('(')?;
+        let tuple_close = closing_paren(sql, tuple_open)?;
+        // 多行 VALUES 的参数位置无法统一调整
+        if sql[tuple_close + 1..].trim_start().starts_with(',') {
+            return None;
+        }
+
+        let columns = &sql[columns_open + 1..columns_close];
+        let fill = self.fill(sql, columns, &self.created_at, &self.created_by, args);
+        if fill.is_empty() {
+            return Some(sql.to_string());
+        }
+        let names: Vec<&str> = fill.iter().map(|(c, _)| *c).collect();
+        let exprs: Vec<&str> = fill.iter().map(|(_, e)| e.as_str()).collect();
+        Some(format!(
+            "{}{}, {}{}, {}",
+            &sql[..=columns_open],
+            names.join(", "),
+            &sql[columns_open + 1..=tuple_open],
+            exprs.join(", "),
+            &sql[tuple_open + 1..]
+        ))
+    }
+
+    /// 为 `UPDATE t SET ...` 补充 `updated_at` / `updated_by`
+    pub(crate) fn update(&self, sql: &str, args: &mut Vec<Value>) -> Option<String> {
+        let sql = sql.trim_end().trim_end_matches(';');
+        if !sql
+            .trim_start()
+            .get(..6)
+            .is_some_and(|kw| kw.eq_ignore_ascii_case("UPDATE"))
+        {
+            return None;
+        }
+        let set = find_keyword(sql, "SET", 0)?;
+        let end = ["WHERE", "FROM", "ORDER", "LIMIT", "RETURNING"]
+            .iter()
+            .filter_map(|kw| find_keyword(sql, kw, set))
+            .min()
+            .unwrap_or(sql.len());
+
+        let fill = self.fill(
+            sql,
+            &sql[set + 3..end],
+            &self.updated_at,
+            &self.updated_by,
+            args,
+        );
+        if fill.is_empty() {
+            return Some(sql.to_string());
+        }
+        let assignments: Vec<String> = fill.iter().map(|(c, e)| format!("{} = {}", c, e)).collect();
+        Some(format!(
+            "{} {},{}",
+            &sql[..set + 3],
+            assignments.join(", "),
+            &sql[set + 3..]
+        ))
+    }
+
+    /// 需填充的列及其表达式，操作人参数写入 `args`
+    ///
+    /// 新列插在语句最前：`?` 占位符时参数插在最前，`$n` 占位符时使用下一个编号追加在最后。
+    fn fill<'a>(
+        &self,
+        sql: &str,
+        present: &str,
+        at: &'a str,
+        by: &'a str,
+        args: &mut Vec<Value>,
+    ) -> Vec<(&'a str, String)> {
+        let mut fill = Vec::new();
+        if find_keyword(present, at, 0).is_none() {
+            fill.push((at, "CURRENT_TIMESTAMP".to_string()));
+        }
+        if let Some(user) = current_user()
+            && find_keyword(present, by, 0).is_none()
+        {
+            if placeholder::has_numbered(sql) {
+                args.push(Value::from(user.as_ref()));
+                fill.push((by, format!("${}", args.len())));
+            } else {
+                args.insert(0, Value::from(user.as_ref()));
+                fill.push((by, "?".to_string()));
+            }
+        }
+        fill
+    }
+}
+
+/// `open` 处左括号对应的右括号位置
+fn closing_paren(sql: &str, open: usize) -> Option<usize> {
+    let inner = &sql[open + 1..];
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (i, c) in inner.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return Some(open + 1 + i),
+            (None, ')') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn as_user<R>(f: impl FnOnce() -> R) -> R {
+        AUDIT_USER.sync_scope(Arc::from("alice"), f)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(AuditColumns::parse("true"), Some(AuditColumns::default()));
+        assert_eq!(AuditColumns::parse("false"), None);
+        let columns = AuditColumns::parse("ctime, cuser, mtime, muser").unwrap();
+        assert_eq!(
+            (columns.created_at.as_str(), columns.updated_by.as_str()),
+            ("ctime", "muser")
+        );
+        assert_eq!(AuditColumns::parse("ctime,cuser"), None);
+    }
+
+    #[test]
+    fn test_insert() {
+        let columns = AuditColumns::default();
+        let mut args = vec![json!("a"), json!(1)];
+        let sql =
+            as_user(|| columns.insert("INSERT INTO users (name, age) VALUES (?, ?);", &mut args))
+                .unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO users (created_at, created_by, name, age) VALUES (CURRENT_TIMESTAMP, ?, ?, ?)"
+        );
+        assert_eq!(args, vec![json!("alice"), json!("a"), json!(1)]);
+
+        let mut args = vec![json!("a"), json!("bob")];
+        let sql = as_user(|| columns.insert("INSERT INTO users (name, created_by) VALUES ($1, $2) RETURNING id", &mut args)).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO users (created_at, name, created_by) VALUES (CURRENT_TIMESTAMP, $1, $2) RETURNING id"
+        );
+        assert_eq!(args.len(), 2);
+
+        // 没有操作人时只填充时间
+        let mut args = vec![json!("a")];
+        let sql = columns
+            .insert("INSERT INTO users (name) VALUES (lower(?))", &mut args)
+            .unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO users (created_at, name) VALUES (CURRENT_TIMESTAMP, lower(?))"
+        );
+
+        assert!(
+            columns
+                .insert("INSERT INTO users (name) VALUES (?), (?)", &mut vec![])
+                .is_none()
+        );
+        assert!(
+            columns
+                .insert("INSERT INTO users SELECT * FROM tmp", &mut vec![])
+                .is_none()
+        );
+        assert!(
+            columns
+                .insert("UPDATE users SET name = ?", &mut vec![])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let columns = AuditColumns::default();
+        let mut args = vec![json!("a"), json!(1)];
+        let sql = as_user(|| columns.update("UPDATE users SET name = ? WHERE id = ?", &mut args))
+            .unwrap();
+        assert_eq!(
+            sql,
+            "UPDATE users SET updated_at = CURRENT_TIMESTAMP, updated_by = ?, name = ? WHERE id = ?"
+        );
+        assert_eq!(args, vec![json!("alice"), json!("a"), json!(1)]);
+
+        let mut args = vec![json!("a"), json!(1)];
+        let sql = as_user(|| columns.update("UPDATE users SET name = $1 WHERE id = $2", &mut args))
+            .unwrap();
+        assert_eq!(
+            sql,
+            "UPDATE users SET updated_at = CURRENT_TIMESTAMP, updated_by = $3, name = $1 WHERE id = $2"
+        );
+        assert_eq!(args[2], json!("alice"));
+
+        // 条件中出现的审计列不视为已赋值
+        let sql = columns
+            .update(
+                "UPDATE users SET updated_at = ? WHERE updated_by = ?",
+                &mut vec![],
+            )
+            .unwrap();
+        assert_eq!(sql, "UPDATE users SET updated_at = ? WHERE updated_by = ?");
+        assert!(columns.update("DELETE FROM users", &mut vec![]).is_none());
+    }
+}
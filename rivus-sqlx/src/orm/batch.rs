@@ -0,0 +1,179 @@
+/// 单条 `INSERT ... VALUES (...)` 语句拆分结果，用于生成多行 VALUES
+#[derive(Debug)]
+pub(crate) struct MultiValues<'a> {
+    head: &'a str,
+    tuple: &'a str,
+    tail: &'a str,
+}
+
+impl<'a> MultiValues<'a> {
+    /// 解析 `VALUES` 后的单个元组；非该形式（如 `INSERT ... SELECT` 或已是多行）返回 `None`
+    pub(crate) fn parse(sql: &'a str) -> Option<Self> {
+        let kw_end = find_values_keyword(sql)?;
+        let rest = &sql[kw_end..];
+        let open = kw_end + (rest.len() - rest.trim_start().len());
+        if !sql[open..].starts_with('(') {
+            return None;
+        }
+        let close = find_closing_paren(sql, open)?;
+        let tail = &sql[close + 1..];
+        if tail.trim_start().starts_with(',') {
+            return None;
+        }
+        Some(Self {
+            head: &sql[..kw_end],
+            tuple: &sql[open..=close],
+            tail,
+        })
+    }
+
+    /// 生成 `rows` 行的 VALUES 语句；`$n` 占位符按每行 `cols` 个参数顺延
+    pub(crate) fn render(&self, rows: usize, cols: usize) -> String {
+        let mut sql = String::with_capacity(
+            self.head.len() + self.tail.len() + (self.tuple.len() + 2) * rows + 1,
+        );
+        sql.push_str(self.head);
+        sql.push(' ');
+        for r in 0..rows {
+            if r > 0 {
+                sql.push_str(", ");
+            }
+            push_shifted(&mut sql, self.tuple, r * cols);
+        }
+        sql.push_str(self.tail);
+        sql
+    }
+}
+
+/// 查找第一个位于括号与引号外的 `VALUES` 关键字（即列清单之后的那个），返回其结束位置
+///
+/// `ON DUPLICATE KEY UPDATE b = VALUES(b)` 等尾部中的 `VALUES` 不会被误认。
+fn find_values_keyword(sql: &str) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let mut quote: Option<u8> = None;
+    let mut depth = 0usize;
+    for (i, &c) in bytes.iter().enumerate() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                b'\'' | b'"' | b'`' => quote = Some(c),
+                b'(' => depth += 1,
+                b')' => depth = depth.saturating_sub(1),
+                _ if depth == 0
+                    && bytes.len() - i >= 6
+                    && bytes[i..i + 6].eq_ignore_ascii_case(b"values")
+                    && (i == 0 || !is_ident(bytes[i - 1]))
+                    && bytes.get(i + 6).is_none_or(|b| !is_ident(*b)) =>
+                {
+                    return Some(i + 6);
+                }
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+fn find_closing_paren(sql: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote: Option<u8> = None;
+    for (i, &c) in sql.as_bytes().iter().enumerate().skip(open) {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                b'\'' | b'"' | b'`' => quote = Some(c),
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+/// 复制元组，并将引号外的 `$n` 替换为 `$(n + shift)`
+fn push_shifted(out: &mut String, tuple: &str, shift: usize) {
+    let bytes = tuple.as_bytes();
+    let mut quote: Option<u8> = None;
+    let mut last = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if matches!(c, b'\'' | b'"' | b'`') => quote = Some(c),
+            None if c == b'$' && shift > 0 => {
+                let digits = bytes[i + 1..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                if digits > 0 {
+                    let n: usize = tuple[i + 1..i + 1 + digits].parse().unwrap_or(0);
+                    out.push_str(&tuple[last..i]);
+                    out.push('$');
+                    out.push_str(&(n + shift).to_string());
+                    i += 1 + digits;
+                    last = i;
+                    continue;
+                }
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    out.push_str(&tuple[last..]);
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_question_marks() {
+        let mv = MultiValues::parse("INSERT INTO t (a, b) VALUES (?, ?) RETURNING id").unwrap();
+        assert_eq!(
+            mv.render(3, 2),
+            "INSERT INTO t (a, b) VALUES (?, ?), (?, ?), (?, ?) RETURNING id"
+        );
+    }
+
+    #[test]
+    fn test_render_dollar_placeholders() {
+        let mv = MultiValues::parse("insert into t (a, b) values ($1, '$1' || $2)").unwrap();
+        assert_eq!(
+            mv.render(2, 2),
+            "insert into t (a, b) values ($1, '$1' || $2), ($3, '$1' || $4)"
+        );
+    }
+
+    #[test]
+    fn test_render_on_duplicate_key_update() {
+        let mv = MultiValues::parse(
+            "INSERT INTO t (a, b) VALUES (?, ?) ON DUPLICATE KEY UPDATE b = VALUES(b)",
+        )
+        .unwrap();
+        assert_eq!(
+            mv.render(2, 2),
+            "INSERT INTO t (a, b) VALUES (?, ?), (?, ?) ON DUPLICATE KEY UPDATE b = VALUES(b)"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_non_values() {
+        assert!(MultiValues::parse("INSERT INTO t (a) SELECT a FROM s").is_none());
+        assert!(MultiValues::parse("INSERT INTO t (a) VALUES (?), (?)").is_none());
+        assert!(MultiValues::parse("INSERT INTO t (values_x) SELECT 1").is_none());
+    }
+}
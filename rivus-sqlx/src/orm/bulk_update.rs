@@ -0,0 +1,126 @@
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use crate::orm::crud_traits::CrudRepository;
+use crate::orm::sqlx_impl::{self, SqlxRepository};
+use serde_json::Value;
+
+impl SqlxRepository {
+    /// 按主键批量更新多行，每行的值可以不同
+    ///
+    /// 每行依次为键值与 `columns` 对应的新值，生成
+    /// `UPDATE t SET c = CASE key WHEN ? THEN ? ... ELSE c END WHERE key IN (...)`，一次往返完成。
+    /// 参数个数超过驱动上限时分多条语句在同一事务中执行。键重复时以第一行为准。
+    /// 表名与列名直接拼接，不应来自外部输入。返回受影响的总行数。
+    pub async fn bulk_update(
+        &self,
+        cnn: &DbPool,
+        table: &str,
+        key: &str,
+        columns: &[&str],
+        rows: Vec<Vec<Value>>,
+    ) -> Result<u64, DbError> {
+        if columns.is_empty() {
+            return Err(DbError::from("bulk_update requires at least one column"));
+        }
+        if let Some(row) = rows.iter().find(|r| r.len() != columns.len() + 1) {
+            return Err(DbError::from(format!(
+                "bulk_update expects {} values per row (key first), got {}",
+                columns.len() + 1,
+                row.len()
+            )));
+        }
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let max_params = sqlx_impl::max_params(&cnn.inner)
+            .ok_or_else(|| DbError::from("Unsupported database type"))?;
+        let rows_per_chunk = (max_params / (columns.len() * 2 + 1)).max(1);
+        let mut chunks = Vec::with_capacity(rows.len().div_ceil(rows_per_chunk));
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            let chunk: Vec<Vec<Value>> = rows.by_ref().take(rows_per_chunk).collect();
+            let sql = bulk_update_sql(table, key, columns, chunk.len());
+            chunks.push((sql, bulk_update_args(chunk, columns.len())));
+        }
+
+        if chunks.len() == 1 {
+            let (sql, args) = chunks.pop().unwrap_or_default();
+            return self.update(cnn, &sql, args).await;
+        }
+        cnn.transaction(|tx| async move {
+            let mut affected = 0;
+            for (sql, args) in chunks {
+                affected += self.update(&tx, &sql, args).await?;
+            }
+            Ok(affected)
+        })
+        .await
+    }
+}
+
+fn bulk_update_sql(table: &str, key: &str, columns: &[&str], rows: usize) -> String {
+    let whens = vec!["WHEN ? THEN ?"; rows].join(" ");
+    let assignments = columns
+        .iter()
+        .map(|c| format!("{c} = CASE {key} {whens} ELSE {c} END"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let keys = vec!["?"; rows].join(", ");
+    format!(
+        "UPDATE {} SET {} WHERE {} IN ({})",
+        table, assignments, key, keys
+    )
+}
+
+/// 参数顺序与 [`bulk_update_sql`] 一致：逐列的 (键, 值) 对，最后是 IN 列表中的键
+fn bulk_update_args(rows: Vec<Vec<Value>>, columns: usize) -> Vec<Value> {
+    let mut args = Vec::with_capacity(rows.len() * (columns * 2 + 1));
+    for col in 1..=columns {
+        for row in &rows {
+            args.push(row[0].clone());
+            args.push(row[col].clone());
+        }
+    }
+    args.extend(
+        rows.into_iter()
+            .map(|row| row.into_iter().next().unwrap_or(Value::Null)),
+    );
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bulk_update_sql() {
+        assert_eq!(
+            bulk_update_sql("users", "id", &["name", "age"], 2),
+            "UPDATE users SET name = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE name END, \
+             age = CASE id WHEN ? THEN ? WHEN ? THEN ? ELSE age END WHERE id IN (?, ?)"
+        );
+        assert_eq!(
+            bulk_update_args(
+                vec![
+                    vec![json!(1), json!("a"), json!(10)],
+                    vec![json!(2), json!("b"), json!(20)]
+                ],
+                2
+            ),
+            vec![
+                json!(1),
+                json!("a"),
+                json!(2),
+                json!("b"),
+                json!(1),
+                json!(10),
+                json!(2),
+                json!(20),
+                json!(1),
+                json!(2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,354 @@
+use crate::db_pool::DbPool;
+use crate::orm::crud_traits::CrudRepository;
+use crate::tenant;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// 查询缓存策略
+#[derive(Debug, Clone)]
+pub struct CachePolicy {
+    pub ttl: Duration,
+    pub tags: Vec<String>,
+}
+
+impl CachePolicy {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+}
+
+/// 缓存后端，可替换为 Redis 等实现
+pub trait CacheBackend: Send + Sync {
+    fn get(&self, key: &str) -> impl Future<Output = Option<Value>> + Send;
+
+    fn put(
+        &self,
+        key: String,
+        value: Value,
+        policy: &CachePolicy,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// 移除带有该标签的所有条目
+    fn invalidate_tag(&self, tag: &str) -> impl Future<Output = ()> + Send;
+
+    fn clear(&self) -> impl Future<Output = ()> + Send;
+}
+
+struct MemoryEntry {
+    value: Value,
+    expires_at: Instant,
+    tags: Vec<String>,
+    tick: u64,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    entries: HashMap<String, MemoryEntry>,
+    // 访问顺序: tick -> key
+    order: BTreeMap<u64, String>,
+    tags: HashMap<String, HashSet<String>>,
+    tick: u64,
+}
+
+impl MemoryState {
+    fn remove(&mut self, key: &str) -> Option<MemoryEntry> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        for tag in &entry.tags {
+            if let Some(keys) = self.tags.get_mut(tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tags.remove(tag);
+                }
+            }
+        }
+        Some(entry)
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.tick);
+            entry.tick = tick;
+            self.order.insert(tick, key.to_string());
+        }
+    }
+}
+
+/// 内存 LRU 缓存
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    async fn get(&self, key: &str) -> Option<Value> {
+        let mut state = self.state.lock().unwrap();
+        let expired = state.entries.get(key)?.expires_at <= Instant::now();
+        if expired {
+            state.remove(key);
+            return None;
+        }
+        state.touch(key);
+        state.entries.get(key).map(|e| e.value.clone())
+    }
+
+    async fn put(&self, key: String, value: Value, policy: &CachePolicy) {
+        let mut state = self.state.lock().unwrap();
+        state.remove(&key);
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.order.pop_first() else { break };
+            state.remove(&oldest);
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        for tag in &policy.tags {
+            state
+                .tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        state.order.insert(tick, key.clone());
+        state.entries.insert(
+            key,
+            MemoryEntry {
+                value,
+                expires_at: Instant::now() + policy.ttl,
+                tags: policy.tags.clone(),
+                tick,
+            },
+        );
+    }
+
+    async fn invalidate_tag(&self, tag: &str) {
+        let mut state = self.state.lock().unwrap();
+        let keys: Vec<String> = state
+            .tags
+            .get(tag)
+            .map(|k| k.iter().cloned().collect())
+            .unwrap_or_default();
+        for key in keys {
+            state.remove(&key);
+        }
+    }
+
+    async fn clear(&self) {
+        *self.state.lock().unwrap() = MemoryState::default();
+    }
+}
+
+/// 带查询缓存的仓库
+///
+/// 仅对通过 [`CachedRepository::cache`] 注册过的 SQL 生效，缓存键为路由后的连接池名 + SQL + 参数；事务中不使用缓存。
+/// 写操作不会自动失效缓存，需调用 [`CachedRepository::invalidate_tag`]。
+pub struct CachedRepository<R, B = MemoryCache> {
+    inner: R,
+    backend: B,
+    policies: RwLock<HashMap<String, CachePolicy>>,
+}
+
+impl<R> CachedRepository<R, MemoryCache> {
+    pub fn new(inner: R) -> Self {
+        Self::with_backend(inner, MemoryCache::default())
+    }
+}
+
+impl<R, B: CacheBackend> CachedRepository<R, B> {
+    pub fn with_backend(inner: R, backend: B) -> Self {
+        Self {
+            inner,
+            backend,
+            policies: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 为指定 SQL 开启缓存
+    pub fn cache(self, sql: &str, policy: CachePolicy) -> Self {
+        self.policies
+            .write()
+            .unwrap()
+            .insert(sql.to_string(), policy);
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub async fn invalidate_tag(&self, tag: &str) {
+        self.backend.invalidate_tag(tag).await;
+    }
+
+    pub async fn invalidate_all(&self) {
+        self.backend.clear().await;
+    }
+
+    fn policy(&self, sql: &str) -> Option<CachePolicy> {
+        self.policies.read().unwrap().get(sql).cloned()
+    }
+}
+
+// 按路由后的连接池区分缓存，多租户时各租户互不命中；事务中可能读到未提交的数据，不使用缓存
+fn cache_key(cnn: &DbPool, sql: &str, args: &[Value]) -> Option<String> {
+    if cnn.in_transaction() {
+        return None;
+    }
+    let pool = tenant::route(cnn).ok()?;
+    Some(format!(
+        "{}\u{1f}{}\u{1f}{}",
+        pool.name,
+        sql,
+        Value::from(args.to_vec())
+    ))
+}
+
+impl<R, B> CrudRepository for CachedRepository<R, B>
+where
+    R: CrudRepository<Connection = DbPool, Args = Vec<Value>> + Sync,
+    R::Error: From<String> + Send,
+    B: CacheBackend,
+{
+    type Connection = R::Connection;
+    type Error = R::Error;
+    type Args = Vec<Value>;
+
+    async fn get<T>(
+        &self,
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<Option<T>, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let Some(policy) = self.policy(sql) else {
+            return self.inner.get(cnn, sql, args).await;
+        };
+        let Some(key) = cache_key(cnn, sql, &args) else {
+            return self.inner.get(cnn, sql, args).await;
+        };
+
+        let value = match self.backend.get(&key).await {
+            Some(v) => v,
+            None => {
+                let row: Option<Value> = self.inner.get(cnn, sql, args).await?;
+                let v = row.unwrap_or(Value::Null);
+                self.backend.put(key, v.clone(), &policy).await;
+                v
+            }
+        };
+        serde_json::from_value(value).map_err(|e| R::Error::from(format!("缓存反序列化错误 (Cache deserialization error): {}", e)))
+    }
+
+    async fn list<T>(
+        &self,
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<Vec<T>, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let Some(policy) = self.policy(sql) else {
+            return self.inner.list(cnn, sql, args).await;
+        };
+        let Some(key) = cache_key(cnn, sql, &args) else {
+            return self.inner.list(cnn, sql, args).await;
+        };
+
+        let value = match self.backend.get(&key).await {
+            Some(v) => v,
+            None => {
+                let rows: Vec<Value> = self.inner.list(cnn, sql, args).await?;
+                let v = Value::Array(rows);
+                self.backend.put(key, v.clone(), &policy).await;
+                v
+            }
+        };
+        serde_json::from_value(value).map_err(|e| R::Error::from(format!("缓存反序列化错误 (Cache deserialization error): {}", e)))
+    }
+
+    fn create<T>(
+        &self,
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> impl Future<Output = Result<T, Self::Error>> + Send
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.inner.create(cnn, sql, args)
+    }
+
+    fn batch_create<T>(
+        &self,
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Vec<Self::Args>,
+    ) -> impl Future<Output = Result<Vec<T>, Self::Error>> + Send
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.inner.batch_create(cnn, sql, args)
+    }
+
+    fn update(
+        &self,
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send {
+        self.inner.update(cnn, sql, args)
+    }
+
+    fn delete(
+        &self,
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send {
+        self.inner.delete(cnn, sql, args)
+    }
+}
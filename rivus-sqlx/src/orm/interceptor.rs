@@ -0,0 +1,121 @@
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use crate::metrics;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// 一次仓库调用的上下文，`before_query` 可改写其中的 SQL 与参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryContext {
+    /// 连接池名称
+    pub pool: String,
+    /// mapper 语句 id，见 [`metrics::with_statement_id`]
+    pub statement: Option<Arc<str>>,
+    pub sql: String,
+    /// `batch_create` 的每行参数为一个数组
+    pub args: Vec<Value>,
+}
+
+/// 查询拦截器，按注册顺序在每次仓库调用前后执行
+///
+/// 可用于改写 SQL（如追加租户条件）、记录审计事件或否决语句。
+pub trait Interceptor: Send + Sync {
+    /// 执行前调用；返回 `Err` 时否决执行，该错误返回给调用方并触发 `on_error`
+    fn before_query(&self, _ctx: &mut QueryContext) -> Result<(), DbError> {
+        Ok(())
+    }
+
+    /// 执行成功后调用，`rows` 为返回或影响的行数
+    fn after_query(&self, _ctx: &QueryContext, _rows: u64) {}
+
+    /// 执行失败或被否决时调用
+    fn on_error(&self, _ctx: &QueryContext, _error: &DbError) {}
+}
+
+type Chain = Arc<Vec<Arc<dyn Interceptor>>>;
+
+static INTERCEPTORS: LazyLock<RwLock<Chain>> = LazyLock::new(|| RwLock::new(Arc::new(Vec::new())));
+
+/// 注册全局拦截器，追加在已有拦截器之后
+pub fn add_interceptor(interceptor: Arc<dyn Interceptor>) {
+    let mut chain = INTERCEPTORS.write().unwrap();
+    let mut list = chain.as_ref().clone();
+    list.push(interceptor);
+    *chain = Arc::new(list);
+}
+
+/// 移除全部拦截器
+pub fn clear_interceptors() {
+    *INTERCEPTORS.write().unwrap() = Arc::new(Vec::new());
+}
+
+fn chain() -> Chain {
+    INTERCEPTORS.read().unwrap().clone()
+}
+
+/// 依次执行 `before_query`，返回改写后的上下文；未注册拦截器时返回 `None`
+pub(crate) fn before(
+    pool: &DbPool,
+    sql: &str,
+    args: &[Value],
+) -> Result<Option<Interception>, DbError> {
+    let chain = chain();
+    if chain.is_empty() {
+        return Ok(None);
+    }
+    let mut ctx = QueryContext {
+        pool: pool.name.clone(),
+        statement: metrics::current_statement_id(),
+        sql: sql.to_string(),
+        args: args.to_vec(),
+    };
+    for interceptor in chain.iter() {
+        if let Err(e) = interceptor.before_query(&mut ctx) {
+            chain.iter().for_each(|i| i.on_error(&ctx, &e));
+            return Err(e);
+        }
+    }
+    Ok(Some(Interception { chain, ctx }))
+}
+
+/// 已通过 `before_query` 的调用
+pub(crate) struct Interception {
+    chain: Chain,
+    pub(crate) ctx: QueryContext,
+}
+
+impl Interception {
+    pub(crate) fn succeeded(&self, rows: u64) {
+        self.chain
+            .iter()
+            .for_each(|i| i.after_query(&self.ctx, rows));
+    }
+
+    pub(crate) fn failed(&self, error: &DbError) {
+        self.chain.iter().for_each(|i| i.on_error(&self.ctx, error));
+    }
+}
+
+/// 在拦截器链中执行一次调用
+pub(crate) async fn intercept<R, F, Fut>(
+    pool: &DbPool,
+    sql: String,
+    args: Vec<Value>,
+    rows: impl FnOnce(&R) -> u64,
+    run: F,
+) -> Result<R, DbError>
+where
+    F: FnOnce(String, Vec<Value>) -> Fut,
+    Fut: Future<Output = Result<R, DbError>>,
+{
+    let Some(interception) = before(pool, &sql, &args)? else {
+        return run(sql, args).await;
+    };
+    let result = run(interception.ctx.sql.clone(), interception.ctx.args.clone()).await;
+    match &result {
+        Ok(r) => interception.succeeded(rows(r)),
+        Err(e) => interception.failed(e),
+    }
+    result
+}
@@ -0,0 +1,323 @@
+use crate::db_pool::{DbPool, DbPoolInner};
+use crate::driver::OtherPool;
+use crate::error::DbError;
+use crate::mapper_store::Mappers;
+use crate::orm::crud_traits::CrudRepository;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// SQL 匹配规则，比较前合并连续空白
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SqlMatcher {
+    Any,
+    Exact(String),
+    Contains(String),
+}
+
+impl SqlMatcher {
+    pub fn exact(sql: &str) -> Self {
+        SqlMatcher::Exact(normalize(sql))
+    }
+
+    pub fn contains(fragment: &str) -> Self {
+        SqlMatcher::Contains(normalize(fragment))
+    }
+
+    /// 按 mapper 中 `namespace.id` 的 SQL 精确匹配，仅适用于不含动态标签的语句
+    pub fn mapper(mappers: &Mappers, namespace: &str, id: &str) -> Option<Self> {
+        mappers.sql(namespace, id).map(Self::exact)
+    }
+
+    fn matches(&self, sql: &str) -> bool {
+        match self {
+            SqlMatcher::Any => true,
+            SqlMatcher::Exact(expected) => *expected == normalize(sql),
+            SqlMatcher::Contains(fragment) => normalize(sql).contains(fragment.as_str()),
+        }
+    }
+}
+
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 一次调用的记录
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCall {
+    pub sql: String,
+    /// `batch_create` 的每行参数记录为一个数组
+    pub args: Vec<Value>,
+}
+
+#[derive(Clone, Debug)]
+enum Response {
+    Rows(Vec<Value>),
+    Affected(u64),
+    Error(String),
+}
+
+#[derive(Debug)]
+struct Expectation {
+    matcher: SqlMatcher,
+    args: Option<Vec<Value>>,
+    times: Option<usize>,
+    response: Response,
+    calls: usize,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    expectations: Vec<Expectation>,
+    calls: Vec<MockCall>,
+}
+
+/// 按预设响应返回结果的 [`CrudRepository`]，用于不连接数据库的单元测试
+///
+/// ```ignore
+/// let mock = MockRepository::new();
+/// mock.when(SqlMatcher::contains("FROM users")).returns_rows(vec![json!({"id": 1, "name": "alice"})]);
+/// mock.when(SqlMatcher::contains("UPDATE users")).times(1).returns_affected(1);
+///
+/// let user: Option<User> = mock.get(&MockRepository::pool(), "SELECT * FROM users WHERE id = ?", vec![1.into()]).await?;
+/// mock.verify();
+/// ```
+///
+/// 按注册顺序取第一个匹配且未用完次数的预设；没有匹配时返回错误。克隆的实例共享预设与调用记录。
+#[derive(Clone, Debug, Default)]
+pub struct MockRepository {
+    state: Arc<Mutex<MockState>>,
+}
+
+/// 预设构造器，以 `returns_*` 结束并注册
+pub struct When<'a> {
+    mock: &'a MockRepository,
+    matcher: SqlMatcher,
+    args: Option<Vec<Value>>,
+    times: Option<usize>,
+}
+
+impl When<'_> {
+    /// 只匹配参数完全相同的调用
+    pub fn with_args(mut self, args: Vec<Value>) -> Self {
+        self.args = Some(args);
+        self
+    }
+
+    /// 最多匹配 `n` 次，[`MockRepository::verify`] 时要求恰好调用 `n` 次
+    pub fn times(mut self, n: usize) -> Self {
+        self.times = Some(n);
+        self
+    }
+
+    /// 查询返回的行，按目标类型反序列化；`get` 取第一行
+    pub fn returns_rows(self, rows: Vec<Value>) {
+        self.register(Response::Rows(rows))
+    }
+
+    /// 更新 / 删除返回的影响行数
+    pub fn returns_affected(self, n: u64) {
+        self.register(Response::Affected(n))
+    }
+
+    pub fn returns_error(self, message: &str) {
+        self.register(Response::Error(message.to_string()))
+    }
+
+    fn register(self, response: Response) {
+        self.mock.state().expectations.push(Expectation {
+            matcher: self.matcher,
+            args: self.args,
+            times: self.times,
+            response,
+            calls: 0,
+        });
+    }
+}
+
+impl MockRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 不连接数据库的占位连接池，作为 `CrudRepository` 方法的连接参数
+    pub fn pool() -> DbPool {
+        DbPool {
+            name: "mock".to_string(),
+            inner: DbPoolInner::Other(OtherPool::unsupported("mock")),
+            slow_query_threshold: None,
+            retry_policy: None,
+            query_timeout: None,
+            cache_statements: true,
+        }
+    }
+
+    pub fn when(&self, matcher: SqlMatcher) -> When<'_> {
+        When {
+            mock: self,
+            matcher,
+            args: None,
+            times: None,
+        }
+    }
+
+    /// 按调用顺序返回全部调用记录
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// 校验预设均被调用：指定 `times` 的需恰好调用该次数，其余至少调用一次
+    ///
+    /// # Panics
+    ///
+    /// 存在未满足的预设时 panic，列出全部未满足项。
+    pub fn verify(&self) {
+        let state = self.state();
+        let unmet: Vec<String> = state
+            .expectations
+            .iter()
+            .filter(|e| match e.times {
+                Some(n) => e.calls != n,
+                None => e.calls == 0,
+            })
+            .map(|e| format!("{:?} expected {} call(s), got {}", e.matcher, e.times.map_or("≥1".to_string(), |n| n.to_string()), e.calls))
+            .collect();
+        assert!(
+            unmet.is_empty(),
+            "MockRepository expectations not met:\n{}",
+            unmet.join("\n")
+        );
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        // 测试线程 panic 后仍可读取调用记录
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn respond(&self, sql: &str, args: Vec<Value>) -> Result<Response, DbError> {
+        let mut state = self.state();
+        let matched = state.expectations.iter_mut().find(|e| {
+            e.matcher.matches(sql)
+                && e.args.as_ref().is_none_or(|a| *a == args)
+                && e.times.is_none_or(|n| e.calls < n)
+        });
+        let response = match matched {
+            Some(e) => {
+                e.calls += 1;
+                Ok(e.response.clone())
+            }
+            None => Err(DbError::from(format!(
+                "MockRepository: no expectation matches SQL: {}",
+                normalize(sql)
+            ))),
+        };
+        state.calls.push(MockCall {
+            sql: sql.to_string(),
+            args,
+        });
+        match response? {
+            Response::Error(message) => Err(DbError::from(message)),
+            response => Ok(response),
+        }
+    }
+
+    fn rows<T: DeserializeOwned>(&self, sql: &str, args: Vec<Value>) -> Result<Vec<T>, DbError> {
+        match self.respond(sql, args)? {
+            Response::Rows(rows) => rows
+                .into_iter()
+                .map(|row| {
+                    serde_json::from_value(row).map_err(|e| DbError::Config(format!("反序列化错误 (Deserialization error): {}", e)))
+                })
+                .collect(),
+            _ => Err(DbError::from(format!(
+                "MockRepository: expectation for '{}' does not return rows",
+                normalize(sql)
+            ))),
+        }
+    }
+
+    fn affected(&self, sql: &str, args: Vec<Value>) -> Result<u64, DbError> {
+        match self.respond(sql, args)? {
+            Response::Affected(n) => Ok(n),
+            _ => Err(DbError::from(format!(
+                "MockRepository: expectation for '{}' does not return affected rows",
+                normalize(sql)
+            ))),
+        }
+    }
+}
+
+impl CrudRepository for MockRepository {
+    type Connection = DbPool;
+    type Error = DbError;
+    type Args = Vec<Value>;
+
+    async fn get<T>(
+        &self,
+        _cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<Option<T>, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+    {
+        Ok(self.rows(sql, args)?.into_iter().next())
+    }
+
+    async fn list<T>(
+        &self,
+        _cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<Vec<T>, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.rows(sql, args)
+    }
+
+    async fn create<T>(
+        &self,
+        _cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<T, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.rows(sql, args)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| DbError::Config("创建操作未返回行 (Create did not return a row)".into()))
+    }
+
+    async fn batch_create<T>(
+        &self,
+        _cnn: &Self::Connection,
+        sql: &str,
+        args: Vec<Self::Args>,
+    ) -> Result<Vec<T>, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.rows(sql, args.into_iter().map(Value::Array).collect())
+    }
+
+    async fn update(
+        &self,
+        _cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<u64, Self::Error> {
+        self.affected(sql, args)
+    }
+
+    async fn delete(
+        &self,
+        _cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<u64, Self::Error> {
+        self.affected(sql, args)
+    }
+}
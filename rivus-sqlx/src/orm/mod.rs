@@ -1,4 +1,18 @@
 pub mod crud_traits;
 pub mod sqlx_impl;
 pub mod other_impl;
-pub mod row_de;
\ No newline at end of file
+pub mod row_de;
+pub mod page;
+pub mod cache;
+pub mod placeholder;
+pub(crate) mod batch;
+pub mod query_log;
+pub mod soft_delete;
+pub mod upsert;
+pub mod bulk_update;
+pub mod query;
+pub mod procedure;
+pub mod mock;
+pub mod interceptor;
+pub mod audit;
+pub mod statement;
@@ -1,10 +1,15 @@
-use crate::db_pool::DbPool;
+use crate::db_pool::{DbConnection, DbPool, DbPoolInner, TRANSACTION_CONTEXT};
+use crate::driver::{DriverConnection, OtherPool};
 use crate::error::DbError;
 use crate::orm::crud_traits::CrudRepository;
+use crate::orm::query_log::QueryTimer;
+use crate::orm::row_de;
 use serde::de::DeserializeOwned;
-use serde_json::Value;
-use std::future::Future;
+use serde_json::{Map, Value};
 
+/// 经由外部驱动（[`crate::driver::DbDriver`]）执行的仓库
+///
+/// `SqlxRepository` 遇到外部驱动的连接池时同样转到这里执行，通常无需直接使用。
 pub struct OtherRepository;
 
 impl CrudRepository for OtherRepository {
@@ -12,81 +17,198 @@ impl CrudRepository for OtherRepository {
     type Error = DbError;
     type Args = Vec<Value>;
 
-    fn get<T>(
+    async fn get<T>(
         &self,
-        _cnn: &Self::Connection,
-        _sql: &str,
-        _args: Self::Args,
-    ) -> impl Future<Output = Result<Option<T>, Self::Error>> + Send
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<Option<T>, Self::Error>
     where
         T: DeserializeOwned + Send,
     {
-        async move {
-            Err(DbError::Config("Other repository not implemented".into()))
-        }
+        get(cnn, other_pool(cnn)?, sql, args).await
     }
 
-    fn list<T>(
+    async fn list<T>(
         &self,
-        _cnn: &Self::Connection,
-        _sql: &str,
-        _args: Self::Args,
-    ) -> impl Future<Output = Result<Vec<T>, Self::Error>> + Send
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<Vec<T>, Self::Error>
     where
         T: DeserializeOwned + Send,
     {
-        async move {
-            Err(DbError::Config("Other repository not implemented".into()))
-        }
+        list(cnn, other_pool(cnn)?, sql, args).await
     }
 
-    fn create<T>(
+    async fn create<T>(
         &self,
-        _cnn: &Self::Connection,
-        _sql: &str,
-        _args: Self::Args,
-    ) -> impl Future<Output = Result<T, Self::Error>> + Send
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<T, Self::Error>
     where
         T: DeserializeOwned + Send,
     {
-        async move {
-            Err(DbError::Config("Other repository not implemented".into()))
-        }
+        create(cnn, other_pool(cnn)?, sql, args).await
     }
 
-    fn batch_create<T>(
+    async fn batch_create<T>(
         &self,
-        _cnn: &Self::Connection,
-        _sql: &str,
-        _args: Vec<Self::Args>,
-    ) -> impl Future<Output = Result<Vec<T>, Self::Error>> + Send
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Vec<Self::Args>,
+    ) -> Result<Vec<T>, Self::Error>
     where
         T: DeserializeOwned + Send,
     {
-        async move {
-            Err(DbError::Config("Other repository not implemented".into()))
-        }
+        batch_create(cnn, other_pool(cnn)?, sql, args).await
     }
 
-    fn update(
+    async fn update(
         &self,
-        _cnn: &Self::Connection,
-        _sql: &str,
-        _args: Self::Args,
-    ) -> impl Future<Output = Result<u64, Self::Error>> + Send {
-        async move {
-            Err(DbError::Config("Other repository not implemented".into()))
-        }
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<u64, Self::Error> {
+        update(cnn, other_pool(cnn)?, sql, args).await
     }
 
-    fn delete(
+    async fn delete(
         &self,
-        _cnn: &Self::Connection,
-        _sql: &str,
-        _args: Self::Args,
-    ) -> impl Future<Output = Result<u64, Self::Error>> + Send {
-        async move {
-            Err(DbError::Config("Other repository not implemented".into()))
-        }
+        cnn: &Self::Connection,
+        sql: &str,
+        args: Self::Args,
+    ) -> Result<u64, Self::Error> {
+        update(cnn, other_pool(cnn)?, sql, args).await
     }
 }
+
+fn other_pool(cnn: &DbPool) -> Result<&OtherPool, DbError> {
+    match &cnn.inner {
+        DbPoolInner::Other(p) => Ok(p),
+        _ => Err(DbError::from(format!(
+            "Database '{}' does not use an external driver",
+            cnn.name
+        ))),
+    }
+}
+
+pub(crate) async fn get<T: DeserializeOwned>(
+    pool: &DbPool,
+    other: &OtherPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Option<T>, DbError> {
+    fetch(pool, other, sql, args)
+        .await?
+        .into_iter()
+        .next()
+        .map(from_row)
+        .transpose()
+}
+
+pub(crate) async fn list<T: DeserializeOwned>(
+    pool: &DbPool,
+    other: &OtherPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<T>, DbError> {
+    fetch(pool, other, sql, args)
+        .await?
+        .into_iter()
+        .map(from_row)
+        .collect()
+}
+
+pub(crate) async fn create<T: DeserializeOwned>(
+    pool: &DbPool,
+    other: &OtherPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<T, DbError> {
+    get(pool, other, sql, args)
+        .await?
+        .ok_or_else(|| DbError::Config("创建操作未返回行 (Create did not return a row)".into()))
+}
+
+/// 方言未知，逐行执行
+pub(crate) async fn batch_create<T: DeserializeOwned>(
+    pool: &DbPool,
+    other: &OtherPool,
+    sql: &str,
+    args: Vec<Vec<Value>>,
+) -> Result<Vec<T>, DbError> {
+    let mut results = Vec::with_capacity(args.len());
+    for arg in args {
+        results.push(create(pool, other, sql, arg).await?);
+    }
+    Ok(results)
+}
+
+pub(crate) async fn update(
+    pool: &DbPool,
+    other: &OtherPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<u64, DbError> {
+    let tx_conn = TRANSACTION_CONTEXT
+        .try_with(|map| map.borrow().get(&pool.name).cloned())
+        .ok()
+        .flatten();
+
+    let mut timer = QueryTimer::start(pool, sql, &args);
+    let affected = if let Some(conn_arc) = tx_conn {
+        let mut conn_guard = conn_arc.lock().await;
+        driver_connection(&mut conn_guard)?
+            .execute(sql, args)
+            .await?
+    } else {
+        other.acquire().await?.execute(sql, args).await?
+    };
+    timer.rows(affected);
+    Ok(affected)
+}
+
+async fn fetch(
+    pool: &DbPool,
+    other: &OtherPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<Map<String, Value>>, DbError> {
+    let tx_conn = TRANSACTION_CONTEXT
+        .try_with(|map| map.borrow().get(&pool.name).cloned())
+        .ok()
+        .flatten();
+
+    let mut timer = QueryTimer::start(pool, sql, &args);
+    let rows = if let Some(conn_arc) = tx_conn {
+        let mut conn_guard = conn_arc.lock().await;
+        driver_connection(&mut conn_guard)?.fetch(sql, args).await?
+    } else {
+        other.acquire().await?.fetch(sql, args).await?
+    };
+    timer.rows(rows.len() as u64);
+    Ok(rows)
+}
+
+fn driver_connection(conn: &mut DbConnection) -> Result<&mut Box<dyn DriverConnection>, DbError> {
+    match conn {
+        DbConnection::Other(c) => Ok(c),
+        _ => Err(DbError::Config(
+            "事务类型不匹配 (Transaction type mismatch)".into(),
+        )),
+    }
+}
+
+fn from_row<T: DeserializeOwned>(row: Map<String, Value>) -> Result<T, DbError> {
+    let row = match row_de::mapping_for::<T>() {
+        Some(mapping) => row
+            .into_iter()
+            .map(|(column, value)| (mapping.field_name(&column).into_owned(), value))
+            .collect(),
+        None => row,
+    };
+    serde_json::from_value(Value::Object(row))
+        .map_err(|e| DbError::Config(format!("反序列化错误 (Deserialization error): {}", e)))
+}
@@ -0,0 +1,76 @@
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use crate::orm::crud_traits::CrudRepository;
+use crate::orm::sqlx_impl::SqlxRepository;
+use rivus_core::page::{Page, PageRequest};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+impl SqlxRepository {
+    /// 执行单列的计数查询（如 `SELECT COUNT(*) FROM ...`）并返回结果
+    ///
+    /// 无结果行或值为 NULL 时返回 0；查询返回多列、或值不是非负整数时报错。
+    pub async fn count(&self, cnn: &DbPool, sql: &str, args: Vec<Value>) -> Result<u64, DbError> {
+        let Some(row) = self.get::<Map<String, Value>>(cnn, sql, args).await? else {
+            return Ok(0);
+        };
+        if row.len() != 1 {
+            return Err(DbError::from(format!(
+                "count expects a single column, got {}",
+                row.len()
+            )));
+        }
+        let value = row
+            .into_iter()
+            .next()
+            .map(|(_, v)| v)
+            .unwrap_or(Value::Null);
+        let count = match &value {
+            Value::Null => Some(0),
+            Value::Number(n) => n.as_u64(),
+            // DECIMAL / NUMERIC 以字符串返回
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        };
+        count.ok_or_else(|| DbError::from(format!("count expects a non-negative integer, got {}", value)))
+    }
+
+    /// 查询是否至少返回一行
+    ///
+    /// 原始 SQL 作为子查询并追加 `LIMIT 1`，不会读取全部结果。
+    pub async fn exists(&self, cnn: &DbPool, sql: &str, args: Vec<Value>) -> Result<bool, DbError> {
+        let sql = sql.trim().trim_end_matches(';');
+        let exists_sql = format!("SELECT 1 AS present FROM ({}) rivus_exists_t LIMIT 1", sql);
+        Ok(self.get::<Value>(cnn, &exists_sql, args).await?.is_some())
+    }
+
+    /// 分页查询
+    ///
+    /// 先以子查询统计总数，再追加 `LIMIT/OFFSET` 获取当前页。
+    /// 原始 SQL 不应包含 `LIMIT`，排序请写在 SQL 中。
+    pub async fn page<T>(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        req: PageRequest,
+    ) -> Result<Page<T>, DbError>
+    where
+        T: DeserializeOwned + Serialize + Send,
+    {
+        let sql = sql.trim().trim_end_matches(';');
+
+        let count_sql = format!("SELECT COUNT(*) AS total FROM ({}) rivus_page_t", sql);
+        let total = self.count(cnn, &count_sql, args.clone()).await?;
+
+        if total == 0 || req.offset() >= total {
+            return Ok(Page::new(total, Vec::new()));
+        }
+
+        // limit/offset 为数值，直接拼接，兼容各数据库的占位符风格
+        let page_sql = format!("{} LIMIT {} OFFSET {}", sql, req.limit(), req.offset());
+        let items = self.list::<T>(cnn, &page_sql, args).await?;
+        Ok(Page::new(total, items))
+    }
+}
@@ -0,0 +1,254 @@
+use std::borrow::Cow;
+
+/// 将 `?` 占位符转换为 Postgres 的 `$1, $2, ...`
+///
+/// 跳过引号与注释中的内容；`??` 转义为字面量 `?`（如 JSONB 的 `?` 运算符）。
+/// SQL 中已包含 `$n` 占位符时原样返回。
+pub fn to_numbered(sql: &str) -> Cow<'_, str> {
+    if !sql.contains('?') || has_numbered(sql) {
+        return Cow::Borrowed(sql);
+    }
+
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len() + 8);
+    let mut n = 0;
+    let mut last = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' | b'"' => i = skip_quoted(bytes, i),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = skip_line_comment(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b'?' if bytes.get(i + 1) == Some(&b'?') => {
+                out.push_str(&sql[last..=i]);
+                i += 2;
+                last = i;
+            }
+            b'?' => {
+                n += 1;
+                out.push_str(&sql[last..i]);
+                out.push('$');
+                out.push_str(&n.to_string());
+                i += 1;
+                last = i;
+            }
+            _ => i += 1,
+        }
+    }
+    out.push_str(&sql[last..]);
+    Cow::Owned(out)
+}
+
+/// 推断每个参数绑定的列名（按参数顺序），用于日志脱敏
+///
+/// 支持 `col = ?` 等比较形式与 `INSERT INTO t (a, b) VALUES (?, ?)`；无法推断时为 `None`。
+pub(crate) fn placeholder_columns(sql: &str) -> Vec<Option<String>> {
+    let insert = insert_columns(sql);
+    let bytes = sql.as_bytes();
+    let mut cols: Vec<Option<String>> = Vec::new();
+    let mut seq = 0;
+    let mut depth = 0usize;
+    let mut tuple_pos = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let (idx, end) = match bytes[i] {
+            b'\'' | b'"' => {
+                i = skip_quoted(bytes, i);
+                continue;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = skip_line_comment(bytes, i);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_block_comment(bytes, i);
+                continue;
+            }
+            b'(' => {
+                depth += 1;
+                if depth == 1 {
+                    tuple_pos = 0;
+                }
+                i += 1;
+                continue;
+            }
+            b')' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+                continue;
+            }
+            b',' => {
+                if depth == 1 {
+                    tuple_pos += 1;
+                }
+                i += 1;
+                continue;
+            }
+            b'?' if bytes.get(i + 1) == Some(&b'?') => {
+                i += 2;
+                continue;
+            }
+            b'?' => {
+                seq += 1;
+                (seq - 1, i + 1)
+            }
+            b'$' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                let end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| !b.is_ascii_digit())
+                    .map_or(bytes.len(), |p| i + 1 + p);
+                match sql[i + 1..end].parse::<usize>() {
+                    Ok(n) if n > 0 => (n - 1, end),
+                    _ => {
+                        i = end;
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        let col = match &insert {
+            Some((names, values_at)) if i >= *values_at && depth == 1 => names.get(tuple_pos).cloned(),
+            _ => compared_column(&sql[..i]),
+        };
+        if cols.len() <= idx {
+            cols.resize(idx + 1, None);
+        }
+        if cols[idx].is_none() {
+            cols[idx] = col;
+        }
+        i = end;
+    }
+    cols
+}
+
+/// `INSERT INTO t (a, b) VALUES ...` 的列名及 `VALUES` 位置
+fn insert_columns(sql: &str) -> Option<(Vec<String>, usize)> {
+    let upper = sql.to_ascii_uppercase();
+    if !upper.trim_start().starts_with("INSERT") {
+        return None;
+    }
+    let values_at = upper.find("VALUES")?;
+    let open = sql[..values_at].find('(')?;
+    let close = open + sql[open..values_at].find(')')?;
+    let names = sql[open + 1..close]
+        .split(',')
+        .map(|c| unquote(c.trim()).to_string())
+        .collect();
+    Some((names, values_at))
+}
+
+/// 占位符前 `col <op>` 中的列名
+fn compared_column(prefix: &str) -> Option<String> {
+    let mut s = prefix.trim_end();
+    if let Some(op) = ["<>", "!=", ">=", "<=", "=", ">", "<"]
+        .iter()
+        .find(|op| s.ends_with(*op))
+    {
+        s = &s[..s.len() - op.len()];
+    } else if s.len() >= 4 && s[s.len() - 4..].eq_ignore_ascii_case("like") {
+        s = s[..s.len() - 4].trim_end();
+        if s.len() >= 3 && s[s.len() - 3..].eq_ignore_ascii_case("not") {
+            s = &s[..s.len() - 3];
+        }
+    } else {
+        return None;
+    }
+
+    let s = s.trim_end();
+    let start = s
+        .rfind(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '`' | '"')))
+        .map_or(0, |p| p + 1);
+    let ident = unquote(s[start..].rsplit('.').next()?);
+    (!ident.is_empty() && !ident.starts_with(|c: char| c.is_ascii_digit()))
+        .then(|| ident.to_string())
+}
+
+fn unquote(ident: &str) -> &str {
+    ident.trim_matches(|c| matches!(c, '`' | '"' | '[' | ']'))
+}
+
+/// 引号与注释外是否存在 `$n`
+pub(crate) fn has_numbered(sql: &str) -> bool {
+    let bytes = sql.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' | b'"' => i = skip_quoted(bytes, i),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = skip_line_comment(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b'$' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => return true,
+            _ => i += 1,
+        }
+    }
+    false
+}
+
+/// 查找顶层（括号、引号与注释外）的关键字，返回其起始位置
+pub(crate) fn find_keyword(sql: &str, keyword: &str, from: usize) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let kw = keyword.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut depth = 0usize;
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' | b'"' | b'`' => i = skip_quoted(bytes, i),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = skip_line_comment(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b'(' => {
+                depth += 1;
+                i += 1;
+            }
+            b')' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            _ if depth == 0
+                && bytes[i..].len() >= kw.len()
+                && bytes[i..i + kw.len()].eq_ignore_ascii_case(kw)
+                && (i == 0 || !is_word(bytes[i - 1]))
+                && bytes.get(i + kw.len()).is_none_or(|&b| !is_word(b)) =>
+            {
+                return Some(i);
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn skip_quoted(bytes: &[u8], start: usize) -> usize {
+    let q = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == q {
+            // 连续两个引号为转义
+            if bytes.get(i + 1) == Some(&q) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+fn skip_line_comment(bytes: &[u8], start: usize) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |p| start + p + 1)
+}
+
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    bytes[start + 2..]
+        .windows(2)
+        .position(|w| w == b"*/")
+        .map_or(bytes.len(), |p| start + 2 + p + 2)
+}
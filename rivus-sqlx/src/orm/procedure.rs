@@ -0,0 +1,230 @@
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use crate::orm::crud_traits::CrudRepository;
+use crate::orm::query::Dialect;
+use crate::orm::sqlx_impl::SqlxRepository;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// MySQL 中承载 OUT 参数的会话变量前缀
+const VAR_PREFIX: &str = "@rivus_";
+
+/// 存储过程参数
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProcParam {
+    In(Value),
+    /// 输出参数，名称即结果中的字段名
+    Out(String),
+    InOut(String, Value),
+}
+
+impl ProcParam {
+    pub fn input(value: impl Into<Value>) -> Self {
+        ProcParam::In(value.into())
+    }
+
+    pub fn out(name: &str) -> Self {
+        ProcParam::Out(name.to_string())
+    }
+
+    pub fn inout(name: &str, value: impl Into<Value>) -> Self {
+        ProcParam::InOut(name.to_string(), value.into())
+    }
+}
+
+impl SqlxRepository {
+    /// 调用存储过程，返回 OUT / INOUT 参数组成的一行
+    ///
+    /// MySQL 通过会话变量取回输出参数（`SET @v = ?; CALL p(?, @v); SELECT @v`），
+    /// 三条语句在同一事务连接上执行，结果字段名为 [`ProcParam::Out`] 中的名称；
+    /// Postgres 执行 `CALL p($1, NULL)`，结果字段名为过程定义中的参数名。
+    /// 没有输出参数时返回 `None`。SQLite 不支持存储过程。
+    pub async fn call<T>(
+        &self,
+        cnn: &DbPool,
+        procedure: &str,
+        params: Vec<ProcParam>,
+    ) -> Result<Option<T>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let dialect = Dialect::of(cnn)?;
+        let plan = CallPlan::new(dialect, procedure, params)?;
+        let Some(outputs) = plan.outputs else {
+            // Postgres 的 CALL 直接返回输出参数
+            if dialect == Dialect::Postgres {
+                return self.get(cnn, &plan.sql, plan.args).await;
+            }
+            self.update(cnn, &plan.sql, plan.args).await?;
+            return Ok(None);
+        };
+        cnn.transaction(|tx| async move {
+            for (sql, value) in plan.setup {
+                self.update(&tx, &sql, vec![value]).await?;
+            }
+            self.update(&tx, &plan.sql, plan.args).await?;
+            self.get(&tx, &outputs, vec![]).await
+        })
+        .await
+    }
+
+    /// 调用标量函数 `SELECT func(?, ...)`，返回值为 NULL 或无结果时返回 `None`
+    pub async fn call_function<T>(
+        &self,
+        cnn: &DbPool,
+        function: &str,
+        args: Vec<Value>,
+    ) -> Result<Option<T>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        check_ident(function)?;
+        let placeholders = vec!["?"; args.len()].join(", ");
+        let sql = format!("SELECT {}({}) AS result", function, placeholders);
+        let Some(mut row) = self.get::<Map<String, Value>>(cnn, &sql, args).await? else {
+            return Ok(None);
+        };
+        match row.remove("result") {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| DbError::from(format!("Cannot decode result of {}: {}", function, e))),
+        }
+    }
+}
+
+/// 按方言拆分出的调用语句
+#[derive(Debug)]
+struct CallPlan {
+    /// MySQL 中为 INOUT 参数赋初值的语句
+    setup: Vec<(String, Value)>,
+    sql: String,
+    args: Vec<Value>,
+    /// MySQL 中读取输出参数的语句
+    outputs: Option<String>,
+}
+
+impl CallPlan {
+    fn new(dialect: Dialect, procedure: &str, params: Vec<ProcParam>) -> Result<Self, DbError> {
+        if dialect == Dialect::Sqlite {
+            return Err(DbError::from(
+                "Stored procedures are not supported by SQLite",
+            ));
+        }
+        check_ident(procedure)?;
+        let mut plan = CallPlan {
+            setup: Vec::new(),
+            sql: String::new(),
+            args: Vec::new(),
+            outputs: None,
+        };
+        let mut slots = Vec::with_capacity(params.len());
+        let mut outputs = Vec::new();
+
+        for param in params {
+            match (dialect, param) {
+                (_, ProcParam::In(value)) => {
+                    slots.push("?".to_string());
+                    plan.args.push(value);
+                }
+                (Dialect::Postgres, ProcParam::Out(name)) => {
+                    check_ident(&name)?;
+                    slots.push("NULL".to_string());
+                }
+                (Dialect::Postgres, ProcParam::InOut(name, value)) => {
+                    check_ident(&name)?;
+                    slots.push("?".to_string());
+                    plan.args.push(value);
+                }
+                (_, ProcParam::Out(name)) => {
+                    check_ident(&name)?;
+                    slots.push(format!("{}{}", VAR_PREFIX, name));
+                    outputs.push(name);
+                }
+                (_, ProcParam::InOut(name, value)) => {
+                    check_ident(&name)?;
+                    plan.setup
+                        .push((format!("SET {}{} = ?", VAR_PREFIX, name), value));
+                    slots.push(format!("{}{}", VAR_PREFIX, name));
+                    outputs.push(name);
+                }
+            }
+        }
+
+        plan.sql = format!("CALL {}({})", procedure, slots.join(", "));
+        if !outputs.is_empty() {
+            let columns = outputs
+                .iter()
+                .map(|n| format!("{}{n} AS {n}", VAR_PREFIX))
+                .collect::<Vec<_>>();
+            plan.outputs = Some(format!("SELECT {}", columns.join(", ")));
+        }
+        Ok(plan)
+    }
+}
+
+/// 过程名 / 参数名直接拼接进 SQL，只允许标识符字符（过程名可带 schema 前缀）
+fn check_ident(name: &str) -> Result<(), DbError> {
+    let valid = !name.is_empty()
+        && name.split('.').all(|part| {
+            !part.is_empty()
+                && !part.starts_with(|c: char| c.is_ascii_digit())
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(DbError::from(format!(
+            "Invalid procedure or parameter name '{}'",
+            name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params() -> Vec<ProcParam> {
+        vec![
+            ProcParam::input(1),
+            ProcParam::out("total"),
+            ProcParam::inout("counter", 5),
+        ]
+    }
+
+    #[test]
+    fn test_mysql_plan() {
+        let plan = CallPlan::new(Dialect::MySql, "shop.place_order", params()).unwrap();
+        assert_eq!(
+            plan.setup,
+            vec![("SET @rivus_counter = ?".to_string(), json!(5))]
+        );
+        assert_eq!(
+            plan.sql,
+            "CALL shop.place_order(?, @rivus_total, @rivus_counter)"
+        );
+        assert_eq!(plan.args, vec![json!(1)]);
+        assert_eq!(
+            plan.outputs.as_deref(),
+            Some("SELECT @rivus_total AS total, @rivus_counter AS counter")
+        );
+    }
+
+    #[test]
+    fn test_postgres_plan() {
+        let plan = CallPlan::new(Dialect::Postgres, "place_order", params()).unwrap();
+        assert!(plan.setup.is_empty());
+        assert_eq!(plan.sql, "CALL place_order(?, NULL, ?)");
+        assert_eq!(plan.args, vec![json!(1), json!(5)]);
+        assert_eq!(plan.outputs, None);
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        assert!(CallPlan::new(Dialect::MySql, "p; DROP TABLE users", vec![]).is_err());
+        assert!(CallPlan::new(Dialect::MySql, "p", vec![ProcParam::out("a b")]).is_err());
+        assert!(CallPlan::new(Dialect::Sqlite, "p", vec![]).is_err());
+    }
+}
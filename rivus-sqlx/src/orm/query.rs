@@ -0,0 +1,272 @@
+use crate::db_pool::{DbPool, DbPoolInner};
+use crate::error::DbError;
+use crate::orm::audit::AuditColumns;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// 单表查询构造器，适用于无需 XML mapper 的简单增删改查
+///
+/// ```ignore
+/// let users: Vec<User> = Query::table("users")
+///     .select(&["id", "name"])
+///     .filter("age > ?", 18)
+///     .order_by("id")
+///     .limit(10)
+///     .list(&pool)
+///     .await?;
+/// ```
+///
+/// 条件中的 `?` 按方言改写为对应占位符，参数经由驱动绑定；表名、列名直接拼接，不应来自外部输入。
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    table: String,
+    columns: Vec<String>,
+    filters: Vec<String>,
+    args: Vec<Value>,
+    order: Vec<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    audit: Option<AuditColumns>,
+}
+
+impl Query {
+    pub fn table(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// 查询的列，未指定时为 `*`
+    pub fn select(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// 追加一个带单个参数的条件，多个条件以 `AND` 连接
+    pub fn filter(self, cond: &str, arg: impl Into<Value>) -> Self {
+        self.filter_args(cond, vec![arg.into()])
+    }
+
+    /// 追加一个带任意个参数的条件
+    pub fn filter_args(mut self, cond: &str, args: Vec<Value>) -> Self {
+        self.filters.push(cond.to_string());
+        self.args.extend(args);
+        self
+    }
+
+    pub fn order_by(mut self, column: &str) -> Self {
+        self.order.push(column.to_string());
+        self
+    }
+
+    pub fn order_by_desc(mut self, column: &str) -> Self {
+        self.order.push(format!("{} DESC", column));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// 插入、更新时填充审计列，操作人见 [`crate::orm::audit::with_user`]
+    pub fn audit(mut self, columns: AuditColumns) -> Self {
+        self.audit = Some(columns);
+        self
+    }
+
+    /// 生成 SELECT 语句及参数
+    pub fn build(&self, cnn: &DbPool) -> Result<(String, Vec<Value>), DbError> {
+        Ok((self.select_sql(Dialect::of(cnn)?), self.args.clone()))
+    }
+
+    pub async fn list<T>(&self, cnn: &DbPool) -> Result<Vec<T>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let (sql, args) = self.build(cnn)?;
+        cnn.list(&sql, args).await
+    }
+
+    /// 获取第一行；未指定 `limit` 时追加 `LIMIT 1`
+    pub async fn get<T>(&self, cnn: &DbPool) -> Result<Option<T>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let query = Query {
+            limit: Some(self.limit.unwrap_or(1)),
+            ..self.clone()
+        };
+        let (sql, args) = query.build(cnn)?;
+        cnn.get(&sql, args).await
+    }
+
+    /// 插入一行，忽略条件、排序等设置，返回影响的行数
+    pub async fn insert(&self, cnn: &DbPool, values: Vec<(&str, Value)>) -> Result<u64, DbError> {
+        if values.is_empty() {
+            return Err(DbError::from("insert requires at least one column"));
+        }
+        let columns: Vec<&str> = values.iter().map(|(c, _)| *c).collect();
+        let placeholders = vec!["?"; columns.len()].join(", ");
+        let mut sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            self.table,
+            columns.join(", "),
+            placeholders
+        );
+        let mut args = values.into_iter().map(|(_, v)| v).collect();
+        if let Some(audit) = &self.audit {
+            sql = audit.insert(&sql, &mut args).unwrap_or(sql);
+        }
+        cnn.execute(&sql, args).await
+    }
+
+    /// 按条件更新，返回影响的行数；没有条件时拒绝执行
+    pub async fn update(&self, cnn: &DbPool, values: Vec<(&str, Value)>) -> Result<u64, DbError> {
+        if values.is_empty() {
+            return Err(DbError::from("update requires at least one column"));
+        }
+        let set = values
+            .iter()
+            .map(|(c, _)| format!("{} = ?", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut sql = format!(
+            "UPDATE {} SET {}{}",
+            self.table,
+            set,
+            self.guarded_where("update")?
+        );
+        let mut args: Vec<Value> = values.into_iter().map(|(_, v)| v).collect();
+        args.extend(self.args.iter().cloned());
+        if let Some(audit) = &self.audit {
+            sql = audit.update(&sql, &mut args).unwrap_or(sql);
+        }
+        cnn.update(&sql, args).await
+    }
+
+    /// 按条件删除，返回影响的行数；没有条件时拒绝执行
+    pub async fn delete(&self, cnn: &DbPool) -> Result<u64, DbError> {
+        let sql = format!(
+            "DELETE FROM {}{}",
+            self.table,
+            self.guarded_where("delete")?
+        );
+        cnn.delete(&sql, self.args.clone()).await
+    }
+
+    fn select_sql(&self, dialect: Dialect) -> String {
+        let columns = if self.columns.is_empty() {
+            "*".to_string()
+        } else {
+            self.columns.join(", ")
+        };
+        let mut sql = format!(
+            "SELECT {} FROM {}{}",
+            columns,
+            self.table,
+            self.where_clause()
+        );
+        if !self.order.is_empty() {
+            sql.push_str(&format!(" ORDER BY {}", self.order.join(", ")));
+        }
+        match (self.limit, self.offset) {
+            (Some(limit), _) => sql.push_str(&format!(" LIMIT {}", limit)),
+            // MySQL / SQLite 的 OFFSET 必须跟在 LIMIT 之后
+            (None, Some(_)) => match dialect {
+                Dialect::MySql => sql.push_str(" LIMIT 18446744073709551615"),
+                Dialect::Sqlite => sql.push_str(" LIMIT -1"),
+                Dialect::Postgres => {}
+            },
+            (None, None) => {}
+        }
+        if let Some(offset) = self.offset {
+            sql.push_str(&format!(" OFFSET {}", offset));
+        }
+        sql
+    }
+
+    fn where_clause(&self) -> String {
+        match self.filters.as_slice() {
+            [] => String::new(),
+            [cond] => format!(" WHERE {}", cond),
+            filters => {
+                let conds = filters
+                    .iter()
+                    .map(|c| format!("({})", c))
+                    .collect::<Vec<_>>()
+                    .join(" AND ");
+                format!(" WHERE {}", conds)
+            }
+        }
+    }
+
+    fn guarded_where(&self, op: &str) -> Result<String, DbError> {
+        if self.filters.is_empty() {
+            return Err(DbError::from(format!(
+                "refusing to {} all rows of '{}' without a filter",
+                op, self.table
+            )));
+        }
+        Ok(self.where_clause())
+    }
+}
+
+/// SQL 方言
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl Dialect {
+    pub(crate) fn of(cnn: &DbPool) -> Result<Self, DbError> {
+        match &cnn.inner {
+            DbPoolInner::MySql(_) => Ok(Dialect::MySql),
+            DbPoolInner::Postgres(_) => Ok(Dialect::Postgres),
+            DbPoolInner::Sqlite(_) => Ok(Dialect::Sqlite),
+            DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_sql() {
+        let query = Query::table("users")
+            .select(&["id", "name"])
+            .filter("age > ?", 18)
+            .filter_args("name = ? OR name = ?", vec!["a".into(), "b".into()])
+            .order_by("id")
+            .order_by_desc("age")
+            .limit(10);
+        assert_eq!(
+            query.select_sql(Dialect::Postgres),
+            "SELECT id, name FROM users WHERE (age > ?) AND (name = ? OR name = ?) ORDER BY id, age DESC LIMIT 10"
+        );
+
+        let query = Query::table("users").filter("id = ?", 1).offset(20);
+        assert_eq!(
+            query.select_sql(Dialect::Postgres),
+            "SELECT * FROM users WHERE id = ? OFFSET 20"
+        );
+        assert_eq!(
+            query.select_sql(Dialect::Sqlite),
+            "SELECT * FROM users WHERE id = ? LIMIT -1 OFFSET 20"
+        );
+        assert_eq!(
+            query.select_sql(Dialect::MySql),
+            "SELECT * FROM users WHERE id = ? LIMIT 18446744073709551615 OFFSET 20"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use crate::db_pool::DbPool;
+use crate::metrics::{self, QueryEvent};
+use crate::orm::placeholder::placeholder_columns;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// 参数摘要最多展示的个数
+const MAX_SUMMARY_ARGS: usize = 10;
+/// 单个参数摘要的最大字符数
+const MAX_SUMMARY_LEN: usize = 64;
+/// 脱敏后的占位内容
+const REDACTED: &str = "***";
+
+/// 语句日志开关（debug 级别，target 为 `rivus_sqlx::statement`）
+static STATEMENT_LOG: AtomicBool = AtomicBool::new(false);
+
+/// 需要脱敏的列名（小写）
+static REDACTED_COLUMNS: LazyLock<RwLock<HashSet<String>>> =
+    LazyLock::new(|| RwLock::new(HashSet::from(["password".to_string()])));
+
+/// 运行时开启或关闭语句日志
+pub fn set_statement_log(enabled: bool) {
+    STATEMENT_LOG.store(enabled, Ordering::Relaxed);
+}
+
+pub fn statement_log_enabled() -> bool {
+    STATEMENT_LOG.load(Ordering::Relaxed)
+}
+
+/// 替换脱敏列名集合（忽略大小写），默认包含 `password`
+pub fn set_redacted_columns<I, S>(columns: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    *REDACTED_COLUMNS.write().unwrap() = columns
+        .into_iter()
+        .map(|c| c.as_ref().to_ascii_lowercase())
+        .collect();
+}
+
+/// 追加脱敏列名
+pub fn add_redacted_column(column: &str) {
+    REDACTED_COLUMNS
+        .write()
+        .unwrap()
+        .insert(column.to_ascii_lowercase());
+}
+
+/// 语句计时器，释放时上报指标并输出语句日志与慢查询日志
+///
+/// 出错提前返回时同样计时，此时行数记为未知并按失败统计。
+pub(crate) struct QueryTimer<'a> {
+    pool_name: &'a str,
+    sql: &'a str,
+    statement: Option<Arc<str>>,
+    threshold: Option<Duration>,
+    statement_log: bool,
+    /// 仅在需要输出日志时保留参数副本
+    args: Option<Vec<Value>>,
+    rows: Option<u64>,
+    start: Instant,
+}
+
+impl<'a> QueryTimer<'a> {
+    pub(crate) fn start(pool: &'a DbPool, sql: &'a str, args: &[Value]) -> Self {
+        let threshold = pool.slow_query_threshold;
+        let statement_log = statement_log_enabled()
+            && tracing::enabled!(target: "rivus_sqlx::statement", tracing::Level::DEBUG);
+        Self {
+            pool_name: &pool.name,
+            sql,
+            statement: metrics::current_statement_id(),
+            threshold,
+            statement_log,
+            args: (threshold.is_some() || statement_log).then(|| args.to_vec()),
+            rows: None,
+            start: Instant::now(),
+        }
+    }
+
+    /// 记录返回或影响的行数，同时标记执行成功
+    pub(crate) fn rows(&mut self, rows: u64) {
+        self.rows = Some(rows);
+    }
+}
+
+impl Drop for QueryTimer<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        let statement = self.statement.as_deref();
+        metrics::record_query(&QueryEvent {
+            pool: self.pool_name,
+            statement,
+            elapsed,
+            rows: self.rows,
+            success: self.rows.is_some(),
+        });
+
+        let slow = self.threshold.is_some_and(|t| elapsed >= t);
+        if !slow && !self.statement_log {
+            return;
+        }
+
+        let sql = self.sql.trim();
+        let params = summarize_args(sql, self.args.as_deref().unwrap_or_default());
+        let rows = self.rows.map_or_else(|| "-".to_string(), |r| r.to_string());
+        if self.statement_log {
+            tracing::debug!(
+                target: "rivus_sqlx::statement",
+                pool = self.pool_name,
+                statement,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "{} params: {} rows: {} ({:?})",
+                sql,
+                params,
+                rows,
+                elapsed,
+            );
+        }
+        if slow {
+            tracing::warn!(
+                pool = self.pool_name,
+                statement,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "slow query ({:?}): {} params: {}",
+                elapsed,
+                sql,
+                params,
+            );
+        }
+    }
+}
+
+/// 生成参数摘要：脱敏列的值替换为 `***`，过长的值和多余的参数被截断
+pub(crate) fn summarize_args(sql: &str, args: &[Value]) -> String {
+    if args.is_empty() {
+        return "[]".to_string();
+    }
+
+    let columns = placeholder_columns(sql);
+    let redacted = REDACTED_COLUMNS.read().unwrap();
+    let mut parts: Vec<String> = args
+        .iter()
+        .take(MAX_SUMMARY_ARGS)
+        .enumerate()
+        .map(|(i, v)| {
+            let is_redacted = columns
+                .get(i)
+                .and_then(Option::as_deref)
+                .is_some_and(|c| redacted.contains(&c.to_ascii_lowercase()));
+            if is_redacted {
+                return REDACTED.to_string();
+            }
+            let s = v.to_string();
+            if s.chars().count() > MAX_SUMMARY_LEN {
+                let head: String = s.chars().take(MAX_SUMMARY_LEN).collect();
+                format!("{}...", head)
+            } else {
+                s
+            }
+        })
+        .collect();
+    if args.len() > MAX_SUMMARY_ARGS {
+        parts.push(format!("...(+{})", args.len() - MAX_SUMMARY_ARGS));
+    }
+    format!("[{}]", parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_args() {
+        assert_eq!(summarize_args("SELECT 1", &[]), "[]");
+        assert_eq!(
+            summarize_args(
+                "SELECT * FROM t WHERE a = ? AND b = ? AND c IS ?",
+                &[Value::from(1), Value::from("a"), Value::Null]
+            ),
+            r#"[1, "a", null]"#
+        );
+
+        let long = "x".repeat(100);
+        let summary = summarize_args("SELECT ?", &[Value::from(long)]);
+        assert_eq!(
+            summary,
+            format!("[\"{}...]", "x".repeat(MAX_SUMMARY_LEN - 1))
+        );
+
+        let many: Vec<Value> = (0..12).map(Value::from).collect();
+        assert_eq!(
+            summarize_args("SELECT ?", &many),
+            "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, ...(+2)]"
+        );
+    }
+
+    #[test]
+    fn test_summarize_redacts_password() {
+        let args = [Value::from("tom"), Value::from("secret")];
+        assert_eq!(
+            summarize_args(
+                "SELECT * FROM users WHERE name = ? AND u.`Password` = ?",
+                &args
+            ),
+            r#"["tom", ***]"#
+        );
+        assert_eq!(
+            summarize_args(
+                "INSERT INTO users (name, password) VALUES (?, ?), (?, ?)",
+                &[args.clone(), args.clone()].concat()
+            ),
+            r#"["tom", ***, "tom", ***]"#
+        );
+        assert_eq!(
+            summarize_args("UPDATE users SET password = $2 WHERE name = $1", &args),
+            r#"["tom", ***]"#
+        );
+        // 字符串字面量中的 ? 不计入
+        assert_eq!(
+            summarize_args(
+                "SELECT '?' FROM users WHERE name LIKE ? OR password <> ?",
+                &args
+            ),
+            r#"["tom", ***]"#
+        );
+    }
+}
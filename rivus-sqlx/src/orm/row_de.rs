@@ -1,7 +1,14 @@
+use crate::error::DbError;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use serde::de::{self, DeserializeSeed, MapAccess, Visitor};
 use serde::forward_to_deserialize_any;
 use sqlx::{Column, Row, TypeInfo, ValueRef};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::{Arc, LazyLock, RwLock};
 use sqlx::mysql::MySqlRow;
 use sqlx::postgres::PgRow;
 use sqlx::sqlite::SqliteRow;
@@ -18,10 +25,28 @@ pub trait RowReader {
     fn get_f64(&self, idx: usize) -> Result<f64, String>;
     fn get_string(&self, idx: usize) -> Result<String, String>;
     fn get_json(&self, idx: usize) -> Result<serde_json::Value, String>;
+    fn get_bytes(&self, idx: usize) -> Result<Vec<u8>, String>;
+    fn get_uuid(&self, idx: usize) -> Result<String, String>;
+    fn get_date(&self, idx: usize) -> Result<NaiveDate, String>;
+    fn get_time(&self, idx: usize) -> Result<NaiveTime, String>;
+    fn get_datetime(&self, idx: usize) -> Result<NaiveDateTime, String>;
+    fn get_datetime_utc(&self, idx: usize) -> Result<DateTime<Utc>, String>;
+
+    /// 读取定点数；默认按文本或数值解析，支持原生 DECIMAL 的驱动应覆盖
+    fn get_decimal(&self, idx: usize) -> Result<Decimal, String> {
+        if let Ok(s) = self.get_string(idx) {
+            return s.trim().parse::<Decimal>().map_err(|e| e.to_string());
+        }
+        if let Ok(v) = self.get_i64(idx) {
+            return Ok(Decimal::from(v));
+        }
+        let v = self.get_f64(idx)?;
+        Decimal::try_from(v).map_err(|e| e.to_string())
+    }
 }
 
 macro_rules! impl_row_reader {
-    ($row_type:ty) => {
+    ($row_type:ty $(, $extra:item)*) => {
         impl RowReader for $row_type {
             fn column_count(&self) -> usize {
                 self.columns().len()
@@ -33,7 +58,18 @@ macro_rules! impl_row_reader {
                 self.try_get_raw(idx).map(|r| r.is_null()).unwrap_or(true)
             }
             fn type_name(&self, idx: usize) -> &str {
-                self.column(idx).type_info().name()
+                let name = self.column(idx).type_info().name();
+                if name != "NULL" {
+                    return name;
+                }
+                // SQLite 的表达式列（如 COUNT(*)）没有声明类型，按值的实际类型处理
+                match self.try_get_raw(idx).map(|v| v.type_info().name().to_string()).as_deref() {
+                    Ok("INTEGER") => "INTEGER",
+                    Ok("REAL") => "REAL",
+                    Ok("TEXT") => "TEXT",
+                    Ok("BLOB") => "BLOB",
+                    _ => name,
+                }
             }
             fn get_bool(&self, idx: usize) -> Result<bool, String> {
                 self.try_get::<bool, _>(idx).map_err(|e| e.to_string())
@@ -62,18 +98,177 @@ macro_rules! impl_row_reader {
             fn get_json(&self, idx: usize) -> Result<serde_json::Value, String> {
                 self.try_get::<serde_json::Value, _>(idx).map_err(|e| e.to_string())
             }
+            fn get_uuid(&self, idx: usize) -> Result<String, String> {
+                // 原生 UUID / 16 字节二进制
+                if let Ok(v) = self.try_get::<sqlx::types::Uuid, _>(idx) {
+                    return Ok(v.hyphenated().to_string());
+                }
+                // 文本存储的 UUID，统一为小写带连字符格式
+                let s = self.try_get::<String, _>(idx).map_err(|e| e.to_string())?;
+                sqlx::types::Uuid::parse_str(&s)
+                    .map(|v| v.hyphenated().to_string())
+                    .map_err(|e| format!("Invalid UUID in column {}: {}", self.column(idx).name(), e))
+            }
+            fn get_bytes(&self, idx: usize) -> Result<Vec<u8>, String> {
+                self.try_get::<Vec<u8>, _>(idx).map_err(|e| e.to_string())
+            }
+            fn get_date(&self, idx: usize) -> Result<NaiveDate, String> {
+                self.try_get::<NaiveDate, _>(idx).map_err(|e| e.to_string())
+            }
+            fn get_time(&self, idx: usize) -> Result<NaiveTime, String> {
+                self.try_get::<NaiveTime, _>(idx).map_err(|e| e.to_string())
+            }
+            fn get_datetime(&self, idx: usize) -> Result<NaiveDateTime, String> {
+                self.try_get::<NaiveDateTime, _>(idx).map_err(|e| e.to_string())
+            }
+            fn get_datetime_utc(&self, idx: usize) -> Result<DateTime<Utc>, String> {
+                self.try_get::<DateTime<Utc>, _>(idx).map_err(|e| e.to_string())
+            }
+            $($extra)*
         }
     };
 }
 
-impl_row_reader!(MySqlRow);
-impl_row_reader!(PgRow);
+impl_row_reader!(
+    MySqlRow,
+    fn get_decimal(&self, idx: usize) -> Result<Decimal, String> {
+        self.try_get::<Decimal, _>(idx).map_err(|e| e.to_string())
+    }
+);
+impl_row_reader!(
+    PgRow,
+    fn get_decimal(&self, idx: usize) -> Result<Decimal, String> {
+        self.try_get::<Decimal, _>(idx).map_err(|e| e.to_string())
+    }
+);
+// SQLite 无原生定点数类型，使用默认的文本 / 数值解析
 impl_row_reader!(SqliteRow);
 
+/// 列名到字段名的转换规则
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NamingRule {
+    /// 列名即字段名
+    #[default]
+    AsIs,
+    /// `user_name` 列对应 `userName` 字段
+    CamelCase,
+    /// `userName` 列对应 `user_name` 字段
+    SnakeCase,
+}
+
+impl NamingRule {
+    pub fn apply<'a>(&self, column: &'a str) -> Cow<'a, str> {
+        match self {
+            NamingRule::AsIs => Cow::Borrowed(column),
+            NamingRule::CamelCase if column.contains('_') => {
+                let mut out = String::with_capacity(column.len());
+                for (i, part) in column.split('_').filter(|p| !p.is_empty()).enumerate() {
+                    let mut chars = part.chars();
+                    match chars.next() {
+                        Some(c) if i > 0 => out.extend(c.to_uppercase().chain(chars)),
+                        Some(c) => out.extend(std::iter::once(c).chain(chars)),
+                        None => {}
+                    }
+                }
+                Cow::Owned(out)
+            }
+            NamingRule::SnakeCase if column.chars().any(char::is_uppercase) => {
+                // 连续大写（如 `userID`）视为一个词
+                let mut out = String::with_capacity(column.len() + 4);
+                let mut prev_lower = false;
+                for c in column.chars() {
+                    if c.is_uppercase() {
+                        if prev_lower {
+                            out.push('_');
+                        }
+                        out.extend(c.to_lowercase());
+                    } else {
+                        out.push(c);
+                    }
+                    prev_lower = c.is_lowercase() || c.is_ascii_digit();
+                }
+                Cow::Owned(out)
+            }
+            _ => Cow::Borrowed(column),
+        }
+    }
+}
+
+/// 结果集列名到结构体字段名的映射：转换规则加显式别名，别名优先
+///
+/// ```ignore
+/// row_de::set_column_mapping(ColumnMapping::new(NamingRule::CamelCase));
+/// row_de::set_entity_mapping::<User>(ColumnMapping::default().alias("usr_nm", "name"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ColumnMapping {
+    naming: NamingRule,
+    aliases: HashMap<String, String>,
+}
+
+impl ColumnMapping {
+    pub fn new(naming: NamingRule) -> Self {
+        Self {
+            naming,
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// 将列 `column` 映射到字段 `field`
+    pub fn alias(mut self, column: &str, field: &str) -> Self {
+        self.aliases.insert(column.to_string(), field.to_string());
+        self
+    }
+
+    pub fn field_name<'a>(&'a self, column: &'a str) -> Cow<'a, str> {
+        match self.aliases.get(column) {
+            Some(field) => Cow::Borrowed(field),
+            None => self.naming.apply(column),
+        }
+    }
+}
+
+static DEFAULT_MAPPING: LazyLock<RwLock<Option<Arc<ColumnMapping>>>> =
+    LazyLock::new(|| RwLock::new(None));
+static ENTITY_MAPPINGS: LazyLock<RwLock<HashMap<&'static str, Arc<ColumnMapping>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 设置全局列名映射，未单独设置映射的实体均使用该映射
+pub fn set_column_mapping(mapping: ColumnMapping) {
+    *DEFAULT_MAPPING.write().unwrap() = Some(Arc::new(mapping));
+}
+
+/// 设置实体 `T` 的列名映射，替换全局映射（不合并）
+pub fn set_entity_mapping<T>(mapping: ColumnMapping) {
+    ENTITY_MAPPINGS
+        .write()
+        .unwrap()
+        .insert(std::any::type_name::<T>(), Arc::new(mapping));
+}
+
+/// 移除全局与所有实体的列名映射
+pub fn clear_column_mappings() {
+    *DEFAULT_MAPPING.write().unwrap() = None;
+    ENTITY_MAPPINGS.write().unwrap().clear();
+}
+
+/// 实体 `T` 生效的列名映射，未设置时返回 `None`
+pub(crate) fn mapping_for<T>() -> Option<Arc<ColumnMapping>> {
+    if let Some(mapping) = ENTITY_MAPPINGS
+        .read()
+        .unwrap()
+        .get(std::any::type_name::<T>())
+    {
+        return Some(mapping.clone());
+    }
+    DEFAULT_MAPPING.read().unwrap().clone()
+}
+
 pub struct RowDeserializer<'a, R: RowReader> {
     row: &'a R,
     col_idx: usize,
     count: usize,
+    mapping: Option<Arc<ColumnMapping>>,
     _marker: PhantomData<R>,
 }
 
@@ -83,9 +278,24 @@ impl<'a, R: RowReader> RowDeserializer<'a, R> {
             row,
             col_idx: 0,
             count: row.column_count(),
+            mapping: None,
             _marker: PhantomData,
         }
     }
+
+    /// 按映射将列名转换为字段名
+    pub fn mapping(mut self, mapping: Arc<ColumnMapping>) -> Self {
+        self.mapping = Some(mapping);
+        self
+    }
+
+    /// 使用实体 `T` 生效的列名映射
+    pub(crate) fn for_entity<T>(row: &'a R) -> Self {
+        Self {
+            mapping: mapping_for::<T>(),
+            ..Self::new(row)
+        }
+    }
 }
 
 impl<'de, 'a, R: RowReader> de::Deserializer<'de> for RowDeserializer<'a, R> {
@@ -116,7 +326,11 @@ impl<'de, 'a, R: RowReader> MapAccess<'de> for RowDeserializer<'a, R> {
             return Ok(None);
         }
         let col_name = self.row.column_name(self.col_idx);
-        let name_de = de::IntoDeserializer::into_deserializer(col_name);
+        let field = match &self.mapping {
+            Some(mapping) => mapping.field_name(col_name),
+            None => Cow::Borrowed(col_name),
+        };
+        let name_de = de::IntoDeserializer::into_deserializer(&*field);
         seed.deserialize(name_de).map(Some)
     }
 
@@ -138,6 +352,24 @@ struct ColValueDeserializer<'a, R: RowReader> {
     col_idx: usize,
 }
 
+/// MySQL BLOB / BINARY 系列、SQLite BLOB 与 Postgres BYTEA
+fn is_binary_type(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BINARY" | "VARBINARY" | "BYTEA"
+    )
+}
+
+impl<R: RowReader> ColValueDeserializer<'_, R> {
+    /// DECIMAL / NUMERIC 列的值，其他类型返回 `None`
+    fn decimal_column(&self) -> Option<Decimal> {
+        match self.row.type_name(self.col_idx) {
+            "DECIMAL" | "NUMERIC" => self.row.get_decimal(self.col_idx).ok(),
+            _ => None,
+        }
+    }
+}
+
 impl<'de, 'a, R: RowReader> de::Deserializer<'de> for ColValueDeserializer<'a, R> {
     type Error = de::value::Error;
 
@@ -168,16 +400,53 @@ impl<'de, 'a, R: RowReader> de::Deserializer<'de> for ColValueDeserializer<'a, R
                 let v = self.row.get_string(self.col_idx).map_err(de::Error::custom)?;
                 visitor.visit_string(v)
             }
+            // 以文本交给 visitor，Decimal 字段无损解析，String 字段保留原始精度
+            "DECIMAL" | "NUMERIC" => {
+                let v = self
+                    .row
+                    .get_decimal(self.col_idx)
+                    .map_err(de::Error::custom)?;
+                visitor.visit_string(v.to_string())
+            }
+            "UUID" => {
+                let v = self.row.get_uuid(self.col_idx).map_err(de::Error::custom)?;
+                visitor.visit_string(v)
+            }
             "JSON" | "JSONB" => {
                  let v = self.row.get_json(self.col_idx).map_err(de::Error::custom)?;
                  v.deserialize_any(visitor).map_err(de::Error::custom)
             }
-            "DATETIME" | "TIMESTAMP" | "TIMESTAMPTZ" => {
-                 let v = self.row.get_string(self.col_idx).map_err(de::Error::custom)?;
-                 visitor.visit_string(v)
+            // 日期时间按 chrono 的 serde 格式输出，可直接反序列化为 chrono 类型；
+            // String 字段走 deserialize_string，保持数据库的默认格式
+            "DATE" => {
+                let v = self.row.get_date(self.col_idx).map_err(de::Error::custom)?;
+                visitor.visit_string(v.format("%Y-%m-%d").to_string())
             }
-            _ if type_name == "BLOB" => {
-                 visitor.visit_unit()
+            "TIME" => {
+                let v = self.row.get_time(self.col_idx).map_err(de::Error::custom)?;
+                visitor.visit_string(v.format("%H:%M:%S%.f").to_string())
+            }
+            "DATETIME" | "TIMESTAMP" => {
+                let v = self
+                    .row
+                    .get_datetime(self.col_idx)
+                    .map_err(de::Error::custom)?;
+                visitor.visit_string(v.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+            }
+            "TIMESTAMPTZ" => {
+                let v = self
+                    .row
+                    .get_datetime_utc(self.col_idx)
+                    .map_err(de::Error::custom)?;
+                visitor.visit_string(v.to_rfc3339())
+            }
+            // 按 u8 序列交给 visitor，`Vec<u8>` 与 `serde_json::Value` 均可接收
+            _ if is_binary_type(type_name) => {
+                let v = self
+                    .row
+                    .get_bytes(self.col_idx)
+                    .map_err(de::Error::custom)?;
+                visitor.visit_seq(de::value::SeqDeserializer::new(v.into_iter()))
             }
             _ => {
                 // Fallback attempts
@@ -233,6 +502,8 @@ impl<'de, 'a, R: RowReader> de::Deserializer<'de> for ColValueDeserializer<'a, R
     {
         if let Ok(v) = self.row.get_f64(self.col_idx) {
             visitor.visit_f64(v)
+        } else if let Some(v) = self.decimal_column().and_then(|d| d.to_f64()) {
+            visitor.visit_f64(v)
         } else {
              self.deserialize_any(visitor)
         }
@@ -249,9 +520,180 @@ impl<'de, 'a, R: RowReader> de::Deserializer<'de> for ColValueDeserializer<'a, R
         }
     }
 
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let Ok(v) = self.row.get_bytes(self.col_idx) {
+            visitor.visit_byte_buf(v)
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    /// 整数列按变体序号、字符串列按变体名映射为单元变体
+    ///
+    /// 序号即声明顺序（从 0 开始）；需要按判别值映射时使用 `serde_repr`。
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.row.is_null(self.col_idx) {
+            return Err(de::Error::custom(format!(
+                "NULL in column {} for enum {}",
+                self.row.column_name(self.col_idx),
+                name
+            )));
+        }
+        if let Ok(v) = self.row.get_i64(self.col_idx) {
+            let index = u32::try_from(v).map_err(|_| {
+                de::Error::invalid_value(de::Unexpected::Signed(v), &"a non-negative variant index")
+            })?;
+            return visitor.visit_enum(de::IntoDeserializer::<Self::Error>::into_deserializer(
+                index,
+            ));
+        }
+        let v = self
+            .row
+            .get_string(self.col_idx)
+            .map_err(de::Error::custom)?;
+        visitor.visit_enum(de::IntoDeserializer::<Self::Error>::into_deserializer(v))
+    }
+
     forward_to_deserialize_any! {
         i8 i16 i32 i128 u8 u16 u32 u64 u128 f32 char str 
-        bytes byte_buf unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// 单列的值到字段类型的转换，供 `#[derive(FromRowDe)]` 生成的代码使用
+pub trait FromColumn: Sized {
+    fn from_column<R: RowReader>(row: &R, idx: usize) -> Result<Self, String>;
+
+    /// 结果集中没有对应列时的值；默认为 `None`，即报错
+    fn missing() -> Option<Self> {
+        None
     }
 }
+
+macro_rules! impl_from_column {
+    ($($ty:ty => |$row:ident, $idx:ident| $body:expr;)*) => {
+        $(
+            impl FromColumn for $ty {
+                fn from_column<R: RowReader>($row: &R, $idx: usize) -> Result<Self, String> {
+                    $body
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_column_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FromColumn for $ty {
+                fn from_column<R: RowReader>(row: &R, idx: usize) -> Result<Self, String> {
+                    let v = row.get_i64(idx)?;
+                    <$ty>::try_from(v).map_err(|_| format!("{} out of range for {}", v, stringify!($ty)))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_column_int!(i8, i16, i32, u8, u16, u32, u64);
+
+impl_from_column! {
+    bool => |row, idx| row.get_bool(idx).or_else(|_| row.get_i64(idx).map(|v| v != 0));
+    i64 => |row, idx| row.get_i64(idx);
+    f64 => |row, idx| row.get_f64(idx);
+    f32 => |row, idx| row.get_f64(idx).map(|v| v as f32);
+    String => |row, idx| row.get_string(idx);
+    Vec<u8> => |row, idx| row.get_bytes(idx);
+    serde_json::Value => |row, idx| row.get_json(idx);
+    Decimal => |row, idx| row.get_decimal(idx);
+    NaiveDate => |row, idx| row.get_date(idx);
+    NaiveTime => |row, idx| row.get_time(idx);
+    NaiveDateTime => |row, idx| row.get_datetime(idx);
+    DateTime<Utc> => |row, idx| row.get_datetime_utc(idx);
+    sqlx::types::Uuid => |row, idx| {
+        let v = row.get_uuid(idx)?;
+        sqlx::types::Uuid::parse_str(&v).map_err(|e| e.to_string())
+    };
+}
+
+impl<T: FromColumn> FromColumn for Option<T> {
+    fn from_column<R: RowReader>(row: &R, idx: usize) -> Result<Self, String> {
+        if row.is_null(idx) {
+            Ok(None)
+        } else {
+            T::from_column(row, idx).map(Some)
+        }
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// 按列下标直接构造结构体，跳过 [`RowDeserializer`] 逐字段按名称匹配的开销，由 `#[derive(FromRowDe)]` 生成
+///
+/// 列下标按结果集的第一行计算一次，之后各行直接按下标读取；见 `DbPool::list_as` / `DbPool::get_as`。
+pub trait FromRowDe: Sized {
+    /// 各字段对应的列下标，列不存在时为 `None`
+    fn column_indices<R: RowReader>(row: &R) -> Vec<Option<usize>>;
+
+    /// 按 [`FromRowDe::column_indices`] 的结果读取一行
+    fn from_row_at<R: RowReader>(row: &R, indices: &[Option<usize>]) -> Result<Self, DbError>;
+}
+
+/// 按列名查找各字段的列下标
+#[doc(hidden)]
+pub fn column_indices<R: RowReader>(row: &R, columns: &[&str]) -> Vec<Option<usize>> {
+    let names: Vec<&str> = (0..row.column_count())
+        .map(|i| row.column_name(i))
+        .collect();
+    columns
+        .iter()
+        .map(|c| names.iter().position(|n| n == c))
+        .collect()
+}
+
+/// 读取一个字段，列不存在时使用 [`FromColumn::missing`]
+#[doc(hidden)]
+pub fn read_column<T: FromColumn, R: RowReader>(
+    row: &R,
+    idx: Option<usize>,
+    column: &str,
+) -> Result<T, DbError> {
+    match idx {
+        Some(idx) => T::from_column(row, idx)
+            .map_err(|e| DbError::from(format!("Column '{}': {}", column, e))),
+        None => T::missing()
+            .ok_or_else(|| DbError::from(format!("Column '{}' not found in result set", column))),
+    }
+}
+
+/// 映射整个结果集，列下标只计算一次
+pub(crate) fn map_rows<T: FromRowDe, R: RowReader>(rows: &[R]) -> Result<Vec<T>, DbError> {
+    let Some(first) = rows.first() else {
+        return Ok(Vec::new());
+    };
+    let indices = T::column_indices(first);
+    rows.iter()
+        .map(|row| T::from_row_at(row, &indices))
+        .collect()
+}
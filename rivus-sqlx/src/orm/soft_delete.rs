@@ -0,0 +1,140 @@
+use crate::orm::placeholder::find_keyword;
+use std::future::Future;
+
+tokio::task_local! {
+    /// 为 true 时查询不过滤已软删除的行
+    static INCLUDE_DELETED: bool;
+}
+
+/// 查询语句中 WHERE 之后可能出现的子句，过滤条件插在它们之前
+const SELECT_TAIL: &[&str] = &[
+    "GROUP", "HAVING", "WINDOW", "ORDER", "LIMIT", "OFFSET", "FOR", "UNION",
+];
+
+/// 在此范围内执行的查询包含已软删除的行
+pub async fn with_deleted<F: Future>(fut: F) -> F::Output {
+    INCLUDE_DELETED.scope(true, fut).await
+}
+
+/// 当前任务是否包含已软删除的行
+pub fn including_deleted() -> bool {
+    INCLUDE_DELETED.try_with(|v| *v).unwrap_or(false)
+}
+
+/// 为查询追加 `column IS NULL`；原 WHERE 条件加括号后再拼接
+///
+/// 只处理顶层的第一个 SELECT，子查询与 UNION 之后的部分不变。
+pub(crate) fn filter_deleted(sql: &str, column: &str) -> String {
+    let sql = sql.trim_end().trim_end_matches(';');
+    let Some(from) = find_keyword(sql, "FROM", 0) else {
+        return sql.to_string();
+    };
+    let filter = find_keyword(sql, "WHERE", from);
+    let tail = SELECT_TAIL
+        .iter()
+        .filter_map(|kw| find_keyword(sql, kw, filter.unwrap_or(from)))
+        .min()
+        .unwrap_or(sql.len());
+
+    let mut out = match filter {
+        Some(filter) => format!(
+            "{} WHERE ({}) AND ",
+            sql[..filter].trim_end(),
+            sql[filter + 5..tail].trim()
+        ),
+        None => format!("{} WHERE ", sql[..tail].trim_end()),
+    };
+    out.push_str(&format!("{} IS NULL", column));
+    if tail < sql.len() {
+        out.push(' ');
+        out.push_str(&sql[tail..]);
+    }
+    out
+}
+
+/// 将 `DELETE FROM t [WHERE ...]` 改写为 `UPDATE t SET column = CURRENT_TIMESTAMP ...`
+///
+/// 已删除的行不重复更新；列名带表别名（如 `u.deleted_at`）时 SET 使用不带别名的列名。
+pub(crate) fn soft_delete_sql(sql: &str, column: &str) -> Option<String> {
+    let sql = sql.trim_end().trim_end_matches(';');
+    let trimmed = sql.trim_start();
+    if !trimmed
+        .get(..6)
+        .is_some_and(|kw| kw.eq_ignore_ascii_case("DELETE"))
+    {
+        return None;
+    }
+    let from = find_keyword(trimmed, "FROM", 0)?;
+    let filter = find_keyword(trimmed, "WHERE", from);
+    let tail = ["ORDER", "LIMIT", "RETURNING"]
+        .iter()
+        .filter_map(|kw| find_keyword(trimmed, kw, filter.unwrap_or(from)))
+        .min()
+        .unwrap_or(trimmed.len());
+
+    let table = trimmed[from + 4..filter.unwrap_or(tail)].trim();
+    if table.is_empty() {
+        return None;
+    }
+    let set_column = column.rsplit('.').next().unwrap_or(column);
+    let mut out = format!(
+        "UPDATE {} SET {} = CURRENT_TIMESTAMP WHERE ",
+        table, set_column
+    );
+    if let Some(filter) = filter {
+        out.push_str(&format!("({}) AND ", trimmed[filter + 5..tail].trim()));
+    }
+    out.push_str(&format!("{} IS NULL", column));
+    if tail < trimmed.len() {
+        out.push(' ');
+        out.push_str(&trimmed[tail..]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_deleted() {
+        assert_eq!(filter_deleted("SELECT * FROM users", "deleted_at"), "SELECT * FROM users WHERE deleted_at IS NULL");
+        assert_eq!(
+            filter_deleted("SELECT * FROM users WHERE a = ? OR b = ? ORDER BY id LIMIT 10;", "deleted_at"),
+            "SELECT * FROM users WHERE (a = ? OR b = ?) AND deleted_at IS NULL ORDER BY id LIMIT 10"
+        );
+        // 子查询中的 WHERE / ORDER 不受影响
+        assert_eq!(
+            filter_deleted(
+                "SELECT u.* FROM users u WHERE u.id IN (SELECT user_id FROM orders WHERE total > ? ORDER BY id) GROUP BY u.id",
+                "u.deleted_at"
+            ),
+            "SELECT u.* FROM users u WHERE (u.id IN (SELECT user_id FROM orders WHERE total > ? ORDER BY id)) AND u.deleted_at IS NULL GROUP BY u.id"
+        );
+        assert_eq!(
+            filter_deleted("SELECT * FROM users ORDER BY id", "deleted_at"),
+            "SELECT * FROM users WHERE deleted_at IS NULL ORDER BY id"
+        );
+    }
+
+    #[test]
+    fn test_soft_delete_sql() {
+        assert_eq!(
+            soft_delete_sql("DELETE FROM users WHERE id = ?", "deleted_at").unwrap(),
+            "UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE (id = ?) AND deleted_at IS NULL"
+        );
+        assert_eq!(
+            soft_delete_sql("delete from users", "deleted_at").unwrap(),
+            "UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE deleted_at IS NULL"
+        );
+        assert_eq!(
+            soft_delete_sql(
+                "DELETE FROM users u WHERE u.id = $1 RETURNING u.id",
+                "u.deleted_at"
+            )
+            .unwrap(),
+            "UPDATE users u SET deleted_at = CURRENT_TIMESTAMP WHERE (u.id = $1) AND u.deleted_at IS NULL RETURNING u.id"
+        );
+        assert!(soft_delete_sql("UPDATE users SET a = 1", "deleted_at").is_none());
+    }
+}
@@ -1,11 +1,34 @@
 use crate::db_pool::{DbConnection, DbPool, DbPoolInner, TRANSACTION_CONTEXT};
 use crate::error::DbError;
+use crate::metrics;
+use crate::orm::audit::AuditColumns;
+use crate::orm::batch::MultiValues;
 use crate::orm::crud_traits::CrudRepository;
-use crate::orm::row_de::RowDeserializer;
+use crate::orm::interceptor;
+use crate::orm::other_impl;
+use crate::orm::placeholder;
+use crate::orm::query_log::QueryTimer;
+use crate::orm::soft_delete;
+use crate::orm::row_de::{self, FromRowDe, RowDeserializer, RowReader};
+use crate::sql_parser::IdMapper;
+use crate::sql_tpl::value::as_uuid_arg;
+use crate::tenant;
+use async_stream::try_stream;
+use futures::stream::{self, BoxStream, TryStreamExt};
+use rust_decimal::Decimal;
+use serde::Deserialize;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use sqlx::{Database, Executor, IntoArguments};
+use sqlx::database::HasStatementCache;
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection, Database, Either, Executor, IntoArguments};
+use std::borrow::Cow;
 use std::future::Future;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::pin::pin;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 pub struct SqlxRepository;
 
@@ -24,14 +47,19 @@ impl CrudRepository for SqlxRepository {
         T: DeserializeOwned + Send,
     {
         let sql = sql.to_string();
-        let cnn = cnn.clone();
+        let cnn = tenant::route(cnn).map(Cow::into_owned);
         async move {
-            match &cnn.inner {
-                DbPoolInner::MySql(_) => execute_get_generic::<MySqlDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Sqlite(_) => execute_get_generic::<SqliteDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Postgres(_) => execute_get_generic::<PostgresDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
-            }
+            let cnn = cnn?;
+            let pool = &cnn;
+            interceptor::intercept(pool, sql, args, |r: &Option<T>| r.is_some() as u64, |sql, args| timed(pool, async move {
+                match &pool.inner {
+                    DbPoolInner::MySql(_) => execute_get_generic::<MySqlDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Sqlite(_) => execute_get_generic::<SqliteDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Postgres(_) => execute_get_generic::<PostgresDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Other(p) => other_impl::get(pool, p, &sql, args).await,
+                }
+            }))
+            .await
         }
     }
 
@@ -45,14 +73,19 @@ impl CrudRepository for SqlxRepository {
         T: DeserializeOwned + Send,
     {
         let sql = sql.to_string();
-        let cnn = cnn.clone();
+        let cnn = tenant::route(cnn).map(Cow::into_owned);
         async move {
-            match &cnn.inner {
-                DbPoolInner::MySql(_) => execute_list_generic::<MySqlDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Sqlite(_) => execute_list_generic::<SqliteDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Postgres(_) => execute_list_generic::<PostgresDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
-            }
+            let cnn = cnn?;
+            let pool = &cnn;
+            interceptor::intercept(pool, sql, args, |r: &Vec<T>| r.len() as u64, |sql, args| timed(pool, async move {
+                match &pool.inner {
+                    DbPoolInner::MySql(_) => execute_list_generic::<MySqlDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Sqlite(_) => execute_list_generic::<SqliteDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Postgres(_) => execute_list_generic::<PostgresDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Other(p) => other_impl::list(pool, p, &sql, args).await,
+                }
+            }))
+            .await
         }
     }
 
@@ -66,14 +99,19 @@ impl CrudRepository for SqlxRepository {
         T: DeserializeOwned + Send,
     {
         let sql = sql.to_string();
-        let cnn = cnn.clone();
+        let cnn = tenant::route(cnn).map(Cow::into_owned);
         async move {
-            match &cnn.inner {
-                DbPoolInner::MySql(_) => execute_create_generic::<MySqlDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Sqlite(_) => execute_create_generic::<SqliteDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Postgres(_) => execute_create_generic::<PostgresDriver, T>(&cnn, &sql, args).await,
-                DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
-            }
+            let cnn = cnn?;
+            let pool = &cnn;
+            interceptor::intercept(pool, sql, args, |_: &T| 1, |sql, args| timed(pool, async move {
+                match &pool.inner {
+                    DbPoolInner::MySql(_) => execute_create_generic::<MySqlDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Sqlite(_) => execute_create_generic::<SqliteDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Postgres(_) => execute_create_generic::<PostgresDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Other(p) => other_impl::create(pool, p, &sql, args).await,
+                }
+            }))
+            .await
         }
     }
 
@@ -87,19 +125,28 @@ impl CrudRepository for SqlxRepository {
         T: DeserializeOwned + Send,
     {
         let sql = sql.to_string();
-        let cnn = cnn.clone();
+        let cnn = tenant::route(cnn).map(Cow::into_owned);
         async move {
-            let mut results = Vec::new();
-            for arg in args {
-                let res: T = match &cnn.inner {
-                    DbPoolInner::MySql(_) => execute_create_generic::<MySqlDriver, T>(&cnn, &sql, arg).await?,
-                    DbPoolInner::Sqlite(_) => execute_create_generic::<SqliteDriver, T>(&cnn, &sql, arg).await?,
-                    DbPoolInner::Postgres(_) => execute_create_generic::<PostgresDriver, T>(&cnn, &sql, arg).await?,
-                    DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type"))?,
-                };
-                results.push(res);
-            }
-            Ok(results)
+            let cnn = cnn?;
+            let pool = &cnn;
+            // 拦截器看到的每行参数为一个数组
+            let rows = args.into_iter().map(Value::Array).collect();
+            interceptor::intercept(pool, sql, rows, |r: &Vec<T>| r.len() as u64, |sql, rows| timed(pool, async move {
+                let args = rows
+                    .into_iter()
+                    .map(|row| match row {
+                        Value::Array(args) => Ok(args),
+                        _ => Err(DbError::from("batch arguments must be arrays")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                match &pool.inner {
+                    DbPoolInner::MySql(_) => execute_batch_generic::<MySqlDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Sqlite(_) => execute_batch_generic::<SqliteDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Postgres(_) => execute_batch_generic::<PostgresDriver, T>(pool, &sql, args).await,
+                    DbPoolInner::Other(p) => other_impl::batch_create(pool, p, &sql, args).await,
+                }
+            }))
+            .await
         }
     }
 
@@ -110,14 +157,19 @@ impl CrudRepository for SqlxRepository {
         args: Self::Args,
     ) -> impl Future<Output = Result<u64, Self::Error>> + Send {
         let sql = sql.to_string();
-        let cnn = cnn.clone();
+        let cnn = tenant::route(cnn).map(Cow::into_owned);
         async move {
-            match &cnn.inner {
-                DbPoolInner::MySql(_) => execute_update_generic::<MySqlDriver>(&cnn, &sql, args).await,
-                DbPoolInner::Sqlite(_) => execute_update_generic::<SqliteDriver>(&cnn, &sql, args).await,
-                DbPoolInner::Postgres(_) => execute_update_generic::<PostgresDriver>(&cnn, &sql, args).await,
-                DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
-            }
+            let cnn = cnn?;
+            let pool = &cnn;
+            interceptor::intercept(pool, sql, args, |n: &u64| *n, |sql, args| timed(pool, async move {
+                match &pool.inner {
+                    DbPoolInner::MySql(_) => execute_update_generic::<MySqlDriver>(pool, &sql, args).await,
+                    DbPoolInner::Sqlite(_) => execute_update_generic::<SqliteDriver>(pool, &sql, args).await,
+                    DbPoolInner::Postgres(_) => execute_update_generic::<PostgresDriver>(pool, &sql, args).await,
+                    DbPoolInner::Other(p) => other_impl::update(pool, p, &sql, args).await,
+                }
+            }))
+            .await
         }
     }
 
@@ -131,10 +183,337 @@ impl CrudRepository for SqlxRepository {
     }
 }
 
+impl SqlxRepository {
+    /// 流式查询，逐行反序列化，不会一次性加载全部结果
+    ///
+    /// 在事务上下文中调用时使用事务连接，流存活期间该连接被占用。
+    pub fn stream<'a, T>(
+        &self,
+        cnn: &'a DbPool,
+        sql: &'a str,
+        args: Vec<Value>,
+    ) -> BoxStream<'a, Result<T, DbError>>
+    where
+        T: DeserializeOwned + Send + 'a,
+    {
+        let cnn = match tenant::route(cnn) {
+            Ok(cnn) => cnn,
+            Err(e) => return Box::pin(stream::once(async { Err(e) })),
+        };
+        let interception = match interceptor::before(&cnn, sql, &args) {
+            Ok(interception) => interception,
+            Err(e) => return Box::pin(stream::once(async { Err(e) })),
+        };
+        Box::pin(try_stream! {
+            let (sql, args) = match &interception {
+                Some(i) => (Cow::Owned(i.ctx.sql.clone()), i.ctx.args.clone()),
+                None => (Cow::Borrowed(sql), args),
+            };
+            let mut rows = dispatch_stream::<T>(&cnn, &sql, args);
+            let mut n = 0;
+            loop {
+                match rows.try_next().await {
+                    Ok(Some(row)) => {
+                        n += 1;
+                        yield row;
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        if let Some(i) = &interception {
+                            i.failed(&e);
+                        }
+                        Err(e)?;
+                    }
+                }
+            }
+            if let Some(i) = &interception {
+                i.succeeded(n);
+            }
+        })
+    }
+
+    /// 执行返回多个结果集的语句（MySQL 存储过程、多条语句），按顺序返回各结果集
+    ///
+    /// 每条语句对应一个结果集，不返回行的语句（如 INSERT、MySQL `CALL` 末尾的状态结果）对应空集合。
+    /// 各结果集结构不同时可使用 `serde_json::Value`。无参数时以文本协议执行，可包含多条语句；
+    /// 带参数时 Postgres 只允许单条语句。
+    pub async fn list_multi<T>(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Vec<T>>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let cnn = tenant::route(cnn)?;
+        let cnn = cnn.as_ref();
+        let rows = |sets: &Vec<Vec<T>>| sets.iter().map(|s| s.len() as u64).sum();
+        interceptor::intercept(cnn, sql.to_string(), args, rows, |sql, args| timed(cnn, async move {
+            match &cnn.inner {
+                DbPoolInner::MySql(_) => execute_list_multi_generic::<MySqlDriver, T>(cnn, &sql, args).await,
+                DbPoolInner::Sqlite(_) => execute_list_multi_generic::<SqliteDriver, T>(cnn, &sql, args).await,
+                DbPoolInner::Postgres(_) => execute_list_multi_generic::<PostgresDriver, T>(cnn, &sql, args).await,
+                DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
+            }
+        }))
+        .await
+    }
+}
+
+fn dispatch_stream<'a, T>(
+    cnn: &'a DbPool,
+    sql: &'a str,
+    args: Vec<Value>,
+) -> BoxStream<'a, Result<T, DbError>>
+where
+    T: DeserializeOwned + Send + 'a,
+{
+    match &cnn.inner {
+        DbPoolInner::MySql(_) => execute_stream_generic::<MySqlDriver, T>(cnn, sql, args),
+        DbPoolInner::Sqlite(_) => execute_stream_generic::<SqliteDriver, T>(cnn, sql, args),
+        DbPoolInner::Postgres(_) => execute_stream_generic::<PostgresDriver, T>(cnn, sql, args),
+        DbPoolInner::Other(_) => Box::pin(stream::once(async {
+            Err(DbError::from("Unsupported database type"))
+        })),
+    }
+}
+
+impl SqlxRepository {
+    /// 执行插入并按 mapper 的 `useGeneratedKeys`/`keyColumn` 返回生成的主键
+    ///
+    /// MySQL/SQLite 读取 `last_insert_id`，Postgres 自动追加 `RETURNING keyColumn`。
+    /// 未开启 `useGeneratedKeys` 时只执行插入，返回 `None`。mapper 配置 `audit` 时填充 `created_at` / `created_by`，
+    /// 操作人见 [`crate::orm::audit::with_user`]。
+    pub async fn insert_returning_key(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+    ) -> Result<Option<i64>, DbError> {
+        let (sql, args) = audited_insert(sql, args, mapper)?;
+        self.insert_key(cnn, &sql, args, mapper).await
+    }
+
+    async fn insert_key(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+    ) -> Result<Option<i64>, DbError> {
+        let Some(key_column) = mapper.generated_key_column() else {
+            self.update(cnn, sql, args).await?;
+            return Ok(None);
+        };
+
+        let cnn = tenant::route(cnn)?;
+        let cnn = cnn.as_ref();
+        let rows = |key: &Option<i64>| key.is_some() as u64;
+        match &cnn.inner {
+            DbPoolInner::MySql(_) => {
+                interceptor::intercept(cnn, sql.to_string(), args, rows, |sql, args| {
+                    timed(cnn, async move {
+                        execute_insert_key_generic::<MySqlDriver>(cnn, &sql, args).await
+                    })
+                })
+                .await
+            }
+            DbPoolInner::Sqlite(_) => {
+                interceptor::intercept(cnn, sql.to_string(), args, rows, |sql, args| {
+                    timed(cnn, async move {
+                        execute_insert_key_generic::<SqliteDriver>(cnn, &sql, args).await
+                    })
+                })
+                .await
+            }
+            DbPoolInner::Postgres(_) => {
+                let sql = if has_returning(sql) {
+                    sql.to_string()
+                } else {
+                    format!(
+                        "{} RETURNING {}",
+                        sql.trim_end().trim_end_matches(';'),
+                        key_column
+                    )
+                };
+                let row: Option<Value> = self.get(cnn, &sql, args).await?;
+                Ok(row.and_then(|r| r.get(key_column).and_then(Value::as_i64)))
+            }
+            DbPoolInner::Other(_) => Err(DbError::from("Unsupported database type")),
+        }
+    }
+
+    /// 执行插入并返回插入的行
+    ///
+    /// SQL 含 `RETURNING` 时等同于 `create`；否则通过生成的主键回查整行，
+    /// 使同一条不带 `RETURNING` 的语句可用于 MySQL、SQLite 与 Postgres。
+    pub async fn create_with_mapper<T>(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+    ) -> Result<T, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let (sql, args) = audited_insert(sql, args, mapper)?;
+        let sql = sql.as_ref();
+        if has_returning(sql) {
+            return self.create(cnn, sql, args).await;
+        }
+
+        let key_column = mapper
+            .generated_key_column()
+            .ok_or_else(|| DbError::from("create_with_mapper requires useGeneratedKeys=\"true\" or a RETURNING clause"))?;
+        let table = insert_table(sql).ok_or_else(|| DbError::from(format!("Cannot find table name in insert: {}", sql)))?;
+
+        let key = self
+            .insert_key(cnn, sql, args, mapper)
+            .await?
+            .ok_or_else(|| DbError::from("Insert did not generate a key"))?;
+
+        let select = format!("SELECT * FROM {} WHERE {} = ?", table, key_column);
+        self.get(cnn, &select, vec![Value::from(key)])
+            .await?
+            .ok_or_else(|| DbError::Config("创建操作未返回行 (Create did not return a row)".into()))
+    }
+
+    /// 按 mapper 的 `versionColumn` 执行乐观锁更新
+    ///
+    /// 自动追加 `version = version + 1` 与 `AND version = ?`（绑定 `version`），
+    /// 未更新任何行时返回 [`DbError::StaleVersion`]。未配置版本列时等同于 `update`。
+    /// mapper 配置 `audit` 时填充 `updated_at` / `updated_by`。
+    pub async fn update_with_mapper(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+        version: i64,
+    ) -> Result<u64, DbError> {
+        let (sql, mut args) = audited_update(sql, args, mapper)?;
+        let sql = sql.as_ref();
+        let Some(column) = mapper.version_column.as_deref() else {
+            return self.update(cnn, sql, args).await;
+        };
+
+        let placeholder = if placeholder::has_numbered(sql) {
+            format!("${}", args.len() + 1)
+        } else {
+            "?".to_string()
+        };
+        let sql = versioned_update(sql, column, &placeholder)
+            .ok_or_else(|| DbError::from(format!("versionColumn requires an UPDATE ... SET statement: {}", sql)))?;
+        args.push(Value::from(version));
+
+        match self.update(cnn, &sql, args).await? {
+            0 => Err(DbError::StaleVersion(version)),
+            n => Ok(n),
+        }
+    }
+
+    /// 查询单行；mapper 配置 `softDelete` 时过滤已软删除的行
+    ///
+    /// 在 [`soft_delete::with_deleted`] 范围内执行时不过滤。
+    pub async fn get_with_mapper<T>(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+    ) -> Result<Option<T>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.get(cnn, &select_sql(sql, mapper), args).await
+    }
+
+    /// 查询多行；mapper 配置 `softDelete` 时过滤已软删除的行
+    pub async fn list_with_mapper<T>(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+    ) -> Result<Vec<T>, DbError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.list(cnn, &select_sql(sql, mapper), args).await
+    }
+
+    /// 删除；mapper 配置 `softDelete` 时改写为将该列置为当前时间的 UPDATE
+    pub async fn delete_with_mapper(
+        &self,
+        cnn: &DbPool,
+        sql: &str,
+        args: Vec<Value>,
+        mapper: &IdMapper,
+    ) -> Result<u64, DbError> {
+        let Some(column) = mapper.soft_delete_column.as_deref() else {
+            return self.delete(cnn, sql, args).await;
+        };
+        let sql = soft_delete::soft_delete_sql(sql, column)
+            .ok_or_else(|| DbError::from(format!("softDelete requires a DELETE FROM statement: {}", sql)))?;
+        self.update(cnn, &sql, args).await
+    }
+}
+
+/// mapper 配置 `audit` 时为插入补充 `created_at` / `created_by`
+fn audited_insert<'a>(
+    sql: &'a str,
+    mut args: Vec<Value>,
+    mapper: &IdMapper,
+) -> Result<(Cow<'a, str>, Vec<Value>), DbError> {
+    let Some(columns) = mapper.audit.as_deref().and_then(AuditColumns::parse) else {
+        return Ok((Cow::Borrowed(sql), args));
+    };
+    let sql = columns
+        .insert(sql, &mut args)
+        .ok_or_else(|| DbError::from(format!("audit requires a single-row INSERT ... (columns) VALUES statement: {}", sql)))?;
+    Ok((Cow::Owned(sql), args))
+}
+
+/// mapper 配置 `audit` 时为更新补充 `updated_at` / `updated_by`
+fn audited_update<'a>(
+    sql: &'a str,
+    mut args: Vec<Value>,
+    mapper: &IdMapper,
+) -> Result<(Cow<'a, str>, Vec<Value>), DbError> {
+    let Some(columns) = mapper.audit.as_deref().and_then(AuditColumns::parse) else {
+        return Ok((Cow::Borrowed(sql), args));
+    };
+    let sql = columns
+        .update(sql, &mut args)
+        .ok_or_else(|| DbError::from(format!("audit requires an UPDATE ... SET statement: {}", sql)))?;
+    Ok((Cow::Owned(sql), args))
+}
+
+fn select_sql<'a>(sql: &'a str, mapper: &IdMapper) -> Cow<'a, str> {
+    match mapper.soft_delete_column.as_deref() {
+        Some(column) if !soft_delete::including_deleted() => Cow::Owned(soft_delete::filter_deleted(sql, column)),
+        _ => Cow::Borrowed(sql),
+    }
+}
+
 // --- 抽象驱动层 (Abstraction Layer) ---
 
 trait SqlxDriver: Send + Sync {
-    type DB: Database;
+    type DB: Database<Row: RowReader> + HasStatementCache;
+
+    /// 单条语句允许绑定的最大参数个数
+    const MAX_PARAMS: usize;
+
+    /// 是否支持 `INSERT ... RETURNING`；不支持时批量插入逐行执行，通过 `last_insert_id` 返回生成的主键
+    const RETURNING: bool = true;
+
+    /// 执行前按方言改写 SQL（如占位符风格）
+    fn prepare_sql(sql: &str) -> Cow<'_, str> {
+        Cow::Borrowed(sql)
+    }
 
     /// 绑定参数到查询
     fn bind_arg<'q>(
@@ -155,6 +534,28 @@ trait SqlxDriver: Send + Sync {
 
     /// 获取受影响的行数
     fn get_rows_affected(result: &<Self::DB as Database>::QueryResult) -> u64;
+
+    /// 获取自增主键，不支持时返回 `None`
+    fn last_insert_id(result: &<Self::DB as Database>::QueryResult) -> Option<i64>;
+
+    /// 按语句文本（一个 `LIKE` 参数）查找其他连接上正在执行的语句的后端 id（结果列为 `id`），
+    /// 不支持终止服务端语句时为 `None`
+    const FIND_BACKEND_SQL: Option<&'static str> = None;
+
+    /// 终止指定后端正在执行的语句
+    fn cancel_sql(_backend_id: i64) -> Option<String> {
+        None
+    }
+}
+
+/// 连接池对应驱动单条语句允许绑定的最大参数个数
+pub(crate) fn max_params(inner: &DbPoolInner) -> Option<usize> {
+    match inner {
+        DbPoolInner::MySql(_) => Some(MySqlDriver::MAX_PARAMS),
+        DbPoolInner::Postgres(_) => Some(PostgresDriver::MAX_PARAMS),
+        DbPoolInner::Sqlite(_) => Some(SqliteDriver::MAX_PARAMS),
+        DbPoolInner::Other(_) => None,
+    }
 }
 
 struct MySqlDriver;
@@ -163,6 +564,12 @@ struct PostgresDriver;
 
 impl SqlxDriver for MySqlDriver {
     type DB = sqlx::MySql;
+    const MAX_PARAMS: usize = 65_535;
+    const RETURNING: bool = false;
+    const FIND_BACKEND_SQL: Option<&'static str> = Some(
+        "SELECT CAST(ID AS SIGNED) AS id FROM information_schema.PROCESSLIST \
+         WHERE ID <> CONNECTION_ID() AND INFO LIKE ?",
+    );
 
     fn bind_arg<'q>(
         query: sqlx::query::Query<'q, Self::DB, <Self::DB as Database>::Arguments<'q>>,
@@ -174,6 +581,8 @@ impl SqlxDriver for MySqlDriver {
             Value::Number(n) => {
                 if let Some(i) = n.as_i64() {
                     query.bind(i)
+                } else if let Some(u) = n.as_u64() {
+                    query.bind(u)
                 } else if let Some(f) = n.as_f64() {
                     query.bind(f)
                 } else {
@@ -182,12 +591,15 @@ impl SqlxDriver for MySqlDriver {
             }
             Value::String(s) => query.bind(s),
             Value::Array(a) => query.bind(Value::Array(a)),
-            Value::Object(o) => query.bind(Value::Object(o)),
+            Value::Object(o) => match as_uuid_arg(&o) {
+                Some(u) => query.bind(u.hyphenated().to_string()),
+                None => query.bind(Value::Object(o)),
+            },
         }
     }
 
     fn from_row<T: DeserializeOwned>(row: &sqlx::mysql::MySqlRow) -> Result<T, DbError> {
-        let de = RowDeserializer::new(row);
+        let de = RowDeserializer::for_entity::<T>(row);
         T::deserialize(de).map_err(|e| DbError::Config(format!("反序列化错误 (Deserialization error): {}", e)))
     }
 
@@ -212,10 +624,19 @@ impl SqlxDriver for MySqlDriver {
     fn get_rows_affected(result: &sqlx::mysql::MySqlQueryResult) -> u64 {
         result.rows_affected()
     }
+
+    fn cancel_sql(backend_id: i64) -> Option<String> {
+        Some(format!("KILL QUERY {}", backend_id))
+    }
+
+    fn last_insert_id(result: &sqlx::mysql::MySqlQueryResult) -> Option<i64> {
+        Some(result.last_insert_id() as i64)
+    }
 }
 
 impl SqlxDriver for SqliteDriver {
     type DB = sqlx::Sqlite;
+    const MAX_PARAMS: usize = 32_766;
 
     fn bind_arg<'q>(
         query: sqlx::query::Query<'q, Self::DB, <Self::DB as Database>::Arguments<'q>>,
@@ -236,12 +657,15 @@ impl SqlxDriver for SqliteDriver {
             Value::String(s) => query.bind(s),
             // Sqlite 原生不支持 JSON 绑定，转为字符串存储
             Value::Array(a) => query.bind(Value::Array(a).to_string()),
-            Value::Object(o) => query.bind(Value::Object(o).to_string()),
+            Value::Object(o) => match as_uuid_arg(&o) {
+                Some(u) => query.bind(u.hyphenated().to_string()),
+                None => query.bind(Value::Object(o).to_string()),
+            },
         }
     }
 
     fn from_row<T: DeserializeOwned>(row: &sqlx::sqlite::SqliteRow) -> Result<T, DbError> {
-        let de = RowDeserializer::new(row);
+        let de = RowDeserializer::for_entity::<T>(row);
         T::deserialize(de).map_err(|e| DbError::Config(format!("反序列化错误 (Deserialization error): {}", e)))
     }
 
@@ -256,7 +680,7 @@ impl SqlxDriver for SqliteDriver {
     fn get_connection(
         conn: &mut DbConnection,
     ) -> Result<&mut <Self::DB as Database>::Connection, DbError> {
-        if let DbConnection::Sqlite(c) = conn {
+        if let DbConnection::Sqlite(c, _) = conn {
             Ok(&mut **c)
         } else {
             Err(DbError::Config("事务类型不匹配 (Transaction type mismatch)".into()))
@@ -266,10 +690,22 @@ impl SqlxDriver for SqliteDriver {
     fn get_rows_affected(result: &sqlx::sqlite::SqliteQueryResult) -> u64 {
         result.rows_affected()
     }
+
+    fn last_insert_id(result: &sqlx::sqlite::SqliteQueryResult) -> Option<i64> {
+        Some(result.last_insert_rowid())
+    }
 }
 
 impl SqlxDriver for PostgresDriver {
     type DB = sqlx::Postgres;
+    const MAX_PARAMS: usize = 65_535;
+    const FIND_BACKEND_SQL: Option<&'static str> = Some(
+        "SELECT pid::int8 AS id FROM pg_stat_activity WHERE pid <> pg_backend_pid() AND query LIKE ?",
+    );
+
+    fn prepare_sql(sql: &str) -> Cow<'_, str> {
+        placeholder::to_numbered(sql)
+    }
 
     fn bind_arg<'q>(
         query: sqlx::query::Query<'q, Self::DB, <Self::DB as Database>::Arguments<'q>>,
@@ -281,6 +717,9 @@ impl SqlxDriver for PostgresDriver {
             Value::Number(n) => {
                 if let Some(i) = n.as_i64() {
                     query.bind(i)
+                } else if let Some(u) = n.as_u64() {
+                    // 超出 BIGINT 范围的无符号整数按 NUMERIC 绑定，避免转为 f64 丢失精度
+                    query.bind(Decimal::from(u))
                 } else if let Some(f) = n.as_f64() {
                     query.bind(f)
                 } else {
@@ -289,12 +728,16 @@ impl SqlxDriver for PostgresDriver {
             }
             Value::String(s) => query.bind(s),
             Value::Array(a) => query.bind(Value::Array(a)),
-            Value::Object(o) => query.bind(Value::Object(o)),
+            // Postgres 不会将 text 隐式转换为 uuid，显式 UUID 参数按 uuid 类型绑定
+            Value::Object(o) => match as_uuid_arg(&o) {
+                Some(u) => query.bind(u),
+                None => query.bind(Value::Object(o)),
+            },
         }
     }
 
     fn from_row<T: DeserializeOwned>(row: &sqlx::postgres::PgRow) -> Result<T, DbError> {
-        let de = RowDeserializer::new(row);
+        let de = RowDeserializer::for_entity::<T>(row);
         T::deserialize(de).map_err(|e| DbError::Config(format!("反序列化错误 (Deserialization error): {}", e)))
     }
 
@@ -319,6 +762,14 @@ impl SqlxDriver for PostgresDriver {
     fn get_rows_affected(result: &sqlx::postgres::PgQueryResult) -> u64 {
         result.rows_affected()
     }
+
+    fn cancel_sql(backend_id: i64) -> Option<String> {
+        Some(format!("SELECT pg_cancel_backend({})", backend_id))
+    }
+
+    fn last_insert_id(_result: &sqlx::postgres::PgQueryResult) -> Option<i64> {
+        None
+    }
 }
 
 // --- 通用执行逻辑 (Generic Execution Logic) ---
@@ -332,39 +783,166 @@ where
     T: DeserializeOwned + Send,
     for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
     for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    match fetch_optional_generic::<D>(pool, sql, args).await? {
+        Some(row) => Ok(Some(D::from_row(&row)?)),
+        None => Ok(None),
+    }
+}
+
+async fn execute_list_generic<D: SqlxDriver, T>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<T>, DbError>
+where
+    T: DeserializeOwned + Send,
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let rows = fetch_all_generic::<D>(pool, sql, args).await?;
+    rows.iter().map(D::from_row).collect()
+}
+
+/// 查询至多一行；处于事务上下文时使用事务连接
+async fn fetch_optional_generic<D: SqlxDriver>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Option<<D::DB as Database>::Row>, DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
 {
     let tx_conn = TRANSACTION_CONTEXT
         .try_with(|map| map.borrow().get(&pool.name).cloned())
         .ok()
         .flatten();
 
-    let mut query = sqlx::query(sql);
+    let mut timer = QueryTimer::start(pool, sql, &args);
+    let (sql, persistent) = tagged::<D>(D::prepare_sql(sql), pool.cache_statements);
+    let mut query = sqlx::query(&sql).persistent(persistent);
     for arg in args {
         query = D::bind_arg(query, arg);
     }
 
     let row = if let Some(conn_arc) = tx_conn {
         let mut conn_guard = conn_arc.lock().await;
-        let conn = D::get_connection(&mut *conn_guard)?;
+        let conn = D::get_connection(&mut conn_guard)?;
         query.fetch_optional(conn).await?
     } else {
-        let p = D::get_pool(pool)?;
-        query.fetch_optional(p).await?
+        let mut conn = acquire::<D>(pool).await?;
+        query.fetch_optional(&mut *conn).await?
     };
+    timer.rows(row.is_some() as u64);
+    Ok(row)
+}
+
+/// 查询所有行；处于事务上下文时使用事务连接
+async fn fetch_all_generic<D: SqlxDriver>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<<D::DB as Database>::Row>, DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let tx_conn = TRANSACTION_CONTEXT
+        .try_with(|map| map.borrow().get(&pool.name).cloned())
+        .ok()
+        .flatten();
+
+    let mut timer = QueryTimer::start(pool, sql, &args);
+    let (sql, persistent) = tagged::<D>(D::prepare_sql(sql), pool.cache_statements);
+    let mut query = sqlx::query(&sql).persistent(persistent);
+    for arg in args {
+        query = D::bind_arg(query, arg);
+    }
 
-    if let Some(row) = row {
-        let t = D::from_row(&row)?;
-        Ok(Some(t))
+    let rows = if let Some(conn_arc) = tx_conn {
+        let mut conn_guard = conn_arc.lock().await;
+        let conn = D::get_connection(&mut conn_guard)?;
+        query.fetch_all(conn).await?
     } else {
-        Ok(None)
+        let mut conn = acquire::<D>(pool).await?;
+        query.fetch_all(&mut *conn).await?
+    };
+    timer.rows(rows.len() as u64);
+    Ok(rows)
+}
+
+async fn execute_get_as_generic<D: SqlxDriver, T: FromRowDe>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Option<T>, DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    match fetch_optional_generic::<D>(pool, sql, args).await? {
+        Some(row) => Ok(Some(T::from_row_at(&row, &T::column_indices(&row))?)),
+        None => Ok(None),
     }
 }
 
-async fn execute_list_generic<D: SqlxDriver, T>(
+async fn execute_list_as_generic<D: SqlxDriver, T: FromRowDe>(
     pool: &DbPool,
     sql: &str,
     args: Vec<Value>,
 ) -> Result<Vec<T>, DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let rows = fetch_all_generic::<D>(pool, sql, args).await?;
+    row_de::map_rows(&rows)
+}
+
+/// 查询单行并按 [`FromRowDe`] 映射；自定义驱动不支持
+pub(crate) async fn get_as<T: FromRowDe + Send>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Option<T>, DbError> {
+    let pool = tenant::route(pool)?;
+    let pool = pool.as_ref();
+    interceptor::intercept(pool, sql.to_string(), args, |r: &Option<T>| r.is_some() as u64, |sql, args| timed(pool, async move {
+        match &pool.inner {
+            DbPoolInner::MySql(_) => execute_get_as_generic::<MySqlDriver, T>(pool, &sql, args).await,
+            DbPoolInner::Sqlite(_) => execute_get_as_generic::<SqliteDriver, T>(pool, &sql, args).await,
+            DbPoolInner::Postgres(_) => execute_get_as_generic::<PostgresDriver, T>(pool, &sql, args).await,
+            DbPoolInner::Other(_) => Err(DbError::from("FromRowDe is not supported by custom drivers")),
+        }
+    }))
+    .await
+}
+
+/// 查询多行并按 [`FromRowDe`] 映射，列下标只计算一次；自定义驱动不支持
+pub(crate) async fn list_as<T: FromRowDe + Send>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<T>, DbError> {
+    let pool = tenant::route(pool)?;
+    let pool = pool.as_ref();
+    interceptor::intercept(pool, sql.to_string(), args, |r: &Vec<T>| r.len() as u64, |sql, args| timed(pool, async move {
+        match &pool.inner {
+            DbPoolInner::MySql(_) => execute_list_as_generic::<MySqlDriver, T>(pool, &sql, args).await,
+            DbPoolInner::Sqlite(_) => execute_list_as_generic::<SqliteDriver, T>(pool, &sql, args).await,
+            DbPoolInner::Postgres(_) => execute_list_as_generic::<PostgresDriver, T>(pool, &sql, args).await,
+            DbPoolInner::Other(_) => Err(DbError::from("FromRowDe is not supported by custom drivers")),
+        }
+    }))
+    .await
+}
+
+async fn execute_list_multi_generic<D: SqlxDriver, T>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<Vec<T>>, DbError>
 where
     T: DeserializeOwned + Send,
     for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
@@ -375,26 +953,104 @@ where
         .ok()
         .flatten();
 
-    let mut query = sqlx::query(sql);
+    let mut timer = QueryTimer::start(pool, sql, &args);
+    let (sql, persistent) = tagged::<D>(D::prepare_sql(sql), pool.cache_statements);
+    let raw = args.is_empty();
+    let mut query = sqlx::query(&sql).persistent(persistent);
     for arg in args {
         query = D::bind_arg(query, arg);
     }
 
-    let rows = if let Some(conn_arc) = tx_conn {
+    let sets = if let Some(conn_arc) = tx_conn {
         let mut conn_guard = conn_arc.lock().await;
-        let conn = D::get_connection(&mut *conn_guard)?;
-        query.fetch_all(conn).await?
+        let conn = D::get_connection(&mut conn_guard)?;
+        if raw {
+            collect_result_sets::<D, T>(conn.fetch_many(sql.as_ref())).await?
+        } else {
+            collect_result_sets::<D, T>(conn.fetch_many(query)).await?
+        }
     } else {
-        let p = D::get_pool(pool)?;
-        query.fetch_all(p).await?
+        let mut conn = acquire::<D>(pool).await?;
+        if raw {
+            collect_result_sets::<D, T>(conn.fetch_many(sql.as_ref())).await?
+        } else {
+            collect_result_sets::<D, T>(conn.fetch_many(query)).await?
+        }
     };
+    timer.rows(sets.iter().map(|s| s.len() as u64).sum());
+    Ok(sets)
+}
 
-    let mut results = Vec::new();
-    for row in rows {
-        let t = D::from_row(&row)?;
-        results.push(t);
+/// `fetch_many` 产生的结果：行，或一个结果集结束时的 `QueryResult`
+type ResultSetStream<'a, DB> =
+    BoxStream<'a, Result<Either<<DB as Database>::QueryResult, <DB as Database>::Row>, sqlx::Error>>;
+
+/// 按结果集边界（`QueryResult`）切分行
+async fn collect_result_sets<D: SqlxDriver, T: DeserializeOwned>(
+    mut results: ResultSetStream<'_, D::DB>,
+) -> Result<Vec<Vec<T>>, DbError> {
+    let mut sets = Vec::new();
+    let mut current = Vec::new();
+    while let Some(item) = results.try_next().await? {
+        match item {
+            Either::Left(_) => sets.push(std::mem::take(&mut current)),
+            Either::Right(row) => current.push(D::from_row::<T>(&row)?),
+        }
+    }
+    if !current.is_empty() {
+        sets.push(current);
     }
-    Ok(results)
+    Ok(sets)
+}
+
+fn execute_stream_generic<'a, D: SqlxDriver + 'a, T>(
+    pool: &'a DbPool,
+    sql: &'a str,
+    args: Vec<Value>,
+) -> BoxStream<'a, Result<T, DbError>>
+where
+    T: DeserializeOwned + Send + 'a,
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    // 事务连接需在当前任务的上下文中获取
+    let tx_conn = TRANSACTION_CONTEXT
+        .try_with(|map| map.borrow().get(&pool.name).cloned())
+        .ok()
+        .flatten();
+
+    Box::pin(try_stream! {
+        // 流式查询计时至流结束或被丢弃，包含调用方处理每行的耗时
+        let mut timer = QueryTimer::start(pool, sql, &args);
+        let (sql, persistent) = tagged::<D>(D::prepare_sql(sql), pool.cache_statements);
+        let mut query = sqlx::query(&sql).persistent(persistent);
+        for arg in args {
+            query = D::bind_arg(query, arg);
+        }
+
+        if let Some(conn_arc) = tx_conn {
+            let mut conn_guard = conn_arc.lock().await;
+            let conn = D::get_connection(&mut conn_guard)?;
+            let mut rows = query.fetch(conn);
+            let mut n = 0;
+            while let Some(row) = rows.try_next().await? {
+                n += 1;
+                timer.rows(n);
+                yield D::from_row::<T>(&row)?;
+            }
+            timer.rows(n);
+        } else {
+            let mut conn = acquire::<D>(pool).await?;
+            let mut rows = query.fetch(&mut *conn);
+            let mut n = 0;
+            while let Some(row) = rows.try_next().await? {
+                n += 1;
+                timer.rows(n);
+                yield D::from_row::<T>(&row)?;
+            }
+            timer.rows(n);
+        }
+    })
 }
 
 async fn execute_create_generic<D: SqlxDriver, T>(
@@ -411,11 +1067,109 @@ where
     opt.ok_or_else(|| DbError::Config("创建操作未返回行 (Create did not return a row)".into()))
 }
 
+/// 批量插入：将单行 `VALUES (...)` 展开为多行，按参数上限分块执行
+///
+/// 返回各块 `RETURNING` 的结果；无法展开的语句逐行执行。多条语句在同一事务中执行，已处于事务中时加入外层事务。
+/// MySQL 不支持 `RETURNING`，多行 INSERT 分配的自增值不保证连续（`innodb_autoinc_lock_mode = 2`、
+/// `auto_increment_increment` 不为 1 时），因此逐行执行并读取各行的 `last_insert_id`，此时 `T` 须能由整数反序列化；
+/// 表没有自增列时返回错误。
+async fn execute_batch_generic<D: SqlxDriver, T>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Vec<Value>>,
+) -> Result<Vec<T>, DbError>
+where
+    T: DeserializeOwned + Send,
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let total = args.len();
+    let cols = args.first().map_or(0, Vec::len);
+    let multi = MultiValues::parse(sql)
+        .filter(|_| cols > 0 && args.iter().all(|a| a.len() == cols))
+        .filter(|_| D::RETURNING);
+
+    let statements: Vec<(Cow<'_, str>, Vec<Value>)> = match &multi {
+        None => args
+            .into_iter()
+            .map(|arg| (Cow::Borrowed(sql), arg))
+            .collect(),
+        Some(multi) => {
+            let rows_per_chunk = (D::MAX_PARAMS / cols).max(1);
+            let mut statements = Vec::with_capacity(total.div_ceil(rows_per_chunk));
+            let mut rows = args.into_iter().peekable();
+            while rows.peek().is_some() {
+                let chunk: Vec<Vec<Value>> = rows.by_ref().take(rows_per_chunk).collect();
+                let chunk_sql = multi.render(chunk.len(), cols);
+                statements.push((Cow::Owned(chunk_sql), chunk.into_iter().flatten().collect()));
+            }
+            statements
+        }
+    };
+    let is_multi = multi.is_some();
+    let single = statements.len() <= 1;
+
+    let run = |tx: DbPool| async move {
+        let mut results = Vec::with_capacity(total);
+        for (sql, params) in statements {
+            if !D::RETURNING {
+                // 逐行执行，每条语句只插入一行
+                let result = execute_result_generic::<D>(&tx, &sql, params).await?;
+                let id = D::last_insert_id(&result).filter(|id| *id != 0).ok_or_else(|| {
+                    DbError::Config(
+                        "批量插入未返回自增主键 (Batch insert returned no generated key)".into(),
+                    )
+                })?;
+                results.push(
+                    serde_json::from_value(Value::from(id))
+                        .map_err(|e| DbError::Config(e.to_string()))?,
+                );
+            } else if is_multi {
+                results.extend(execute_list_generic::<D, T>(&tx, &sql, params).await?);
+            } else {
+                results.push(execute_create_generic::<D, T>(&tx, &sql, params).await?);
+            }
+        }
+        Ok(results)
+    };
+
+    if single {
+        return run(pool.clone()).await;
+    }
+    pool.transaction(run).await
+}
+
 async fn execute_update_generic<D: SqlxDriver>(
     pool: &DbPool,
     sql: &str,
     args: Vec<Value>,
 ) -> Result<u64, DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let result = execute_result_generic::<D>(pool, sql, args).await?;
+    Ok(D::get_rows_affected(&result))
+}
+
+async fn execute_insert_key_generic<D: SqlxDriver>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Option<i64>, DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let result = execute_result_generic::<D>(pool, sql, args).await?;
+    Ok(D::last_insert_id(&result))
+}
+
+async fn execute_result_generic<D: SqlxDriver>(
+    pool: &DbPool,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<<D::DB as Database>::QueryResult, DbError>
 where
     for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
     for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
@@ -425,18 +1179,179 @@ where
         .ok()
         .flatten();
 
-    let mut query = sqlx::query(sql);
+    let mut timer = QueryTimer::start(pool, sql, &args);
+    let (sql, persistent) = tagged::<D>(D::prepare_sql(sql), pool.cache_statements);
+    let mut query = sqlx::query(&sql).persistent(persistent);
     for arg in args {
         query = D::bind_arg(query, arg);
     }
 
     let result = if let Some(conn_arc) = tx_conn {
         let mut conn_guard = conn_arc.lock().await;
-        let conn = D::get_connection(&mut *conn_guard)?;
+        let conn = D::get_connection(&mut conn_guard)?;
         query.execute(conn).await?
     } else {
-        let p = D::get_pool(pool)?;
-        query.execute(p).await?
+        let mut conn = acquire::<D>(pool).await?;
+        query.execute(&mut *conn).await?
     };
-    Ok(D::get_rows_affected(&result))
+    timer.rows(D::get_rows_affected(&result));
+    Ok(result)
+}
+
+tokio::task_local! {
+    /// 设置超时的调用中语句携带的标记，超时后据此在服务端定位语句
+    static QUERY_TAG: String;
+}
+
+#[derive(Deserialize)]
+struct BackendId {
+    id: i64,
+}
+
+/// 生成进程内唯一、跨进程几乎不会重复的语句标记
+fn next_query_tag() -> String {
+    static SEED: LazyLock<u64> = LazyLock::new(|| RandomState::new().build_hasher().finish());
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "rivus-query {:016x}-{:x}",
+        *SEED,
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// 设置超时时为语句加上标记注释；带标记的语句每次都不同，不进入预处理语句缓存
+fn tagged<D: SqlxDriver>(sql: Cow<'_, str>, persistent: bool) -> (Cow<'_, str>, bool) {
+    if D::FIND_BACKEND_SQL.is_none() {
+        return (sql, persistent);
+    }
+    match QUERY_TAG.try_with(|tag| format!("/* {} */ {}", tag, sql)) {
+        Ok(sql) => (Cow::Owned(sql), false),
+        Err(_) => (sql, persistent),
+    }
+}
+
+/// 按连接池的 `query_timeout` 执行，超时后终止服务端语句、取消执行并返回 [`DbError::Timeout`]
+///
+/// 终止语句时原连接仍被未完成的 `fut` 占用，不会误伤之后复用该连接的其他语句。
+async fn timed<R>(
+    pool: &DbPool,
+    fut: impl Future<Output = Result<R, DbError>>,
+) -> Result<R, DbError> {
+    let Some(timeout) = pool.query_timeout else {
+        return fut.await;
+    };
+    let tag = next_query_tag();
+    let mut fut = pin!(QUERY_TAG.scope(tag.clone(), fut));
+    tokio::select! {
+        result = &mut fut => result,
+        _ = tokio::time::sleep(timeout) => {
+            let cancel = async {
+                match &pool.inner {
+                    DbPoolInner::MySql(_) => cancel_backend::<MySqlDriver>(pool, &tag).await,
+                    DbPoolInner::Postgres(_) => cancel_backend::<PostgresDriver>(pool, &tag).await,
+                    _ => Ok(()),
+                }
+            };
+            let cancelled = tokio::time::timeout(timeout, cancel)
+                .await
+                .unwrap_or(Err(DbError::Timeout(timeout)));
+            if let Err(e) = cancelled {
+                tracing::warn!("cancel timed out query on '{}' failed: {}", pool.name, e);
+            }
+            Err(DbError::Timeout(timeout))
+        }
+    }
+}
+
+/// 通过独立连接（不从可能已耗尽的连接池获取）查找带标记的服务端语句并终止
+async fn cancel_backend<D: SqlxDriver>(pool: &DbPool, tag: &str) -> Result<(), DbError>
+where
+    for<'q> <D::DB as Database>::Arguments<'q>: IntoArguments<'q, D::DB>,
+    for<'c> &'c mut <D::DB as Database>::Connection: Executor<'c, Database = D::DB>,
+{
+    let Some(find_sql) = D::FIND_BACKEND_SQL else {
+        return Ok(());
+    };
+    let options = D::get_pool(pool)?.connect_options();
+    let mut conn = <D::DB as Database>::Connection::connect_with(&options).await?;
+
+    let find_sql = D::prepare_sql(find_sql);
+    let query = sqlx::query(&find_sql).persistent(false);
+    let rows = D::bind_arg(query, Value::from(format!("/* {} */%", tag)))
+        .fetch_all(&mut conn)
+        .await?;
+    for row in rows {
+        if let Some(sql) = D::cancel_sql(D::from_row::<BackendId>(&row)?.id) {
+            sqlx::query(&sql)
+                .persistent(false)
+                .execute(&mut conn)
+                .await?;
+        }
+    }
+    conn.close().await?;
+    Ok(())
+}
+
+/// 从连接池获取连接并上报获取耗时
+async fn acquire<D: SqlxDriver>(pool: &DbPool) -> Result<PoolConnection<D::DB>, DbError> {
+    let p = D::get_pool(pool)?;
+    let start = Instant::now();
+    let conn = p.acquire().await?;
+    metrics::record_acquire(pool, start.elapsed());
+    Ok(conn)
+}
+
+fn has_returning(sql: &str) -> bool {
+    sql.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .any(|w| w.eq_ignore_ascii_case("returning"))
+}
+
+/// 为 `UPDATE ... SET ... [WHERE ...]` 追加版本递增与版本条件
+///
+/// 原 WHERE 条件加括号后再拼接，`ORDER BY` / `LIMIT` / `RETURNING` 保留在末尾。
+fn versioned_update(sql: &str, column: &str, placeholder: &str) -> Option<String> {
+    let sql = sql.trim_end().trim_end_matches(';');
+    let set = placeholder::find_keyword(sql, "SET", 0)?;
+    if !sql
+        .trim_start()
+        .get(..6)
+        .is_some_and(|kw| kw.eq_ignore_ascii_case("UPDATE"))
+    {
+        return None;
+    }
+
+    let filter = placeholder::find_keyword(sql, "WHERE", set);
+    let tail = ["ORDER", "LIMIT", "RETURNING"]
+        .iter()
+        .filter_map(|kw| placeholder::find_keyword(sql, kw, filter.unwrap_or(set)))
+        .min()
+        .unwrap_or(sql.len());
+
+    let assignments = &sql[..filter.unwrap_or(tail)];
+    let mut out = format!(
+        "{}, {col} = {col} + 1 WHERE ",
+        assignments.trim_end(),
+        col = column
+    );
+    if let Some(filter) = filter {
+        out.push_str(&format!("({}) AND ", sql[filter + 5..tail].trim()));
+    }
+    out.push_str(&format!("{} = {}", column, placeholder));
+    if tail < sql.len() {
+        out.push(' ');
+        out.push_str(&sql[tail..]);
+    }
+    Some(out)
+}
+
+/// 解析 `INSERT [IGNORE] INTO table ...` 中的表名
+fn insert_table(sql: &str) -> Option<&str> {
+    let upper = sql.to_ascii_uppercase();
+    let pos = upper.find("INTO ")? + 5;
+    let rest = sql[pos..].trim_start();
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '(')
+        .unwrap_or(rest.len());
+    let table = &rest[..end];
+    (!table.is_empty()).then_some(table)
 }
@@ -0,0 +1,119 @@
+//! `#[sql]` 方法与 `#[derive(Entity)]` 生成代码使用的运行时支持：取 mapper 语句或内联模板、以参数渲染并执行
+
+use crate::db_conn::ConnManager;
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use crate::mapper_store;
+use crate::orm::crud_traits::CrudRepository;
+use crate::orm::sqlx_impl::SqlxRepository;
+use crate::sql_parser::IdMapper;
+use crate::sql_tpl::engine::try_render_value;
+use crate::sql_tpl::value::{to_value, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 模板参数：按函数参数名访问，如 `#{user.name}`
+///
+/// 只有一个参数且为结构体 / map 时，其字段也可直接访问（`#{name}`），与参数同名的字段以参数为准。
+#[derive(Debug, Default)]
+pub struct Params {
+    values: HashMap<String, Value>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn arg<T: Serialize>(mut self, name: &str, value: &T) -> Self {
+        self.values.insert(name.to_string(), to_value(value));
+        self
+    }
+
+    fn into_value(mut self) -> Value {
+        if self.values.len() == 1
+            && let Some(Value::Map(fields)) = self.values.values().next()
+        {
+            for (k, v) in fields.clone() {
+                self.values.entry(k).or_insert(v);
+            }
+        }
+        Value::Map(self.values)
+    }
+}
+
+/// 渲染后的语句
+#[derive(Debug)]
+pub struct Statement {
+    pub sql: String,
+    pub args: Vec<serde_json::Value>,
+    mapper: Option<IdMapper>,
+}
+
+impl Statement {
+    /// 从已安装的 mapper 中取出 `namespace.id` 并渲染
+    pub fn render(namespace: &str, id: &str, params: Params) -> Result<Self, DbError> {
+        let mappers = mapper_store::installed()
+            .ok_or_else(|| DbError::from("No mappers installed, call Mappers::install or MapperStore::install first"))?;
+        let content = mappers
+            .sql(namespace, id)
+            .ok_or_else(|| DbError::from(format!("SQL '{}.{}' not found in mappers", namespace, id)))?;
+        let name = format!("{}.{}", namespace, id);
+        let (sql, args) = try_render_value(&name, content, &params.into_value())
+            .map_err(|e| DbError::from(format!("Failed to render SQL '{}': {}", name, e)))?;
+        Ok(Self {
+            sql,
+            args: args.into_iter().map(serde_json::Value::from).collect(),
+            mapper: mappers.mapper(namespace, id).cloned(),
+        })
+    }
+
+    /// 渲染内联模板，`name` 用作模板缓存的键
+    pub fn template(name: &str, template: &str, params: Params) -> Result<Self, DbError> {
+        let (sql, args) = try_render_value(name, template, &params.into_value())
+            .map_err(|e| DbError::from(format!("Failed to render SQL '{}': {}", name, e)))?;
+        Ok(Self {
+            sql,
+            args: args.into_iter().map(serde_json::Value::from).collect(),
+            mapper: None,
+        })
+    }
+
+    /// 查询多行；mapper 配置 `softDelete` 时过滤已软删除的行
+    pub async fn list<T: DeserializeOwned + Send>(self, pool: &DbPool) -> Result<Vec<T>, DbError> {
+        match &self.mapper {
+            Some(mapper) => SqlxRepository.list_with_mapper(pool, &self.sql, self.args, mapper).await,
+            None => SqlxRepository.list(pool, &self.sql, self.args).await,
+        }
+    }
+
+    /// 查询单行；mapper 配置 `softDelete` 时过滤已软删除的行
+    pub async fn get<T: DeserializeOwned + Send>(
+        self,
+        pool: &DbPool,
+    ) -> Result<Option<T>, DbError> {
+        match &self.mapper {
+            Some(mapper) => SqlxRepository.get_with_mapper(pool, &self.sql, self.args, mapper).await,
+            None => SqlxRepository.get(pool, &self.sql, self.args).await,
+        }
+    }
+
+    /// 查询单行，没有结果时返回错误
+    pub async fn one<T: DeserializeOwned + Send>(self, pool: &DbPool) -> Result<T, DbError> {
+        let sql = self.sql.clone();
+        self.get(pool)
+            .await?
+            .ok_or_else(|| DbError::from(format!("Query returned no rows: {}", sql)))
+    }
+
+    /// 执行语句，返回影响的行数
+    pub async fn execute(self, pool: &DbPool) -> Result<u64, DbError> {
+        SqlxRepository.update(pool, &self.sql, self.args).await
+    }
+}
+
+/// 按名称取已注册的连接池
+pub fn pool(name: &str) -> Result<DbPool, DbError> {
+    ConnManager::by(name).ok_or_else(|| DbError::from(format!("Database '{}' not found", name)))
+}
@@ -0,0 +1,108 @@
+use crate::db_pool::{DbPool, DbPoolInner};
+use crate::error::DbError;
+use crate::orm::crud_traits::CrudRepository;
+use crate::orm::sqlx_impl::SqlxRepository;
+use serde_json::Value;
+
+impl SqlxRepository {
+    /// 按唯一键插入或更新一行
+    ///
+    /// MySQL 生成 `ON DUPLICATE KEY UPDATE`，Postgres/SQLite 生成 `ON CONFLICT (keys) DO UPDATE`，
+    /// 非键列以新值覆盖；全部为键列时冲突则忽略。表名与列名直接拼接，不应来自外部输入。
+    /// 返回受影响的行数（MySQL 更新时为 2）。
+    pub async fn upsert(
+        &self,
+        cnn: &DbPool,
+        table: &str,
+        keys: &[&str],
+        values: Vec<(&str, Value)>,
+    ) -> Result<u64, DbError> {
+        if keys.is_empty() {
+            return Err(DbError::from("upsert requires at least one key column"));
+        }
+        if let Some(key) = keys.iter().find(|k| !values.iter().any(|(c, _)| c == *k)) {
+            return Err(DbError::from(format!(
+                "upsert key column '{}' has no value",
+                key
+            )));
+        }
+
+        let on_duplicate_key = match &cnn.inner {
+            DbPoolInner::MySql(_) => true,
+            DbPoolInner::Postgres(_) | DbPoolInner::Sqlite(_) => false,
+            DbPoolInner::Other(_) => return Err(DbError::from("Unsupported database type")),
+        };
+        let columns: Vec<&str> = values.iter().map(|(c, _)| *c).collect();
+        let sql = upsert_sql(on_duplicate_key, table, keys, &columns);
+        let args = values.into_iter().map(|(_, v)| v).collect();
+        self.update(cnn, &sql, args).await
+    }
+}
+
+/// `on_duplicate_key` 为 true 时使用 MySQL 语法，否则使用 `ON CONFLICT`
+fn upsert_sql(on_duplicate_key: bool, table: &str, keys: &[&str], columns: &[&str]) -> String {
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table,
+        columns.join(", "),
+        placeholders
+    );
+    let updates: Vec<&str> = columns
+        .iter()
+        .copied()
+        .filter(|c| !keys.contains(c))
+        .collect();
+
+    if on_duplicate_key {
+        let assignments = if updates.is_empty() {
+            // 无可更新的列时赋值为自身，等同于忽略冲突
+            format!("{k} = {k}", k = keys[0])
+        } else {
+            updates
+                .iter()
+                .map(|c| format!("{c} = VALUES({c})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        format!("{} ON DUPLICATE KEY UPDATE {}", insert, assignments)
+    } else {
+        let action = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            let set = updates
+                .iter()
+                .map(|c| format!("{c} = EXCLUDED.{c}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("DO UPDATE SET {}", set)
+        };
+        format!("{} ON CONFLICT ({}) {}", insert, keys.join(", "), action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_sql() {
+        let columns = ["id", "name", "age"];
+        assert_eq!(
+            upsert_sql(true, "users", &["id"], &columns),
+            "INSERT INTO users (id, name, age) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE name = VALUES(name), age = VALUES(age)"
+        );
+        assert_eq!(
+            upsert_sql(false, "users", &["id"], &columns),
+            "INSERT INTO users (id, name, age) VALUES (?, ?, ?) ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name, age = EXCLUDED.age"
+        );
+        assert_eq!(
+            upsert_sql(true, "tags", &["a", "b"], &["a", "b"]),
+            "INSERT INTO tags (a, b) VALUES (?, ?) ON DUPLICATE KEY UPDATE a = a"
+        );
+        assert_eq!(
+            upsert_sql(false, "tags", &["a", "b"], &["a", "b"]),
+            "INSERT INTO tags (a, b) VALUES (?, ?) ON CONFLICT (a, b) DO NOTHING"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::db_pool::{DbPool, DbPoolInner, RetryPolicy};
+use crate::error::DbError;
+use async_stream::stream;
+use futures::stream::BoxStream;
+use serde_json::Value;
+use sqlx::postgres::PgListener;
+use std::time::Duration;
+
+/// Postgres `NOTIFY` 推送的消息
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+    /// 发送通知的后端进程 ID
+    pub process_id: u32,
+}
+
+impl DbPool {
+    /// 订阅 Postgres 频道，返回通知流
+    ///
+    /// 监听使用独立连接，断开后自动重连并重新 `LISTEN`，重连失败时按指数退避重试；
+    /// 断开期间发出的通知会丢失。丢弃流即取消订阅。仅支持 Postgres。
+    pub async fn listen(&self, channel: &str) -> Result<BoxStream<'static, Notification>, DbError> {
+        let DbPoolInner::Postgres(pool) = &self.inner else {
+            return Err(DbError::from(format!(
+                "LISTEN is not supported by database '{}'",
+                self.name
+            )));
+        };
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(channel).await?;
+
+        let name = self.name.clone();
+        let channel = channel.to_string();
+        let backoff = RetryPolicy::new(0).max_delay(Duration::from_secs(30));
+        Ok(Box::pin(stream! {
+            let mut failures = 0;
+            loop {
+                match listener.recv().await {
+                    Ok(n) => {
+                        failures = 0;
+                        yield Notification {
+                            channel: n.channel().to_string(),
+                            payload: n.payload().to_string(),
+                            process_id: n.process_id(),
+                        };
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        let delay = backoff.delay(failures);
+                        tracing::warn!("listener on '{}' channel '{}' failed ({}), reconnect in {:?}", name, channel, e, delay);
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+        }))
+    }
+
+    /// 向 Postgres 频道发送通知；在事务中调用时于提交后送达
+    pub async fn notify(&self, channel: &str, payload: &str) -> Result<(), DbError> {
+        if !matches!(self.inner, DbPoolInner::Postgres(_)) {
+            return Err(DbError::from(format!(
+                "NOTIFY is not supported by database '{}'",
+                self.name
+            )));
+        }
+        self.execute(
+            "SELECT pg_notify(?, ?)",
+            vec![Value::from(channel), Value::from(payload)],
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use crate::orm::query::Dialect;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 表结构
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TableSchema {
+    pub name: String,
+    /// 按定义顺序排列的列
+    pub columns: Vec<ColumnSchema>,
+    /// 主键列，复合主键按列定义顺序
+    pub primary_key: Vec<String>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+impl TableSchema {
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+}
+
+/// 列定义
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// 数据库声明的类型，如 `varchar(64)`、`integer`；SQLite 未声明类型时为空
+    pub data_type: String,
+    pub nullable: bool,
+    /// 默认值表达式
+    pub default: Option<String>,
+    pub primary_key: bool,
+}
+
+/// 外键：`column` 引用 `ref_table.ref_column`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ForeignKey {
+    pub column: String,
+    pub ref_table: String,
+    pub ref_column: String,
+}
+
+#[derive(Deserialize)]
+struct ColumnRow {
+    name: String,
+    data_type: Option<String>,
+    nullable: i64,
+    default_value: Option<String>,
+    primary_key: i64,
+}
+
+#[derive(Deserialize)]
+struct ForeignKeyRow {
+    column_name: String,
+    ref_table: String,
+    ref_column: String,
+}
+
+const MYSQL_COLUMNS: &str = "SELECT column_name AS name, column_type AS data_type, \
+    CAST(is_nullable = 'YES' AS SIGNED) AS nullable, column_default AS default_value, \
+    CAST(column_key = 'PRI' AS SIGNED) AS primary_key \
+    FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = ? \
+    ORDER BY ordinal_position";
+
+const MYSQL_FOREIGN_KEYS: &str = "SELECT column_name AS column_name, referenced_table_name AS ref_table, \
+    referenced_column_name AS ref_column \
+    FROM information_schema.key_column_usage \
+    WHERE table_schema = DATABASE() AND table_name = ? AND referenced_table_name IS NOT NULL \
+    ORDER BY constraint_name, ordinal_position";
+
+const PG_COLUMNS: &str = "SELECT a.attname::text AS name, format_type(a.atttypid, a.atttypmod) AS data_type, \
+    CASE WHEN a.attnotnull THEN 0 ELSE 1 END::int8 AS nullable, \
+    pg_get_expr(d.adbin, d.adrelid) AS default_value, \
+    CASE WHEN i.indrelid IS NULL THEN 0 ELSE 1 END::int8 AS primary_key \
+    FROM pg_attribute a \
+    LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+    LEFT JOIN pg_index i ON i.indrelid = a.attrelid AND i.indisprimary AND a.attnum = ANY(i.indkey) \
+    WHERE a.attrelid = to_regclass(?) AND a.attnum > 0 AND NOT a.attisdropped \
+    ORDER BY a.attnum";
+
+const PG_FOREIGN_KEYS: &str = "SELECT a.attname::text AS column_name, c.confrelid::regclass::text AS ref_table, \
+    af.attname::text AS ref_column \
+    FROM pg_constraint c \
+    CROSS JOIN LATERAL unnest(c.conkey, c.confkey) AS k(col, ref_col) \
+    JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.col \
+    JOIN pg_attribute af ON af.attrelid = c.confrelid AND af.attnum = k.ref_col \
+    WHERE c.contype = 'f' AND c.conrelid = to_regclass(?) \
+    ORDER BY c.conname";
+
+const SQLITE_COLUMNS: &str = "SELECT name, type AS data_type, \"notnull\" = 0 AS nullable, \
+    dflt_value AS default_value, pk > 0 AS primary_key \
+    FROM pragma_table_info(?) ORDER BY cid";
+
+const SQLITE_FOREIGN_KEYS: &str = "SELECT \"from\" AS column_name, \"table\" AS ref_table, \"to\" AS ref_column \
+    FROM pragma_foreign_key_list(?) ORDER BY id, seq";
+
+impl DbPool {
+    /// 读取表结构：列、类型、可空性、默认值、主键与外键
+    ///
+    /// MySQL 查询当前库的 `information_schema`，Postgres 查询 `pg_catalog`（表名可带 schema，
+    /// 按 `search_path` 解析），SQLite 使用 `PRAGMA table_info`。表不存在时返回错误。
+    pub async fn describe_table(&self, table: &str) -> Result<TableSchema, DbError> {
+        let (columns_sql, foreign_keys_sql) = match Dialect::of(self)? {
+            Dialect::MySql => (MYSQL_COLUMNS, MYSQL_FOREIGN_KEYS),
+            Dialect::Postgres => (PG_COLUMNS, PG_FOREIGN_KEYS),
+            Dialect::Sqlite => (SQLITE_COLUMNS, SQLITE_FOREIGN_KEYS),
+        };
+        let rows: Vec<ColumnRow> = self.list(columns_sql, vec![Value::from(table)]).await?;
+        if rows.is_empty() {
+            return Err(DbError::from(format!(
+                "Table '{}' not found in database '{}'",
+                table, self.name
+            )));
+        }
+        let foreign_keys: Vec<ForeignKeyRow> = self
+            .list(foreign_keys_sql, vec![Value::from(table)])
+            .await?;
+
+        let columns: Vec<ColumnSchema> = rows
+            .into_iter()
+            .map(|r| ColumnSchema {
+                name: r.name,
+                data_type: r.data_type.unwrap_or_default(),
+                nullable: r.nullable != 0,
+                default: r.default_value,
+                primary_key: r.primary_key != 0,
+            })
+            .collect();
+        Ok(TableSchema {
+            name: table.to_string(),
+            primary_key: columns
+                .iter()
+                .filter(|c| c.primary_key)
+                .map(|c| c.name.clone())
+                .collect(),
+            columns,
+            foreign_keys: foreign_keys
+                .into_iter()
+                .map(|r| ForeignKey {
+                    column: r.column_name,
+                    ref_table: r.ref_table,
+                    ref_column: r.ref_column,
+                })
+                .collect(),
+        })
+    }
+}
@@ -12,6 +12,12 @@ use anyhow::{Context, Result};
 pub struct IdMapper {
     pub use_generated_keys: Option<String>,
     pub key_column: Option<String>,
+    /// 乐观锁版本列，见 `versionColumn`
+    pub version_column: Option<String>,
+    /// 软删除标记列，见 `softDelete`
+    pub soft_delete_column: Option<String>,
+    /// 审计列配置，见 `audit`
+    pub audit: Option<String>,
 }
 
 pub type ContentMap = HashMap<String, HashMap<String, Option<String>>>;
@@ -59,15 +65,34 @@ pub struct SqlItem {
     pub use_generated_keys: Option<String>,
     #[serde(rename = "@keyColumn")]
     pub key_column: Option<String>,
+    #[serde(rename = "@versionColumn")]
+    pub version_column: Option<String>,
+    #[serde(rename = "@softDelete")]
+    pub soft_delete_column: Option<String>,
+    #[serde(rename = "@audit")]
+    pub audit: Option<String>,
     #[serde(rename = "$text")]
     pub content: Option<String>,
 }
 
+impl IdMapper {
+    /// `useGeneratedKeys="true"` 时返回主键列名，未指定 `keyColumn` 时默认为 `id`
+    pub fn generated_key_column(&self) -> Option<&str> {
+        match self.use_generated_keys.as_deref() {
+            Some(v) if v.eq_ignore_ascii_case("true") => Some(self.key_column.as_deref().unwrap_or("id")),
+            _ => None,
+        }
+    }
+}
+
 impl From<&SqlItem> for IdMapper {
     fn from(item: &SqlItem) -> Self {
         Self {
             use_generated_keys: item.use_generated_keys.clone(),
             key_column: item.key_column.clone(),
+            version_column: item.version_column.clone(),
+            soft_delete_column: item.soft_delete_column.clone(),
+            audit: item.audit.clone(),
         }
     }
 }
@@ -81,7 +106,7 @@ pub fn parse_mappers_recursively(
     for entry in WalkDir::new(dir_path).into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
         
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "xml") {
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "xml") {
             process_mapper_file(path, content_map, mapper_map)?;
         }
     }
@@ -95,7 +120,17 @@ fn process_mapper_file(
 ) -> Result<()> {
     let xml_content = fs::read_to_string(path)
         .with_context(|| format!("读取文件失败: {}", path.display()))?;
-    let mapper: Mapper = de::from_str(&xml_content)
+    parse_mapper_str(&xml_content, path, content_map, mapper_map)
+}
+
+/// 解析单个 mapper XML 内容，`path` 仅用于错误信息
+pub fn parse_mapper_str(
+    xml_content: &str,
+    path: &Path,
+    content_map: &mut ContentMap,
+    mapper_map: &mut MapperMap,
+) -> Result<()> {
+    let mapper: Mapper = de::from_str(xml_content)
         .with_context(|| format!("XML 解析失败: {}", path.display()))?;
     let namespace = mapper.namespace;
 
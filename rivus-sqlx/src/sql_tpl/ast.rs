@@ -6,7 +6,34 @@ pub enum AstNode {
     Var(String),
     Include { refid: String },
     If { test: String, body: Vec<AstNode> },
-    For { item: String, collection: String, open: String, sep: String, close: String, body: Vec<AstNode> },
+    /// `<for>`：遍历列表（index 为下标）或 Map（index 为键）
+    For {
+        item: String,
+        index: Option<String>,
+        collection: String,
+        open: String,
+        sep: String,
+        close: String,
+        body: Vec<AstNode>,
+    },
+    /// `<bind name=".." value=".."/>`：定义局部变量，作用于其后的兄弟节点
+    Bind {
+        name: String,
+        value: String,
+    },
+    /// `<choose>`：依次匹配 `<when>`，均不满足时使用 `<otherwise>`
+    Choose {
+        whens: Vec<(String, Vec<AstNode>)>,
+        otherwise: Option<Vec<AstNode>>,
+    },
+    /// `<trim>`，`<where>` 与 `<set>` 为其预设形式
+    Trim {
+        prefix: String,
+        suffix: String,
+        prefix_overrides: Vec<String>,
+        suffix_overrides: Vec<String>,
+        body: Vec<AstNode>,
+    },
 }
 
 pub struct RenderBuffer {
@@ -17,6 +44,8 @@ pub struct RenderBuffer {
 pub struct Context<'a> {
     root: &'a Value,
     locals: Vec<(String, &'a Value)>,
+    /// 当前模板所属命名空间，用于解析相对 `<include refid>`
+    namespace: String,
 }
 
 impl<'a> Context<'a> {
@@ -24,18 +53,58 @@ impl<'a> Context<'a> {
         Self {
             root,
             locals: Vec::new(),
+            namespace: String::new(),
         }
     }
 
+    pub fn in_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// 切换命名空间，返回原值
+    pub(crate) fn replace_namespace(&mut self, namespace: String) -> String {
+        std::mem::replace(&mut self.namespace, namespace)
+    }
+
     pub fn push(&mut self, key: &str, value: &'a Value) {
         self.locals.push((key.to_string(), value));
     }
 
+    /// 派生一个追加了局部变量的上下文（用于 `<bind>`）
+    pub fn with_local<'b>(&self, key: &str, value: &'b Value) -> Context<'b>
+    where
+        'a: 'b,
+    {
+        let mut locals: Vec<(String, &'b Value)> =
+            self.locals.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        locals.push((key.to_string(), value));
+        Context {
+            root: self.root,
+            locals,
+            namespace: self.namespace.clone(),
+        }
+    }
+
     pub fn pop(&mut self) {
         self.locals.pop();
     }
 
+    /// 查找变量，支持 `a.b.c` 形式访问嵌套 Map
     pub fn lookup(&self, key: &str) -> &'a Value {
+        let mut path = key.split('.');
+        let head = path.next().unwrap_or_default();
+        path.fold(self.lookup_name(head), |v, seg| match v {
+            Value::Map(m) => m.get(seg).unwrap_or(&Value::Null),
+            _ => &Value::Null,
+        })
+    }
+
+    fn lookup_name(&self, key: &str) -> &'a Value {
         // First check locals (stack) in reverse order
         for (k, v) in self.locals.iter().rev() {
             if k == key {
@@ -1,29 +1,65 @@
+use crate::sql_parser::ContentMap;
 use crate::sql_tpl::ast::{Context, RenderBuffer};
 use crate::sql_tpl::{cache, render};
-use crate::sql_tpl::value::{to_value, SqlParam};
+use crate::sql_tpl::value::{to_value, SqlParam, Value};
 
-/// 渲染模板，返回 SQL 和参数
+/// 渲染模板，返回 SQL 和参数；表达式求值出错时 panic，见 [`try_render_value`]
 pub fn render_template<T: serde::Serialize>(
     template_name: &str,
     template_content: &str,
     param: &T,
 ) -> (String, Vec<SqlParam>) {
+    // 序列化参数为 Value
+    render_value(template_name, template_content, &to_value(param))
+}
+
+/// 使用已构造的 [`Value`] 渲染模板，可直接携带 `Value::Uuid` / `Value::Json` 等类型化参数
+///
+/// 表达式求值出错时 panic，见 [`try_render_value`]
+pub fn render_value(
+    template_name: &str,
+    template_content: &str,
+    value: &Value,
+) -> (String, Vec<SqlParam>) {
+    try_render_value(template_name, template_content, value)
+        .unwrap_or_else(|e| panic!("failed to render template {template_name}: {e}"))
+}
+
+/// 同 [`render_value`]，`<bind>` 表达式求值出错（如整数相加溢出）时返回错误
+pub fn try_render_value(
+    template_name: &str,
+    template_content: &str,
+    value: &Value,
+) -> Result<(String, Vec<SqlParam>), String> {
     // 获取 AST（缓存）
     let ast = cache::get_ast(template_name, template_content);
 
-    // 序列化参数为 Value
-    let value = to_value(param);
-
     // 创建渲染上下文
     let mut buf = RenderBuffer {
         sql: String::with_capacity(template_content.len()),
         params: Vec::with_capacity(10),
     };
 
-    let mut ctx = Context::new(&value);
-    render::render(&ast, &mut ctx, &mut buf);
+    let mut ctx = Context::new(value).in_namespace(render::namespace_of(template_name));
+    render::render(&ast, &mut ctx, &mut buf)?;
+
+    Ok((buf.sql, buf.params))
+}
+
+/// 注册模板（如 `<sql>` 片段）但不渲染，供 `<include>` 引用
+pub fn register_template(template_name: &str, template_content: &str) {
+    cache::get_ast(template_name, template_content);
+}
 
-    (buf.sql, buf.params)
+/// 将 mapper 解析结果按 `namespace.id` 注册为模板
+pub fn register_mappers(content_map: &ContentMap) {
+    for (namespace, items) in content_map {
+        for (id, content) in items {
+            if let Some(content) = content {
+                register_template(&format!("{}.{}", namespace, id), content);
+            }
+        }
+    }
 }
 
 /// 卸载模板缓存
@@ -2,13 +2,103 @@ use crate::sql_tpl::ast::AstNode;
 
 enum TagFrame {
     If { test: String },
+    Trim {
+        tag: &'static str,
+        prefix: String,
+        suffix: String,
+        prefix_overrides: Vec<String>,
+        suffix_overrides: Vec<String>,
+    },
     For { 
         item: String, 
+        index: Option<String>,
         collection: String, 
         open: String, 
         sep: String, 
         close: String 
     },
+    Choose {
+        whens: Vec<(String, Vec<AstNode>)>,
+        otherwise: Option<Vec<AstNode>>,
+    },
+    When {
+        test: String,
+    },
+    Otherwise,
+}
+
+impl TagFrame {
+    fn into_node(self, body: Vec<AstNode>) -> AstNode {
+        match self {
+            TagFrame::If { test } => AstNode::If { test, body },
+            TagFrame::For {
+                item,
+                index,
+                collection,
+                open,
+                sep,
+                close,
+            } => AstNode::For {
+                item,
+                index,
+                collection,
+                open,
+                sep,
+                close,
+                body,
+            },
+            TagFrame::Trim {
+                prefix,
+                suffix,
+                prefix_overrides,
+                suffix_overrides,
+                ..
+            } => AstNode::Trim {
+                prefix,
+                suffix,
+                prefix_overrides,
+                suffix_overrides,
+                body,
+            },
+            // <choose> 内 <when>/<otherwise> 之外的内容（通常为空白）被忽略
+            TagFrame::Choose { whens, otherwise } => AstNode::Choose { whens, otherwise },
+            // 不在 <choose> 内的 <when>/<otherwise> 按 <if>/无条件处理
+            TagFrame::When { test } => AstNode::If { test, body },
+            TagFrame::Otherwise => AstNode::Choose {
+                whens: Vec::new(),
+                otherwise: Some(body),
+            },
+        }
+    }
+
+    fn tag_name(&self) -> &'static str {
+        match self {
+            TagFrame::If { .. } => "if",
+            TagFrame::Trim { tag, .. } => tag,
+            TagFrame::For { .. } => "for",
+            TagFrame::Choose { .. } => "choose",
+            TagFrame::When { .. } => "when",
+            TagFrame::Otherwise => "otherwise",
+        }
+    }
+}
+
+/// 关闭标签帧，将结果挂到父节点（<when>/<otherwise> 挂到所属 <choose>）
+fn close_frame(
+    frame: TagFrame,
+    body: Vec<AstNode>,
+    tag_stack: &mut [TagFrame],
+    nodes_stack: &mut [Vec<AstNode>],
+) {
+    match (frame, tag_stack.last_mut()) {
+        (TagFrame::When { test }, Some(TagFrame::Choose { whens, .. })) => whens.push((test, body)),
+        (TagFrame::Otherwise, Some(TagFrame::Choose { otherwise, .. })) => *otherwise = Some(body),
+        (frame, _) => {
+            if let Some(parent) = nodes_stack.last_mut() {
+                append_node(parent, frame.into_node(body));
+            }
+        }
+    }
 }
 
 pub fn parse_template(template: &str) -> Vec<AstNode> {
@@ -21,94 +111,149 @@ pub fn parse_template(template: &str) -> Vec<AstNode> {
         let remaining = &template[pos..];
 
         // 1. Check for <if ...>
-        if remaining.starts_with("<if ") {
-             if let Some(end_tag) = find_tag_end(remaining) {
-                let tag_content = &remaining[4..end_tag]; // skip "<if "
-                // Extract test="..."
-                if let Some(test) = extract_attr(tag_content, "test") {
-                    nodes_stack.push(Vec::new());
-                    tag_stack.push(TagFrame::If { test: test.to_string() });
-                    pos += end_tag + 1;
-                    continue;
-                }
-             }
-        }
-        
-        // 2. Check for </if>
-        if remaining.starts_with("</if>") {
-            // Check if the current open tag is <if>
-            if let Some(TagFrame::If { .. }) = tag_stack.last() {
-                if let Some(TagFrame::If { test }) = tag_stack.pop() {
-                     let body = nodes_stack.pop().unwrap_or_default();
-                     append_node(nodes_stack.last_mut().expect("Stack underflow"), AstNode::If { test, body });
-                     pos += 5;
-                     continue;
-                }
+        if remaining.starts_with("<if ")
+            && let Some(end_tag) = find_tag_end(remaining)
+        {
+            let tag_content = &remaining[4..end_tag]; // skip "<if "
+            // Extract test="..."
+            if let Some(test) = extract_attr(tag_content, "test") {
+                nodes_stack.push(Vec::new());
+                tag_stack.push(TagFrame::If {
+                    test: test.to_string(),
+                });
+                pos += end_tag + 1;
+                continue;
             }
         }
 
+        // 2. Check for closing tag of the current frame
+        if let Some(frame) = tag_stack.last()
+            && let Some(tag_len) = closing_tag_len(remaining, frame.tag_name())
+        {
+            let frame = tag_stack.pop().expect("Stack underflow");
+            let body = nodes_stack.pop().unwrap_or_default();
+            close_frame(frame, body, &mut tag_stack, &mut nodes_stack);
+            pos += tag_len;
+            continue;
+        }
+
         // 3. Check for <for ...>
-        if remaining.starts_with("<for ") {
-             if let Some(end_tag) = find_tag_end(remaining) {
-                let tag_content = &remaining[5..end_tag]; // skip "<for "
-                if let (Some(item), Some(collection)) = (extract_attr(tag_content, "item"), extract_attr(tag_content, "collection")) {
-                    let open = extract_attr(tag_content, "open").unwrap_or("");
-                    let sep = extract_attr(tag_content, "sep").unwrap_or(",");
-                    let close = extract_attr(tag_content, "close").unwrap_or("");
-                    
-                    nodes_stack.push(Vec::new());
-                    tag_stack.push(TagFrame::For { 
-                        item: item.to_string(), 
-                        collection: collection.to_string(), 
-                        open: open.to_string(), 
-                        sep: sep.to_string(), 
-                        close: close.to_string() 
-                    });
-                    pos += end_tag + 1;
-                    continue;
-                }
-             }
+        if remaining.starts_with("<for ")
+            && let Some(end_tag) = find_tag_end(remaining)
+        {
+            let tag_content = &remaining[5..end_tag]; // skip "<for "
+            if let (Some(item), Some(collection)) = (
+                extract_attr(tag_content, "item"),
+                extract_attr(tag_content, "collection"),
+            ) {
+                let open = extract_attr(tag_content, "open").unwrap_or("");
+                let sep = extract_attr(tag_content, "sep").unwrap_or(",");
+                let close = extract_attr(tag_content, "close").unwrap_or("");
+
+                nodes_stack.push(Vec::new());
+                tag_stack.push(TagFrame::For {
+                    item: item.to_string(),
+                    index: extract_attr(tag_content, "index").map(str::to_string),
+                    collection: collection.to_string(),
+                    open: open.to_string(),
+                    sep: sep.to_string(),
+                    close: close.to_string()
+                });
+                pos += end_tag + 1;
+                continue;
+            }
         }
-        
-        // 4. Check for </for>
-        if remaining.starts_with("</for>") {
-             if let Some(TagFrame::For { .. }) = tag_stack.last() {
-                 if let Some(TagFrame::For { item, collection, open, sep, close }) = tag_stack.pop() {
-                     let body = nodes_stack.pop().unwrap_or_default();
-                     append_node(nodes_stack.last_mut().expect("Stack underflow"), AstNode::For { 
-                        item, collection, open, sep, close, body 
-                     });
-                     pos += 6;
-                     continue;
-                }
-             }
+
+        // 4. Check for <choose> / <when ...> / <otherwise>
+        if remaining.starts_with("<choose>") {
+            nodes_stack.push(Vec::new());
+            tag_stack.push(TagFrame::Choose {
+                whens: Vec::new(),
+                otherwise: None,
+            });
+            pos += 8;
+            continue;
+        }
+        if remaining.starts_with("<when ")
+            && let Some(end_tag) = find_tag_end(remaining)
+            && let Some(test) = extract_attr(&remaining[6..end_tag], "test")
+        {
+            nodes_stack.push(Vec::new());
+            tag_stack.push(TagFrame::When {
+                test: test.to_string(),
+            });
+            pos += end_tag + 1;
+            continue;
+        }
+        if remaining.starts_with("<otherwise>") {
+            nodes_stack.push(Vec::new());
+            tag_stack.push(TagFrame::Otherwise);
+            pos += 11;
+            continue;
         }
 
-        // 5. Check for <include ... />
-        if remaining.starts_with("<include") {
-             if let Some(end_tag) = find_tag_end(remaining) {
-                let tag_content = &remaining[8..end_tag]; // skip "<include"
-                if let Some(refid) = extract_attr(tag_content, "refid") {
-                    append_node(nodes_stack.last_mut().expect("Stack underflow"), AstNode::Include { refid: refid.to_string() });
-                    pos += end_tag + 1;
-                    continue;
-                }
-             }
+        // 5. Check for <where> / <set> / <trim ...>
+        if let Some((frame, tag_len)) = parse_trim_open(remaining) {
+            nodes_stack.push(Vec::new());
+            tag_stack.push(frame);
+            pos += tag_len;
+            continue;
         }
-        
-        // 6. Check for #{var}
-        if remaining.starts_with("#{") {
-             if let Some(end) = remaining.find('}') {
-                 let var_name = remaining[2..end].trim();
-                 if !var_name.is_empty() {
-                    append_node(nodes_stack.last_mut().expect("Stack underflow"), AstNode::Var(var_name.to_string()));
-                    pos += end + 1;
-                    continue;
-                 }
-             }
+
+        // 6. Check for <include ... />
+        if remaining.starts_with("<include")
+            && let Some(end_tag) = find_tag_end(remaining)
+        {
+            let tag_content = &remaining[8..end_tag]; // skip "<include"
+            if let Some(refid) = extract_attr(tag_content, "refid") {
+                append_node(
+                    nodes_stack.last_mut().expect("Stack underflow"),
+                    AstNode::Include {
+                        refid: refid.to_string(),
+                    },
+                );
+                pos += end_tag + 1;
+                continue;
+            }
         }
-        
-        // 6. Text
+
+        // 7. Check for <bind name=".." value=".."/>
+        if remaining.starts_with("<bind ")
+            && let Some(end_tag) = find_tag_end(remaining)
+        {
+            let tag_content = &remaining[6..end_tag]; // skip "<bind "
+            if let (Some(name), Some(value)) = (
+                extract_attr(tag_content, "name"),
+                extract_attr(tag_content, "value"),
+            ) {
+                append_node(
+                    nodes_stack.last_mut().expect("Stack underflow"),
+                    AstNode::Bind {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    },
+                );
+                pos += end_tag + 1;
+                continue;
+            }
+        }
+
+        // 8. Check for #{var}
+        if remaining.starts_with("#{")
+            && let Some(end) = remaining.find('}')
+        {
+            let var_name = remaining[2..end].trim();
+            if !var_name.is_empty() {
+                append_node(
+                    nodes_stack.last_mut().expect("Stack underflow"),
+                    AstNode::Var(var_name.to_string()),
+                );
+                pos += end + 1;
+                continue;
+            }
+        }
+
+        // 9. Text
         let next_tag = remaining.find('<').unwrap_or(remaining.len());
         let next_var = remaining.find("#{").unwrap_or(remaining.len());
         let next_stop = std::cmp::min(next_tag, next_var);
@@ -126,19 +271,70 @@ pub fn parse_template(template: &str) -> Vec<AstNode> {
     // Auto-close unclosed tags
     while let Some(tag) = tag_stack.pop() {
         let body = nodes_stack.pop().unwrap_or_default();
-        let node = match tag {
-            TagFrame::If { test } => AstNode::If { test, body },
-            TagFrame::For { item, collection, open, sep, close } => AstNode::For { item, collection, open, sep, close, body },
-        };
-        // Add to parent (if exists)
-        if let Some(parent) = nodes_stack.last_mut() {
-            append_node(parent, node);
-        }
+        close_frame(tag, body, &mut tag_stack, &mut nodes_stack);
     }
 
     nodes_stack.pop().unwrap_or_default()
 }
 
+/// 匹配 `</tag>`，返回其长度
+fn closing_tag_len(remaining: &str, tag: &str) -> Option<usize> {
+    let rest = remaining.strip_prefix("</")?.strip_prefix(tag)?;
+    rest.starts_with('>').then_some(tag.len() + 3)
+}
+
+/// 解析 `<where>`、`<set>` 与 `<trim ...>` 开始标签，返回帧与标签长度
+fn parse_trim_open(remaining: &str) -> Option<(TagFrame, usize)> {
+    if remaining.starts_with("<where>") {
+        return Some((
+            TagFrame::Trim {
+                tag: "where",
+                prefix: "WHERE".to_string(),
+                suffix: String::new(),
+                prefix_overrides: vec!["AND".to_string(), "OR".to_string()],
+                suffix_overrides: Vec::new(),
+            },
+            7,
+        ));
+    }
+    if remaining.starts_with("<set>") {
+        return Some((
+            TagFrame::Trim {
+                tag: "set",
+                prefix: "SET".to_string(),
+                suffix: String::new(),
+                prefix_overrides: Vec::new(),
+                suffix_overrides: vec![",".to_string()],
+            },
+            5,
+        ));
+    }
+    if remaining.starts_with("<trim") && remaining[5..].starts_with([' ', '>']) {
+        let end_tag = find_tag_end(remaining)?;
+        let tag_content = &remaining[5..end_tag];
+        let overrides = |key| {
+            extract_attr(tag_content, key)
+                .map(|v| v.split('|').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+        return Some((
+            TagFrame::Trim {
+                tag: "trim",
+                prefix: extract_attr(tag_content, "prefix")
+                    .unwrap_or("")
+                    .to_string(),
+                suffix: extract_attr(tag_content, "suffix")
+                    .unwrap_or("")
+                    .to_string(),
+                prefix_overrides: overrides("prefixOverrides"),
+                suffix_overrides: overrides("suffixOverrides"),
+            },
+            end_tag + 1,
+        ));
+    }
+    None
+}
+
 fn append_node(nodes: &mut Vec<AstNode>, node: AstNode) {
     nodes.push(node);
 }
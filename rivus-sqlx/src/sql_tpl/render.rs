@@ -1,6 +1,7 @@
 use crate::sql_tpl::ast::{AstNode, Context, RenderBuffer};
 use crate::sql_tpl::cache::TEMPLATE_CACHE;
 use crate::sql_tpl::value::{value_to_param, Value};
+use std::sync::Arc;
 
 fn eval_atom(expr: &str, ctx: &Context) -> bool {
     let expr = expr.trim();
@@ -30,6 +31,10 @@ fn eval_atom(expr: &str, ctx: &Context) -> bool {
     {
         match left {
             Value::Str(s) => s == &val_str[1..val_str.len() - 1],
+            Value::Uuid(u) => u
+                .hyphenated()
+                .to_string()
+                .eq_ignore_ascii_case(&val_str[1..val_str.len() - 1]),
             _ => false,
         }
     } else {
@@ -84,8 +89,111 @@ pub fn eval_expr(expr: &str, ctx: &Context) -> bool {
     false
 }
 
-pub(crate) fn render(nodes: &[AstNode], ctx: &mut Context, buf: &mut RenderBuffer) {
-    for node in nodes {
+/// 计算 `<bind>` 的 value 表达式：支持字面量、变量以及 `+` 拼接/相加
+///
+/// 任一操作数为 null 时结果为 null，便于后续用 `<if test="x != null">` 判断；整数相加溢出时返回错误
+pub fn eval_value(expr: &str, ctx: &Context) -> Result<Value, String> {
+    let terms: Vec<Value> = split_plus(expr)
+        .into_iter()
+        .map(|t| eval_term(t, ctx))
+        .collect();
+    if terms.len() == 1 {
+        return Ok(terms.into_iter().next().unwrap_or(Value::Null));
+    }
+    if terms.iter().any(|v| matches!(v, Value::Null)) {
+        return Ok(Value::Null);
+    }
+
+    let ints: Option<Vec<i64>> = terms
+        .iter()
+        .map(|v| match v {
+            Value::I64(n) => Some(*n),
+            Value::I32(n) => Some(*n as i64),
+            Value::I16(n) => Some(*n as i64),
+            Value::U8(n) => Some(*n as i64),
+            _ => None,
+        })
+        .collect();
+    if let Some(ints) = ints {
+        return ints
+            .iter()
+            .try_fold(0i64, |sum, n| sum.checked_add(*n))
+            .map(Value::I64)
+            .ok_or_else(|| format!("integer overflow in expression: {expr}"));
+    }
+
+    let mut s = String::new();
+    for v in &terms {
+        match v {
+            Value::Str(v) => s.push_str(v),
+            Value::Bool(v) => s.push_str(&v.to_string()),
+            Value::I16(v) => s.push_str(&v.to_string()),
+            Value::I32(v) => s.push_str(&v.to_string()),
+            Value::I64(v) => s.push_str(&v.to_string()),
+            Value::U8(v) => s.push_str(&v.to_string()),
+            Value::F64(v) => s.push_str(&v.to_string()),
+            Value::Decimal(v) => s.push_str(&v.to_string()),
+            Value::Date(v) => s.push_str(&v.to_string()),
+            Value::Time(v) => s.push_str(&v.to_string()),
+            Value::DateTime(v) => s.push_str(&v.to_string()),
+            Value::DateTimeUtc(v) => s.push_str(&v.to_string()),
+            Value::Uuid(v) => s.push_str(&v.to_string()),
+            _ => return Ok(Value::Null),
+        }
+    }
+    Ok(Value::Str(s))
+}
+
+fn eval_term(term: &str, ctx: &Context) -> Value {
+    let term = term.trim();
+    if term.len() >= 2
+        && ((term.starts_with('\'') && term.ends_with('\''))
+            || (term.starts_with('"') && term.ends_with('"')))
+    {
+        return Value::Str(term[1..term.len() - 1].to_string());
+    }
+    match term {
+        "" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => {
+            if let Ok(n) = term.parse::<i64>() {
+                Value::I64(n)
+            } else if let Ok(n) = term.parse::<f64>() {
+                Value::F64(n)
+            } else {
+                ctx.lookup(term).clone()
+            }
+        }
+    }
+}
+
+/// 按引号外的 `+` 切分表达式
+fn split_plus(expr: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in expr.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '+') => {
+                parts.push(&expr[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&expr[start..]);
+    parts
+}
+
+pub(crate) fn render(
+    nodes: &[AstNode],
+    ctx: &mut Context,
+    buf: &mut RenderBuffer,
+) -> Result<(), String> {
+    for (i, node) in nodes.iter().enumerate() {
         match node {
             AstNode::Text(t) => buf.sql.push_str(t),
             AstNode::Var(name) => {
@@ -94,45 +202,168 @@ pub(crate) fn render(nodes: &[AstNode], ctx: &mut Context, buf: &mut RenderBuffe
                 buf.params.push(value_to_param(v));
             }
             AstNode::Include { refid } => {
-                if let Some(cached) = TEMPLATE_CACHE.get(refid) {
-                    render(&cached.ast, ctx, buf);
-                }
+                let Some((name, ast)) = resolve_include(refid, ctx.namespace()) else {
+                    continue;
+                };
+                // 片段内的相对引用按片段自身的命名空间解析
+                let ns = namespace_of(&name).to_string();
+                let prev = ctx.replace_namespace(ns);
+                let result = render(&ast, ctx, buf);
+                ctx.replace_namespace(prev);
+                result?;
             }
             AstNode::If { test, body } => {
                 if eval_expr(test, ctx) {
-                    render(body, ctx, buf);
+                    render(body, ctx, buf)?;
                 }
             }
             AstNode::For {
                 item,
+                index,
                 collection,
                 open,
                 sep,
                 close,
                 body,
             } => {
-                let arr = match ctx.lookup(collection) {
-                    Value::List(v) => v,
+                // (index 值, item 值)：列表为下标，Map 按键排序以保证 SQL 稳定
+                let entries: Vec<(Value, &Value)> = match ctx.lookup(collection) {
+                    Value::List(v) => v
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| (Value::I64(i as i64), v))
+                        .collect(),
+                    Value::Map(m) => {
+                        let mut keys: Vec<&String> = m.keys().collect();
+                        keys.sort();
+                        keys.into_iter()
+                            .map(|k| (Value::Str(k.clone()), &m[k]))
+                            .collect()
+                    }
                     _ => continue,
                 };
-                if arr.is_empty() {
+                if entries.is_empty() {
                     continue;
                 }
 
                 buf.sql.push_str(open);
-                for (i, v) in arr.iter().enumerate() {
+                for (i, (idx, v)) in entries.iter().enumerate() {
                     if i > 0 {
                         buf.sql.push_str(sep);
                     }
 
-                    ctx.push(item, v);
-                    render(body, ctx, buf);
-                    ctx.pop();
+                    let mut scoped = ctx.with_local(item, v);
+                    if let Some(index) = index {
+                        scoped.push(index, idx);
+                    }
+                    render(body, &mut scoped, buf)?;
                 }
                 buf.sql.push_str(close);
             }
+            AstNode::Bind { name, value } => {
+                // 绑定值仅对后续兄弟节点可见，剩余节点在派生上下文中渲染
+                let v = eval_value(value, ctx)?;
+                let mut scoped = ctx.with_local(name, &v);
+                return render(&nodes[i + 1..], &mut scoped, buf);
+            }
+            AstNode::Choose { whens, otherwise } => {
+                match whens.iter().find(|(test, _)| eval_expr(test, ctx)) {
+                    Some((_, body)) => render(body, ctx, buf)?,
+                    None => {
+                        if let Some(body) = otherwise {
+                            render(body, ctx, buf)?;
+                        }
+                    }
+                }
+            }
+            AstNode::Trim {
+                prefix,
+                suffix,
+                prefix_overrides,
+                suffix_overrides,
+                body,
+            } => {
+                let mut inner = RenderBuffer {
+                    sql: String::new(),
+                    params: Vec::new(),
+                };
+                render(body, ctx, &mut inner)?;
+
+                let content = trim_overrides(&inner.sql, prefix_overrides, suffix_overrides);
+                if content.is_empty() {
+                    continue;
+                }
+
+                if !prefix.is_empty() {
+                    if !buf.sql.is_empty() && !buf.sql.ends_with(char::is_whitespace) {
+                        buf.sql.push(' ');
+                    }
+                    buf.sql.push_str(prefix);
+                    buf.sql.push(' ');
+                }
+                buf.sql.push_str(content);
+                if !suffix.is_empty() {
+                    buf.sql.push(' ');
+                    buf.sql.push_str(suffix);
+                }
+                buf.params.append(&mut inner.params);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解析 `<include refid>`：优先当前命名空间下的 `namespace.refid`，其次按全名查找
+fn resolve_include(refid: &str, namespace: &str) -> Option<(String, Arc<Vec<AstNode>>)> {
+    if !namespace.is_empty() {
+        let name = format!("{}.{}", namespace, refid);
+        if let Some(cached) = TEMPLATE_CACHE.get(&name) {
+            return Some((name, cached.ast.clone()));
+        }
+    }
+    TEMPLATE_CACHE
+        .get(refid)
+        .map(|cached| (refid.to_string(), cached.ast.clone()))
+}
+
+/// 模板名 `namespace.id` 中的命名空间部分
+pub(crate) fn namespace_of(name: &str) -> &str {
+    name.rsplit_once('.').map(|(ns, _)| ns).unwrap_or("")
+}
+
+/// 去除首尾空白，以及首部/尾部第一个匹配的关键字（忽略大小写）
+fn trim_overrides<'a>(sql: &'a str, prefixes: &[String], suffixes: &[String]) -> &'a str {
+    let mut s = sql.trim();
+    for p in prefixes {
+        if s.len() >= p.len()
+            && s.is_char_boundary(p.len())
+            && s[..p.len()].eq_ignore_ascii_case(p)
+            && (!is_word(p) || !s[p.len()..].starts_with(is_word_char))
+        {
+            s = s[p.len()..].trim_start();
+            break;
         }
     }
+    for x in suffixes {
+        let at = s.len().wrapping_sub(x.len());
+        if s.len() >= x.len()
+            && s.is_char_boundary(at)
+            && s[at..].eq_ignore_ascii_case(x)
+            && (!is_word(x) || !s[..at].ends_with(is_word_char))
+        {
+            s = s[..at].trim_end();
+            break;
+        }
+    }
+    s
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_word(s: &str) -> bool {
+    s.chars().all(is_word_char)
 }
 
 #[cfg(test)]
@@ -148,7 +379,7 @@ mod tests {
         // Truthy check
         // We need to mock lookup. Since context is empty, lookup returns Null.
         // Null is falsey.
-        assert_eq!(eval_atom("var", &ctx), false);
+        assert!(!eval_atom("var", &ctx));
 
         // We need a context with values.
         let mut map = HashMap::new();
@@ -178,4 +409,35 @@ mod tests {
         assert!(eval_expr("x == 1 or y == 3", &ctx));
         assert!(!eval_expr("x == 2 or y == 3", &ctx));
     }
+
+    #[test]
+    fn test_eval_value() {
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), Value::Str("tom".to_string()));
+        map.insert("n".to_string(), Value::I64(2));
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+
+        assert_eq!(
+            eval_value("'%' + name + '%'", &ctx).unwrap(),
+            Value::Str("%tom%".to_string())
+        );
+        assert_eq!(
+            eval_value("'a+b' + n", &ctx).unwrap(),
+            Value::Str("a+b2".to_string())
+        );
+        assert_eq!(eval_value("n + 3", &ctx).unwrap(), Value::I64(5));
+        assert_eq!(
+            eval_value("name", &ctx).unwrap(),
+            Value::Str("tom".to_string())
+        );
+        assert_eq!(eval_value("'%' + missing", &ctx).unwrap(), Value::Null);
+
+        let mut map = HashMap::new();
+        map.insert("big".to_string(), Value::I64(i64::MAX));
+        let root = Value::Map(map);
+        let ctx = Context::new(&root);
+        // 整数相加溢出时返回错误
+        assert!(eval_value("big + 1", &ctx).is_err());
+    }
 }
@@ -7,6 +7,8 @@ use serde::{
         SerializeTupleStruct, SerializeTupleVariant,
     },
 };
+use serde_json::Value as JsonValue;
+use sqlx::types::Uuid;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -26,6 +28,9 @@ pub enum Value {
     DateTime(NaiveDateTime),
     DateTimeUtc(DateTime<Utc>),
     Decimal(Decimal),
+    Uuid(Uuid),
+    /// 整体作为一个 JSON 参数绑定（Postgres 为 JSONB）
+    Json(JsonValue),
     List(Vec<Value>),
     Map(HashMap<String, Value>),
 }
@@ -45,6 +50,8 @@ pub enum SqlParam {
     DateTime(NaiveDateTime),
     DateTimeUtc(DateTime<Utc>),
     Decimal(Decimal),
+    Uuid(Uuid),
+    Json(JsonValue),
     Null,
 }
 
@@ -63,8 +70,82 @@ pub fn value_to_param(v: &Value) -> SqlParam {
         Value::DateTime(v) => SqlParam::DateTime(*v),
         Value::DateTimeUtc(v) => SqlParam::DateTimeUtc(*v),
         Value::Decimal(v) => SqlParam::Decimal(*v),
+        Value::Uuid(v) => SqlParam::Uuid(*v),
+        Value::Json(v) => SqlParam::Json(v.clone()),
         Value::Null => SqlParam::Null,
-        _ => unreachable!("Cannot convert complex value (List/Map) to SqlParam"),
+        // 直接引用的列表 / 对象（如 serde_json::Value 字段）整体作为 JSON 参数
+        Value::List(_) | Value::Map(_) => SqlParam::Json(to_json(v)),
+    }
+}
+
+/// 转为 JSON，日期时间等按各自的 serde 格式输出
+pub fn to_json(v: &Value) -> JsonValue {
+    match v {
+        Value::Null => JsonValue::Null,
+        Value::Bool(v) => JsonValue::Bool(*v),
+        Value::I16(v) => JsonValue::from(*v),
+        Value::I32(v) => JsonValue::from(*v),
+        Value::I64(v) => JsonValue::from(*v),
+        Value::U8(v) => JsonValue::from(*v),
+        Value::F64(v) => JsonValue::from(*v),
+        Value::Str(v) => JsonValue::String(v.clone()),
+        Value::Bytes(v) => JsonValue::from(v.clone()),
+        Value::Date(v) => serde_json::to_value(v).unwrap_or_default(),
+        Value::Time(v) => serde_json::to_value(v).unwrap_or_default(),
+        Value::DateTime(v) => serde_json::to_value(v).unwrap_or_default(),
+        Value::DateTimeUtc(v) => serde_json::to_value(v).unwrap_or_default(),
+        Value::Decimal(v) => JsonValue::String(v.to_string()),
+        Value::Uuid(v) => JsonValue::String(v.hyphenated().to_string()),
+        Value::Json(v) => v.clone(),
+        Value::List(items) => JsonValue::Array(items.iter().map(to_json).collect()),
+        Value::Map(map) => JsonValue::Object(map.iter().map(|(k, v)| (k.clone(), to_json(v))).collect()),
+    }
+}
+
+/// 驱动参数中显式 UUID 的标记键，见 [`uuid_arg`]
+pub const UUID_ARG_KEY: &str = "$uuid";
+
+/// 显式 UUID 驱动参数 `{"$uuid": "..."}`：Postgres 按 uuid 类型绑定，MySQL / SQLite 按标准格式字符串绑定
+///
+/// 普通字符串参数始终按文本绑定，不做 UUID 识别。
+pub fn uuid_arg(v: Uuid) -> JsonValue {
+    let mut map = serde_json::Map::with_capacity(1);
+    map.insert(
+        UUID_ARG_KEY.to_string(),
+        JsonValue::String(v.hyphenated().to_string()),
+    );
+    JsonValue::Object(map)
+}
+
+/// 识别 [`uuid_arg`] 生成的参数
+pub(crate) fn as_uuid_arg(map: &serde_json::Map<String, JsonValue>) -> Option<Uuid> {
+    match map.get(UUID_ARG_KEY) {
+        Some(JsonValue::String(s)) if map.len() == 1 => Uuid::try_parse(s).ok(),
+        _ => None,
+    }
+}
+
+/// 转为驱动绑定使用的参数：UUID 见 [`uuid_arg`]，JSON 保持原结构（MySQL 绑定为 JSON，Postgres 为 JSONB）
+impl From<SqlParam> for JsonValue {
+    fn from(p: SqlParam) -> Self {
+        match p {
+            SqlParam::I16(v) => to_json(&Value::I16(v)),
+            SqlParam::I32(v) => to_json(&Value::I32(v)),
+            SqlParam::I64(v) => to_json(&Value::I64(v)),
+            SqlParam::U8(v) => to_json(&Value::U8(v)),
+            SqlParam::F64(v) => to_json(&Value::F64(v)),
+            SqlParam::String(v) => JsonValue::String(v),
+            SqlParam::Bytes(v) => to_json(&Value::Bytes(v)),
+            SqlParam::Bool(v) => JsonValue::Bool(v),
+            SqlParam::Date(v) => to_json(&Value::Date(v)),
+            SqlParam::Time(v) => to_json(&Value::Time(v)),
+            SqlParam::DateTime(v) => to_json(&Value::DateTime(v)),
+            SqlParam::DateTimeUtc(v) => to_json(&Value::DateTimeUtc(v)),
+            SqlParam::Decimal(v) => to_json(&Value::Decimal(v)),
+            SqlParam::Uuid(v) => uuid_arg(v),
+            SqlParam::Json(v) => v,
+            SqlParam::Null => JsonValue::Null,
+        }
     }
 }
 
@@ -374,6 +455,8 @@ impl_from!(NaiveTime, Time);
 impl_from!(NaiveDateTime, DateTime);
 impl_from!(DateTime<Utc>, DateTimeUtc);
 impl_from!(Decimal, Decimal);
+impl_from!(Uuid, Uuid);
+impl_from!(JsonValue, Json);
 
 impl From<u64> for Value {
     fn from(v: u64) -> Self {
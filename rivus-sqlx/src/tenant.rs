@@ -0,0 +1,78 @@
+use crate::db_conn::ConnManager;
+use crate::db_pool::DbPool;
+use crate::error::DbError;
+use std::borrow::Cow;
+use std::future::Future;
+use std::sync::{Arc, LazyLock, RwLock};
+
+tokio::task_local! {
+    /// 当前请求所属的租户
+    static TENANT_ID: Arc<str>;
+}
+
+/// 租户到连接池的映射
+///
+/// 返回租户对应的连接池名称，该连接池需已通过 [`ConnManager::open`] 注册；
+/// 返回 `None` 表示该连接池不按租户路由（租户连接池自身也应返回 `None`）。
+///
+/// ```ignore
+/// tenant::set_resolver(Arc::new(|pool: &str, tenant: &str| {
+///     (pool == "default").then(|| format!("tenant_{}", tenant))
+/// }));
+/// ```
+pub trait TenantResolver: Send + Sync {
+    fn resolve(&self, pool: &str, tenant: &str) -> Option<String>;
+}
+
+impl<F> TenantResolver for F
+where
+    F: Fn(&str, &str) -> Option<String> + Send + Sync,
+{
+    fn resolve(&self, pool: &str, tenant: &str) -> Option<String> {
+        self(pool, tenant)
+    }
+}
+
+static RESOLVER: LazyLock<RwLock<Option<Arc<dyn TenantResolver>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+/// 设置全局租户路由，替换已有的设置
+pub fn set_resolver(resolver: Arc<dyn TenantResolver>) {
+    *RESOLVER.write().unwrap() = Some(resolver);
+}
+
+/// 移除全局租户路由
+pub fn clear_resolver() {
+    *RESOLVER.write().unwrap() = None;
+}
+
+/// 以指定租户执行，期间的仓库调用与事务路由到该租户的连接池
+pub async fn with_tenant<F: Future>(tenant: impl Into<Arc<str>>, fut: F) -> F::Output {
+    TENANT_ID.scope(tenant.into(), fut).await
+}
+
+/// 当前任务的租户
+pub fn current_tenant() -> Option<Arc<str>> {
+    TENANT_ID.try_with(Arc::clone).ok()
+}
+
+/// 按当前租户选择实际使用的连接池；未设置租户或路由时返回原连接池
+pub(crate) fn route(pool: &DbPool) -> Result<Cow<'_, DbPool>, DbError> {
+    let Some(tenant) = current_tenant() else {
+        return Ok(Cow::Borrowed(pool));
+    };
+    let Some(resolver) = RESOLVER.read().unwrap().clone() else {
+        return Ok(Cow::Borrowed(pool));
+    };
+    match resolver.resolve(&pool.name, &tenant) {
+        Some(name) if name != pool.name => {
+            let mut routed = ConnManager::by(&name)
+                .ok_or_else(|| DbError::from(format!("Database '{}' for tenant '{}' not found", name, tenant)))?;
+            // 保留调用方通过 `with_options` 指定的选项
+            routed.query_timeout = pool.query_timeout.or(routed.query_timeout);
+            routed.cache_statements &= pool.cache_statements;
+            Ok(Cow::Owned(routed))
+        }
+        _ => Ok(Cow::Borrowed(pool)),
+    }
+}
@@ -0,0 +1,166 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::audit::{self, AuditColumns};
+use rivus_sqlx::orm::query::Query;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use rivus_sqlx::sql_parser::{parse_mapper_str, ContentMap, MapperMap};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+const MAPPER: &str = r#"
+<mapper namespace="NoteMapper">
+    <insert id="create" useGeneratedKeys="true" audit="true">
+        INSERT INTO notes (title) VALUES (?)
+    </insert>
+    <update id="rename" audit="true" versionColumn="version">
+        UPDATE notes SET title = ? WHERE id = ?
+    </update>
+    <insert id="createMany" audit="true">
+        INSERT INTO notes (title) VALUES (?), (?)
+    </insert>
+</mapper>
+"#;
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: i64,
+    title: String,
+    version: i64,
+    created_at: Option<String>,
+    created_by: Option<String>,
+    updated_at: Option<String>,
+    updated_by: Option<String>,
+}
+
+async fn note(pool: &DbPool, id: i64) -> Note {
+    pool.get("SELECT * FROM notes WHERE id = ?", vec![Value::from(id)])
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+async fn setup(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE notes (id INTEGER PRIMARY KEY, title TEXT, version INTEGER NOT NULL DEFAULT 0, \
+         created_at TEXT, created_by TEXT, updated_at TEXT, updated_by TEXT)",
+    )
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_audit_with_mapper() {
+    let pool = setup("audit_mapper_test").await;
+    let mut contents = ContentMap::new();
+    let mut mappers = MapperMap::new();
+    parse_mapper_str(
+        MAPPER,
+        Path::new("NoteMapper.xml"),
+        &mut contents,
+        &mut mappers,
+    )
+    .unwrap();
+    let sql = |id: &str| contents["NoteMapper"][id].as_deref().unwrap();
+    let mapper = |id: &str| &mappers["NoteMapper"][id];
+    let repo = SqlxRepository;
+
+    let id = audit::with_user(
+        "alice",
+        repo.insert_returning_key(
+            &pool,
+            sql("create"),
+            vec![Value::from("a")],
+            mapper("create"),
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let created = note(&pool, id).await;
+    assert_eq!(created.title, "a");
+    assert!(created.created_at.is_some());
+    assert_eq!(created.created_by.as_deref(), Some("alice"));
+    assert!(created.updated_at.is_none());
+
+    let renamed = audit::with_user(
+        "bob",
+        repo.update_with_mapper(
+            &pool,
+            sql("rename"),
+            vec![Value::from("b"), Value::from(id)],
+            mapper("rename"),
+            0,
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(renamed, 1);
+    let updated = note(&pool, id).await;
+    assert_eq!((updated.title.as_str(), updated.version), ("b", 1));
+    assert_eq!(updated.created_by.as_deref(), Some("alice"));
+    assert!(updated.updated_at.is_some());
+    assert_eq!(updated.updated_by.as_deref(), Some("bob"));
+
+    // 没有操作人时只填充时间
+    let id = repo
+        .insert_returning_key(
+            &pool,
+            sql("create"),
+            vec![Value::from("c")],
+            mapper("create"),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let anonymous = note(&pool, id).await;
+    assert!(anonymous.created_at.is_some());
+    assert!(anonymous.created_by.is_none());
+
+    let err = repo
+        .insert_returning_key(
+            &pool,
+            sql("createMany"),
+            vec![Value::from("d"), Value::from("e")],
+            mapper("createMany"),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("audit requires"));
+}
+
+#[tokio::test]
+async fn test_audit_with_query() {
+    let pool = setup("audit_query_test").await;
+    let notes = Query::table("notes").audit(AuditColumns::default());
+
+    audit::with_user("carol", async {
+        notes
+            .insert(
+                &pool,
+                vec![("id", Value::from(1)), ("title", Value::from("x"))],
+            )
+            .await
+            .unwrap();
+        notes
+            .clone()
+            .filter("id = ?", 1)
+            .update(&pool, vec![("title", Value::from("y"))])
+            .await
+            .unwrap();
+    })
+    .await;
+
+    let n = note(&pool, 1).await;
+    assert_eq!(n.id, 1);
+    assert_eq!(n.title, "y");
+    assert_eq!(n.created_by.as_deref(), Some("carol"));
+    assert_eq!(n.updated_by.as_deref(), Some("carol"));
+    assert!(n.created_at.is_some() && n.updated_at.is_some());
+}
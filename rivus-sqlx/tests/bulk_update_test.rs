@@ -0,0 +1,131 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    id: i64,
+    name: String,
+    stock: i64,
+}
+
+async fn items(pool: &DbPool) -> Vec<Item> {
+    pool.list("SELECT id, name, stock FROM items ORDER BY id", vec![])
+        .await
+        .unwrap()
+}
+
+fn item(id: i64, name: &str, stock: i64) -> Item {
+    Item {
+        id,
+        name: name.to_string(),
+        stock,
+    }
+}
+
+async fn setup(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL, stock INTEGER NOT NULL)",
+    )
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_bulk_update_sqlite() {
+    let pool = setup("bulk_update_test").await;
+    pool.execute_raw("INSERT INTO items VALUES (1, 'a', 1), (2, 'b', 2), (3, 'c', 3)")
+        .await
+        .unwrap();
+
+    let repo = SqlxRepository;
+    let rows = vec![
+        vec![json!(1), json!("apple"), json!(10)],
+        vec![json!(3), json!("cherry"), json!(30)],
+        vec![json!(4), json!("missing"), json!(40)],
+    ];
+    let affected = repo
+        .bulk_update(&pool, "items", "id", &["name", "stock"], rows)
+        .await
+        .unwrap();
+    assert_eq!(affected, 2);
+    assert_eq!(
+        items(&pool).await,
+        vec![item(1, "apple", 10), item(2, "b", 2), item(3, "cherry", 30)]
+    );
+
+    // 只更新部分列
+    let rows = vec![vec![json!(2), json!(20)]];
+    assert_eq!(
+        repo.bulk_update(&pool, "items", "id", &["stock"], rows)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(items(&pool).await[1], item(2, "b", 20));
+
+    assert_eq!(
+        repo.bulk_update(&pool, "items", "id", &["stock"], vec![])
+            .await
+            .unwrap(),
+        0
+    );
+
+    // 每行值个数不匹配
+    let err = repo
+        .bulk_update(
+            &pool,
+            "items",
+            "id",
+            &["name", "stock"],
+            vec![vec![json!(1), json!("x")]],
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("3 values per row"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_bulk_update_chunks_in_transaction() {
+    let pool = setup("bulk_update_chunk_test").await;
+    let total = 12_000;
+    let values: Vec<Vec<Value>> = (1..=total)
+        .map(|i| vec![json!(i), json!("n"), json!(0)])
+        .collect();
+    pool.batch_create::<Value>(
+        "INSERT INTO items (id, name, stock) VALUES (?, ?, ?)",
+        values,
+    )
+    .await
+    .unwrap();
+
+    // SQLite 上限 32766 个参数，单列每行 3 个参数，需分两条语句执行
+    let rows: Vec<Vec<Value>> = (1..=total).map(|i| vec![json!(i), json!(i * 2)]).collect();
+    let affected = SqlxRepository
+        .bulk_update(&pool, "items", "id", &["stock"], rows)
+        .await
+        .unwrap();
+    assert_eq!(affected, total as u64);
+
+    let all = items(&pool).await;
+    assert!(all.iter().all(|it| it.stock == it.id * 2));
+
+    // 后一条语句失败时整体回滚
+    let mut rows: Vec<Vec<Value>> = (1..=total).map(|i| vec![json!(i), json!(-1)]).collect();
+    rows[total as usize - 1][1] = Value::Null;
+    assert!(
+        SqlxRepository
+            .bulk_update(&pool, "items", "id", &["stock"], rows)
+            .await
+            .is_err()
+    );
+    assert!(items(&pool).await.iter().all(|it| it.stock == it.id * 2));
+}
@@ -0,0 +1,194 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::cache::{CacheBackend, CachePolicy, CachedRepository, MemoryCache};
+use rivus_sqlx::orm::crud_traits::CrudRepository;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Dict {
+    code: String,
+    label: String,
+}
+
+const GET_SQL: &str = "SELECT code, label FROM dict WHERE code = ?";
+const LIST_SQL: &str = "SELECT code, label FROM dict ORDER BY code";
+
+async fn new_pool(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE dict (code TEXT PRIMARY KEY, label TEXT)")
+        .await
+        .unwrap();
+    pool.execute_raw("INSERT INTO dict (code, label) VALUES ('a', 'A'), ('b', 'B')")
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_cache_hit_and_tag_invalidation() {
+    let pool = new_pool("cache_tag").await;
+    let repo = CachedRepository::new(SqlxRepository)
+        .cache(
+            GET_SQL,
+            CachePolicy::new(Duration::from_secs(60)).tag("dict"),
+        )
+        .cache(
+            LIST_SQL,
+            CachePolicy::new(Duration::from_secs(60)).tag("dict"),
+        );
+
+    let a: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("a")])
+        .await
+        .unwrap();
+    assert_eq!(a.unwrap().label, "A");
+    let list: Vec<Dict> = repo.list(&pool, LIST_SQL, vec![]).await.unwrap();
+    assert_eq!(list.len(), 2);
+
+    repo.update(
+        &pool,
+        "UPDATE dict SET label = ? WHERE code = ?",
+        vec![Value::from("A2"), Value::from("a")],
+    )
+    .await
+    .unwrap();
+
+    // 命中缓存，仍为旧值
+    let a: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("a")])
+        .await
+        .unwrap();
+    assert_eq!(a.unwrap().label, "A");
+
+    // 未注册的 SQL 不缓存
+    let fresh: Option<Dict> = repo
+        .get(
+            &pool,
+            "SELECT code, label FROM dict WHERE code = ? LIMIT 1",
+            vec![Value::from("a")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(fresh.unwrap().label, "A2");
+
+    repo.invalidate_tag("dict").await;
+    let a: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("a")])
+        .await
+        .unwrap();
+    assert_eq!(a.unwrap().label, "A2");
+    let list: Vec<Dict> = repo.list(&pool, LIST_SQL, vec![]).await.unwrap();
+    assert_eq!(
+        list[0],
+        Dict {
+            code: "a".to_string(),
+            label: "A2".to_string()
+        }
+    );
+
+    // None 结果同样缓存
+    let missing: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("zz")])
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn test_cache_ttl_expiry() {
+    let pool = new_pool("cache_ttl").await;
+    let repo = CachedRepository::new(SqlxRepository)
+        .cache(GET_SQL, CachePolicy::new(Duration::from_millis(50)));
+
+    let _: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("b")])
+        .await
+        .unwrap();
+    repo.update(
+        &pool,
+        "UPDATE dict SET label = ? WHERE code = ?",
+        vec![Value::from("B2"), Value::from("b")],
+    )
+    .await
+    .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    let b: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("b")])
+        .await
+        .unwrap();
+    assert_eq!(b.unwrap().label, "B2");
+}
+
+#[tokio::test]
+async fn test_memory_cache_lru_eviction() {
+    let cache = MemoryCache::new(2);
+    let policy = CachePolicy::new(Duration::from_secs(60));
+
+    cache.put("k1".to_string(), json!(1), &policy).await;
+    cache.put("k2".to_string(), json!(2), &policy).await;
+    // 访问 k1，使 k2 成为最久未使用
+    assert_eq!(cache.get("k1").await, Some(json!(1)));
+    cache.put("k3".to_string(), json!(3), &policy).await;
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("k2").await, None);
+    assert_eq!(cache.get("k1").await, Some(json!(1)));
+    assert_eq!(cache.get("k3").await, Some(json!(3)));
+}
+
+#[tokio::test]
+async fn test_cache_per_pool_and_bypassed_in_transaction() {
+    let pool = new_pool("cache_pool_a").await;
+    let other = new_pool("cache_pool_b").await;
+    other
+        .execute_raw("UPDATE dict SET label = 'B-A' WHERE code = 'a'")
+        .await
+        .unwrap();
+    let repo = CachedRepository::new(SqlxRepository)
+        .cache(GET_SQL, CachePolicy::new(Duration::from_secs(60)));
+
+    // 不同连接池的缓存互不命中
+    let a: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("a")])
+        .await
+        .unwrap();
+    assert_eq!(a.unwrap().label, "A");
+    let a: Option<Dict> = repo
+        .get(&other, GET_SQL, vec![Value::from("a")])
+        .await
+        .unwrap();
+    assert_eq!(a.unwrap().label, "B-A");
+
+    // 事务中读到本事务的修改，不读写缓存
+    let label = pool
+        .transaction(|tx| {
+            let repo = &repo;
+            async move {
+                repo.update(
+                    &tx,
+                    "UPDATE dict SET label = ? WHERE code = ?",
+                    vec![Value::from("A2"), Value::from("a")],
+                )
+                .await?;
+                let a: Option<Dict> = repo.get(&tx, GET_SQL, vec![Value::from("a")]).await?;
+                Ok::<_, DbError>(a.unwrap().label)
+            }
+        })
+        .await
+        .unwrap();
+    assert_eq!(label, "A2");
+    let a: Option<Dict> = repo
+        .get(&pool, GET_SQL, vec![Value::from("a")])
+        .await
+        .unwrap();
+    assert_eq!(a.unwrap().label, "A");
+}
@@ -0,0 +1,80 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::row_de::{self, ColumnMapping, NamingRule};
+use serde::Deserialize;
+use serde_json::json;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    userId: i64,
+    userName: String,
+    #[serde(default)]
+    nickName: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct LegacyUser {
+    user_id: i64,
+    name: String,
+}
+
+#[test]
+fn test_naming_rules() {
+    assert_eq!(NamingRule::CamelCase.apply("user_name"), "userName");
+    assert_eq!(NamingRule::CamelCase.apply("created_at_2"), "createdAt2");
+    assert_eq!(NamingRule::CamelCase.apply("name"), "name");
+    assert_eq!(NamingRule::SnakeCase.apply("userName"), "user_name");
+    assert_eq!(NamingRule::SnakeCase.apply("UserID"), "user_id");
+    assert_eq!(NamingRule::SnakeCase.apply("user_name"), "user_name");
+    assert_eq!(NamingRule::AsIs.apply("user_name"), "user_name");
+
+    let mapping = ColumnMapping::new(NamingRule::CamelCase).alias("usr_nm", "userName");
+    assert_eq!(mapping.field_name("usr_nm"), "userName");
+    assert_eq!(mapping.field_name("nick_name"), "nickName");
+}
+
+#[tokio::test]
+async fn test_column_mapping() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:column_mapping?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("column_mapping", "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE users (user_id INTEGER PRIMARY KEY, usr_nm TEXT NOT NULL, nick_name TEXT)")
+        .await
+        .unwrap();
+    pool.execute("INSERT INTO users (user_id, usr_nm, nick_name) VALUES (?, ?, ?)", vec![json!(1), json!("alice"), json!("al")])
+        .await
+        .unwrap();
+
+    row_de::set_column_mapping(ColumnMapping::new(NamingRule::CamelCase).alias("usr_nm", "userName"));
+    let users: Vec<User> = pool.list("SELECT user_id, usr_nm, nick_name FROM users", vec![]).await.unwrap();
+    assert_eq!(
+        users,
+        vec![User {
+            userId: 1,
+            userName: "alice".into(),
+            nickName: Some("al".into()),
+        }]
+    );
+
+    // 实体映射替换全局映射
+    row_de::set_entity_mapping::<LegacyUser>(ColumnMapping::default().alias("usr_nm", "name"));
+    let legacy: Option<LegacyUser> = pool
+        .get("SELECT user_id, usr_nm FROM users", vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        legacy,
+        Some(LegacyUser {
+            user_id: 1,
+            name: "alice".into(),
+        })
+    );
+
+    row_de::clear_column_mappings();
+    let unmapped: Result<Vec<User>, _> =
+        pool.list("SELECT user_id, usr_nm FROM users", vec![]).await;
+    assert!(unmapped.is_err());
+}
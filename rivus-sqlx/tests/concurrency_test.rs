@@ -15,6 +15,11 @@ async fn test_concurrent_transactions() {
         max_idle_conns: 5,
         timeout: 5,
         max_lifetime: 3600,
+        slow_query_threshold: 1000,
+        query_timeout: 0,
+        tx_max_retries: 0,
+        tx_retry_backoff: 50,
+        statement_cache_size: 100,
     };
 
     let pool = Arc::new(DbPool::new("test_db", "sqlite", &config).await.unwrap());
@@ -0,0 +1,27 @@
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+
+// close_all 会清空全局连接池，单独放在一个测试二进制中
+#[tokio::test]
+async fn test_close_all() {
+    let config = DatabaseOptions::new("sqlite".to_string(), "sqlite::memory:".to_string());
+    ConnManager::open("close_all_a", "sqlite", &config)
+        .await
+        .unwrap();
+    ConnManager::open("close_all_b", "sqlite", &config)
+        .await
+        .unwrap();
+
+    let pool = ConnManager::by("close_all_a").unwrap();
+    assert_eq!(ConnManager::close_all().await, 2);
+
+    assert!(ConnManager::by("close_all_a").is_none());
+    assert!(ConnManager::by("close_all_b").is_none());
+    assert!(
+        pool.execute_raw("SELECT 1").await.is_err(),
+        "Closed pool should reject queries"
+    );
+
+    // 可重复调用
+    assert_eq!(ConnManager::close_all().await, 0);
+}
@@ -0,0 +1,67 @@
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_yaml::YamlLoaderError;
+use serde::Deserialize;
+use std::env;
+
+#[derive(Deserialize)]
+struct AppConfig {
+    database: DatabaseOptions,
+}
+
+#[test]
+fn test_database_options_from_yaml() {
+    unsafe { env::set_var("RIVUS_SQLX_TEST_DB_URL", "sqlite::memory:") };
+
+    let yaml = r#"
+database:
+  type: sqlite
+  url: "${RIVUS_SQLX_TEST_DB_URL}"
+  max_open_conns: 20
+  connection_timeout: 3
+  slow_query_threshold: 500
+  tx_max_retries: 3
+"#;
+    let config: AppConfig = rivus_yaml::load_from_str(yaml).expect("Failed to load config");
+    let db = config.database;
+
+    assert_eq!(db.r#type, "sqlite");
+    assert_eq!(db.url, "sqlite::memory:");
+    assert_eq!(db.max_open_conns, 20);
+    assert_eq!(db.timeout, 3);
+    assert_eq!(db.slow_query_threshold, 500);
+    assert_eq!(db.tx_max_retries, 3);
+    assert_eq!(db.tx_retry_backoff, 50);
+    // 未配置的字段使用默认值
+    assert_eq!(
+        db.max_idle_conns,
+        DatabaseOptions::new(String::new(), String::new()).max_idle_conns
+    );
+}
+
+#[test]
+fn test_database_options_from_yaml_file() {
+    unsafe { env::set_var("RIVUS_SQLX_TEST_DB_FILE_URL", "sqlite::memory:") };
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    std::fs::write(
+        &path,
+        r#"
+app:
+  database:
+    type: sqlite
+    url: "${RIVUS_SQLX_TEST_DB_FILE_URL}"
+    max_open_conns: 5
+"#,
+    )
+    .unwrap();
+
+    let db = DatabaseOptions::from_yaml(&path, "app.database").unwrap();
+    assert_eq!(db.r#type, "sqlite");
+    assert_eq!(db.url, "sqlite::memory:");
+    assert_eq!(db.max_open_conns, 5);
+    assert_eq!(db.timeout, 10);
+
+    let err = DatabaseOptions::from_yaml(&path, "database").unwrap_err();
+    assert!(matches!(err, YamlLoaderError::MissingSection(key) if key == "database"));
+}
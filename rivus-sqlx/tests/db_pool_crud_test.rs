@@ -0,0 +1,231 @@
+use rivus_sqlx::db_pool::{DbPool, TRANSACTION_CONTEXT};
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde::Deserialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    id: i64,
+    name: String,
+}
+
+async fn new_pool(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_pool_crud() {
+    let pool = new_pool("pool_crud").await;
+
+    let item: Item = pool
+        .create(
+            "INSERT INTO items (id, name) VALUES (?, ?) RETURNING id, name",
+            vec![Value::from(1), Value::from("a")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        item,
+        Item {
+            id: 1,
+            name: "a".to_string()
+        }
+    );
+
+    let items: Vec<Item> = pool
+        .batch_create(
+            "INSERT INTO items (id, name) VALUES (?, ?) RETURNING id, name",
+            vec![
+                vec![Value::from(2), Value::from("b")],
+                vec![Value::from(3), Value::from("c")],
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 2);
+
+    let list: Vec<Item> = pool
+        .list(
+            "SELECT id, name FROM items WHERE id > ? ORDER BY id",
+            vec![Value::from(1)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(list.iter().map(|i| i.id).collect::<Vec<_>>(), vec![2, 3]);
+
+    let rows = pool
+        .update(
+            "UPDATE items SET name = ? WHERE id = ?",
+            vec![Value::from("z"), Value::from(2)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    let fetched: Option<Item> = pool
+        .get(
+            "SELECT id, name FROM items WHERE id = ?",
+            vec![Value::from(2)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(fetched.unwrap().name, "z");
+
+    let rows = pool
+        .delete("DELETE FROM items WHERE id = ?", vec![Value::from(3)])
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    let fetched: Option<Item> = pool
+        .get(
+            "SELECT id, name FROM items WHERE id = ?",
+            vec![Value::from(3)],
+        )
+        .await
+        .unwrap();
+    assert!(fetched.is_none());
+}
+
+#[tokio::test]
+async fn test_pool_crud_in_transaction() {
+    let pool = new_pool("pool_crud_tx").await;
+
+    TRANSACTION_CONTEXT
+        .scope(RefCell::new(HashMap::new()), async {
+            pool.start_transaction().await.unwrap();
+            pool.update(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                vec![Value::from(1), Value::from("a")],
+            )
+            .await
+            .unwrap();
+
+            // 事务内可见
+            let fetched: Option<Item> = pool
+                .get(
+                    "SELECT id, name FROM items WHERE id = ?",
+                    vec![Value::from(1)],
+                )
+                .await
+                .unwrap();
+            assert!(fetched.is_some());
+
+            pool.rollback_transaction().await.unwrap();
+        })
+        .await;
+
+    let list: Vec<Item> = pool
+        .list("SELECT id, name FROM items", vec![])
+        .await
+        .unwrap();
+    assert!(list.is_empty());
+}
+
+#[tokio::test]
+async fn test_pool_execute_binds_args() {
+    let pool = new_pool("pool_execute").await;
+
+    let name = "o'brien; DROP TABLE items; --";
+    let rows = pool
+        .execute(
+            "INSERT INTO items (id, name) VALUES (?, ?)",
+            vec![Value::from(1), Value::from(name)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    let fetched: Option<Item> = pool
+        .get(
+            "SELECT id, name FROM items WHERE id = ?",
+            vec![Value::from(1)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(fetched.unwrap().name, name);
+
+    TRANSACTION_CONTEXT
+        .scope(RefCell::new(HashMap::new()), async {
+            pool.start_transaction().await.unwrap();
+            pool.execute("DELETE FROM items WHERE id = ?", vec![Value::from(1)])
+                .await
+                .unwrap();
+            pool.rollback_transaction().await.unwrap();
+        })
+        .await;
+
+    let rows = pool
+        .execute("DELETE FROM items WHERE id = ?", vec![Value::from(1)])
+        .await
+        .unwrap();
+    assert_eq!(rows, 1, "Rolled back delete should leave the row");
+}
+
+#[tokio::test]
+async fn test_pool_batch_create_multi_values() {
+    let pool = new_pool("pool_batch").await;
+
+    let rows: Vec<Vec<Value>> = (1..=500)
+        .map(|i| vec![Value::from(i), Value::from(format!("item-{}", i))])
+        .collect();
+    let items: Vec<Item> = pool
+        .batch_create(
+            "INSERT INTO items (id, name) VALUES (?, ?) RETURNING id, name",
+            rows,
+        )
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 500);
+    assert_eq!(
+        items[499],
+        Item {
+            id: 500,
+            name: "item-500".to_string()
+        }
+    );
+
+    let empty: Vec<Item> = pool
+        .batch_create(
+            "INSERT INTO items (id, name) VALUES (?, ?) RETURNING id, name",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(empty.is_empty());
+}
+
+#[tokio::test]
+async fn test_pool_batch_create_rolls_back_on_failure() {
+    let pool = new_pool("pool_batch_tx").await;
+
+    // INSERT ... SELECT 无法展开为多行，逐行执行；第二行主键冲突时第一行也应回滚
+    let rows = vec![
+        vec![Value::from(1), Value::from("a")],
+        vec![Value::from(1), Value::from("b")],
+    ];
+    let result: Result<Vec<Item>, _> = pool
+        .batch_create(
+            "INSERT INTO items (id, name) SELECT ?, ? RETURNING id, name",
+            rows,
+        )
+        .await;
+    assert!(result.is_err());
+
+    let list: Vec<Item> = pool
+        .list("SELECT id, name FROM items", vec![])
+        .await
+        .unwrap();
+    assert!(
+        list.is_empty(),
+        "Failed batch should roll back earlier rows"
+    );
+}
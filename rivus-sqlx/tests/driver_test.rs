@@ -0,0 +1,189 @@
+use futures::future::BoxFuture;
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::driver::{DbDriver, DriverConnection, DriverPool};
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::sync::{Arc, Mutex};
+
+type Table = Arc<Mutex<Vec<String>>>;
+
+/// 仅支持 `INSERT` / `DELETE` / `SELECT` 的内存表，事务在提交前写入副本
+struct MemDriver;
+
+struct MemPool {
+    table: Table,
+}
+
+struct MemConnection {
+    table: Table,
+    pending: Option<Vec<String>>,
+}
+
+impl DbDriver for MemDriver {
+    fn connect<'a>(
+        &'a self,
+        _config: &'a DatabaseOptions,
+    ) -> BoxFuture<'a, Result<Arc<dyn DriverPool>, DbError>> {
+        Box::pin(async {
+            Ok(Arc::new(MemPool {
+                table: Table::default(),
+            }) as Arc<dyn DriverPool>)
+        })
+    }
+}
+
+impl DriverPool for MemPool {
+    fn acquire(&self) -> BoxFuture<'_, Result<Box<dyn DriverConnection>, DbError>> {
+        Box::pin(async {
+            Ok(Box::new(MemConnection {
+                table: self.table.clone(),
+                pending: None,
+            }) as Box<dyn DriverConnection>)
+        })
+    }
+}
+
+impl MemConnection {
+    fn rows(&mut self) -> Vec<String> {
+        match &self.pending {
+            Some(rows) => rows.clone(),
+            None => self.table.lock().unwrap().clone(),
+        }
+    }
+
+    fn write(&mut self, rows: Vec<String>) {
+        match &mut self.pending {
+            Some(pending) => *pending = rows,
+            None => *self.table.lock().unwrap() = rows,
+        }
+    }
+}
+
+impl DriverConnection for MemConnection {
+    fn execute<'a>(
+        &'a mut self,
+        sql: &'a str,
+        args: Vec<Value>,
+    ) -> BoxFuture<'a, Result<u64, DbError>> {
+        Box::pin(async move {
+            let mut rows = self.rows();
+            let affected = if sql.starts_with("INSERT") {
+                rows.extend(args.iter().filter_map(|v| v.as_str().map(String::from)));
+                args.len() as u64
+            } else if sql.starts_with("DELETE") {
+                let n = rows.len() as u64;
+                rows.clear();
+                n
+            } else {
+                return Err(DbError::from(format!("unsupported statement: {}", sql)));
+            };
+            self.write(rows);
+            Ok(affected)
+        })
+    }
+
+    fn fetch<'a>(
+        &'a mut self,
+        sql: &'a str,
+        args: Vec<Value>,
+    ) -> BoxFuture<'a, Result<Vec<Map<String, Value>>, DbError>> {
+        Box::pin(async move {
+            let filter = args.first().and_then(Value::as_str).map(String::from);
+            let rows = self.rows();
+            if !sql.starts_with("SELECT") {
+                return Err(DbError::from(format!("unsupported query: {}", sql)));
+            }
+            Ok(rows
+                .into_iter()
+                .filter(|name| filter.as_ref().is_none_or(|f| f == name))
+                .map(|name| {
+                    let mut row = Map::new();
+                    row.insert("name".to_string(), json!(name));
+                    row
+                })
+                .collect())
+        })
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), DbError>> {
+        Box::pin(async {
+            self.pending = Some(self.table.lock().unwrap().clone());
+            Ok(())
+        })
+    }
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), DbError>> {
+        Box::pin(async {
+            if let Some(rows) = self.pending.take() {
+                *self.table.lock().unwrap() = rows;
+            }
+            Ok(())
+        })
+    }
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), DbError>> {
+        Box::pin(async {
+            self.pending = None;
+            Ok(())
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    name: String,
+}
+
+async fn names(pool: &DbPool) -> Vec<String> {
+    let items: Vec<Item> = pool.list("SELECT name FROM items", vec![]).await.unwrap();
+    items.into_iter().map(|i| i.name).collect()
+}
+
+#[tokio::test]
+async fn test_registered_driver() {
+    ConnManager::register_driver("memdb", Arc::new(MemDriver));
+    let config = DatabaseOptions::new("memdb".to_string(), "memdb://items".to_string());
+    ConnManager::open("driver_mem", "memdb", &config)
+        .await
+        .unwrap();
+    let pool = ConnManager::by("driver_mem").unwrap();
+
+    let insert = "INSERT INTO items (name) VALUES (?)";
+    assert_eq!(pool.execute(insert, vec![json!("a")]).await.unwrap(), 1);
+    pool.execute(insert, vec![json!("b")]).await.unwrap();
+    assert_eq!(names(&pool).await, vec!["a", "b"]);
+
+    let found: Option<Item> = pool
+        .get("SELECT name FROM items WHERE name = ?", vec![json!("b")])
+        .await
+        .unwrap();
+    assert_eq!(found, Some(Item { name: "b".into() }));
+    let missing: Option<Item> = pool
+        .get("SELECT name FROM items WHERE name = ?", vec![json!("z")])
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+
+    // 事务内读到未提交的写入，回滚后丢弃
+    let failed: Result<(), DbError> = pool
+        .transaction(|tx| async move {
+            tx.execute(insert, vec![json!("c")]).await?;
+            assert_eq!(names(&tx).await, vec!["a", "b", "c"]);
+            Err(DbError::from("abort"))
+        })
+        .await;
+    assert!(failed.is_err());
+    assert_eq!(names(&pool).await, vec!["a", "b"]);
+
+    pool.transaction(|tx| async move { tx.execute(insert, vec![json!("d")]).await })
+        .await
+        .unwrap();
+    assert_eq!(names(&pool).await, vec!["a", "b", "d"]);
+
+    assert_eq!(pool.execute_raw("DELETE FROM items").await.unwrap(), 3);
+    assert!(names(&pool).await.is_empty());
+    assert!(pool.execute_raw("DROP TABLE items").await.is_err());
+}
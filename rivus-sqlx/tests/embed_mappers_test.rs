@@ -0,0 +1,39 @@
+use rivus_sqlx::embed_mappers;
+use rivus_sqlx::sql_tpl::engine::{remove_template, render_template};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct EmptyParam {}
+
+#[test]
+fn test_embed_mappers() -> anyhow::Result<()> {
+    let mappers = embed_mappers!("tests/mappers")?;
+
+    assert_eq!(
+        mappers.sql("EmbedDao", "listUsers").map(str::trim),
+        Some("SELECT * FROM users")
+    );
+    // 子目录同样被嵌入
+    let insert = mappers
+        .mapper("EmbedOrderDao", "insertOrder")
+        .expect("insertOrder not embedded");
+    assert_eq!(insert.generated_key_column(), Some("order_id"));
+
+    mappers.register_templates();
+    let (sql, _) = render_template(
+        "EmbedDao.check",
+        "select <include refid=\"cols\"/> from users",
+        &EmptyParam {},
+    );
+    assert_eq!(sql, "select id, name from users");
+
+    for name in [
+        "EmbedDao.check",
+        "EmbedDao.cols",
+        "EmbedDao.listUsers",
+        "EmbedOrderDao.insertOrder",
+    ] {
+        remove_template(name);
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::Entity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Entity)]
+#[entity(table = "users")]
+struct User {
+    id: i64,
+    #[entity(column = "user_name")]
+    name: String,
+    email: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Entity)]
+#[entity(table = "tags", id = "code")]
+struct Tag {
+    code: String,
+    label: String,
+}
+
+#[tokio::test]
+async fn test_entity_crud() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:entity_crud?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("entity_crud", "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, user_name TEXT NOT NULL, email TEXT)",
+    )
+    .await
+    .unwrap();
+    pool.execute_raw("CREATE TABLE tags (code TEXT PRIMARY KEY, label TEXT NOT NULL)")
+        .await
+        .unwrap();
+    assert_eq!(User::TABLE, "users");
+
+    let mut alice = User {
+        id: 1,
+        name: "alice".into(),
+        email: None,
+    };
+    let bob = User {
+        id: 2,
+        name: "bob".into(),
+        email: Some("bob@example.com".into()),
+    };
+    assert_eq!(alice.insert(&pool).await.unwrap(), 1);
+    assert_eq!(bob.insert(&pool).await.unwrap(), 1);
+    assert_eq!(
+        User::list(&pool).await.unwrap(),
+        vec![alice.clone(), bob.clone()]
+    );
+
+    alice.email = Some("alice@example.com".into());
+    assert_eq!(alice.update_by_id(&pool).await.unwrap(), 1);
+    assert_eq!(User::find_by_id(&pool, 1).await.unwrap(), Some(alice));
+
+    assert_eq!(User::delete_by_id(&pool, 1).await.unwrap(), 1);
+    assert_eq!(User::find_by_id(&pool, 1).await.unwrap(), None);
+    assert_eq!(User::list(&pool).await.unwrap(), vec![bob]);
+
+    let tag = Tag {
+        code: "rs".into(),
+        label: "Rust".into(),
+    };
+    tag.insert(&pool).await.unwrap();
+    assert_eq!(
+        Tag::find_by_id(&pool, "rs".to_string()).await.unwrap(),
+        Some(tag)
+    );
+}
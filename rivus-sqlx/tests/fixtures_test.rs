@@ -0,0 +1,149 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::fixtures;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    id: i64,
+    name: String,
+    active: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Order {
+    id: i64,
+    total: f64,
+    note: Option<String>,
+}
+
+async fn setup(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, active BOOLEAN)",
+    )
+    .await
+    .unwrap();
+    pool.execute_raw(
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER, total REAL, note TEXT)",
+    )
+    .await
+    .unwrap();
+    pool
+}
+
+async fn users(pool: &DbPool) -> Vec<User> {
+    pool.list("SELECT id, name, active FROM users ORDER BY id", vec![])
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_load_fixtures() {
+    let pool = setup("fixtures_load_test").await;
+    pool.execute_raw("INSERT INTO users VALUES (99, 'stale', 1)")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fixtures::load(&pool, "tests/fixtures/shop.yaml")
+            .await
+            .unwrap(),
+        4
+    );
+    assert_eq!(
+        users(&pool).await,
+        vec![
+            User {
+                id: 1,
+                name: "alice".to_string(),
+                active: true
+            },
+            User {
+                id: 2,
+                name: "bob".to_string(),
+                active: false
+            },
+        ]
+    );
+    let orders: Vec<Order> = pool
+        .list("SELECT id, total, note FROM orders ORDER BY id", vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        orders,
+        vec![
+            Order {
+                id: 10,
+                total: 100.5,
+                note: None
+            },
+            Order {
+                id: 11,
+                total: 20.0,
+                note: Some("gift".to_string())
+            },
+        ]
+    );
+
+    // JSON 文件；空列表同样清空对应的表
+    assert_eq!(
+        fixtures::load(&pool, "tests/fixtures/users.json")
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        users(&pool).await,
+        vec![User {
+            id: 3,
+            name: "carol".to_string(),
+            active: true
+        }]
+    );
+    let orders: Vec<Order> = pool
+        .list("SELECT id, total, note FROM orders", vec![])
+        .await
+        .unwrap();
+    assert!(orders.is_empty());
+
+    assert!(
+        fixtures::load(&pool, "tests/fixtures/missing.yaml")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_rollback_isolates_test_body() {
+    let pool = setup("fixtures_rollback_test").await;
+
+    let seen = fixtures::rollback(&pool, |tx| async move {
+        fixtures::load(&tx, "tests/fixtures/shop.yaml")
+            .await
+            .unwrap();
+        users(&tx).await.len()
+    })
+    .await
+    .unwrap();
+    assert_eq!(seen, 2);
+    assert!(users(&pool).await.is_empty());
+
+    // 测试体 panic 时同样回滚
+    let pool2 = pool.clone();
+    let handle = tokio::spawn(async move {
+        fixtures::rollback(&pool2, |tx| async move {
+            tx.execute_raw("INSERT INTO users VALUES (7, 'temp', 1)")
+                .await
+                .unwrap();
+            panic!("assertion failed in test body");
+        })
+        .await
+    });
+    assert!(handle.await.unwrap_err().is_panic());
+    assert!(users(&pool).await.is_empty());
+}
@@ -0,0 +1,118 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::FromRowDe;
+use serde_json::Value;
+
+#[derive(Debug, PartialEq, FromRowDe)]
+struct User {
+    id: i64,
+    #[row_de(column = "user_name")]
+    name: String,
+    age: i32,
+    active: bool,
+    score: f64,
+    email: Option<String>,
+    // 结果集中没有该列
+    nickname: Option<String>,
+}
+
+#[derive(Debug, PartialEq, FromRowDe)]
+struct Pair(i64, String);
+
+#[derive(Debug, FromRowDe)]
+#[allow(dead_code)]
+struct Missing {
+    id: i64,
+    phone: String,
+}
+
+async fn new_pool() -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:from_row_de?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("from_row_de", "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, user_name TEXT NOT NULL, age INTEGER NOT NULL, \
+         active BOOLEAN NOT NULL, score REAL NOT NULL, email TEXT)",
+    )
+    .await
+    .unwrap();
+    pool.execute_raw(
+        "INSERT INTO users VALUES (1, 'alice', 30, 1, 9.5, 'alice@example.com'), (2, 'bob', 25, 0, 7.0, NULL)",
+    )
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_from_row_de() {
+    let pool = new_pool().await;
+
+    let users: Vec<User> = pool
+        .list_as(
+            "SELECT email, score, active, age, user_name, id FROM users ORDER BY id",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        users,
+        vec![
+            User {
+                id: 1,
+                name: "alice".into(),
+                age: 30,
+                active: true,
+                score: 9.5,
+                email: Some("alice@example.com".into()),
+                nickname: None,
+            },
+            User {
+                id: 2,
+                name: "bob".into(),
+                age: 25,
+                active: false,
+                score: 7.0,
+                email: None,
+                nickname: None
+            },
+        ]
+    );
+
+    let user: Option<User> = pool
+        .get_as("SELECT * FROM users WHERE id = ?", vec![Value::from(2)])
+        .await
+        .unwrap();
+    assert_eq!(user.map(|u| u.name).as_deref(), Some("bob"));
+    let user: Option<User> = pool
+        .get_as("SELECT * FROM users WHERE id = ?", vec![Value::from(9)])
+        .await
+        .unwrap();
+    assert!(user.is_none());
+
+    // 元组结构体按位置取列
+    let pairs: Vec<Pair> = pool
+        .list_as("SELECT id, user_name FROM users ORDER BY id", vec![])
+        .await
+        .unwrap();
+    assert_eq!(pairs, vec![Pair(1, "alice".into()), Pair(2, "bob".into())]);
+
+    let err = pool
+        .list_as::<Missing>("SELECT id FROM users", vec![])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Column 'phone' not found"));
+    let err = pool
+        .list_as::<Pair>("SELECT id FROM users", vec![])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Column '1' not found"));
+
+    let empty: Vec<User> = pool
+        .list_as("SELECT * FROM users WHERE id > 10", vec![])
+        .await
+        .unwrap();
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,95 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use rivus_sqlx::sql_parser::IdMapper;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    id: i64,
+    name: String,
+}
+
+fn mapper(use_generated_keys: Option<&str>, key_column: Option<&str>) -> IdMapper {
+    IdMapper {
+        use_generated_keys: use_generated_keys.map(str::to_string),
+        key_column: key_column.map(str::to_string),
+        version_column: None,
+        soft_delete_column: None,
+        audit: None,
+    }
+}
+
+#[tokio::test]
+async fn test_generated_keys_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:generated_key_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("generated_key_test", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT)")
+        .await
+        .unwrap();
+
+    let repo = SqlxRepository;
+    let sql = "INSERT INTO users (name) VALUES (?)";
+
+    let key = repo
+        .insert_returning_key(
+            &pool,
+            sql,
+            vec![Value::from("a")],
+            &mapper(Some("true"), Some("id")),
+        )
+        .await
+        .unwrap();
+    assert_eq!(key, Some(1));
+
+    let user: User = repo
+        .create_with_mapper(
+            &pool,
+            sql,
+            vec![Value::from("b")],
+            &mapper(Some("true"), None),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        user,
+        User {
+            id: 2,
+            name: "b".to_string()
+        }
+    );
+
+    // 未开启 useGeneratedKeys 时只执行插入
+    let key = repo
+        .insert_returning_key(&pool, sql, vec![Value::from("c")], &mapper(None, None))
+        .await
+        .unwrap();
+    assert_eq!(key, None);
+    assert!(
+        repo.create_with_mapper::<User>(&pool, sql, vec![Value::from("d")], &mapper(None, None))
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_generated_key_column() {
+    assert_eq!(
+        mapper(Some("true"), Some("uid")).generated_key_column(),
+        Some("uid")
+    );
+    assert_eq!(
+        mapper(Some("TRUE"), None).generated_key_column(),
+        Some("id")
+    );
+    assert_eq!(
+        mapper(Some("false"), Some("uid")).generated_key_column(),
+        None
+    );
+}
@@ -0,0 +1,108 @@
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::{delete, insert, select, update};
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, DbError>;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct User {
+    id: i64,
+    name: String,
+    age: i64,
+}
+
+pub struct UserDao;
+
+impl UserDao {
+    #[insert("INSERT INTO users (id, name, age) VALUES (#{id}, #{name}, #{age})")]
+    pub async fn insert(user: &User) -> Result<u64> {}
+
+    #[select("SELECT id, name, age FROM users WHERE id = #{id}")]
+    pub async fn get(id: i64) -> Result<Option<User>> {}
+
+    #[select(
+        r#"SELECT id, name, age FROM users
+        <where><if test="min_age != null">age >= #{min_age}</if></where>
+        ORDER BY id"#
+    )]
+    pub async fn list(min_age: Option<i64>) -> Result<Vec<User>> {}
+
+    #[update("UPDATE users SET name = #{name} WHERE id = #{id}")]
+    pub async fn rename(id: i64, name: &str) -> Result<u64> {
+        let updated = exec!()?;
+        assert!(updated <= 1);
+        Ok(updated)
+    }
+
+    #[delete("DELETE FROM users WHERE id = #{id}")]
+    pub async fn delete(id: i64) -> Result<()> {}
+}
+
+#[derive(Deserialize)]
+struct Count {
+    n: i64,
+}
+
+// 同名函数各自使用自己的语句
+#[select("SELECT COUNT(*) AS n FROM users")]
+async fn count() -> Result<Count> {}
+
+mod adults {
+    use super::*;
+
+    #[select("SELECT COUNT(*) AS n FROM users WHERE age >= 18")]
+    pub async fn count() -> Result<Count> {}
+}
+
+#[tokio::test]
+async fn test_inline_sql() -> anyhow::Result<()> {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:inline_sql?mode=memory&cache=shared".to_string(),
+    );
+    ConnManager::open("default", "sqlite", &config).await?;
+    ConnManager::get()
+        .unwrap()
+        .execute_raw(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER NOT NULL)",
+        )
+        .await?;
+
+    for (id, name, age) in [(1, "alice", 30), (2, "bob", 15), (3, "carol", 42)] {
+        assert_eq!(
+            UserDao::insert(&User {
+                id,
+                name: name.into(),
+                age
+            })
+            .await?,
+            1
+        );
+    }
+
+    assert_eq!(
+        UserDao::get(2).await?.map(|u| u.name).as_deref(),
+        Some("bob")
+    );
+    assert_eq!(UserDao::get(9).await?, None);
+    assert_eq!(UserDao::list(None).await?.len(), 3);
+    assert_eq!(
+        UserDao::list(Some(18))
+            .await?
+            .iter()
+            .map(|u| u.id)
+            .collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+
+    assert_eq!(UserDao::rename(1, "alicia").await?, 1);
+    assert_eq!(UserDao::get(1).await?.unwrap().name, "alicia");
+
+    assert_eq!(count().await?.n, 3);
+    assert_eq!(adults::count().await?.n, 2);
+    UserDao::delete(2).await?;
+    assert_eq!(count().await?.n, 2);
+    Ok(())
+}
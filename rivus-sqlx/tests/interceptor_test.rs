@@ -0,0 +1,156 @@
+use futures::TryStreamExt;
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::interceptor::{Interceptor, QueryContext, add_interceptor};
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Doc {
+    id: i64,
+    tenant: String,
+}
+
+async fn setup(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE docs (id INTEGER PRIMARY KEY, tenant TEXT NOT NULL)")
+        .await
+        .unwrap();
+    pool.execute_raw("INSERT INTO docs VALUES (1, 'a'), (2, 'b'), (3, 'a')")
+        .await
+        .unwrap();
+    pool
+}
+
+// 拦截器为全局注册，各测试只处理自己的连接池
+
+/// 为查询追加租户条件
+struct TenantFilter {
+    pool: &'static str,
+}
+
+impl Interceptor for TenantFilter {
+    fn before_query(&self, ctx: &mut QueryContext) -> Result<(), DbError> {
+        if ctx.pool == self.pool && ctx.sql.starts_with("SELECT") {
+            ctx.sql = format!("SELECT * FROM ({}) t WHERE t.tenant = ?", ctx.sql);
+            ctx.args.push(json!("a"));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_rewrite_sql() {
+    let pool = setup("interceptor_rewrite_test").await;
+    add_interceptor(Arc::new(TenantFilter {
+        pool: "interceptor_rewrite_test",
+    }));
+
+    let docs: Vec<Doc> = pool
+        .list("SELECT id, tenant FROM docs ORDER BY id", vec![])
+        .await
+        .unwrap();
+    assert_eq!(docs.iter().map(|d| d.id).collect::<Vec<_>>(), vec![1, 3]);
+
+    let doc: Option<Doc> = pool
+        .get("SELECT id, tenant FROM docs WHERE id = ?", vec![json!(2)])
+        .await
+        .unwrap();
+    assert_eq!(doc, None);
+
+    let repo = SqlxRepository;
+    let streamed: Vec<Doc> = repo
+        .stream(&pool, "SELECT id, tenant FROM docs", vec![])
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(streamed.len(), 2);
+}
+
+/// 否决 DELETE，记录全部回调
+#[derive(Default)]
+struct Audit {
+    events: Mutex<Vec<String>>,
+}
+
+impl Interceptor for Audit {
+    fn before_query(&self, ctx: &mut QueryContext) -> Result<(), DbError> {
+        if ctx.pool != "interceptor_audit_test" {
+            return Ok(());
+        }
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("before {}", ctx.sql));
+        if ctx.sql.starts_with("DELETE") {
+            return Err(DbError::from("DELETE is not allowed"));
+        }
+        Ok(())
+    }
+
+    fn after_query(&self, ctx: &QueryContext, rows: u64) {
+        if ctx.pool == "interceptor_audit_test" {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("after {} rows", rows));
+        }
+    }
+
+    fn on_error(&self, ctx: &QueryContext, error: &DbError) {
+        if ctx.pool == "interceptor_audit_test" {
+            self.events.lock().unwrap().push(format!("error {}", error));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_audit_and_veto() {
+    let pool = setup("interceptor_audit_test").await;
+    let audit = Arc::new(Audit::default());
+    add_interceptor(audit.clone());
+
+    let updated = pool
+        .execute(
+            "UPDATE docs SET tenant = ? WHERE tenant = ?",
+            vec![json!("c"), json!("a")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated, 2);
+
+    let err = pool.execute("DELETE FROM docs", vec![]).await.unwrap_err();
+    assert!(err.to_string().contains("DELETE is not allowed"));
+    let remaining: Vec<Doc> = pool
+        .list("SELECT id, tenant FROM docs", vec![])
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 3);
+
+    assert!(
+        pool.execute("UPDATE missing SET x = 1", vec![])
+            .await
+            .is_err()
+    );
+
+    let events = audit.events.lock().unwrap().clone();
+    assert_eq!(
+        events[0],
+        "before UPDATE docs SET tenant = ? WHERE tenant = ?"
+    );
+    assert_eq!(events[1], "after 2 rows");
+    assert_eq!(events[2], "before DELETE FROM docs");
+    assert!(events[3].starts_with("error") && events[3].contains("DELETE is not allowed"));
+    assert_eq!(events[4], "before SELECT id, tenant FROM docs");
+    assert_eq!(events[5], "after 3 rows");
+    assert_eq!(events[6], "before UPDATE missing SET x = 1");
+    assert!(events[7].starts_with("error") && events[7].contains("missing"));
+    assert_eq!(events.len(), 8);
+}
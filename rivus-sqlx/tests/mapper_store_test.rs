@@ -0,0 +1,95 @@
+use rivus_sqlx::mapper_store::MapperStore;
+use rivus_sqlx::sql_tpl::engine::{remove_template, render_template};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[derive(Serialize)]
+struct EmptyParam {}
+
+fn write_mapper(dir: &Path, select: &str) {
+    let xml = format!(
+        r#"<mapper namespace="ReloadDao">
+    <sql id="cols">id, name</sql>
+    <select id="list">{}</select>
+</mapper>"#,
+        select
+    );
+    fs::write(dir.join("ReloadMapper.xml"), xml).unwrap();
+}
+
+#[test]
+fn test_reload_replaces_snapshot() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    write_mapper(dir.path(), "SELECT * FROM users");
+
+    let store = MapperStore::load(dir.path())?;
+    let before = store.snapshot();
+    assert_eq!(
+        before.sql("ReloadDao", "list").map(str::trim),
+        Some("SELECT * FROM users")
+    );
+
+    // fragment 以 namespace.id 注册，可被 <include> 引用
+    let (sql, _) = render_template(
+        "ReloadDao.check",
+        "select <include refid=\"cols\"/> from t",
+        &EmptyParam {},
+    );
+    assert_eq!(sql, "select id, name from t");
+
+    write_mapper(dir.path(), "SELECT id FROM users");
+    store.reload()?;
+    assert_eq!(
+        store.snapshot().sql("ReloadDao", "list").map(str::trim),
+        Some("SELECT id FROM users")
+    );
+    // 旧快照不受影响
+    assert_eq!(
+        before.sql("ReloadDao", "list").map(str::trim),
+        Some("SELECT * FROM users")
+    );
+
+    // 解析失败时保留当前快照
+    fs::write(
+        dir.path().join("Broken.xml"),
+        "<mapper namespace=\"Broken\"><select id=\"a\">",
+    )?;
+    assert!(store.reload().is_err());
+    assert_eq!(
+        store.snapshot().sql("ReloadDao", "list").map(str::trim),
+        Some("SELECT id FROM users")
+    );
+
+    remove_template("ReloadDao.check");
+    remove_template("ReloadDao.cols");
+    remove_template("ReloadDao.list");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_watch_picks_up_changes() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    write_mapper(dir.path(), "SELECT 1");
+
+    let store = MapperStore::load(dir.path())?;
+    let handle = store.watch(Duration::from_millis(20));
+
+    write_mapper(dir.path(), "SELECT 22");
+    let mut reloaded = false;
+    for _ in 0..100 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        if store.snapshot().sql("ReloadDao", "list").map(str::trim) == Some("SELECT 22") {
+            reloaded = true;
+            break;
+        }
+    }
+    assert!(reloaded, "mapper change was not picked up");
+
+    // store 释放后监听任务退出
+    drop(store);
+    tokio::time::timeout(Duration::from_secs(2), handle).await??;
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::db_pool::{DbPool, PoolStats};
+use rivus_sqlx::metrics::{self, MetricsRecorder, QueryEvent};
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// (pool, statement, rows, success)
+type QueryRecord = (String, Option<String>, Option<u64>, bool);
+
+#[derive(Default)]
+struct TestRecorder {
+    queries: Mutex<Vec<QueryRecord>>,
+    acquires: Mutex<Vec<String>>,
+    stats: Mutex<Vec<(String, PoolStats)>>,
+}
+
+impl MetricsRecorder for TestRecorder {
+    fn query(&self, event: &QueryEvent<'_>) {
+        self.queries.lock().unwrap().push((
+            event.pool.to_string(),
+            event.statement.map(str::to_string),
+            event.rows,
+            event.success,
+        ));
+    }
+
+    fn acquire(&self, pool: &str, _elapsed: Duration) {
+        self.acquires.lock().unwrap().push(pool.to_string());
+    }
+
+    fn pool_stats(&self, pool: &str, stats: &PoolStats) {
+        self.stats.lock().unwrap().push((pool.to_string(), *stats));
+    }
+}
+
+// 接收方为全局状态，放在同一个测试中顺序验证
+#[tokio::test]
+async fn test_metrics_recorder() {
+    let recorder = Arc::new(TestRecorder::default());
+    metrics::set_recorder(recorder.clone());
+
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:metrics_pool?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("metrics_pool", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+
+    metrics::with_statement_id("ItemDao.insert", async {
+        assert_eq!(
+            metrics::current_statement_id().as_deref(),
+            Some("ItemDao.insert")
+        );
+        pool.execute(
+            "INSERT INTO items (id, name) VALUES (?, ?)",
+            vec![Value::from(1), Value::from("a")],
+        )
+        .await
+        .unwrap();
+    })
+    .await;
+
+    // 失败的语句按失败统计
+    let err = pool
+        .execute("INSERT INTO missing (id) VALUES (?)", vec![Value::from(1)])
+        .await;
+    assert!(err.is_err());
+
+    let rows: Vec<Value> = pool
+        .list("SELECT id, name FROM items", vec![])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+
+    let queries = recorder.queries.lock().unwrap().clone();
+    assert_eq!(queries.len(), 4);
+    assert!(queries.iter().all(|(p, ..)| p == "metrics_pool"));
+    assert_eq!(
+        queries[1],
+        (
+            "metrics_pool".to_string(),
+            Some("ItemDao.insert".to_string()),
+            Some(1),
+            true
+        )
+    );
+    assert_eq!(queries[2], ("metrics_pool".to_string(), None, None, false));
+    assert_eq!(queries[3].2, Some(1));
+
+    // 绑定参数的语句经由 acquire 获取连接
+    assert_eq!(recorder.acquires.lock().unwrap().len(), 3);
+    {
+        let stats = recorder.stats.lock().unwrap();
+        assert!(
+            stats
+                .iter()
+                .all(|(p, s)| p == "metrics_pool" && s.in_use >= 1)
+        );
+    }
+
+    // ConnManager 管理的连接池按名称上报状态
+    recorder.stats.lock().unwrap().clear();
+    ConnManager::open("metrics_managed", "sqlite", &config)
+        .await
+        .unwrap();
+    ConnManager::report_metrics();
+    assert!(
+        recorder
+            .stats
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(p, _)| p == "metrics_managed")
+    );
+    ConnManager::close("metrics_managed").await;
+
+    metrics::clear_recorder();
+}
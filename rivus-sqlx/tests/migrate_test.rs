@@ -0,0 +1,117 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::migrate::{MigrationState, Migrator};
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use std::fs;
+
+fn write(dir: &std::path::Path, name: &str, sql: &str) {
+    fs::write(dir.join(name), sql).unwrap();
+}
+
+#[derive(serde::Deserialize)]
+struct Table {
+    name: String,
+}
+
+async fn tables(pool: &DbPool) -> Vec<String> {
+    let rows: Vec<Table> = pool
+        .list("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE '\\_%' ESCAPE '\\' ORDER BY name", vec![])
+        .await
+        .unwrap();
+    rows.into_iter().map(|t| t.name).collect()
+}
+
+fn states(status: &[rivus_sqlx::migrate::MigrationStatus]) -> Vec<(i64, MigrationState)> {
+    status.iter().map(|s| (s.version, s.state)).collect()
+}
+
+#[tokio::test]
+async fn test_migrate_up_down_status() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "1_users.up.sql",
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);",
+    );
+    write(dir.path(), "1_users.down.sql", "DROP TABLE users;");
+    write(
+        dir.path(),
+        "2_orders.up.sql",
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER);",
+    );
+    write(dir.path(), "2_orders.down.sql", "DROP TABLE orders;");
+
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:migrate_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("migrate_test", "sqlite", &config)
+        .await
+        .unwrap();
+
+    let migrator = Migrator::from_dir(dir.path()).await.unwrap();
+    let status = migrator.status(&pool).await.unwrap();
+    assert_eq!(
+        states(&status),
+        vec![(1, MigrationState::Pending), (2, MigrationState::Pending)]
+    );
+    assert_eq!(status[0].description, "users");
+    assert!(status[0].reversible);
+
+    // dry run 不修改数据库
+    let dry = Migrator::from_dir(dir.path()).await.unwrap().dry_run(true);
+    assert_eq!(dry.up(&pool).await.unwrap(), vec![1, 2]);
+    assert!(tables(&pool).await.is_empty());
+
+    assert_eq!(migrator.up(&pool).await.unwrap(), vec![1, 2]);
+    assert_eq!(tables(&pool).await, vec!["orders", "users"]);
+    assert!(migrator.up(&pool).await.unwrap().is_empty());
+
+    assert_eq!(migrator.down(&pool, 1).await.unwrap(), vec![2]);
+    assert_eq!(tables(&pool).await, vec!["users"]);
+    let status = migrator.status(&pool).await.unwrap();
+    assert_eq!(
+        states(&status),
+        vec![(1, MigrationState::Applied), (2, MigrationState::Pending)]
+    );
+
+    // 已执行的脚本被修改
+    write(
+        dir.path(),
+        "1_users.up.sql",
+        "CREATE TABLE users (id INTEGER PRIMARY KEY);",
+    );
+    let changed = Migrator::from_dir(dir.path()).await.unwrap();
+    assert_eq!(
+        changed.status(&pool).await.unwrap()[0].state,
+        MigrationState::Modified
+    );
+    let err = changed.up(&pool).await.unwrap_err();
+    assert!(err.to_string().contains("modified"), "{}", err);
+
+    assert_eq!(migrator.down(&pool, 5).await.unwrap(), vec![1]);
+    assert!(tables(&pool).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_migrate_down_requires_down_script() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "20240101_init.sql",
+        "CREATE TABLE items (id INTEGER PRIMARY KEY);",
+    );
+
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:migrate_simple_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("migrate_simple_test", "sqlite", &config)
+        .await
+        .unwrap();
+    let migrator = Migrator::from_dir(dir.path()).await.unwrap();
+    assert_eq!(migrator.up(&pool).await.unwrap(), vec![20240101]);
+
+    let err = migrator.down(&pool, 1).await.unwrap_err();
+    assert!(err.to_string().contains("no down script"), "{}", err);
+    assert_eq!(tables(&pool).await, vec!["items"]);
+}
@@ -0,0 +1,141 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::mapper_store::Mappers;
+use rivus_sqlx::orm::crud_traits::CrudRepository;
+use rivus_sqlx::orm::mock::{MockCall, MockRepository, SqlMatcher};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::path::Path;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    id: i64,
+    name: String,
+}
+
+/// 业务代码中的数据访问，泛型于仓库实现
+async fn rename<R>(repo: &R, pool: &DbPool, id: i64, name: &str) -> Result<Option<User>, DbError>
+where
+    R: CrudRepository<Connection = DbPool, Error = DbError, Args = Vec<Value>>,
+{
+    let updated = repo
+        .update(
+            pool,
+            "UPDATE users SET name = ? WHERE id = ?",
+            vec![json!(name), json!(id)],
+        )
+        .await?;
+    if updated == 0 {
+        return Ok(None);
+    }
+    repo.get(
+        pool,
+        "SELECT id, name FROM users WHERE id = ?",
+        vec![json!(id)],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_mock_repository() {
+    let mock = MockRepository::new();
+    let pool = MockRepository::pool();
+    mock.when(SqlMatcher::contains("UPDATE users"))
+        .with_args(vec![json!("bob"), json!(2)])
+        .returns_affected(1);
+    mock.when(SqlMatcher::contains("UPDATE users"))
+        .returns_affected(0);
+    mock.when(SqlMatcher::exact(
+        "SELECT id, name\n  FROM users WHERE id = ?",
+    ))
+    .times(1)
+    .returns_rows(vec![json!({"id": 2, "name": "bob"})]);
+
+    assert_eq!(
+        rename(&mock, &pool, 2, "bob").await.unwrap(),
+        Some(User {
+            id: 2,
+            name: "bob".to_string()
+        })
+    );
+    assert_eq!(rename(&mock, &pool, 3, "carol").await.unwrap(), None);
+    mock.verify();
+
+    assert_eq!(
+        mock.calls()[0],
+        MockCall {
+            sql: "UPDATE users SET name = ? WHERE id = ?".to_string(),
+            args: vec![json!("bob"), json!(2)],
+        }
+    );
+    assert_eq!(mock.calls().len(), 3);
+
+    // times 用完后不再匹配
+    let err = rename(&mock, &pool, 2, "bob").await.unwrap_err();
+    assert!(
+        err.to_string().contains("no expectation matches"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_mock_errors_and_batch() {
+    let mock = MockRepository::new();
+    let pool = MockRepository::pool();
+    mock.when(SqlMatcher::contains("INSERT INTO users"))
+        .returns_rows(vec![
+            json!({"id": 1, "name": "a"}),
+            json!({"id": 2, "name": "b"}),
+        ]);
+    mock.when(SqlMatcher::Any)
+        .returns_error("connection refused");
+
+    let created: Vec<User> = mock
+        .batch_create(
+            &pool,
+            "INSERT INTO users (name) VALUES (?) RETURNING id, name",
+            vec![vec![json!("a")], vec![json!("b")]],
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.len(), 2);
+    assert_eq!(mock.calls()[0].args, vec![json!(["a"]), json!(["b"])]);
+
+    let err = mock
+        .delete(&pool, "DELETE FROM users", vec![])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("connection refused"), "{}", err);
+}
+
+#[test]
+#[should_panic(expected = "expectations not met")]
+fn test_verify_unmet() {
+    let mock = MockRepository::new();
+    mock.when(SqlMatcher::contains("FROM users"))
+        .returns_rows(vec![]);
+    mock.verify();
+}
+
+#[tokio::test]
+async fn test_match_by_mapper_id() {
+    let mappers = Mappers::load(Path::new("tests/mappers")).unwrap();
+    let mock = MockRepository::new();
+    mock.when(SqlMatcher::mapper(&mappers, "EmbedDao", "listUsers").unwrap())
+        .returns_rows(vec![json!({"id": 1, "name": "alice"})]);
+    assert!(SqlMatcher::mapper(&mappers, "EmbedDao", "missing").is_none());
+
+    // 空白差异不影响匹配
+    let users: Vec<User> = mock
+        .list(&MockRepository::pool(), "SELECT * FROM users", vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        users,
+        vec![User {
+            id: 1,
+            name: "alice".to_string()
+        }]
+    );
+}
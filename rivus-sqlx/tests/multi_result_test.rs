@@ -0,0 +1,81 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn test_list_multi_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:multi_result_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("multi_result_test", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+    pool.execute_raw(
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER, total INTEGER)",
+    )
+    .await
+    .unwrap();
+    pool.execute_raw("INSERT INTO users VALUES (1, 'alice'), (2, 'bob')")
+        .await
+        .unwrap();
+    pool.execute_raw("INSERT INTO orders VALUES (10, 1, 100), (11, 1, 50)")
+        .await
+        .unwrap();
+
+    let repo = SqlxRepository;
+
+    // 无参数：多条语句，结构不同的结果集
+    let sets: Vec<Vec<Value>> = repo
+        .list_multi(
+            &pool,
+            "SELECT id, name FROM users ORDER BY id; SELECT id, total FROM orders ORDER BY id",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        sets,
+        vec![
+            vec![
+                json!({"id": 1, "name": "alice"}),
+                json!({"id": 2, "name": "bob"})
+            ],
+            vec![
+                json!({"id": 10, "total": 100}),
+                json!({"id": 11, "total": 50})
+            ],
+        ]
+    );
+
+    // 带参数；不返回行的语句对应空集合
+    let sets: Vec<Vec<Value>> = repo
+        .list_multi(
+            &pool,
+            "UPDATE users SET name = ? WHERE id = ?; SELECT name FROM users WHERE id = ?; SELECT id FROM orders WHERE user_id = ?",
+            vec![json!("bobby"), json!(2), json!(2), json!(2)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(sets, vec![vec![], vec![json!({"name": "bobby"})], vec![]]);
+
+    // 事务中使用事务连接
+    let sets: Vec<Vec<Value>> = pool
+        .transaction(|tx| async move {
+            tx.execute("INSERT INTO users VALUES (3, 'carol')", vec![])
+                .await?;
+            repo.list_multi(
+                &tx,
+                "SELECT COUNT(*) AS n FROM users; SELECT COUNT(*) AS n FROM orders",
+                vec![],
+            )
+            .await
+        })
+        .await
+        .unwrap();
+    assert_eq!(sets, vec![vec![json!({"n": 3})], vec![json!({"n": 2})]]);
+}
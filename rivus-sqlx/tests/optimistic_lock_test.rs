@@ -0,0 +1,117 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use rivus_sqlx::sql_parser::{parse_mapper_str, ContentMap, MapperMap};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+const MAPPER: &str = r#"
+<mapper namespace="AccountMapper">
+    <update id="rename" versionColumn="version">
+        UPDATE accounts SET name = ? WHERE id = ? OR id = ?;
+    </update>
+    <update id="touch">
+        UPDATE accounts SET name = name WHERE id = ?
+    </update>
+</mapper>
+"#;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Account {
+    id: i64,
+    name: String,
+    version: i64,
+}
+
+async fn account(pool: &DbPool, id: i64) -> Account {
+    pool.get("SELECT * FROM accounts WHERE id = ?", vec![Value::from(id)])
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_update_with_version_column() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:optimistic_lock?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("optimistic_lock", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw("CREATE TABLE accounts (id INTEGER PRIMARY KEY, name TEXT, version INTEGER NOT NULL DEFAULT 0)")
+        .await
+        .unwrap();
+    pool.execute_raw("INSERT INTO accounts (id, name) VALUES (1, 'a'), (2, 'b')")
+        .await
+        .unwrap();
+
+    let mut contents = ContentMap::new();
+    let mut mappers = MapperMap::new();
+    parse_mapper_str(
+        MAPPER,
+        Path::new("AccountMapper.xml"),
+        &mut contents,
+        &mut mappers,
+    )
+    .unwrap();
+    let sql = contents["AccountMapper"]["rename"].as_deref().unwrap();
+    let mapper = &mappers["AccountMapper"]["rename"];
+    assert_eq!(mapper.version_column.as_deref(), Some("version"));
+
+    let repo = SqlxRepository;
+    let args = || vec![Value::from("x"), Value::from(1), Value::from(-1)];
+
+    let rows = repo
+        .update_with_mapper(&pool, sql, args(), mapper, 0)
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    assert_eq!(
+        account(&pool, 1).await,
+        Account {
+            id: 1,
+            name: "x".to_string(),
+            version: 1
+        }
+    );
+
+    // 使用过期的版本号：原条件中的 OR 不应绕过版本检查
+    let err = repo
+        .update_with_mapper(&pool, sql, args(), mapper, 0)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::StaleVersion(0)), "{}", err);
+    assert_eq!(account(&pool, 1).await.version, 1);
+    assert_eq!(account(&pool, 2).await.version, 0);
+
+    // 未配置 versionColumn 时为普通更新
+    let touch = contents["AccountMapper"]["touch"].as_deref().unwrap();
+    let rows = repo
+        .update_with_mapper(
+            &pool,
+            touch,
+            vec![Value::from(2)],
+            &mappers["AccountMapper"]["touch"],
+            99,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    assert_eq!(account(&pool, 2).await.version, 0);
+
+    // 非 UPDATE 语句报错
+    let err = repo
+        .update_with_mapper(
+            &pool,
+            "DELETE FROM accounts WHERE id = ?",
+            vec![Value::from(2)],
+            mapper,
+            0,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DbError::Config(_)));
+}
@@ -0,0 +1,147 @@
+use rivus_core::page::PageRequest;
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Item {
+    id: i64,
+    name: String,
+}
+
+#[tokio::test]
+async fn test_page() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:page_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("page_test", "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+    for i in 1..=25 {
+        pool.execute(
+            "INSERT INTO items (id, name) VALUES (?, ?)",
+            vec![Value::from(i), Value::from(format!("item-{}", i))],
+        )
+        .await
+        .unwrap();
+    }
+
+    let repo = SqlxRepository;
+    let sql = "SELECT id, name FROM items WHERE id > ? ORDER BY id";
+
+    let page = repo
+        .page::<Item>(&pool, sql, vec![Value::from(0)], PageRequest::new(3, 10))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 25);
+    assert_eq!(
+        page.items.iter().map(|i| i.id).collect::<Vec<_>>(),
+        (21..=25).collect::<Vec<_>>()
+    );
+
+    let page = repo
+        .page::<Item>(&pool, sql, vec![Value::from(20)], PageRequest::new(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].id, 21);
+
+    // 超出范围的页
+    let page = repo
+        .page::<Item>(&pool, sql, vec![Value::from(0)], PageRequest::new(9, 10))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 25);
+    assert!(page.items.is_empty());
+}
+
+#[test]
+fn test_page_request_offset() {
+    assert_eq!(PageRequest::new(1, 10).offset(), 0);
+    assert_eq!(PageRequest::new(3, 10).offset(), 20);
+    assert_eq!(PageRequest::new(0, 10).offset(), 0);
+    assert_eq!(PageRequest::new(2, 0).limit(), 1);
+}
+
+#[tokio::test]
+async fn test_count_and_exists() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:count_exists_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("count_exists_test", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, price REAL)")
+        .await
+        .unwrap();
+    pool.execute_raw("INSERT INTO items VALUES (1, 'a', 1.5), (2, 'b', 2.0), (3, 'c', NULL)")
+        .await
+        .unwrap();
+
+    let repo = SqlxRepository;
+    assert_eq!(
+        repo.count(&pool, "SELECT COUNT(*) FROM items", vec![])
+            .await
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        repo.count(
+            &pool,
+            "SELECT COUNT(*) FROM items WHERE id > ?",
+            vec![Value::from(1)]
+        )
+        .await
+        .unwrap(),
+        2
+    );
+    // 无结果行 / NULL 视为 0
+    assert_eq!(
+        repo.count(&pool, "SELECT id FROM items WHERE id > 10", vec![])
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        repo.count(&pool, "SELECT MAX(id) FROM items WHERE id > 10", vec![])
+            .await
+            .unwrap(),
+        0
+    );
+    assert!(
+        repo.count(&pool, "SELECT id, name FROM items", vec![])
+            .await
+            .is_err()
+    );
+    assert!(
+        repo.count(&pool, "SELECT price FROM items WHERE id = 1", vec![])
+            .await
+            .is_err()
+    );
+
+    assert!(
+        repo.exists(
+            &pool,
+            "SELECT id FROM items WHERE name = ?",
+            vec![Value::from("b")]
+        )
+        .await
+        .unwrap()
+    );
+    assert!(
+        !repo
+            .exists(
+                &pool,
+                "SELECT id FROM items WHERE name = ?;",
+                vec![Value::from("z")]
+            )
+            .await
+            .unwrap()
+    );
+}
@@ -0,0 +1,26 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+
+#[tokio::test]
+async fn test_listen_requires_postgres() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:pg_notify_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("pg_notify_test", "sqlite", &config)
+        .await
+        .unwrap();
+
+    let err = pool.listen("orders").await.err().unwrap();
+    assert!(
+        err.to_string().contains("LISTEN is not supported"),
+        "{}",
+        err
+    );
+    let err = pool.notify("orders", "{}").await.unwrap_err();
+    assert!(
+        err.to_string().contains("NOTIFY is not supported"),
+        "{}",
+        err
+    );
+}
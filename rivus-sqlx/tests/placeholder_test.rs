@@ -0,0 +1,35 @@
+use rivus_sqlx::orm::placeholder::to_numbered;
+
+#[test]
+fn test_to_numbered() {
+    assert_eq!(
+        to_numbered("SELECT * FROM t WHERE a = ? AND b = ?"),
+        "SELECT * FROM t WHERE a = $1 AND b = $2"
+    );
+    assert_eq!(
+        to_numbered("INSERT INTO t VALUES (?, ?), (?, ?)"),
+        "INSERT INTO t VALUES ($1, $2), ($3, $4)"
+    );
+}
+
+#[test]
+fn test_to_numbered_skips_literals_and_comments() {
+    assert_eq!(
+        to_numbered("SELECT '?', \"a?\" FROM t -- why?\nWHERE a = ? /* ? */ AND b = 'it''s ?'"),
+        "SELECT '?', \"a?\" FROM t -- why?\nWHERE a = $1 /* ? */ AND b = 'it''s ?'"
+    );
+}
+
+#[test]
+fn test_to_numbered_escape_and_passthrough() {
+    assert_eq!(
+        to_numbered("SELECT data ?? 'k' FROM t WHERE id = ?"),
+        "SELECT data ? 'k' FROM t WHERE id = $1"
+    );
+    // 已使用 $n 的 SQL 保持不变
+    assert_eq!(
+        to_numbered("SELECT * FROM t WHERE a = $1 AND b ? 'k'"),
+        "SELECT * FROM t WHERE a = $1 AND b ? 'k'"
+    );
+    assert_eq!(to_numbered("SELECT 1"), "SELECT 1");
+}
@@ -0,0 +1,50 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::procedure::ProcParam;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_call_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:procedure_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("procedure_test", "sqlite", &config)
+        .await
+        .unwrap();
+    let repo = SqlxRepository;
+
+    let abs: Option<i64> = repo
+        .call_function(&pool, "abs", vec![Value::from(-42)])
+        .await
+        .unwrap();
+    assert_eq!(abs, Some(42));
+    let upper: Option<String> = repo
+        .call_function(&pool, "upper", vec![Value::from("rivus")])
+        .await
+        .unwrap();
+    assert_eq!(upper.as_deref(), Some("RIVUS"));
+    let null: Option<String> = repo
+        .call_function(&pool, "nullif", vec![Value::from(1), Value::from(1)])
+        .await
+        .unwrap();
+    assert_eq!(null, None);
+
+    // 函数名直接拼接，拒绝非标识符
+    assert!(
+        repo.call_function::<i64>(&pool, "abs(1); --", vec![])
+            .await
+            .is_err()
+    );
+
+    let err = repo
+        .call::<Value>(
+            &pool,
+            "place_order",
+            vec![ProcParam::input(1), ProcParam::out("total")],
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not supported"), "{}", err);
+}
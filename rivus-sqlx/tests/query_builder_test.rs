@@ -0,0 +1,108 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::query::Query;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    id: i64,
+    name: String,
+}
+
+#[tokio::test]
+async fn test_query_builder_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:query_builder_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("query_builder_test", "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER)")
+        .await
+        .unwrap();
+
+    let users = Query::table("users");
+    for (id, name, age) in [
+        (1, "alice", 30),
+        (2, "bob", 17),
+        (3, "carol", 25),
+        (4, "dave", 40),
+    ] {
+        let values = vec![
+            ("id", Value::from(id)),
+            ("name", Value::from(name)),
+            ("age", Value::from(age)),
+        ];
+        assert_eq!(users.insert(&pool, values).await.unwrap(), 1);
+    }
+
+    let adults: Vec<User> = Query::table("users")
+        .select(&["id", "name"])
+        .filter("age > ?", 18)
+        .order_by_desc("age")
+        .limit(2)
+        .list(&pool)
+        .await
+        .unwrap();
+    let names: Vec<&str> = adults.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, ["dave", "alice"]);
+
+    // 只有 OFFSET 时补齐 LIMIT
+    let rest: Vec<User> = Query::table("users")
+        .select(&["id", "name"])
+        .order_by("id")
+        .offset(2)
+        .list(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rest.iter().map(|u| u.id).collect::<Vec<_>>(), [3, 4]);
+
+    let bob: Option<User> = Query::table("users")
+        .select(&["id", "name"])
+        .filter_args("name = ? OR name = ?", vec!["bob".into(), "nobody".into()])
+        .get(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        bob,
+        Some(User {
+            id: 2,
+            name: "bob".to_string()
+        })
+    );
+
+    let updated = Query::table("users")
+        .filter("age < ?", 18)
+        .update(&pool, vec![("name", Value::from("bobby"))])
+        .await
+        .unwrap();
+    assert_eq!(updated, 1);
+
+    assert_eq!(
+        Query::table("users")
+            .filter("id = ?", 4)
+            .delete(&pool)
+            .await
+            .unwrap(),
+        1
+    );
+    let all: Vec<User> = Query::table("users")
+        .select(&["id", "name"])
+        .order_by("id")
+        .list(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        all.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(),
+        ["alice", "bobby", "carol"]
+    );
+
+    // 没有条件时拒绝全表更新 / 删除
+    assert!(Query::table("users").delete(&pool).await.is_err());
+    assert!(
+        Query::table("users")
+            .update(&pool, vec![("age", Value::from(0))])
+            .await
+            .is_err()
+    );
+}
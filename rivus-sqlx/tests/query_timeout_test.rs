@@ -0,0 +1,63 @@
+use rivus_sqlx::db_pool::{DbPool, QueryOptions};
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Deserialize)]
+struct Count {
+    n: i64,
+}
+
+/// 在 SQLite 上持续数秒的查询
+const SLOW_SQL: &str = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < 200000000) SELECT count(*) AS n FROM c";
+
+async fn new_pool(name: &str, query_timeout: u64) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    )
+    .query_timeout(query_timeout);
+    DbPool::new(name, "sqlite", &config).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_query_options_timeout() {
+    let pool = new_pool("query_timeout_options", 0).await;
+    assert_eq!(pool.query_timeout, None);
+
+    let start = Instant::now();
+    let timeout = Duration::from_millis(100);
+    let err = pool
+        .with_options(QueryOptions::new().timeout(timeout))
+        .get::<Count>(SLOW_SQL, vec![])
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DbError::Timeout(d) if d == timeout),
+        "{:?}",
+        err
+    );
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // 选项只作用于返回的连接池
+    let quick: Count = pool.get("SELECT 1 AS n", vec![]).await.unwrap().unwrap();
+    assert_eq!(quick.n, 1);
+}
+
+#[tokio::test]
+async fn test_pool_query_timeout() {
+    let pool = new_pool("query_timeout_pool", 100).await;
+    assert_eq!(pool.query_timeout, Some(Duration::from_millis(100)));
+
+    let count: Count = pool.get("SELECT 1 AS n", vec![]).await.unwrap().unwrap();
+    assert_eq!(count.n, 1);
+    assert!(matches!(
+        pool.list::<Count>(SLOW_SQL, vec![]).await,
+        Err(DbError::Timeout(_))
+    ));
+
+    // 单次调用可放宽连接池的默认值
+    let relaxed = pool.with_options(QueryOptions::new().timeout(Duration::from_secs(30)));
+    assert_eq!(relaxed.query_timeout, Some(Duration::from_secs(30)));
+}
@@ -0,0 +1,411 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::row_de::{RowDeserializer, RowReader};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+async fn new_pool(name: &str, ddl: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw(ddl).await.unwrap();
+    pool
+}
+
+/// 模拟驱动行：单列，按给定类型名与文本值返回
+struct MockRow {
+    type_name: &'static str,
+    value: &'static str,
+}
+
+impl RowReader for MockRow {
+    fn column_count(&self) -> usize {
+        1
+    }
+    fn column_name(&self, _idx: usize) -> &str {
+        "id"
+    }
+    fn is_null(&self, _idx: usize) -> bool {
+        false
+    }
+    fn type_name(&self, _idx: usize) -> &str {
+        self.type_name
+    }
+    fn get_bool(&self, _idx: usize) -> Result<bool, String> {
+        Err("not a bool".to_string())
+    }
+    fn get_i64(&self, _idx: usize) -> Result<i64, String> {
+        Err("not an i64".to_string())
+    }
+    fn get_f64(&self, _idx: usize) -> Result<f64, String> {
+        Err("not a f64".to_string())
+    }
+    fn get_string(&self, _idx: usize) -> Result<String, String> {
+        // 与 Postgres 二进制协议下的 UUID / NUMERIC 一致：无法按字符串读取
+        Err("not a string".to_string())
+    }
+    fn get_json(&self, _idx: usize) -> Result<serde_json::Value, String> {
+        Err("not json".to_string())
+    }
+    fn get_uuid(&self, _idx: usize) -> Result<String, String> {
+        Uuid::parse_str(self.value)
+            .map(|u| u.to_string())
+            .map_err(|e| e.to_string())
+    }
+    fn get_decimal(&self, _idx: usize) -> Result<Decimal, String> {
+        self.value
+            .parse()
+            .map_err(|e: rust_decimal::Error| e.to_string())
+    }
+    fn get_bytes(&self, _idx: usize) -> Result<Vec<u8>, String> {
+        Ok(self.value.as_bytes().to_vec())
+    }
+    fn get_date(&self, _idx: usize) -> Result<NaiveDate, String> {
+        self.value
+            .parse()
+            .map_err(|e: chrono::ParseError| e.to_string())
+    }
+    fn get_time(&self, _idx: usize) -> Result<NaiveTime, String> {
+        self.value
+            .parse()
+            .map_err(|e: chrono::ParseError| e.to_string())
+    }
+    fn get_datetime(&self, _idx: usize) -> Result<NaiveDateTime, String> {
+        self.value
+            .parse()
+            .map_err(|e: chrono::ParseError| e.to_string())
+    }
+    fn get_datetime_utc(&self, _idx: usize) -> Result<DateTime<Utc>, String> {
+        self.value
+            .parse()
+            .map_err(|e: chrono::ParseError| e.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UuidRow {
+    id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+struct UuidStrRow {
+    id: String,
+}
+
+#[test]
+fn test_uuid_column_type() {
+    let row = MockRow {
+        type_name: "UUID",
+        value: "67E55044-10B1-426F-9247-BB680E5FE0C8",
+    };
+    let expected = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+    let typed = UuidRow::deserialize(RowDeserializer::new(&row)).unwrap();
+    assert_eq!(typed.id, expected);
+
+    let text = UuidStrRow::deserialize(RowDeserializer::new(&row)).unwrap();
+    assert_eq!(text.id, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+}
+
+#[tokio::test]
+async fn test_uuid_roundtrip_sqlite() {
+    let pool = new_pool(
+        "row_de_uuid",
+        "CREATE TABLE tokens (id TEXT PRIMARY KEY, owner TEXT)",
+    )
+    .await;
+    let id = Uuid::new_v4();
+
+    pool.execute(
+        "INSERT INTO tokens (id, owner) VALUES (?, ?)",
+        vec![Value::from(id.to_string()), Value::from("tom")],
+    )
+    .await
+    .unwrap();
+
+    let row: Option<UuidRow> = pool
+        .get(
+            "SELECT id FROM tokens WHERE id = ?",
+            vec![Value::from(id.to_string())],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.unwrap().id, id);
+
+    let row: Option<UuidStrRow> = pool.get("SELECT id FROM tokens", vec![]).await.unwrap();
+    assert_eq!(row.unwrap().id, id.to_string());
+}
+
+#[derive(Debug, Deserialize)]
+struct Amount {
+    id: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct AmountStr {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AmountF64 {
+    id: f64,
+}
+
+#[test]
+fn test_decimal_column_type() {
+    for type_name in ["DECIMAL", "NUMERIC"] {
+        let row = MockRow {
+            type_name,
+            value: "12345678901234567.890",
+        };
+
+        let typed = Amount::deserialize(RowDeserializer::new(&row)).unwrap();
+        assert_eq!(
+            typed.id,
+            "12345678901234567.890".parse::<Decimal>().unwrap()
+        );
+
+        // 保留原始精度与小数位
+        let text = AmountStr::deserialize(RowDeserializer::new(&row)).unwrap();
+        assert_eq!(text.id, "12345678901234567.890");
+
+        let float = AmountF64::deserialize(RowDeserializer::new(&row)).unwrap();
+        assert!((float.id - 12345678901234567.89).abs() < 1.0);
+    }
+}
+
+#[tokio::test]
+async fn test_decimal_roundtrip_sqlite() {
+    let pool = new_pool("row_de_decimal", "CREATE TABLE prices (id TEXT)").await;
+    let price: Decimal = "19.990".parse().unwrap();
+
+    // Decimal 经 serde 序列化为字符串
+    pool.execute(
+        "INSERT INTO prices (id) VALUES (?)",
+        vec![serde_json::to_value(price).unwrap()],
+    )
+    .await
+    .unwrap();
+
+    let row: Option<Amount> = pool.get("SELECT id FROM prices", vec![]).await.unwrap();
+    assert_eq!(row.unwrap().id, price);
+}
+
+#[derive(Debug, Deserialize)]
+struct TimestampTz {
+    id: DateTime<Utc>,
+}
+
+#[test]
+fn test_timestamptz_column_type() {
+    let row = MockRow {
+        type_name: "TIMESTAMPTZ",
+        value: "2024-03-01T08:30:00+08:00",
+    };
+    let typed = TimestampTz::deserialize(RowDeserializer::new(&row)).unwrap();
+    assert_eq!(typed.id.to_rfc3339(), "2024-03-01T00:30:00+00:00");
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    day: NaiveDate,
+    at: NaiveTime,
+    created_at: NaiveDateTime,
+    updated_at: Option<NaiveDateTime>,
+    // String 字段保持数据库的默认格式
+    raw: String,
+    #[serde(with = "standard")]
+    formatted: Option<NaiveDateTime>,
+}
+
+/// 与 `rivus_utils::date_format::standard` 相同的字段格式：经 String 读取后按格式解析
+mod standard {
+    use chrono::NaiveDateTime;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<NaiveDateTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[tokio::test]
+async fn test_chrono_columns_sqlite() {
+    let pool = new_pool(
+        "row_de_chrono",
+        "CREATE TABLE events (day DATE, at TIME, created_at DATETIME, updated_at TIMESTAMP, raw DATETIME, formatted DATETIME)",
+    )
+    .await;
+    pool.execute(
+        "INSERT INTO events VALUES (?, ?, ?, ?, ?, ?)",
+        vec![
+            Value::from("2024-03-01"),
+            Value::from("08:30:15"),
+            Value::from("2024-03-01 08:30:15"),
+            Value::Null,
+            Value::from("2024-03-01 08:30:15"),
+            Value::from("2024-03-01 08:30:15"),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let event: Event = pool
+        .get("SELECT * FROM events", vec![])
+        .await
+        .unwrap()
+        .unwrap();
+    let expected =
+        NaiveDateTime::parse_from_str("2024-03-01 08:30:15", "%Y-%m-%d %H:%M:%S").unwrap();
+    assert_eq!(event.day, expected.date());
+    assert_eq!(event.at, expected.time());
+    assert_eq!(event.created_at, expected);
+    assert_eq!(event.updated_at, None);
+    assert_eq!(event.raw, "2024-03-01 08:30:15");
+    assert_eq!(event.formatted, Some(expected));
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Status {
+    Draft,
+    Active,
+    Archived,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Low,
+    High,
+}
+
+#[derive(Debug, Deserialize)]
+struct Ticket {
+    status: Status,
+    level: Level,
+    prev_status: Option<Status>,
+}
+
+#[tokio::test]
+async fn test_enum_columns_sqlite() {
+    let pool = new_pool(
+        "row_de_enum",
+        "CREATE TABLE tickets (status TINYINT, level VARCHAR(16), prev_status TINYINT)",
+    )
+    .await;
+    pool.execute(
+        "INSERT INTO tickets VALUES (?, ?, ?), (?, ?, ?)",
+        vec![
+            Value::from(1),
+            Value::from("high"),
+            Value::Null,
+            Value::from(7),
+            Value::from("low"),
+            Value::from(0),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let ticket: Ticket = pool
+        .get("SELECT * FROM tickets WHERE status = 1", vec![])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ticket.status, Status::Active);
+    assert_eq!(ticket.level, Level::High);
+    assert_eq!(ticket.prev_status, None);
+
+    // 越界的序号报错
+    let err = pool
+        .get::<Ticket>("SELECT * FROM tickets WHERE status = 7", vec![])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("variant index"), "{}", err);
+
+    let unknown = pool
+        .get::<Ticket>(
+            "SELECT 0 AS status, 'medium' AS level, NULL AS prev_status",
+            vec![],
+        )
+        .await;
+    assert!(unknown.is_err());
+}
+
+#[derive(Debug, Deserialize)]
+struct Attachment {
+    data: Vec<u8>,
+    thumb: Option<Vec<u8>>,
+    #[serde(with = "byte_buf")]
+    raw: Vec<u8>,
+    json: Value,
+}
+
+/// 按 `deserialize_byte_buf` 读取，同 `serde_bytes`
+mod byte_buf {
+    use serde::Deserializer;
+    use serde::de::{self, Visitor};
+    use std::fmt;
+
+    struct BytesVisitor;
+
+    impl Visitor<'_> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("bytes")
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(v)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+#[tokio::test]
+async fn test_blob_columns_sqlite() {
+    let pool = new_pool(
+        "row_de_blob",
+        "CREATE TABLE files (data BLOB, thumb BLOB, raw BLOB, json BLOB)",
+    )
+    .await;
+    pool.execute_raw("INSERT INTO files VALUES (X'00FF10', NULL, X'CAFE', X'0102')")
+        .await
+        .unwrap();
+
+    let file: Attachment = pool
+        .get("SELECT * FROM files", vec![])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(file.data, vec![0x00, 0xff, 0x10]);
+    assert_eq!(file.thumb, None);
+    assert_eq!(file.raw, vec![0xca, 0xfe]);
+    assert_eq!(file.json, serde_json::json!([1, 2]));
+}
+
+#[test]
+fn test_bytea_column_type() {
+    let row = MockRow {
+        type_name: "BYTEA",
+        value: "abc",
+    };
+    #[derive(Deserialize)]
+    struct Row {
+        id: Vec<u8>,
+    }
+    let typed = Row::deserialize(RowDeserializer::new(&row)).unwrap();
+    assert_eq!(typed.id, b"abc");
+}
@@ -0,0 +1,62 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::schema::{ColumnSchema, ForeignKey};
+
+#[tokio::test]
+async fn test_describe_table_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:schema_describe?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("schema_describe", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw("CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR(64) NOT NULL)")
+        .await
+        .unwrap();
+    pool.execute_raw(
+        "CREATE TABLE orders (
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            seq INTEGER NOT NULL,
+            status TEXT DEFAULT 'new',
+            note,
+            PRIMARY KEY (user_id, seq)
+        )",
+    )
+    .await
+    .unwrap();
+
+    let users = pool.describe_table("users").await.unwrap();
+    assert_eq!(users.primary_key, vec!["id"]);
+    assert_eq!(
+        users.column("name"),
+        Some(&ColumnSchema {
+            name: "name".into(),
+            data_type: "VARCHAR(64)".into(),
+            nullable: false,
+            default: None,
+            primary_key: false,
+        })
+    );
+    assert!(users.foreign_keys.is_empty());
+
+    let orders = pool.describe_table("orders").await.unwrap();
+    let names: Vec<&str> = orders.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["user_id", "seq", "status", "note"]);
+    assert_eq!(orders.primary_key, vec!["user_id", "seq"]);
+    let status = orders.column("status").unwrap();
+    assert!(status.nullable);
+    assert_eq!(status.default.as_deref(), Some("'new'"));
+    assert_eq!(orders.column("note").unwrap().data_type, "");
+    assert_eq!(
+        orders.foreign_keys,
+        vec![ForeignKey {
+            column: "user_id".into(),
+            ref_table: "users".into(),
+            ref_column: "id".into(),
+        }]
+    );
+
+    let err = pool.describe_table("missing").await.unwrap_err();
+    assert!(err.to_string().contains("missing"));
+}
@@ -0,0 +1,84 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde_json::Value;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 收集日志输出的 writer
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Captured {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+const SLOW_SQL: &str = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < ?) SELECT COUNT(*) AS n FROM c";
+
+async fn new_pool(name: &str, threshold_ms: u64) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    )
+    .slow_query_threshold(threshold_ms);
+    DbPool::new(name, "sqlite", &config).await.unwrap()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_slow_query_logged() {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let pool = new_pool("slow_query_logged", 1).await;
+    assert_eq!(pool.slow_query_threshold, Some(Duration::from_millis(1)));
+    let _: Option<Value> = pool
+        .get(SLOW_SQL, vec![Value::from(300_000)])
+        .await
+        .unwrap();
+
+    let out = captured.text();
+    assert!(out.contains("WARN"), "{}", out);
+    assert!(out.contains("slow query"), "{}", out);
+    assert!(out.contains("WITH RECURSIVE"), "{}", out);
+    assert!(out.contains("params: [300000]"), "{}", out);
+    assert!(out.contains("pool=\"slow_query_logged\""), "{}", out);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_slow_query_disabled() {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    // 阈值为 0 表示关闭
+    let pool = new_pool("slow_query_disabled", 0).await;
+    assert_eq!(pool.slow_query_threshold, None);
+    let _: Option<Value> = pool
+        .get(SLOW_SQL, vec![Value::from(300_000)])
+        .await
+        .unwrap();
+
+    assert!(!captured.text().contains("slow query"));
+}
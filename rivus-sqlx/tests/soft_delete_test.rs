@@ -0,0 +1,113 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::soft_delete::{including_deleted, with_deleted};
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use rivus_sqlx::sql_parser::{parse_mapper_str, ContentMap, MapperMap};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+const MAPPER: &str = r#"
+<mapper namespace="PostMapper">
+    <select id="list" softDelete="deleted_at">
+        SELECT id, title FROM posts ORDER BY id
+    </select>
+    <select id="get" softDelete="deleted_at">
+        SELECT id, title FROM posts WHERE id = ? OR title = ?
+    </select>
+    <delete id="remove" softDelete="deleted_at">
+        DELETE FROM posts WHERE id = ?
+    </delete>
+</mapper>
+"#;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Post {
+    id: i64,
+    title: String,
+}
+
+#[tokio::test]
+async fn test_soft_delete_mapper() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:soft_delete?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("soft_delete", "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT, deleted_at DATETIME)",
+    )
+    .await
+    .unwrap();
+    pool.execute_raw("INSERT INTO posts (id, title) VALUES (1, 'a'), (2, 'b'), (3, 'c')")
+        .await
+        .unwrap();
+
+    let mut contents = ContentMap::new();
+    let mut mappers = MapperMap::new();
+    parse_mapper_str(
+        MAPPER,
+        Path::new("PostMapper.xml"),
+        &mut contents,
+        &mut mappers,
+    )
+    .unwrap();
+    let sql = |id: &str| contents["PostMapper"][id].as_deref().unwrap();
+    let mapper = |id: &str| &mappers["PostMapper"][id];
+    let repo = SqlxRepository;
+
+    let rows = repo
+        .delete_with_mapper(&pool, sql("remove"), vec![Value::from(2)], mapper("remove"))
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    // 已删除的行不重复删除
+    let rows = repo
+        .delete_with_mapper(&pool, sql("remove"), vec![Value::from(2)], mapper("remove"))
+        .await
+        .unwrap();
+    assert_eq!(rows, 0);
+
+    let deleted: Vec<Post> = pool
+        .list(
+            "SELECT id, title FROM posts WHERE deleted_at IS NOT NULL",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        deleted,
+        vec![Post {
+            id: 2,
+            title: "b".to_string()
+        }]
+    );
+
+    let posts: Vec<Post> = repo
+        .list_with_mapper(&pool, sql("list"), vec![], mapper("list"))
+        .await
+        .unwrap();
+    assert_eq!(posts.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1, 3]);
+
+    // 原条件中的 OR 不会绕过过滤
+    let post: Option<Post> = repo
+        .get_with_mapper(
+            &pool,
+            sql("get"),
+            vec![Value::from(2), Value::from("b")],
+            mapper("get"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(post, None);
+
+    assert!(!including_deleted());
+    let all: Vec<Post> = with_deleted(async {
+        assert!(including_deleted());
+        repo.list_with_mapper(&pool, sql("list"), vec![], mapper("list"))
+            .await
+            .unwrap()
+    })
+    .await;
+    assert_eq!(all.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+}
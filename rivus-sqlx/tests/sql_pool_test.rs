@@ -0,0 +1,62 @@
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::{embed_mappers, insert, select, sql};
+use serde::Deserialize;
+
+type Result<T> = std::result::Result<T, DbError>;
+
+#[derive(Debug, Deserialize)]
+pub struct Count {
+    n: i64,
+}
+
+/// 同一仓储中的方法分别访问不同数据库
+pub struct FolderRepo;
+
+#[sql("FolderDao", pool = "archive")]
+impl FolderRepo {
+    #[sql("count")]
+    pub async fn archived() -> Result<Count> {}
+
+    #[sql("count", pool = "analytics")]
+    pub async fn analyzed() -> Result<Count> {}
+}
+
+#[sql("FolderDao.count", pool = "analytics")]
+async fn count_analytics() -> Result<Count> {}
+
+#[insert(
+    "INSERT INTO sys_folder (id, name, owner) VALUES (#{id}, 'x', 'x')",
+    pool = "archive"
+)]
+async fn archive(id: i64) -> Result<u64> {}
+
+#[select("SELECT COUNT(*) AS n FROM sys_folder", pool = "missing")]
+async fn count_missing() -> Result<Count> {}
+
+#[tokio::test]
+async fn test_sql_pool() -> anyhow::Result<()> {
+    for name in ["analytics", "archive"] {
+        let config = DatabaseOptions::new(
+            "sqlite".to_string(),
+            format!("sqlite:file:sql_pool_{}?mode=memory&cache=shared", name),
+        );
+        ConnManager::open(name, "sqlite", &config).await?;
+        ConnManager::by(name)
+            .unwrap()
+            .execute_raw("CREATE TABLE sys_folder (id INTEGER PRIMARY KEY, name TEXT NOT NULL, owner TEXT NOT NULL)")
+            .await?;
+    }
+    embed_mappers!("tests/mappers")?.install();
+
+    assert_eq!(archive(1).await?, 1);
+    assert_eq!(archive(2).await?, 1);
+    assert_eq!(FolderRepo::archived().await?.n, 2);
+    assert_eq!(FolderRepo::analyzed().await?.n, 0);
+    assert_eq!(count_analytics().await?.n, 0);
+
+    let err = count_missing().await.unwrap_err();
+    assert!(err.to_string().contains("Database 'missing' not found"));
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use rivus_sqlx::sql_tpl::engine::{remove_template, render_template, try_render_value};
+use rivus_sqlx::sql_tpl::value::{to_value, SqlParam};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Query<'a> {
+    name: Option<&'a str>,
+    ids: Vec<i64>,
+}
+
+fn norm(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn test_bind_like_pattern() {
+    let tpl = r#"<bind name="pattern" value="'%' + name + '%'"/>
+select * from users
+<where>
+  <if test="pattern != null">and name like #{pattern}</if>
+</where>"#;
+
+    let (sql, params) = render_template(
+        "bind_like",
+        tpl,
+        &Query {
+            name: Some("tom"),
+            ids: vec![],
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users WHERE name like ?");
+    assert!(matches!(&params[0], SqlParam::String(s) if s == "%tom%"));
+
+    // 参与拼接的变量为 null 时绑定结果也为 null
+    let (sql, params) = render_template(
+        "bind_like",
+        tpl,
+        &Query {
+            name: None,
+            ids: vec![],
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users");
+    assert!(params.is_empty());
+
+    remove_template("bind_like");
+}
+
+#[test]
+fn test_bind_inside_loop() {
+    let tpl = r#"select * from t where code in <for item="id" collection="ids" open="(" sep="," close=")"><bind name="code" value="'C-' + id"/>#{code}</for>"#;
+
+    let (sql, params) = render_template(
+        "bind_loop",
+        tpl,
+        &Query {
+            name: None,
+            ids: vec![1, 2],
+        },
+    );
+    assert_eq!(norm(&sql), "select * from t where code in (?,?)");
+    assert!(matches!(&params[0], SqlParam::String(s) if s == "C-1"));
+    assert!(matches!(&params[1], SqlParam::String(s) if s == "C-2"));
+
+    remove_template("bind_loop");
+}
+
+#[test]
+fn test_bind_integer_overflow() {
+    let tpl = r#"<bind name="next" value="ids + 1"/>select #{next}"#;
+
+    #[derive(Serialize)]
+    struct Max {
+        ids: i64,
+    }
+    let value = to_value(&Max { ids: i64::MAX });
+    assert!(try_render_value("bind_overflow", tpl, &value).is_err());
+
+    remove_template("bind_overflow");
+}
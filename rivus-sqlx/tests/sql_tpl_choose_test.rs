@@ -0,0 +1,114 @@
+use rivus_sqlx::sql_tpl::engine::{remove_template, render_template};
+use rivus_sqlx::sql_tpl::value::SqlParam;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Query<'a> {
+    name: Option<&'a str>,
+    age: Option<i64>,
+}
+
+fn norm(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn test_choose_first_match_wins() {
+    let tpl = r#"select * from users where
+<choose>
+  <when test="name != null">name = #{name}</when>
+  <when test="age != null">age = #{age}</when>
+  <otherwise>status = 1</otherwise>
+</choose>"#;
+
+    // 多个 when 同时满足时只取第一个
+    let (sql, params) = render_template(
+        "choose_first",
+        tpl,
+        &Query {
+            name: Some("tom"),
+            age: Some(3),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users where name = ?");
+    assert_eq!(params.len(), 1);
+
+    let (sql, params) = render_template(
+        "choose_first",
+        tpl,
+        &Query {
+            name: None,
+            age: Some(3),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users where age = ?");
+    assert!(matches!(params[0], SqlParam::I64(3)));
+
+    let (sql, params) = render_template(
+        "choose_first",
+        tpl,
+        &Query {
+            name: None,
+            age: None,
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users where status = 1");
+    assert!(params.is_empty());
+
+    remove_template("choose_first");
+}
+
+#[test]
+fn test_choose_without_otherwise() {
+    let tpl = r#"select * from users<choose><when test="age != null"> where age = #{age}</when></choose>"#;
+
+    let (sql, params) = render_template(
+        "choose_no_otherwise",
+        tpl,
+        &Query {
+            name: None,
+            age: None,
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users");
+    assert!(params.is_empty());
+
+    remove_template("choose_no_otherwise");
+}
+
+#[test]
+fn test_choose_nested_in_where() {
+    let tpl = r#"select * from users
+<where>
+  <choose>
+    <when test="name != null">
+      <if test="age != null">and age = #{age}</if>
+      and name = #{name}
+    </when>
+    <otherwise>and status = 1</otherwise>
+  </choose>
+</where>"#;
+
+    let (sql, params) = render_template(
+        "choose_nested",
+        tpl,
+        &Query {
+            name: Some("tom"),
+            age: Some(3),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users WHERE age = ? and name = ?");
+    assert_eq!(params.len(), 2);
+
+    let (sql, _) = render_template(
+        "choose_nested",
+        tpl,
+        &Query {
+            name: None,
+            age: Some(3),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users WHERE status = 1");
+
+    remove_template("choose_nested");
+}
@@ -0,0 +1,100 @@
+use rivus_sqlx::sql_tpl::engine::{remove_template, render_template};
+use rivus_sqlx::sql_tpl::value::SqlParam;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+struct Row {
+    name: &'static str,
+    tags: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Pair {
+    a: i64,
+    b: i64,
+}
+
+#[derive(Serialize)]
+struct Query {
+    rows: Vec<Row>,
+    pairs: Vec<Pair>,
+    fields: HashMap<&'static str, i64>,
+}
+
+fn query() -> Query {
+    Query {
+        rows: vec![
+            Row {
+                name: "x",
+                tags: vec!["t1", "t2"],
+            },
+            Row {
+                name: "y",
+                tags: vec!["t3"],
+            },
+        ],
+        pairs: vec![Pair { a: 1, b: 2 }, Pair { a: 3, b: 4 }],
+        fields: HashMap::from([("status", 1), ("age", 30)]),
+    }
+}
+
+#[test]
+fn test_for_index() {
+    let tpl = r#"insert into t (pos, name) values <for item="row" index="i" collection="rows" sep=", ">(#{i}, #{row.name})</for>"#;
+
+    let (sql, params) = render_template("for_index", tpl, &query());
+    assert_eq!(sql, "insert into t (pos, name) values (?, ?), (?, ?)");
+    assert!(matches!(params[0], SqlParam::I64(0)));
+    assert!(matches!(&params[1], SqlParam::String(s) if s == "x"));
+    assert!(matches!(params[2], SqlParam::I64(1)));
+    assert!(matches!(&params[3], SqlParam::String(s) if s == "y"));
+
+    remove_template("for_index");
+}
+
+#[test]
+fn test_for_map() {
+    // Map 按键排序遍历，index 为键、item 为值
+    let tpl = r#"update t set <for item="v" index="k" collection="fields" sep=", "><if test="k == 'age'">age</if><if test="k == 'status'">status</if>=#{v}</for>"#;
+
+    let (sql, params) = render_template("for_map", tpl, &query());
+    assert_eq!(sql, "update t set age=?, status=?");
+    assert!(matches!(params[0], SqlParam::I64(30)));
+    assert!(matches!(params[1], SqlParam::I64(1)));
+
+    remove_template("for_map");
+}
+
+#[test]
+fn test_nested_for() {
+    let tpl = r#"insert into tag (name, tag) values <for item="row" collection="rows" sep=", "><for item="tag" collection="row.tags" sep=", ">(#{row.name}, #{tag})</for></for>"#;
+
+    let (sql, params) = render_template("for_nested", tpl, &query());
+    assert_eq!(
+        sql,
+        "insert into tag (name, tag) values (?, ?), (?, ?), (?, ?)"
+    );
+    let values: Vec<&str> = params
+        .iter()
+        .map(|p| match p {
+            SqlParam::String(s) => s.as_str(),
+            _ => panic!("unexpected param {:?}", p),
+        })
+        .collect();
+    assert_eq!(values, ["x", "t1", "x", "t2", "y", "t3"]);
+
+    remove_template("for_nested");
+}
+
+#[test]
+fn test_for_composite_in() {
+    let tpl = r#"select * from t where (a, b) in <for item="p" collection="pairs" open="(" sep=", " close=")">(#{p.a}, #{p.b})</for>"#;
+
+    let (sql, params) = render_template("for_composite_in", tpl, &query());
+    assert_eq!(sql, "select * from t where (a, b) in ((?, ?), (?, ?))");
+    assert_eq!(params.len(), 4);
+    assert!(matches!(params[3], SqlParam::I64(4)));
+
+    remove_template("for_composite_in");
+}
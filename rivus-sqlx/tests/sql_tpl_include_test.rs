@@ -6,7 +6,6 @@ struct EmptyParam {}
 #[cfg(test)]
 mod tests {
 
-    use serde::Serialize;
     use rivus_sqlx::sql_tpl::engine::{remove_template, render_template};
     use crate::EmptyParam;
 
@@ -52,4 +51,82 @@ mod tests {
         remove_template(tpl2_name);
         remove_template(main_name);
     }
+
+    #[test]
+    fn test_include_namespace_resolution() {
+        use rivus_sqlx::sql_tpl::engine::register_template;
+        let param = EmptyParam {};
+
+        register_template("ns_a.columns", "id, name");
+        register_template("ns_b.columns", "id, title");
+        register_template("ns_b.join", "join ns_b_detail d on d.id = t.id");
+        // 同名全局片段优先级低于当前命名空间
+        register_template("columns", "*");
+
+        let (sql, _) = render_template(
+            "ns_a.list",
+            "select <include refid=\"columns\"/> from a",
+            &param,
+        );
+        assert_eq!(sql.trim(), "select id, name from a");
+
+        // 跨命名空间引用 namespace.id
+        let (sql, _) = render_template(
+            "ns_a.listB",
+            "select <include refid=\"ns_b.columns\"/> from b",
+            &param,
+        );
+        assert_eq!(sql.trim(), "select id, title from b");
+
+        // 被引用片段内的相对 refid 按其自身命名空间解析
+        register_template(
+            "ns_b.withJoin",
+            "<include refid=\"columns\"/> from t <include refid=\"join\"/>",
+        );
+        let (sql, _) = render_template(
+            "ns_a.joined",
+            "select <include refid=\"ns_b.withJoin\"/>",
+            &param,
+        );
+        assert_eq!(
+            sql.trim(),
+            "select id, title from t join ns_b_detail d on d.id = t.id"
+        );
+
+        for name in [
+            "ns_a.columns",
+            "ns_b.columns",
+            "ns_b.join",
+            "columns",
+            "ns_a.list",
+            "ns_a.listB",
+            "ns_b.withJoin",
+            "ns_a.joined",
+        ] {
+            remove_template(name);
+        }
+    }
+
+    #[test]
+    fn test_register_mappers() {
+        use rivus_sqlx::sql_parser::ContentMap;
+        use rivus_sqlx::sql_tpl::engine::register_mappers;
+        let param = EmptyParam {};
+
+        let mut content_map = ContentMap::new();
+        let ns = content_map.entry("MapperNs".to_string()).or_default();
+        ns.insert("cols".to_string(), Some("id, name".to_string()));
+        ns.insert("empty".to_string(), None);
+        register_mappers(&content_map);
+
+        let (sql, _) = render_template(
+            "MapperNs.list",
+            "select <include refid=\"cols\"/> from users",
+            &param,
+        );
+        assert_eq!(sql.trim(), "select id, name from users");
+
+        remove_template("MapperNs.cols");
+        remove_template("MapperNs.list");
+    }
 }
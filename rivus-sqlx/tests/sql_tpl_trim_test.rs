@@ -0,0 +1,129 @@
+use rivus_sqlx::sql_tpl::engine::{remove_template, render_template};
+use rivus_sqlx::sql_tpl::value::SqlParam;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Query<'a> {
+    name: Option<&'a str>,
+    age: Option<i64>,
+}
+
+fn norm(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn test_where_tag() {
+    let tpl = r#"select * from users
+<where>
+  <if test="name != null">and name = #{name}</if>
+  <if test="age != null">AND age = #{age}</if>
+</where>"#;
+
+    let (sql, params) = render_template(
+        "trim_where_all",
+        tpl,
+        &Query {
+            name: Some("tom"),
+            age: Some(3),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users WHERE name = ? AND age = ?");
+    assert_eq!(params.len(), 2);
+
+    let (sql, params) = render_template(
+        "trim_where_all",
+        tpl,
+        &Query {
+            name: None,
+            age: Some(3),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users WHERE age = ?");
+    assert!(matches!(params[0], SqlParam::I64(3)));
+
+    // 条件全部为空时整个 WHERE 被省略
+    let (sql, params) = render_template(
+        "trim_where_all",
+        tpl,
+        &Query {
+            name: None,
+            age: None,
+        },
+    );
+    assert_eq!(norm(&sql), "select * from users");
+    assert!(params.is_empty());
+
+    remove_template("trim_where_all");
+}
+
+#[test]
+fn test_where_keeps_column_prefix() {
+    let tpl = r#"select * from t <where>order_no = #{name}</where>"#;
+    let (sql, _) = render_template(
+        "trim_where_word",
+        tpl,
+        &Query {
+            name: Some("x"),
+            age: None,
+        },
+    );
+    assert_eq!(norm(&sql), "select * from t WHERE order_no = ?");
+    remove_template("trim_where_word");
+}
+
+#[test]
+fn test_set_tag() {
+    let tpl = r#"update users
+<set>
+  <if test="name != null">name = #{name},</if>
+  <if test="age != null">age = #{age},</if>
+</set>
+where id = 1"#;
+
+    let (sql, params) = render_template(
+        "trim_set",
+        tpl,
+        &Query {
+            name: Some("tom"),
+            age: None,
+        },
+    );
+    assert_eq!(norm(&sql), "update users SET name = ? where id = 1");
+    assert_eq!(params.len(), 1);
+
+    remove_template("trim_set");
+}
+
+#[test]
+fn test_trim_tag() {
+    let tpl = r#"insert into users <trim prefix="(" suffix=")" suffixOverrides=",">
+  <if test="name != null">name,</if>
+  <if test="age != null">age,</if>
+</trim> values (1)"#;
+
+    let (sql, _) = render_template(
+        "trim_generic",
+        tpl,
+        &Query {
+            name: Some("tom"),
+            age: Some(1),
+        },
+    );
+    assert_eq!(norm(&sql), "insert into users ( name, age ) values (1)");
+
+    let tpl =
+        r#"select * from t <trim prefix="WHERE" prefixOverrides="AND |OR ">OR a = #{age}</trim>"#;
+    let (sql, _) = render_template(
+        "trim_generic_or",
+        tpl,
+        &Query {
+            name: None,
+            age: Some(1),
+        },
+    );
+    assert_eq!(norm(&sql), "select * from t WHERE a = ?");
+
+    remove_template("trim_generic");
+    remove_template("trim_generic_or");
+}
@@ -0,0 +1,107 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::sql_tpl::engine::{render_template, render_value};
+use rivus_sqlx::sql_tpl::value::{SqlParam, Value, uuid_arg};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+struct Param {
+    name: String,
+    attrs: serde_json::Value,
+}
+
+#[test]
+fn test_json_field_binds_as_json() {
+    let param = Param {
+        name: "tom".to_string(),
+        attrs: json!({"age": 18, "tags": ["a", "b"]}),
+    };
+    let (sql, params) = render_template(
+        "typedParamJson",
+        "UPDATE users SET attrs = #{attrs} WHERE name = #{name}",
+        &param,
+    );
+
+    assert_eq!(sql, "UPDATE users SET attrs = ? WHERE name = ?");
+    match &params[0] {
+        SqlParam::Json(v) => assert_eq!(v, &json!({"age": 18, "tags": ["a", "b"]})),
+        other => panic!("param[0] should be Json, got {:?}", other),
+    }
+    assert!(matches!(&params[1], SqlParam::String(s) if s == "tom"));
+}
+
+#[test]
+fn test_render_value_with_uuid() {
+    let id = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let root = Value::Map(HashMap::from([
+        ("id".to_string(), Value::from(id)),
+        ("meta".to_string(), Value::from(json!({"k": "v"}))),
+    ]));
+    let (sql, params) = render_value(
+        "typedParamUuid",
+        r#"SELECT * FROM t WHERE id = #{id}<if test="id == '67E55044-10B1-426F-9247-BB680E5FE0C8'"> AND meta = #{meta}</if>"#,
+        &root,
+    );
+
+    assert_eq!(sql, "SELECT * FROM t WHERE id = ? AND meta = ?");
+    assert!(matches!(params[0], SqlParam::Uuid(u) if u == id));
+    assert!(matches!(&params[1], SqlParam::Json(v) if v == &json!({"k": "v"})));
+
+    // 驱动参数：UUID 为显式标记，JSON 保持原结构
+    let args: Vec<serde_json::Value> = params.into_iter().map(Into::into).collect();
+    assert_eq!(
+        args,
+        vec![
+            json!({"$uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8"}),
+            json!({"k": "v"})
+        ]
+    );
+    assert_eq!(args[0], uuid_arg(id));
+}
+
+#[derive(Debug, Deserialize)]
+struct Doc {
+    id: Uuid,
+    // SQLite 的 JSON 列以文本返回
+    body: String,
+}
+
+#[tokio::test]
+async fn test_execute_rendered_params_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:typed_param?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("typed_param", "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE docs (id TEXT PRIMARY KEY, body JSON)")
+        .await
+        .unwrap();
+
+    let id = Uuid::new_v4();
+    let root = Value::Map(HashMap::from([
+        ("id".to_string(), Value::from(id)),
+        ("body".to_string(), Value::from(json!({"title": "hello"}))),
+    ]));
+    let (sql, params) = render_value(
+        "typedParamInsert",
+        "INSERT INTO docs (id, body) VALUES (#{id}, #{body})",
+        &root,
+    );
+    pool.execute(&sql, params.into_iter().map(Into::into).collect())
+        .await
+        .unwrap();
+
+    let doc: Doc = pool
+        .get("SELECT id, body FROM docs", vec![])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(doc.id, id);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&doc.body).unwrap(),
+        json!({"title": "hello"})
+    );
+}
@@ -1,68 +1,147 @@
-use std::fmt::Debug;
-use rivus_sqlx::sql;
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::{embed_mappers, sql};
+use serde::{Deserialize, Serialize};
+
 // --- 基础结构定义 ---
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Person {
-    #[allow(dead_code)]
     pub name: String,
-    #[allow(dead_code)]
     pub age: u32,
 }
 
-impl Person {
-    pub fn new(name: &str, age: u32) -> Self {
-        Person {
-            name: name.to_string(),
-            age,
-        }
-    }
+#[derive(Debug, Serialize)]
+pub struct NewFolder {
+    id: i64,
+    name: String,
+    owner: String,
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
+#[derive(Debug, Deserialize, PartialEq)]
 pub struct SysFolder {
-    id: i32,
+    id: i64,
     name: String,
 }
 
-// 模拟 Result 类型别名
-type Result<T> = std::result::Result<T, String>;
+#[derive(Debug, Deserialize)]
+pub struct Count {
+    n: i64,
+}
+
+type Result<T> = std::result::Result<T, DbError>;
 
 // --- 用户代码区域 ---
 
 #[derive(Debug)]
-#[sql("ssss")]
+#[sql("FolderDao")]
 pub struct FolderDao;
 
 impl FolderDao {
-    #[sql("list_user")]
-    pub async fn list(person: Person, sex: i32) -> Result<Vec<SysFolder>> {
+    // 指定 mapper 目录后在编译期校验语句 id 与参数名
+    #[sql("listByOwner", mappers = "tests/mappers")]
+    pub async fn list(owner: Person, min_id: Option<i64>) -> Result<Vec<SysFolder>> {
         exec!()
     }
 
-     #[sql("list_person")]
-    pub fn test(person: Person) -> Result<Vec<Person>> {
-         println!("{:?}", person);
-        exec!()
-    }
+    #[sql("getById")]
+    pub async fn get(id: i64) -> Result<Option<SysFolder>> {}
+
+    #[sql("count")]
+    pub async fn count() -> Result<Count> {}
+
+    // 单个结构体参数的字段可直接引用
+    #[sql("insert")]
+    pub async fn insert(folder: &NewFolder) -> Result<u64> {}
+
+    #[sql("deleteAll")]
+    pub async fn delete_all(&self) -> Result<()> {}
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// 命名空间写在 impl 块上
+pub struct FolderQueries;
+
+#[sql("FolderDao")]
+impl FolderQueries {
+    #[sql("getById", mappers = "tests/mappers")]
+    pub async fn get(id: i64) -> anyhow::Result<Option<SysFolder>> {}
+}
 
-    #[tokio::test]
-    async fn test_folder_dao_methods() {
-        let p = Person::new("Alice", 30);
-        let p2 = Person::new("Bob", 25);
+#[sql("FolderDao.count", mappers = "tests/mappers")]
+async fn count_folders() -> Result<Count> {}
 
-        println!(">>> Testing FolderDao::list");
-        // 调用 list
-        let _ = FolderDao::list(p, 1).await;
+#[tokio::test]
+async fn test_sql_methods() -> anyhow::Result<()> {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:sql_macro?mode=memory&cache=shared".to_string(),
+    );
+    ConnManager::open("default", "sqlite", &config).await?;
+    let pool = ConnManager::get().unwrap();
+    pool.execute_raw(
+        "CREATE TABLE sys_folder (id INTEGER PRIMARY KEY, name TEXT NOT NULL, owner TEXT NOT NULL)",
+    )
+    .await?;
 
-        println!("\n>>> Testing FolderDao::test");
-        // 调用 test
-        let _ = FolderDao::test(p2);
+    // 未安装 mapper 时返回错误
+    assert!(
+        FolderDao::count()
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("No mappers installed")
+    );
+    embed_mappers!("tests/mappers")?.install();
+
+    for (id, name, owner) in [
+        (1, "docs", "alice"),
+        (2, "music", "alice"),
+        (3, "photos", "bob"),
+    ] {
+        let folder = NewFolder {
+            id,
+            name: name.into(),
+            owner: owner.into(),
+        };
+        assert_eq!(FolderDao::insert(&folder).await?, 1);
     }
+
+    let alice = || Person {
+        name: "alice".into(),
+        age: 30,
+    };
+    let folders = FolderDao::list(alice(), None).await?;
+    assert_eq!(folders.iter().map(|f| f.id).collect::<Vec<_>>(), vec![1, 2]);
+    let folders = FolderDao::list(alice(), Some(2)).await?;
+    assert_eq!(
+        folders,
+        vec![SysFolder {
+            id: 2,
+            name: "music".into()
+        }]
+    );
+
+    assert_eq!(
+        FolderDao::get(3).await?,
+        Some(SysFolder {
+            id: 3,
+            name: "photos".into()
+        })
+    );
+    assert_eq!(FolderDao::get(9).await?, None);
+    assert_eq!(
+        FolderQueries::get(1)
+            .await
+            .unwrap()
+            .map(|f| f.name)
+            .as_deref(),
+        Some("docs")
+    );
+    assert_eq!(FolderDao::count().await?.n, 3);
+    assert_eq!(count_folders().await?.n, 3);
+
+    FolderDao.delete_all().await?;
+    assert_eq!(FolderDao::count().await?.n, 0);
+    Ok(())
 }
@@ -0,0 +1,68 @@
+use rivus_sqlx::db_pool::{DbPool, DbPoolInner, QueryOptions};
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::Connection;
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    id: i64,
+}
+
+/// 单连接的连接池，便于检查该连接的语句缓存
+async fn new_pool(name: &str, cache_size: usize) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    )
+    .max_open_conns(1)
+    .max_idle_conns(1)
+    .statement_cache_size(cache_size);
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY)").await.unwrap();
+    pool.execute("INSERT INTO items (id) VALUES (?), (?), (?)", vec![json!(1), json!(2), json!(3)])
+        .await
+        .unwrap();
+    pool
+}
+
+async fn cached(pool: &DbPool) -> usize {
+    let DbPoolInner::Sqlite(p) = &pool.inner else {
+        unreachable!()
+    };
+    p.acquire().await.unwrap().cached_statements_size()
+}
+
+/// 按 id 个数展开 `IN (...)`，每种个数产生不同的语句
+async fn query_in(pool: &DbPool, n: usize) -> Vec<i64> {
+    let marks = vec!["?"; n].join(", ");
+    let sql = format!("SELECT id FROM items WHERE id IN ({})", marks);
+    let items: Vec<Item> = pool
+        .list(&sql, (1..=n as i64).map(|i| json!(i)).collect())
+        .await
+        .unwrap();
+    items.into_iter().map(|i| i.id).collect()
+}
+
+#[tokio::test]
+async fn test_statement_cache() {
+    let pool = new_pool("statement_cache_default", 100).await;
+    assert!(pool.cache_statements);
+    let base = cached(&pool).await;
+    assert_eq!(query_in(&pool, 2).await, vec![1, 2]);
+    assert_eq!(cached(&pool).await, base + 1);
+
+    // 单次调用关闭缓存
+    let dynamic = pool.with_options(QueryOptions::new().cache_statement(false));
+    for n in 1..=3 {
+        assert_eq!(query_in(&dynamic, n).await.len(), n);
+    }
+    assert_eq!(cached(&pool).await, base + 1);
+
+    let disabled = new_pool("statement_cache_disabled", 0).await;
+    assert!(!disabled.cache_statements);
+    for n in 1..=3 {
+        query_in(&disabled, n).await;
+    }
+    assert_eq!(cached(&disabled).await, 0);
+}
@@ -0,0 +1,110 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::query_log::{add_redacted_column, set_statement_log, statement_log_enabled};
+use serde_json::Value;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing::level_filters::LevelFilter;
+
+/// 收集日志输出的 writer
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Captured {
+    fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.0.lock().unwrap());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+// 开关为全局状态，放在同一个测试中顺序验证
+#[tokio::test(flavor = "current_thread")]
+async fn test_statement_log_with_redaction() {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_max_level(LevelFilter::DEBUG)
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:statement_log?mode=memory&cache=shared".to_string(),
+    )
+    .slow_query_threshold(0);
+    let pool = DbPool::new("statement_log", "sqlite", &config)
+        .await
+        .unwrap();
+    pool.execute_raw(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, password TEXT, token TEXT)",
+    )
+    .await
+    .unwrap();
+
+    // 默认关闭
+    assert!(!statement_log_enabled());
+    pool.execute(
+        "INSERT INTO users (id, name, password, token) VALUES (?, ?, ?, ?)",
+        vec![
+            Value::from(1),
+            Value::from("tom"),
+            Value::from("s3cret"),
+            Value::from("tk-1"),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(!captured.take().contains("INSERT INTO users"));
+
+    set_statement_log(true);
+    add_redacted_column("token");
+    pool.execute(
+        "INSERT INTO users (id, name, password, token) VALUES (?, ?, ?, ?)",
+        vec![
+            Value::from(2),
+            Value::from("amy"),
+            Value::from("s3cret"),
+            Value::from("tk-2"),
+        ],
+    )
+    .await
+    .unwrap();
+    let out = captured.take();
+    assert!(out.contains("DEBUG"), "{}", out);
+    assert!(
+        out.contains(r#"params: [2, "amy", ***, ***] rows: 1"#),
+        "{}",
+        out
+    );
+    assert!(!out.contains("s3cret") && !out.contains("tk-2"), "{}", out);
+
+    let rows: Vec<Value> = pool
+        .list(
+            "SELECT id FROM users WHERE password = ? ORDER BY id",
+            vec![Value::from("s3cret")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    let out = captured.take();
+    assert!(out.contains("params: [***] rows: 2"), "{}", out);
+
+    set_statement_log(false);
+    pool.execute("DELETE FROM users WHERE id = ?", vec![Value::from(1)])
+        .await
+        .unwrap();
+    assert!(!captured.take().contains("DELETE FROM users"));
+}
@@ -0,0 +1,64 @@
+use futures::TryStreamExt;
+use rivus_sqlx::db_pool::{DbPool, TRANSACTION_CONTEXT};
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde::Deserialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    id: i64,
+    name: String,
+}
+
+#[tokio::test]
+async fn test_stream_rows() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:stream_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("stream_test", "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+    for i in 1..=100 {
+        pool.execute(
+            "INSERT INTO items (id, name) VALUES (?, ?)",
+            vec![Value::from(i), Value::from(format!("item-{}", i))],
+        )
+        .await
+        .unwrap();
+    }
+
+    let repo = SqlxRepository;
+    let mut stream = repo.stream::<Item>(
+        &pool,
+        "SELECT id, name FROM items WHERE id > ? ORDER BY id",
+        vec![Value::from(50)],
+    );
+    let mut count = 0;
+    let mut sum = 0;
+    while let Some(item) = stream.try_next().await.unwrap() {
+        count += 1;
+        sum += item.id;
+    }
+    assert_eq!(count, 50);
+    assert_eq!(sum, (51..=100).sum::<i64>());
+
+    // 事务内可见未提交的数据
+    TRANSACTION_CONTEXT.scope(RefCell::new(HashMap::new()), async {
+        pool.start_transaction().await.unwrap();
+        pool.execute("INSERT INTO items (id, name) VALUES (?, ?)", vec![Value::from(101), Value::from("tx")]).await.unwrap();
+
+        let items: Vec<Item> = repo
+            .stream::<Item>(&pool, "SELECT id, name FROM items WHERE id > ?", vec![Value::from(100)])
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(items, vec![Item { id: 101, name: "tx".to_string() }]);
+
+        pool.rollback_transaction().await.unwrap();
+    }).await;
+}
@@ -0,0 +1,95 @@
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::tenant;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    name: String,
+}
+
+async fn open(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    ConnManager::open(name, "sqlite", &config).await.unwrap();
+    let pool = ConnManager::by(name).unwrap();
+    pool.execute_raw("CREATE TABLE items (name TEXT NOT NULL)")
+        .await
+        .unwrap();
+    pool
+}
+
+async fn names(pool: &DbPool) -> Vec<String> {
+    let items: Vec<Item> = pool
+        .list("SELECT name FROM items ORDER BY name", vec![])
+        .await
+        .unwrap();
+    items.into_iter().map(|i| i.name).collect()
+}
+
+#[tokio::test]
+async fn test_tenant_routing() {
+    let base = open("tenant_base").await;
+    let a = open("tenant_a").await;
+    let b = open("tenant_b").await;
+    tenant::set_resolver(Arc::new(|pool: &str, tenant: &str| {
+        (pool == "tenant_base").then(|| format!("tenant_{}", tenant))
+    }));
+
+    let insert = "INSERT INTO items (name) VALUES (?)";
+    base.execute(insert, vec![json!("shared")]).await.unwrap();
+    tenant::with_tenant("a", base.execute(insert, vec![json!("a1")]))
+        .await
+        .unwrap();
+    tenant::with_tenant("b", async {
+        base.execute(insert, vec![json!("b1")]).await.unwrap();
+        assert_eq!(tenant::current_tenant().as_deref(), Some("b"));
+        assert_eq!(names(&base).await, vec!["b1"]);
+    })
+    .await;
+
+    // 事务开启在租户连接池上
+    tenant::with_tenant("a", async {
+        let outer = &base;
+        base.transaction(|tx| async move {
+            assert_eq!(tx.name, "tenant_a");
+            assert!(outer.in_transaction());
+            tx.execute(insert, vec![json!("a2")]).await?;
+            outer.execute(insert, vec![json!("a3")]).await?;
+            Ok::<_, DbError>(())
+        })
+        .await
+        .unwrap();
+
+        let failed: Result<(), DbError> = base
+            .transaction(|tx| async move {
+                tx.execute(insert, vec![json!("a4")]).await?;
+                Err(DbError::from("abort"))
+            })
+            .await;
+        assert!(failed.is_err());
+        assert!(!base.in_transaction());
+    })
+    .await;
+
+    assert_eq!(names(&base).await, vec!["shared"]);
+    assert_eq!(names(&a).await, vec!["a1", "a2", "a3"]);
+    assert_eq!(names(&b).await, vec!["b1"]);
+
+    let err = tenant::with_tenant("c", base.execute(insert, vec![json!("c1")]))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("tenant_c"));
+
+    tenant::clear_resolver();
+    tenant::with_tenant("a", base.execute(insert, vec![json!("base")]))
+        .await
+        .unwrap();
+    assert_eq!(names(&base).await, vec!["base", "shared"]);
+}
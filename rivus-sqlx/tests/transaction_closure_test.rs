@@ -0,0 +1,167 @@
+use futures::FutureExt;
+use rivus_sqlx::db_pool::{DbPool, IsolationLevel, TxOptions};
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde::Deserialize;
+use serde_json::Value;
+use std::panic::AssertUnwindSafe;
+
+async fn new_pool(name: &str) -> DbPool {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:file:{}?mode=memory&cache=shared", name),
+    );
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+    pool
+}
+
+#[derive(Deserialize)]
+struct Count {
+    n: i64,
+}
+
+async fn count(pool: &DbPool) -> i64 {
+    let row: Option<Count> = pool
+        .get("SELECT COUNT(*) AS n FROM items", vec![])
+        .await
+        .unwrap();
+    row.map(|c| c.n).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_transaction_commits_on_ok() {
+    let pool = new_pool("tx_closure_ok").await;
+
+    let id = pool
+        .transaction(|tx| async move {
+            tx.execute(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                vec![Value::from(1), Value::from("a")],
+            )
+            .await?;
+            tx.execute(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                vec![Value::from(2), Value::from("b")],
+            )
+            .await?;
+            Ok::<_, DbError>(2)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(id, 2);
+    assert_eq!(count(&pool).await, 2);
+}
+
+#[tokio::test]
+async fn test_transaction_rolls_back_on_err() {
+    let pool = new_pool("tx_closure_err").await;
+
+    let result = pool
+        .transaction(|tx| async move {
+            tx.execute(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                vec![Value::from(1), Value::from("a")],
+            )
+            .await?;
+            // 主键冲突
+            tx.execute(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                vec![Value::from(1), Value::from("b")],
+            )
+            .await?;
+            Ok::<_, DbError>(())
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(count(&pool).await, 0);
+}
+
+#[tokio::test]
+async fn test_transaction_rolls_back_on_panic() {
+    let pool = new_pool("tx_closure_panic").await;
+
+    let result = AssertUnwindSafe(pool.transaction(|tx| async move {
+        tx.execute(
+            "INSERT INTO items (id, name) VALUES (?, ?)",
+            vec![Value::from(1), Value::from("a")],
+        )
+        .await?;
+        if count(&tx).await == 1 {
+            panic!("boom");
+        }
+        Ok::<_, DbError>(())
+    }))
+    .catch_unwind()
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(count(&pool).await, 0);
+}
+
+#[tokio::test]
+async fn test_nested_transaction_joins_outer() {
+    let pool = new_pool("tx_closure_nested").await;
+
+    let result = pool
+        .transaction(|tx| async move {
+            tx.transaction(|inner| async move {
+                inner
+                    .execute(
+                        "INSERT INTO items (id, name) VALUES (?, ?)",
+                        vec![Value::from(1), Value::from("a")],
+                    )
+                    .await
+            })
+            .await?;
+            // 外层失败时内层写入一并回滚
+            Err::<(), _>(DbError::from("abort"))
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(count(&pool).await, 0);
+}
+
+#[tokio::test]
+async fn test_read_only_transaction() {
+    let pool = new_pool("tx_closure_read_only").await;
+    pool.execute(
+        "INSERT INTO items (id, name) VALUES (?, ?)",
+        vec![Value::from(1), Value::from("a")],
+    )
+    .await
+    .unwrap();
+
+    let options = TxOptions::new()
+        .isolation(IsolationLevel::Serializable)
+        .read_only();
+    let result = pool
+        .transaction_with(options, |tx| async move {
+            // 只读事务中允许查询，拒绝写入
+            assert_eq!(count(&tx).await, 1);
+            tx.execute(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                vec![Value::from(2), Value::from("b")],
+            )
+            .await
+        })
+        .await;
+    assert!(result.is_err());
+
+    // 事务结束后连接恢复可写
+    pool.transaction(|tx| async move {
+        tx.execute(
+            "INSERT INTO items (id, name) VALUES (?, ?)",
+            vec![Value::from(3), Value::from("c")],
+        )
+        .await
+    })
+    .await
+    .unwrap();
+    assert_eq!(count(&pool).await, 2);
+}
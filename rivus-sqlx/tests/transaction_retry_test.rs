@@ -0,0 +1,103 @@
+use rivus_sqlx::db_pool::{DbPool, RetryPolicy};
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use serde_json::Value;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+async fn new_pool(dir: &tempfile::TempDir, name: &str) -> DbPool {
+    let path = dir.path().join(format!("{}.db", name));
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        format!("sqlite:{}?mode=rwc", path.display()),
+    )
+    .tx_retry(10, 20);
+    let pool = DbPool::new(name, "sqlite", &config).await.unwrap();
+    pool.execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+    pool
+}
+
+#[test]
+fn test_retry_policy_delay() {
+    let policy = RetryPolicy::new(5)
+        .base_delay(Duration::from_millis(100))
+        .max_delay(Duration::from_millis(500));
+    assert_eq!(policy.delay(1), Duration::from_millis(100));
+    assert_eq!(policy.delay(2), Duration::from_millis(200));
+    assert_eq!(policy.delay(3), Duration::from_millis(400));
+    assert_eq!(policy.delay(4), Duration::from_millis(500));
+    assert_eq!(policy.delay(64), Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_transaction_retry_on_busy() {
+    let dir = tempfile::tempdir().unwrap();
+    let pool = new_pool(&dir, "tx_retry_busy").await;
+    assert_eq!(pool.retry_policy.map(|p| p.max_retries), Some(10));
+
+    // 另一个事务先持有写锁，一段时间后提交
+    let (locked_tx, locked_rx) = oneshot::channel();
+    let holder = {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            pool.transaction(|tx| async move {
+                tx.execute(
+                    "INSERT INTO items (id, name) VALUES (?, ?)",
+                    vec![Value::from(1), Value::from("holder")],
+                )
+                .await?;
+                locked_tx.send(()).unwrap();
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                Ok::<_, DbError>(())
+            })
+            .await
+        })
+    };
+    locked_rx.await.unwrap();
+
+    let attempts = Arc::new(AtomicU32::new(0));
+    let result = pool
+        .transaction_retry(|tx| {
+            let attempts = attempts.clone();
+            async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                // 不等待锁，立即返回 SQLITE_BUSY
+                tx.execute_raw("PRAGMA busy_timeout = 0").await?;
+                tx.execute(
+                    "INSERT INTO items (id, name) VALUES (?, ?)",
+                    vec![Value::from(2), Value::from("retry")],
+                )
+                .await
+            }
+        })
+        .await;
+
+    holder.await.unwrap().unwrap();
+    assert_eq!(result.unwrap(), 1);
+    assert!(attempts.load(Ordering::SeqCst) > 1);
+}
+
+#[tokio::test]
+async fn test_transaction_retry_skips_other_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let pool = new_pool(&dir, "tx_retry_other").await;
+
+    let attempts = AtomicU32::new(0);
+    let result = pool
+        .transaction_retry(|tx| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tx.execute("INSERT INTO missing (id) VALUES (1)", vec![])
+                    .await
+            }
+        })
+        .await;
+
+    let err = result.unwrap_err();
+    assert!(!err.is_retryable());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
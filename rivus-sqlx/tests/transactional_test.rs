@@ -0,0 +1,92 @@
+use rivus_sqlx::db_conn::ConnManager;
+use rivus_sqlx::error::DbError;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::transactional;
+use serde::Deserialize;
+use serde_json::Value;
+
+type Result<T> = std::result::Result<T, DbError>;
+
+#[derive(Deserialize)]
+struct Count {
+    n: i64,
+}
+
+async fn insert(pool: &str, id: i64) -> Result<u64> {
+    let pool = ConnManager::by(pool).unwrap();
+    pool.execute("INSERT INTO items (id) VALUES (?)", vec![Value::from(id)])
+        .await
+}
+
+async fn count(pool: &str) -> i64 {
+    let pool = ConnManager::by(pool).unwrap();
+    let row: Option<Count> = pool
+        .get("SELECT COUNT(*) AS n FROM items", vec![])
+        .await
+        .unwrap();
+    row.map(|c| c.n).unwrap_or_default()
+}
+
+#[transactional]
+async fn insert_pair(first: i64, second: i64) -> Result<u64> {
+    let n = insert("default", first).await?;
+    Ok(n + insert("default", second).await?)
+}
+
+// 嵌套调用加入外层事务，外层出错时一起回滚
+#[transactional]
+async fn insert_then_fail(id: i64) -> anyhow::Result<()> {
+    insert_pair(id, id + 1).await?;
+    anyhow::bail!("abort after {}", id)
+}
+
+struct Orders;
+
+impl Orders {
+    #[transactional(pool = "orders")]
+    async fn insert_all(&self, ids: &[i64]) -> Result<()> {
+        for id in ids {
+            insert("orders", *id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_transactional() -> anyhow::Result<()> {
+    for name in ["default", "orders"] {
+        let config = DatabaseOptions::new(
+            "sqlite".to_string(),
+            format!(
+                "sqlite:file:transactional_{}?mode=memory&cache=shared",
+                name
+            ),
+        );
+        ConnManager::open(name, "sqlite", &config).await?;
+        ConnManager::by(name)
+            .unwrap()
+            .execute_raw("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+            .await?;
+    }
+
+    assert_eq!(insert_pair(1, 2).await?, 2);
+    assert_eq!(count("default").await, 2);
+
+    // 第二条主键冲突，第一条随之回滚
+    assert!(insert_pair(3, 1).await.is_err());
+    assert_eq!(count("default").await, 2);
+
+    assert!(
+        insert_then_fail(10)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("abort after 10")
+    );
+    assert_eq!(count("default").await, 2);
+
+    Orders.insert_all(&[1, 2, 3]).await?;
+    assert!(Orders.insert_all(&[4, 1]).await.is_err());
+    assert_eq!(count("orders").await, 3);
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use rivus_sqlx::db_pool::DbPool;
+use rivus_sqlx::models::db_config::DatabaseOptions;
+use rivus_sqlx::orm::sqlx_impl::SqlxRepository;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Setting {
+    tenant: String,
+    key: String,
+    value: String,
+}
+
+async fn settings(pool: &DbPool) -> Vec<Setting> {
+    pool.list(
+        "SELECT tenant, key, value FROM settings ORDER BY tenant, key",
+        vec![],
+    )
+    .await
+    .unwrap()
+}
+
+fn setting(tenant: &str, key: &str, value: &str) -> Setting {
+    Setting {
+        tenant: tenant.to_string(),
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_upsert_sqlite() {
+    let config = DatabaseOptions::new(
+        "sqlite".to_string(),
+        "sqlite:file:upsert_test?mode=memory&cache=shared".to_string(),
+    );
+    let pool = DbPool::new("upsert_test", "sqlite", &config).await.unwrap();
+    pool.execute_raw(
+        "CREATE TABLE settings (tenant TEXT, key TEXT, value TEXT, PRIMARY KEY (tenant, key))",
+    )
+    .await
+    .unwrap();
+
+    let repo = SqlxRepository;
+    let row = |value: &str| {
+        vec![
+            ("tenant", Value::from("t1")),
+            ("key", Value::from("theme")),
+            ("value", Value::from(value)),
+        ]
+    };
+
+    assert_eq!(
+        repo.upsert(&pool, "settings", &["tenant", "key"], row("dark"))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        repo.upsert(&pool, "settings", &["tenant", "key"], row("light"))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(settings(&pool).await, vec![setting("t1", "theme", "light")]);
+
+    // 全部为键列：冲突时忽略
+    let keys_only = vec![("tenant", Value::from("t1")), ("key", Value::from("theme"))];
+    assert_eq!(
+        repo.upsert(&pool, "settings", &["tenant", "key"], keys_only)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(settings(&pool).await, vec![setting("t1", "theme", "light")]);
+
+    // 键列缺少值
+    let err = repo
+        .upsert(
+            &pool,
+            "settings",
+            &["tenant", "key"],
+            vec![("tenant", Value::from("t1"))],
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("'key'"), "{}", err);
+    assert!(repo.upsert(&pool, "settings", &[], row("x")).await.is_err());
+}
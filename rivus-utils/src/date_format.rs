@@ -1,5 +1,5 @@
-use chrono::NaiveDateTime;
-use serde::{self, Serializer};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{self, Deserialize, Deserializer, Serializer};
 
 pub fn serialize_with_custom_format<S>(
     date: &Option<NaiveDateTime>,
@@ -15,6 +15,38 @@ where
     }
 }
 
+/// 按指定格式反序列化；同时接受数据库常见的输出格式（`T` / 空格分隔、带小数秒、仅日期）
+pub fn deserialize_with_custom_format<'de, D>(
+    format: &str,
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(s) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let s = s.trim();
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+        return Ok(Some(dt));
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, format) {
+        return Ok(d.and_hms_opt(0, 0, 0));
+    }
+    for fallback in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fallback) {
+            return Ok(Some(dt));
+        }
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(d.and_hms_opt(0, 0, 0));
+    }
+    Err(serde::de::Error::custom(format!(
+        "invalid datetime `{}`, expected format `{}`",
+        s, format
+    )))
+}
+
 macro_rules! define_format {
         ($name:ident, $format:expr) => {
             pub mod $name {
@@ -28,6 +60,13 @@ macro_rules! define_format {
                 {
                     serialize_with_custom_format(date, $format, serializer)
                 }
+
+                pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    deserialize_with_custom_format($format, deserializer)
+                }
             }
         };
     }
@@ -35,4 +74,3 @@ macro_rules! define_format {
 // 预定义一些常用格式
 define_format!(standard, "%Y-%m-%d %H:%M:%S");
 define_format!(date_only, "%Y-%m-%d");
-
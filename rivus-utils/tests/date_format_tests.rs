@@ -131,4 +131,40 @@ mod date_format_tests {
             assert!(result.is_ok(), "Failed to serialize with format: {}", format);
         }
     }
-}
\ No newline at end of file
+
+    #[derive(serde::Deserialize)]
+    struct Row {
+        #[serde(default, with = "date_format::standard")]
+        created_at: Option<NaiveDateTime>,
+        #[serde(default, with = "date_format::date_only")]
+        birthday: Option<NaiveDateTime>,
+    }
+
+    #[test]
+    fn test_deserialize_formats() {
+        let dt = NaiveDateTime::parse_from_str("2023-12-25 15:30:45", "%Y-%m-%d %H:%M:%S").unwrap();
+        let midnight =
+            NaiveDateTime::parse_from_str("2023-12-25 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+
+        let row: Row = serde_json::from_str(
+            r#"{"created_at": "2023-12-25 15:30:45", "birthday": "2023-12-25"}"#,
+        )
+        .unwrap();
+        assert_eq!(row.created_at, Some(dt));
+        assert_eq!(row.birthday, Some(midnight));
+
+        // 数据库常见输出：ISO 格式、小数秒
+        let row: Row = serde_json::from_str(
+            r#"{"created_at": "2023-12-25T15:30:45.000", "birthday": "2023-12-25 15:30:45"}"#,
+        )
+        .unwrap();
+        assert_eq!(row.created_at, Some(dt));
+        assert_eq!(row.birthday, Some(dt));
+
+        let row: Row = serde_json::from_str(r#"{"created_at": null}"#).unwrap();
+        assert_eq!(row.created_at, None);
+        assert_eq!(row.birthday, None);
+
+        assert!(serde_json::from_str::<Row>(r#"{"created_at": "25/12/2023"}"#).is_err());
+    }
+}
@@ -3,16 +3,20 @@ use axum::middleware::from_fn;
 use axum::{Router, middleware};
 use axum::{extract::Request, middleware::Next, response::Response};
 use std::future::Future;
+use std::pin::Pin;
 use tokio::signal;
 
 mod i18n_middleware;
 pub mod result;
 pub mod i18n;
 
+type ShutdownHook = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
 pub struct WebServer {
     router: Router,
     address: String,
     i18n_dir: String,
+    shutdown_hooks: Vec<ShutdownHook>,
 }
 
 impl WebServer {
@@ -21,6 +25,7 @@ impl WebServer {
             router,
             address: address.into(),
             i18n_dir: "i18n".to_string(),
+            shutdown_hooks: Vec::new(),
         }
     }
 
@@ -39,6 +44,25 @@ impl WebServer {
         self
     }
 
+    /// 收到停止信号后、等待进行中的请求结束前，按注册顺序执行
+    ///
+    /// ```ignore
+    /// WebServer::new(router, "0.0.0.0:8080")
+    ///     .on_shutdown(|| async {
+    ///         rivus_ws::conn_mgr::shutdown(1012, "service restart").await;
+    ///     })
+    ///     .run()
+    ///     .await?;
+    /// ```
+    pub fn on_shutdown<F, Fut>(mut self, f: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown_hooks.push(Box::new(move || Box::pin(f())));
+        self
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         // 初始化 i18n
         i18n::init(&self.i18n_dir);
@@ -49,7 +73,14 @@ impl WebServer {
         tracing::info!("⌛️ Waiting for connections...");
         tracing::info!("💡 Press Ctrl+C to stop the server");
         // 优雅关闭处理
-        let server = axum::serve(listener, self.router).with_graceful_shutdown(shutdown_signal());
+        let hooks = self.shutdown_hooks;
+        let shutdown = async move {
+            shutdown_signal().await;
+            for hook in hooks {
+                hook().await;
+            }
+        };
+        let server = axum::serve(listener, self.router).with_graceful_shutdown(shutdown);
         if let Err(e) = server.await {
             tracing::error!("Server error: {}", e);
             return Err(anyhow::anyhow!("Server error: {}", e));
@@ -0,0 +1,149 @@
+use crate::config::WsConfig;
+use crate::ws_handler::{handle_connection, WsHandlers};
+use axum::extract::ws::{CloseFrame, Message, Utf8Bytes, WebSocket};
+use axum::http::{header, HeaderMap, Uri};
+use futures::future::BoxFuture;
+
+/// 认证失败时关闭连接使用的状态码与原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthRejection {
+    pub code: u16,
+    pub reason: String,
+}
+
+impl AuthRejection {
+    pub fn new(code: u16, reason: impl Into<String>) -> Self {
+        Self {
+            code,
+            reason: reason.into(),
+        }
+    }
+
+    /// 1008 Policy Violation
+    pub fn policy(reason: impl Into<String>) -> Self {
+        Self::new(1008, reason)
+    }
+}
+
+/// 认证所需的握手信息，以及（需要时）客户端发送的首条文本消息
+#[derive(Debug, Clone, Default)]
+pub struct AuthContext {
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub first_message: Option<Utf8Bytes>,
+}
+
+impl AuthContext {
+    pub fn new(uri: Uri, headers: HeaderMap) -> Self {
+        Self {
+            uri,
+            headers,
+            first_message: None,
+        }
+    }
+
+    /// 查询参数的原始值（不做百分号解码）
+    pub fn query_param(&self, key: &str) -> Option<&str> {
+        self.uri
+            .query()?
+            .split('&')
+            .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+            .find_map(|(k, v)| (k == key).then_some(v))
+    }
+
+    /// `Authorization: Bearer <token>` 中的 token
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.headers
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")
+    }
+}
+
+/// 连接认证：通过时返回 cli_id，失败时以 [`AuthRejection`] 关闭连接
+///
+/// `Fn(AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>>` 的闭包自动实现该 trait。
+pub trait Authenticator: Send + Sync {
+    fn authenticate(&self, ctx: AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>>;
+
+    /// 是否需要先读取客户端的首条文本消息（如 `{"token": ...}`）再认证
+    fn needs_first_message(&self) -> bool {
+        false
+    }
+}
+
+impl<F> Authenticator for F
+where
+    F: Fn(AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> + Send + Sync,
+{
+    fn authenticate(&self, ctx: AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> {
+        self(ctx)
+    }
+}
+
+/// 以首条文本消息认证的闭包
+pub struct FirstMessageAuth<F>(pub F);
+
+impl<F> Authenticator for FirstMessageAuth<F>
+where
+    F: Fn(AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> + Send + Sync,
+{
+    fn authenticate(&self, ctx: AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> {
+        (self.0)(ctx)
+    }
+
+    fn needs_first_message(&self) -> bool {
+        true
+    }
+}
+
+/// 认证后处理 WebSocket 连接：首条消息认证最多等待 `config.auth_timeout`，认证通过后以返回的 cli_id 调用 [`handle_connection`]，失败时发送关闭帧后断开
+pub async fn handle_authenticated_connection(
+    mut socket: WebSocket,
+    mut ctx: AuthContext,
+    authenticator: &dyn Authenticator,
+    handlers: WsHandlers,
+    config: WsConfig,
+) {
+    if authenticator.needs_first_message() {
+        match tokio::time::timeout(config.auth_timeout, first_text(&mut socket)).await {
+            Ok(Some(text)) => ctx.first_message = Some(text),
+            Ok(None) => return,
+            Err(_) => {
+                reject(socket, AuthRejection::policy("authentication timeout")).await;
+                return;
+            }
+        }
+    }
+
+    match authenticator.authenticate(ctx).await {
+        Ok(cli_id) => handle_connection(socket, cli_id, handlers, config).await,
+        Err(rejection) => {
+            tracing::warn!(code = rejection.code, reason = %rejection.reason, "WebSocket authentication rejected");
+            reject(socket, rejection).await;
+        }
+    }
+}
+
+// 读取首条文本消息，跳过控制帧；连接关闭或收到其他消息时返回 None
+async fn first_text(socket: &mut WebSocket) -> Option<Utf8Bytes> {
+    while let Some(Ok(msg)) = socket.recv().await {
+        match msg {
+            Message::Text(text) => return Some(text),
+            Message::Ping(_) | Message::Pong(_) => continue,
+            _ => return None,
+        }
+    }
+    None
+}
+
+async fn reject(mut socket: WebSocket, rejection: AuthRejection) {
+    let frame = CloseFrame {
+        code: rejection.code,
+        reason: rejection.reason.into(),
+    };
+    if let Err(e) = socket.send(Message::Close(Some(frame))).await {
+        tracing::debug!(error = ?e, "Failed to send close frame");
+    }
+}
@@ -0,0 +1,366 @@
+use crate::codec::Codec;
+use crate::middleware::Inbound;
+use crate::send_queue::{self, OverflowPolicy, PushError, QueueReceiver, QueueSender};
+use crate::ws_handler::WsHandlers;
+use axum::body::Bytes;
+use axum::extract::ws::{CloseFrame, Message, Utf8Bytes};
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::time;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame as TungsteniteCloseFrame;
+use tokio_tungstenite::tungstenite::Message as TungsteniteMessage;
+
+/// 连接建立处理器，可在其中发送订阅消息
+pub type ConnectHandler = Arc<dyn Fn(WsSender) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// WebSocket 客户端配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// 心跳 ping 的发送间隔（默认 30 秒），为 0 时不发送心跳，也不检查 `ping_timeout`
+    pub ping_interval: Duration,
+    /// 超过该时间未收到服务端任何消息则重新连接（默认 90 秒）
+    pub ping_timeout: Duration,
+    /// 建立连接的超时时间（默认 10 秒）
+    pub connect_timeout: Duration,
+    /// 待发送消息的队列容量（默认 1000），断线期间的消息在重连后发出
+    pub queue_capacity: usize,
+    /// 队列已满时的处理策略（默认丢弃最早的消息）；为 [`OverflowPolicy::Disconnect`] 时关闭客户端
+    pub overflow_policy: OverflowPolicy,
+    /// 首次重连的等待时间（默认 500 毫秒），之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 重连等待时间的上限（默认 30 秒）
+    pub max_backoff: Duration,
+    /// 连续重连失败的最大次数，超过后停止（默认不限制）
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(30),
+            ping_timeout: Duration::from_secs(90),
+            connect_timeout: Duration::from_secs(10),
+            queue_capacity: 1000,
+            overflow_policy: OverflowPolicy::DropOldest,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置心跳间隔
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// 设置心跳超时时间
+    pub fn with_ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = timeout;
+        self
+    }
+
+    /// 设置建立连接的超时时间
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// 设置待发送消息的队列容量
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity;
+        self
+    }
+
+    /// 设置队列已满时的处理策略
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// 设置重连等待时间的初始值与上限
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// 设置连续重连失败的最大次数
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = Some(retries);
+        self
+    }
+}
+
+/// 基于 tokio-tungstenite 的 WebSocket 客户端，断线后按指数退避自动重连，需开启 `client` feature
+///
+/// 收到的消息交给 [`WsHandlers`]（处理器的 `cli_id` 参数为 [`Self::with_id`] 设置的 ID），
+/// 连接断开时调用关闭处理器，之后重新连接。
+///
+/// ```ignore
+/// let sender = WsClient::new("wss://feed.example.com/ws")
+///     .with_handlers(WsHandlers::new().on_decoded(JsonCodec, handle_tick))
+///     .on_connect(|sender| Box::pin(async move {
+///         let _ = sender.send_encoded(&Subscribe { symbols: vec!["BTC"] }, &JsonCodec);
+///     }))
+///     .spawn();
+/// ```
+pub struct WsClient {
+    url: String,
+    id: u64,
+    handlers: WsHandlers,
+    config: ClientConfig,
+    on_connect: Option<ConnectHandler>,
+}
+
+impl WsClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            id: 0,
+            handlers: WsHandlers::new(),
+            config: ClientConfig::default(),
+            on_connect: None,
+        }
+    }
+
+    /// 设置传给处理器的 ID（默认 0），用于区分多个上游连接
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn with_handlers(mut self, handlers: WsHandlers) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    pub fn with_config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 每次连接（包括重连）建立后调用，用于重新发送订阅等消息
+    pub fn on_connect<F>(mut self, f: F) -> Self
+    where
+        F: Fn(WsSender) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.on_connect = Some(Arc::new(f));
+        self
+    }
+
+    /// 在后台任务中连接并保持连接，返回发送端；调用 [`WsSender::close`] 后停止
+    pub fn spawn(self) -> WsSender {
+        let (tx, rx) = send_queue::channel(self.config.queue_capacity, self.config.overflow_policy);
+        let sender = WsSender {
+            queue: tx,
+            connected: Arc::new(AtomicBool::new(false)),
+            stopped: Arc::new(AtomicBool::new(false)),
+            stop: Arc::new(Notify::new()),
+        };
+        tokio::spawn(self.run(sender.clone(), rx));
+        sender
+    }
+
+    async fn run(self, sender: WsSender, mut queue: QueueReceiver) {
+        let mut pending = None;
+        let mut failures = 0u32;
+        let mut backoff = self.config.initial_backoff;
+        loop {
+            match time::timeout(
+                self.config.connect_timeout,
+                tokio_tungstenite::connect_async(&self.url),
+            )
+            .await
+            {
+                Ok(Ok((socket, _))) => {
+                    tracing::info!(url = %self.url, "WebSocket client connected");
+                    failures = 0;
+                    backoff = self.config.initial_backoff;
+                    sender.connected.store(true, Ordering::Relaxed);
+                    if let Some(f) = &self.on_connect {
+                        f(sender.clone()).await;
+                    }
+                    let stopped = self.serve(socket, &mut queue, &mut pending).await;
+                    sender.connected.store(false, Ordering::Relaxed);
+                    if let Some(f) = &self.handlers.on_close {
+                        f(self.id).await;
+                    }
+                    if stopped {
+                        break;
+                    }
+                }
+                Ok(Err(e)) => tracing::warn!(url = %self.url, error = %e, "WebSocket client failed to connect"),
+                Err(_) => tracing::warn!(url = %self.url, "WebSocket client connect timed out"),
+            }
+
+            failures += 1;
+            if self.config.max_retries.is_some_and(|max| failures > max) {
+                tracing::error!(url = %self.url, failures, "WebSocket client giving up reconnecting");
+                break;
+            }
+            tracing::info!(url = %self.url, delay = ?backoff, "WebSocket client reconnecting");
+            tokio::select! {
+                _ = time::sleep(backoff) => {}
+                _ = sender.stop.notified() => break,
+            }
+            if sender.stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            backoff = (backoff * 2).min(self.config.max_backoff);
+        }
+        sender.stopped.store(true, Ordering::Relaxed);
+    }
+
+    // 处理一个连接，返回是否已停止（调用了 close 或队列已关闭）；发送失败的消息保存在 pending 中，重连后先发出
+    async fn serve(
+        &self,
+        socket: tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+        queue: &mut QueueReceiver,
+        pending: &mut Option<TungsteniteMessage>,
+    ) -> bool {
+        let (mut sink, mut stream) = socket.split();
+        let chain = self.handlers.chain();
+        // 间隔为 0 时关闭心跳，time::interval 不接受 0
+        let heartbeat = !self.config.ping_interval.is_zero();
+        let mut ping = time::interval(self.config.ping_interval.max(Duration::from_millis(1)));
+        let mut last_activity = Instant::now();
+
+        if let Some(message) = pending.take()
+            && let Err(e) = sink.send(message.clone()).await
+        {
+            tracing::warn!(error = %e, "WebSocket client failed to send message");
+            *pending = Some(message);
+            return false;
+        }
+
+        loop {
+            tokio::select! {
+                outbound = queue.next() => {
+                    let Some(message) = outbound else {
+                        let _ = sink.close().await;
+                        return true;
+                    };
+                    let closing = matches!(message, Message::Close(_));
+                    let message = to_tungstenite(message);
+                    if let Err(e) = sink.send(message.clone()).await {
+                        tracing::warn!(error = %e, "WebSocket client failed to send message");
+                        if !closing {
+                            *pending = Some(message);
+                        }
+                        return closing;
+                    }
+                    if closing {
+                        return true;
+                    }
+                }
+                inbound = stream.next() => {
+                    last_activity = Instant::now();
+                    match inbound {
+                        Some(Ok(TungsteniteMessage::Text(text))) => {
+                            chain.clone().run(self.id, Inbound::Text(Utf8Bytes::from(text.as_str()))).await;
+                        }
+                        Some(Ok(TungsteniteMessage::Binary(data))) => {
+                            chain.clone().run(self.id, Inbound::Binary(data)).await;
+                        }
+                        Some(Ok(TungsteniteMessage::Close(frame))) => {
+                            tracing::info!(frame = ?frame, "WebSocket server closed connection");
+                            return false;
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            tracing::warn!(error = %e, "WebSocket client receive error");
+                            return false;
+                        }
+                        None => return false,
+                    }
+                }
+                _ = ping.tick(), if heartbeat => {
+                    if last_activity.elapsed() > self.config.ping_timeout {
+                        tracing::warn!(url = %self.url, "WebSocket server ping timeout, reconnecting");
+                        return false;
+                    }
+                    if sink.send(TungsteniteMessage::Ping(Bytes::new())).await.is_err() {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn to_tungstenite(message: Message) -> TungsteniteMessage {
+    match message {
+        Message::Text(text) => TungsteniteMessage::Text(text.as_str().into()),
+        Message::Binary(data) => TungsteniteMessage::Binary(data),
+        Message::Ping(data) => TungsteniteMessage::Ping(data),
+        Message::Pong(data) => TungsteniteMessage::Pong(data),
+        Message::Close(frame) => TungsteniteMessage::Close(
+            frame.map(|frame| TungsteniteCloseFrame { code: frame.code.into(), reason: frame.reason.as_str().into() }),
+        ),
+    }
+}
+
+/// [`WsClient`] 的发送端，可克隆；消息先写入队列，断线期间保留，重连后发出
+#[derive(Clone)]
+pub struct WsSender {
+    queue: QueueSender,
+    connected: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+    stop: Arc<Notify>,
+}
+
+impl WsSender {
+    /// 写入消息，不等待
+    pub fn send(&self, message: Message) -> Result<(), PushError> {
+        self.queue.push(message)
+    }
+
+    pub fn send_text(&self, text: impl Into<String>) -> Result<(), PushError> {
+        self.send(Message::Text(text.into().into()))
+    }
+
+    pub fn send_binary(&self, data: impl Into<Bytes>) -> Result<(), PushError> {
+        self.send(Message::Binary(data.into()))
+    }
+
+    /// 以 `codec` 编码 `value` 后写入
+    pub fn send_encoded<T: Serialize + ?Sized, C: Codec>(
+        &self,
+        value: &T,
+        codec: &C,
+    ) -> anyhow::Result<()> {
+        Ok(self.send(codec.encode(value)?)?)
+    }
+
+    /// 当前是否已连接
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// 客户端是否已停止（调用了 [`Self::close`] 或超过最大重连次数）
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// 丢弃尚未发送的消息，发送关闭帧（1000）并停止重连
+    pub fn close(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.queue.close(CloseFrame {
+            code: 1000,
+            reason: Utf8Bytes::from_static(""),
+        });
+        self.stop.notify_one();
+    }
+}
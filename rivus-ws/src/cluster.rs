@@ -0,0 +1,236 @@
+use crate::conn_mgr::{broadcast_local, disconnect_local, send_local, send_to_group_local};
+use axum::extract::ws::{CloseFrame, Message};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+#[cfg(feature = "redis")]
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 订阅中断后重新订阅的间隔
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+static CLUSTER: OnceLock<Cluster> = OnceLock::new();
+
+struct Cluster {
+    node_id: String,
+    backplane: Arc<dyn Backplane>,
+}
+
+/// 节点间转发消息的通道，如 Redis pub/sub
+///
+/// 每个节点发布的消息会被所有节点（包括自身）收到，自身发布的消息按节点 ID 忽略。
+pub trait Backplane: Send + Sync + 'static {
+    /// 发布消息到所有节点
+    fn publish(&self, payload: Vec<u8>) -> BoxFuture<'_, anyhow::Result<()>>;
+
+    /// 订阅所有节点发布的消息；流结束视为订阅中断，稍后重新订阅
+    fn subscribe(&self) -> BoxFuture<'_, anyhow::Result<BoxStream<'static, Vec<u8>>>>;
+}
+
+/// 集群消息的投递目标
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    Client(u64),
+    Group(String),
+    All,
+}
+
+/// 集群中转发的消息帧
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+    /// 断开客户端，只用于 [`Target::Client`]
+    Close {
+        code: u16,
+        reason: String,
+    },
+}
+
+/// 节点间转发的消息，以 MessagePack 编码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClusterMessage {
+    /// 发布消息的节点
+    pub node: String,
+    pub target: Target,
+    pub frame: Frame,
+}
+
+impl ClusterMessage {
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(rmp_serde::to_vec(self)?)
+    }
+
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        Ok(rmp_serde::from_slice(data)?)
+    }
+}
+
+/// 开启集群模式：`send_message`、`send_binary`、`broadcast`、`send_to_group` 等在本节点投递的同时发布到其他节点，
+/// 并投递其他节点发布的消息到本节点的连接。只能开启一次，且不能与离线消息（[`crate::store::enable`]）同时开启
+pub async fn enable(backplane: impl Backplane) -> anyhow::Result<()> {
+    if CLUSTER.get().is_some() {
+        return Err(anyhow::anyhow!("cluster mode already enabled"));
+    }
+    if crate::store::get().is_some() {
+        return Err(anyhow::anyhow!(
+            "cluster mode cannot be enabled with message store"
+        ));
+    }
+    let backplane: Arc<dyn Backplane> = Arc::new(backplane);
+    let stream = backplane.subscribe().await?;
+    let node_id = new_node_id();
+    let cluster = Cluster {
+        node_id: node_id.clone(),
+        backplane: backplane.clone(),
+    };
+    if CLUSTER.set(cluster).is_err() {
+        return Err(anyhow::anyhow!("cluster mode already enabled"));
+    }
+    tracing::info!(node_id = %node_id, "WebSocket cluster mode enabled");
+    tokio::spawn(receive_loop(backplane, node_id, stream));
+    Ok(())
+}
+
+/// 本节点的 ID；未开启集群模式时为 `None`
+pub fn node_id() -> Option<&'static str> {
+    CLUSTER.get().map(|c| c.node_id.as_str())
+}
+
+// 进程 ID 加启动时间，足以区分同一集群中的节点
+fn new_node_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", std::process::id(), nanos)
+}
+
+/// 集群模式下发布消息到其他节点，返回是否已发布；未开启集群模式时返回 `false`。只转发文本、二进制与关闭帧
+pub(crate) async fn publish(target: Target, message: &Message) -> anyhow::Result<bool> {
+    let Some(cluster) = CLUSTER.get() else {
+        return Ok(false);
+    };
+    let frame = match message {
+        Message::Text(text) => Frame::Text(text.to_string()),
+        Message::Binary(data) => Frame::Binary(data.to_vec()),
+        Message::Close(Some(frame)) => Frame::Close {
+            code: frame.code,
+            reason: frame.reason.to_string(),
+        },
+        _ => return Ok(false),
+    };
+    let payload = ClusterMessage {
+        node: cluster.node_id.clone(),
+        target,
+        frame,
+    }
+    .encode()?;
+    cluster.backplane.publish(payload).await?;
+    Ok(true)
+}
+
+async fn receive_loop(
+    backplane: Arc<dyn Backplane>,
+    node_id: String,
+    mut stream: BoxStream<'static, Vec<u8>>,
+) {
+    loop {
+        while let Some(payload) = stream.next().await {
+            match ClusterMessage::decode(&payload) {
+                Ok(message) if message.node != node_id => deliver(message).await,
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "Invalid cluster message"),
+            }
+        }
+        tracing::warn!("Cluster subscription ended, resubscribing");
+        stream = loop {
+            tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            match backplane.subscribe().await {
+                Ok(stream) => break stream,
+                Err(e) => tracing::error!(error = ?e, "Failed to resubscribe to cluster backplane"),
+            }
+        };
+    }
+}
+
+// 投递其他节点发布的消息到本节点的连接，目标不在本节点时忽略
+async fn deliver(message: ClusterMessage) {
+    let frame = match message.frame {
+        Frame::Text(text) => Message::Text(text.into()),
+        Frame::Binary(data) => Message::Binary(data.into()),
+        Frame::Close { code, reason } => {
+            if let Target::Client(cli_id) = message.target {
+                let _ = disconnect_local(
+                    cli_id,
+                    CloseFrame {
+                        code,
+                        reason: reason.into(),
+                    },
+                )
+                .await;
+            }
+            return;
+        }
+    };
+    match message.target {
+        Target::Client(cli_id) => {
+            let _ = send_local(cli_id, frame).await;
+        }
+        Target::Group(group) => {
+            let _ = send_to_group_local(&group, frame).await;
+        }
+        Target::All => {
+            broadcast_local(frame).await;
+        }
+    }
+}
+
+/// 基于 Redis pub/sub 的 [`Backplane`]，需开启 `redis` feature
+#[cfg(feature = "redis")]
+pub struct RedisBackplane {
+    client: redis::Client,
+    connection: redis::aio::MultiplexedConnection,
+    channel: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisBackplane {
+    /// 连接 Redis，所有节点须使用相同的 `channel`
+    pub async fn connect(url: &str, channel: impl Into<String>) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_multiplexed_async_connection().await?;
+        Ok(Self {
+            client,
+            connection,
+            channel: channel.into(),
+        })
+    }
+}
+
+#[cfg(feature = "redis")]
+impl Backplane for RedisBackplane {
+    fn publish(&self, payload: Vec<u8>) -> BoxFuture<'_, anyhow::Result<()>> {
+        let mut connection = self.connection.clone();
+        Box::pin(async move {
+            connection
+                .publish::<_, _, ()>(&self.channel, payload)
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn subscribe(&self) -> BoxFuture<'_, anyhow::Result<BoxStream<'static, Vec<u8>>>> {
+        Box::pin(async move {
+            let mut pubsub = self.client.get_async_pubsub().await?;
+            pubsub.subscribe(&self.channel).await?;
+            Ok(pubsub
+                .into_on_message()
+                .map(|msg| msg.get_payload_bytes().to_vec())
+                .boxed())
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use axum::extract::ws::Message;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 消息编解码：出站时将值编码为 WebSocket 消息，入站时将文本或二进制帧的内容解码为值
+///
+/// 内置 [`JsonCodec`]（文本帧）与 [`MsgPackCodec`]（二进制帧）；protobuf 等格式可自行实现该 trait。
+pub trait Codec: Clone + Send + Sync + 'static {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> anyhow::Result<Message>;
+
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> anyhow::Result<T>;
+}
+
+/// JSON 编解码，编码为文本帧（默认）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> anyhow::Result<Message> {
+        Ok(Message::Text(serde_json::to_string(value)?.into()))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(data)?)
+    }
+}
+
+/// MessagePack 编解码，编码为二进制帧；结构体编码为以字段名为键的 map，便于与其他语言的客户端互通
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackCodec;
+
+impl Codec for MsgPackCodec {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> anyhow::Result<Message> {
+        Ok(Message::Binary(rmp_serde::to_vec_named(value)?.into()))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> anyhow::Result<T> {
+        Ok(rmp_serde::from_slice(data)?)
+    }
+}
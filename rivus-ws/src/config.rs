@@ -0,0 +1,75 @@
+use crate::rate_limit::RateLimit;
+use crate::send_queue::OverflowPolicy;
+use std::time::Duration;
+
+/// WebSocket 连接配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsConfig {
+    /// 心跳 ping 的发送间隔（默认 30 秒），为 0 时不发送心跳，也不检查 `ping_timeout`
+    pub ping_interval: Duration,
+    /// 超过该时间未收到客户端任何消息则断开连接（默认 120 秒），应大于 `ping_interval`
+    pub ping_timeout: Duration,
+    /// 首条消息认证时等待客户端消息的时间（默认 10 秒）
+    pub auth_timeout: Duration,
+    /// 每个连接待发送消息的队列容量（默认 100）
+    pub send_queue_capacity: usize,
+    /// 发送队列已满时的处理策略（默认断开慢消费者）
+    pub overflow_policy: OverflowPolicy,
+    /// 每个连接接收消息的速率限制（默认不限制）
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl Default for WsConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(30),
+            ping_timeout: Duration::from_secs(120),
+            auth_timeout: Duration::from_secs(10),
+            send_queue_capacity: 100,
+            overflow_policy: OverflowPolicy::default(),
+            rate_limit: None,
+        }
+    }
+}
+
+impl WsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置心跳间隔
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// 设置心跳超时时间
+    pub fn with_ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = timeout;
+        self
+    }
+
+    /// 设置首条消息认证的等待时间
+    pub fn with_auth_timeout(mut self, timeout: Duration) -> Self {
+        self.auth_timeout = timeout;
+        self
+    }
+
+    /// 设置发送队列容量
+    pub fn with_send_queue_capacity(mut self, capacity: usize) -> Self {
+        self.send_queue_capacity = capacity;
+        self
+    }
+
+    /// 设置发送队列已满时的处理策略
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// 设置每个连接接收消息的速率限制
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+}
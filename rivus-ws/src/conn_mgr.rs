@@ -1,8 +1,17 @@
+use crate::cluster::{self, Target};
+use crate::codec::{Codec, JsonCodec};
+use crate::metrics;
+use crate::presence::{self, PresenceStatus};
+use crate::send_queue::{PushError, QueueSender};
+use crate::store;
 use anyhow::anyhow;
-use futures::channel::mpsc;
-use futures::SinkExt;
-use std::collections::HashMap;
+use axum::body::Bytes;
+use axum::extract::ws::{CloseFrame, Message};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime};
 use tokio::sync::Mutex;
 
 pub struct Msg {
@@ -16,30 +25,245 @@ pub static CONN_MGR: LazyLock<Arc<Mutex<ConnectionManager>>> = LazyLock::new(||
     Arc::new(Mutex::new(ConnectionManager::new()))
 });
 
+/// 超出连接上限时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LimitPolicy {
+    /// 拒绝新连接
+    #[default]
+    Reject,
+    /// 关闭最早建立的连接（按客户端上限时为该客户端最早的连接），接受新连接
+    EvictOldest,
+}
+
+/// 连接数上限，`None` 表示不限制
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    /// 所有客户端的连接总数上限
+    pub max_connections: Option<usize>,
+    /// 单个客户端的连接数上限
+    pub max_per_client: Option<usize>,
+    pub policy: LimitPolicy,
+}
+
+impl ConnectionLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.max_connections = Some(max);
+        self
+    }
+
+    pub fn with_max_per_client(mut self, max: usize) -> Self {
+        self.max_per_client = Some(max);
+        self
+    }
+
+    pub fn with_policy(mut self, policy: LimitPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// [`LimitPolicy::Reject`] 策略下新连接超出上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitExceeded {
+    /// 超出连接总数上限
+    Global(usize),
+    /// 超出单个客户端的连接数上限
+    PerClient(usize),
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global(max) => write!(f, "too many connections (max {})", max),
+            Self::PerClient(max) => write!(f, "too many connections for client (max {})", max),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// 因超出上限被关闭的连接收到的关闭状态码（1008 Policy Violation）
+pub const LIMIT_CLOSE_CODE: u16 = 1008;
+
+/// 连接的元数据（设备类型、授权范围、订阅主题等），键值对
+pub type Metadata = serde_json::Map<String, serde_json::Value>;
+
+struct Connection {
+    sender: QueueSender,
+    meta: Metadata,
+}
+
+#[derive(Default)]
 pub struct ConnectionManager {
-    connections: HashMap<u64, HashMap<usize, mpsc::Sender<String>>>,
+    connections: HashMap<u64, HashMap<usize, Connection>>,
     next_conn_id: usize,
+    limits: ConnectionLimits,
+    // 分组 -> 成员客户端
+    groups: HashMap<String, HashSet<u64>>,
+    // 客户端 -> 所在分组，断开时据此清理
+    client_groups: HashMap<u64, HashSet<String>>,
+    // 离线客户端 -> 最后一个连接断开的时间
+    last_seen: HashMap<u64, SystemTime>,
 }
 
 impl ConnectionManager {
     pub fn new() -> Self {
-        Self {
-            connections: HashMap::new(),
-            next_conn_id: 0,
-        }
+        Self::default()
+    }
+
+    /// 设置连接数上限，只作用于之后新建的连接
+    pub fn set_limits(&mut self, limits: ConnectionLimits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> &ConnectionLimits {
+        &self.limits
+    }
+
+    /// 添加新连接并返回连接ID
+    ///
+    /// 超出 [`ConnectionLimits`] 时按策略拒绝新连接，或向最早的连接发送关闭帧并将其移除。
+    pub fn add_connection(
+        &mut self,
+        cli_id: u64,
+        sender: QueueSender,
+    ) -> Result<usize, LimitExceeded> {
+        self.add_connection_with(cli_id, sender, Metadata::new())
     }
 
-    // 添加新连接并返回连接ID
-    pub fn add_connection(&mut self, cli_id: u64, sender: mpsc::Sender<String>) -> usize {
+    /// 添加带元数据的连接，同 [`Self::add_connection`]
+    pub fn add_connection_with(
+        &mut self,
+        cli_id: u64,
+        sender: QueueSender,
+        meta: Metadata,
+    ) -> Result<usize, LimitExceeded> {
+        if let Some(max) = self.limits.max_per_client
+            && self.client_connection_count(cli_id) >= max
+        {
+            if self.limits.policy == LimitPolicy::Reject || max == 0 {
+                return Err(LimitExceeded::PerClient(max));
+            }
+            let oldest = self.connections[&cli_id].keys().copied().min();
+            if let Some(conn_id) = oldest {
+                self.evict(cli_id, conn_id, "too many connections for client");
+            }
+        }
+        if let Some(max) = self.limits.max_connections
+            && self.connection_count() >= max
+        {
+            if self.limits.policy == LimitPolicy::Reject || max == 0 {
+                return Err(LimitExceeded::Global(max));
+            }
+            let oldest = self
+                .connections
+                .iter()
+                .flat_map(|(cli_id, conns)| conns.keys().map(move |conn_id| (*conn_id, *cli_id)))
+                .min();
+            if let Some((conn_id, oldest_cli)) = oldest {
+                self.evict(oldest_cli, conn_id, "too many connections");
+            }
+        }
+
         let conn_id = self.next_conn_id;
         self.next_conn_id += 1;
 
+        if !self.connections.contains_key(&cli_id) {
+            self.last_seen.remove(&cli_id);
+            presence::notify(cli_id, PresenceStatus::Online, SystemTime::now());
+        }
         self.connections
             .entry(cli_id)
             .or_default()
-            .insert(conn_id, sender);
+            .insert(conn_id, Connection { sender, meta });
 
-        conn_id
+        Ok(conn_id)
+    }
+
+    /// 连接的元数据；连接不存在时返回 `None`
+    pub fn metadata(&self, cli_id: u64, conn_id: usize) -> Option<&Metadata> {
+        self.connection(cli_id, conn_id).map(|c| &c.meta)
+    }
+
+    /// 设置连接的元数据项，返回连接是否存在
+    pub fn set_metadata(
+        &mut self,
+        cli_id: u64,
+        conn_id: usize,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> bool {
+        match self
+            .connections
+            .get_mut(&cli_id)
+            .and_then(|conns| conns.get_mut(&conn_id))
+        {
+            Some(conn) => {
+                conn.meta.insert(key.into(), value.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 移除连接的元数据项，返回原值
+    pub fn remove_metadata(
+        &mut self,
+        cli_id: u64,
+        conn_id: usize,
+        key: &str,
+    ) -> Option<serde_json::Value> {
+        self.connections
+            .get_mut(&cli_id)?
+            .get_mut(&conn_id)?
+            .meta
+            .remove(key)
+    }
+
+    fn connection(&self, cli_id: u64, conn_id: usize) -> Option<&Connection> {
+        self.connections.get(&cli_id)?.get(&conn_id)
+    }
+
+    // 通知连接关闭并将其移除；连接 ID 递增，最小的即最早建立的
+    fn evict(&mut self, cli_id: u64, conn_id: usize, reason: &'static str) {
+        tracing::warn!(cli_id = %cli_id, conn_id = %conn_id, reason, "Evicting connection over limit");
+        self.close_connection(
+            cli_id,
+            conn_id,
+            CloseFrame {
+                code: LIMIT_CLOSE_CODE,
+                reason: reason.into(),
+            },
+        );
+    }
+
+    // 丢弃尚未发送的消息，发送关闭帧并移除连接；连接不存在时返回 false
+    fn close_connection(&mut self, cli_id: u64, conn_id: usize, frame: CloseFrame) -> bool {
+        let Some(conn) = self.connection(cli_id, conn_id) else {
+            return false;
+        };
+        conn.sender.close(frame);
+        self.remove_connection(cli_id, conn_id);
+        true
+    }
+
+    // 在尚未发送的消息之后发送关闭帧并移除连接；连接不存在时返回 false
+    pub(crate) fn finish_connection(
+        &mut self,
+        cli_id: u64,
+        conn_id: usize,
+        frame: CloseFrame,
+    ) -> bool {
+        let Some(conn) = self.connection(cli_id, conn_id) else {
+            return false;
+        };
+        conn.sender.finish(frame);
+        self.remove_connection(cli_id, conn_id);
+        true
     }
 
     // 移除单个连接
@@ -48,40 +272,414 @@ impl ConnectionManager {
             cli_conns.remove(&conn_id);
             if cli_conns.is_empty() {
                 self.connections.remove(&cli_id);
+                self.leave_all_groups(cli_id);
+                self.mark_offline(cli_id, SystemTime::now());
                 tracing::info!(user_id = ?cli_id, "Removed user from connection manager");
             }
         }
     }
+
+    /// 客户端加入分组；客户端没有活动连接时返回 `false`
+    ///
+    /// 客户端的所有连接均断开后自动退出所有分组。
+    pub fn join_group(&mut self, cli_id: u64, group: &str) -> bool {
+        if !self.connections.contains_key(&cli_id) {
+            return false;
+        }
+        self.groups
+            .entry(group.to_string())
+            .or_default()
+            .insert(cli_id);
+        self.client_groups
+            .entry(cli_id)
+            .or_default()
+            .insert(group.to_string());
+        true
+    }
+
+    /// 客户端退出分组，返回客户端原先是否在该分组中
+    pub fn leave_group(&mut self, cli_id: u64, group: &str) -> bool {
+        let Some(members) = self.groups.get_mut(group) else {
+            return false;
+        };
+        let removed = members.remove(&cli_id);
+        if members.is_empty() {
+            self.groups.remove(group);
+        }
+        if let Some(groups) = self.client_groups.get_mut(&cli_id) {
+            groups.remove(group);
+            if groups.is_empty() {
+                self.client_groups.remove(&cli_id);
+            }
+        }
+        removed
+    }
+
+    /// 分组中的客户端
+    pub fn group_members(&self, group: &str) -> Vec<u64> {
+        self.groups
+            .get(group)
+            .map(|m| m.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// 客户端所在的分组
+    pub fn client_groups(&self, cli_id: u64) -> Vec<String> {
+        self.client_groups
+            .get(&cli_id)
+            .map(|g| g.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn leave_all_groups(&mut self, cli_id: u64) {
+        for group in self.client_groups.remove(&cli_id).unwrap_or_default() {
+            if let Some(members) = self.groups.get_mut(&group) {
+                members.remove(&cli_id);
+                if members.is_empty() {
+                    self.groups.remove(&group);
+                }
+            }
+        }
+    }
+
+    /// 当前连接总数
+    pub fn connection_count(&self) -> usize {
+        self.connections.values().map(HashMap::len).sum()
+    }
+
+    /// 移除所有连接与分组
+    pub fn clear(&mut self) {
+        let now = SystemTime::now();
+        for cli_id in self.online_clients() {
+            self.mark_offline(cli_id, now);
+        }
+        self.connections.clear();
+        self.groups.clear();
+        self.client_groups.clear();
+    }
+
+    fn mark_offline(&mut self, cli_id: u64, at: SystemTime) {
+        self.last_seen.insert(cli_id, at);
+        presence::notify(cli_id, PresenceStatus::Offline, at);
+    }
+
+    /// 客户端是否有活动连接
+    pub fn is_online(&self, cli_id: u64) -> bool {
+        self.connections.contains_key(&cli_id)
+    }
+
+    /// 所有有活动连接的客户端
+    pub fn online_clients(&self) -> Vec<u64> {
+        self.connections.keys().copied().collect()
+    }
+
+    /// 客户端最后一个连接断开的时间；在线或从未连接时返回 `None`
+    pub fn last_seen(&self, cli_id: u64) -> Option<SystemTime> {
+        self.last_seen.get(&cli_id).copied()
+    }
+
+    /// 当前有连接的客户端数
+    pub fn client_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// 客户端当前的连接数
+    pub fn client_connection_count(&self, cli_id: u64) -> usize {
+        self.connections.get(&cli_id).map_or(0, HashMap::len)
+    }
+
+    /// 客户端当前各连接的 ID
+    pub fn connection_ids(&self, cli_id: u64) -> Vec<usize> {
+        self.connections
+            .get(&cli_id)
+            .map(|conns| conns.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// 各客户端当前的连接数
+    pub fn connections_per_client(&self) -> HashMap<u64, usize> {
+        self.connections
+            .iter()
+            .map(|(cli_id, conns)| (*cli_id, conns.len()))
+            .collect()
+    }
+
+    // 复制所有连接的发送端，便于释放锁后再发送
+    fn all_senders(&self) -> Vec<(u64, usize, QueueSender)> {
+        self.senders_where(|_, _| true)
+    }
+
+    fn senders_of(&self, cli_ids: impl IntoIterator<Item = u64>) -> Vec<(u64, usize, QueueSender)> {
+        cli_ids
+            .into_iter()
+            .filter_map(|cli_id| self.connections.get(&cli_id).map(|conns| (cli_id, conns)))
+            .flat_map(|(cli_id, conns)| conns.iter().map(move |(conn_id, c)| (cli_id, *conn_id, c.sender.clone())))
+            .collect()
+    }
+
+    fn senders_where(
+        &self,
+        filter: impl Fn(u64, &Metadata) -> bool,
+    ) -> Vec<(u64, usize, QueueSender)> {
+        self.connections
+            .iter()
+            .flat_map(|(cli_id, conns)| conns.iter().map(move |(conn_id, c)| (*cli_id, *conn_id, c)))
+            .filter(|(cli_id, _, c)| filter(*cli_id, &c.meta))
+            .map(|(cli_id, conn_id, c)| (cli_id, conn_id, c.sender.clone()))
+            .collect()
+    }
 }
 
 
 pub async fn send_message(cli_id: u64, body: String) -> anyhow::Result<()> {
     tracing::debug!("cli_id: {}, websocket channel received message body: {}", cli_id, body);
-    let mut conn_mgr = CONN_MGR.lock().await;
-    if let Some(cli_conns) = conn_mgr.connections.get_mut(&cli_id) {
-        let mut failed_conn_ids = Vec::new();
+    send_to(cli_id, Message::Text(body.into())).await
+}
 
-        for (conn_id, sender) in cli_conns.iter_mut() {
-            if let Err(e) = sender.send(body.clone()).await {
-                tracing::error!(error = ?e, cli_id = %cli_id, conn_id = %conn_id, "Failed to send message to connection");
-                failed_conn_ids.push(*conn_id);
-            }
+/// 将 `value` 序列化为 JSON 文本，发送到客户端的所有连接
+pub async fn send_json<T: Serialize + ?Sized>(cli_id: u64, value: &T) -> anyhow::Result<()> {
+    send_encoded(cli_id, value, &JsonCodec).await
+}
+
+/// 以 `codec` 编码 `value`，发送到客户端的所有连接
+pub async fn send_encoded<T: Serialize + ?Sized, C: Codec>(
+    cli_id: u64,
+    value: &T,
+    codec: &C,
+) -> anyhow::Result<()> {
+    send_to(cli_id, codec.encode(value)?).await
+}
+
+/// 向客户端的所有连接发送二进制消息（如 protobuf / flatbuffers 负载）
+pub async fn send_binary(cli_id: u64, data: Bytes) -> anyhow::Result<()> {
+    tracing::debug!(cli_id = %cli_id, bytes = data.len(), "websocket channel received binary message");
+    send_to(cli_id, Message::Binary(data)).await
+}
+
+// 发送到客户端的所有连接；集群模式下同时发布到其他节点，客户端可能连接在其他节点上，因此不再报告客户端不存在。
+// 开启离线消息时（与集群模式互斥），客户端不存在则暂存消息
+async fn send_to(cli_id: u64, message: Message) -> anyhow::Result<()> {
+    let local = send_local(cli_id, message.clone()).await;
+    if cluster::publish(Target::Client(cli_id), &message).await? {
+        return Ok(());
+    }
+    if local.is_err()
+        && let Some(store) = store::get()
+    {
+        // 持有客户端顺序锁确认其仍离线后再暂存，与连接建立时取出暂存消息互斥，避免消息滞留
+        let _client_lock = store::lock_client(cli_id).await;
+        let targets = CONN_MGR.lock().await.senders_of([cli_id]);
+        if targets.is_empty() {
+            store.push(cli_id, message).await?;
+            tracing::debug!(cli_id = %cli_id, "Client offline, message stored");
+            return Ok(());
         }
+        let (_, failed) = deliver(targets, message);
+        remove_failed(&failed).await;
+        return Ok(());
+    }
+    local
+}
 
-        // 移除失败的连接
-        for conn_id in failed_conn_ids {
-            cli_conns.remove(&conn_id);
-            tracing::debug!(cli_id = %cli_id, conn_id = %conn_id, "Removed failed connection");
+// 发送到本节点上客户端的所有连接，移除发送失败的连接；客户端不存在时返回错误
+pub(crate) async fn send_local(cli_id: u64, message: Message) -> anyhow::Result<()> {
+    let targets = CONN_MGR.lock().await.senders_of([cli_id]);
+    if targets.is_empty() {
+        tracing::debug!("Client not found in connection manager");
+        return Err(anyhow!("Client not found, client id: {}", cli_id));
+    }
+    let (_, failed) = deliver(targets, message);
+    remove_failed(&failed).await;
+    Ok(())
+}
+
+/// 向所有连接广播消息，返回成功送达的连接数（集群模式下只计本节点）
+///
+/// 发送前复制各连接的发送端并释放全局锁，消息写入各连接的发送队列而不等待；已断开的连接随后被移除。
+pub async fn broadcast(body: String) -> usize {
+    let message = Message::Text(body.into());
+    let delivered = broadcast_local(message.clone()).await;
+    if let Err(e) = cluster::publish(Target::All, &message).await {
+        tracing::error!(error = ?e, "Failed to publish broadcast to cluster");
+    }
+    delivered
+}
+
+/// 向元数据满足 `filter(cli_id, metadata)` 的连接广播消息，返回成功送达的连接数；只作用于本节点的连接
+///
+/// ```ignore
+/// broadcast_where(body, |_, meta| meta.get("device").and_then(|v| v.as_str()) == Some("ios")).await;
+/// ```
+pub async fn broadcast_where(body: String, filter: impl Fn(u64, &Metadata) -> bool) -> usize {
+    let targets = CONN_MGR.lock().await.senders_where(filter);
+    let (delivered, failed) = deliver(targets, Message::Text(body.into()));
+    remove_failed(&failed).await;
+    delivered
+}
+
+pub(crate) async fn broadcast_local(message: Message) -> usize {
+    let targets = CONN_MGR.lock().await.all_senders();
+    let (delivered, failed) = deliver(targets, message);
+    remove_failed(&failed).await;
+    delivered
+}
+
+/// 向分组内所有客户端的所有连接发送消息，返回成功送达的连接数；分组不存在时返回错误
+///
+/// 集群模式下同时发布到其他节点，只计本节点送达的连接数，本节点没有该分组时返回 0。
+pub async fn send_to_group(group: &str, body: String) -> anyhow::Result<usize> {
+    let message = Message::Text(body.into());
+    let local = send_to_group_local(group, message.clone()).await;
+    if cluster::publish(Target::Group(group.to_string()), &message).await? {
+        return Ok(local.unwrap_or(0));
+    }
+    local
+}
+
+pub(crate) async fn send_to_group_local(group: &str, message: Message) -> anyhow::Result<usize> {
+    let targets = {
+        let conn_mgr = CONN_MGR.lock().await;
+        let Some(members) = conn_mgr.groups.get(group) else {
+            return Err(anyhow!("Group not found: {}", group));
+        };
+        conn_mgr.senders_of(members.iter().copied())
+    };
+    let (delivered, failed) = deliver(targets, message);
+    remove_failed(&failed).await;
+    Ok(delivered)
+}
+
+/// 断开客户端的所有连接（如封禁、强制下线），返回断开的连接数；客户端不存在时返回错误
+///
+/// 尚未发送的消息被丢弃，客户端收到带 `code`（应用自定义状态码一般取 4000-4999）与 `reason` 的关闭帧。
+/// 集群模式下同时通知其他节点，客户端不在本节点时返回 0。
+pub async fn disconnect(cli_id: u64, code: u16, reason: &str) -> anyhow::Result<usize> {
+    let frame = CloseFrame {
+        code,
+        reason: reason.to_string().into(),
+    };
+    let local = disconnect_local(cli_id, frame.clone()).await;
+    if cluster::publish(Target::Client(cli_id), &Message::Close(Some(frame))).await? {
+        return Ok(local.unwrap_or(0));
+    }
+    local
+}
+
+pub(crate) async fn disconnect_local(cli_id: u64, frame: CloseFrame) -> anyhow::Result<usize> {
+    let mut manager = CONN_MGR.lock().await;
+    let conn_ids = manager.connection_ids(cli_id);
+    if conn_ids.is_empty() {
+        return Err(anyhow!("Client not found, client id: {}", cli_id));
+    }
+    for conn_id in &conn_ids {
+        manager.close_connection(cli_id, *conn_id, frame.clone());
+    }
+    tracing::info!(cli_id = %cli_id, code = frame.code, reason = %frame.reason, "Disconnected client");
+    Ok(conn_ids.len())
+}
+
+/// 断开本节点上客户端的单个连接，同 [`disconnect`]；连接不存在时返回错误
+pub async fn disconnect_conn(
+    cli_id: u64,
+    conn_id: usize,
+    code: u16,
+    reason: &str,
+) -> anyhow::Result<()> {
+    let frame = CloseFrame {
+        code,
+        reason: reason.to_string().into(),
+    };
+    if !CONN_MGR
+        .lock()
+        .await
+        .close_connection(cli_id, conn_id, frame)
+    {
+        return Err(anyhow!(
+            "Connection not found, client id: {}, connection id: {}",
+            cli_id,
+            conn_id
+        ));
+    }
+    tracing::info!(cli_id = %cli_id, conn_id = %conn_id, code, reason, "Disconnected connection");
+    Ok(())
+}
+
+/// [`shutdown`] 等待连接关闭的最长时间
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 优雅关闭：在各连接待发送的消息之后发送关闭帧，最多等待 [`SHUTDOWN_TIMEOUT`] 让连接关闭，然后清空连接管理器。
+/// 返回关闭前的连接数
+///
+/// 可在 rivus-web 的 `WebServer::on_shutdown` 中调用，如 `shutdown(1012, "service restart")`，让客户端在部署后重连。
+pub async fn shutdown(code: u16, reason: &str) -> usize {
+    shutdown_within(code, reason, SHUTDOWN_TIMEOUT).await
+}
+
+/// 同 [`shutdown`]，指定等待连接关闭的最长时间
+pub async fn shutdown_within(code: u16, reason: &str, timeout: Duration) -> usize {
+    let targets = CONN_MGR.lock().await.all_senders();
+    let total = targets.len();
+    tracing::info!(
+        connections = total,
+        code,
+        reason,
+        "Shutting down WebSocket connections"
+    );
+    for (_, _, tx) in &targets {
+        tx.finish(CloseFrame {
+            code,
+            reason: reason.to_string().into(),
+        });
+    }
+    drop(targets);
+
+    let drained = tokio::time::timeout(timeout, async {
+        while CONN_MGR.lock().await.connection_count() > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
         }
+    })
+    .await;
+    let mut manager = CONN_MGR.lock().await;
+    if drained.is_err() {
+        tracing::warn!(
+            remaining = manager.connection_count(),
+            "WebSocket connections did not close in time"
+        );
+    }
+    manager.clear();
+    total
+}
 
-        // 如果用户没有任何连接了，清理用户
-        if cli_conns.is_empty() {
-            conn_mgr.connections.remove(&cli_id);
-            tracing::info!(cli_id = %cli_id, "Removed Client from connection manager - no active connections");
+// 写入各连接的发送队列，返回送达的连接数与已关闭的 (cli_id, conn_id)；队列已满被丢弃的消息不计入送达
+fn deliver(
+    targets: Vec<(u64, usize, QueueSender)>,
+    message: Message,
+) -> (usize, Vec<(u64, usize)>) {
+    let mut delivered = 0;
+    let mut failed = Vec::new();
+    for (cli_id, conn_id, tx) in targets {
+        match tx.push(message.clone()) {
+            Ok(()) => delivered += 1,
+            Err(PushError::Dropped) => {
+                tracing::warn!(cli_id = %cli_id, conn_id = %conn_id, "Send queue full, message dropped");
+            }
+            Err(PushError::Closed) => {
+                metrics::incr(&metrics::SEND_FAILURES);
+                tracing::error!(cli_id = %cli_id, conn_id = %conn_id, "Failed to send message to connection");
+                failed.push((cli_id, conn_id));
+            }
         }
-        Ok(())
-    } else {
-        tracing::debug!("Client not found in connection manager");
-        Err(anyhow!("Client not found, client id: {}", cli_id))
     }
-}
\ No newline at end of file
+    (delivered, failed)
+}
+
+async fn remove_failed(failed: &[(u64, usize)]) {
+    if failed.is_empty() {
+        return;
+    }
+    let mut conn_mgr = CONN_MGR.lock().await;
+    for (cli_id, conn_id) in failed {
+        conn_mgr.remove_connection(*cli_id, *conn_id);
+        tracing::debug!(cli_id = %cli_id, conn_id = %conn_id, "Removed failed connection");
+    }
+}
@@ -1,2 +1,17 @@
+pub mod auth;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod cluster;
+pub mod codec;
+pub mod config;
 pub mod conn_mgr;
+pub mod metrics;
+pub mod middleware;
+pub mod presence;
+pub mod rate_limit;
+pub mod route;
+pub mod send_queue;
+pub mod store;
 pub mod ws_handler;
+
+pub use route::route;
@@ -0,0 +1,163 @@
+use crate::conn_mgr::CONN_MGR;
+use crate::send_queue::dropped_messages;
+use serde::Serialize;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub(crate) static CONNECTIONS_OPENED: AtomicU64 = AtomicU64::new(0);
+pub(crate) static CONNECTIONS_CLOSED: AtomicU64 = AtomicU64::new(0);
+pub(crate) static CONNECTIONS_REJECTED: AtomicU64 = AtomicU64::new(0);
+pub(crate) static MESSAGES_IN: AtomicU64 = AtomicU64::new(0);
+pub(crate) static MESSAGES_OUT: AtomicU64 = AtomicU64::new(0);
+pub(crate) static SEND_FAILURES: AtomicU64 = AtomicU64::new(0);
+pub(crate) static RATE_LIMITED: AtomicU64 = AtomicU64::new(0);
+pub(crate) static PING_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn incr(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 连接与消息指标的快照；计数器自进程启动起累计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WsMetrics {
+    /// 当前连接数
+    pub active_connections: u64,
+    /// 当前有连接的客户端数
+    pub active_clients: u64,
+    /// 单个客户端当前的最大连接数
+    pub max_connections_per_client: u64,
+    pub connections_opened: u64,
+    pub connections_closed: u64,
+    /// 因超出连接上限被拒绝的连接
+    pub connections_rejected: u64,
+    /// 收到的文本与二进制消息
+    pub messages_in: u64,
+    /// 写出到客户端的文本与二进制消息（不含 ping 与关闭帧）
+    pub messages_out: u64,
+    /// 写入已断开连接或写出到 socket 失败的次数
+    pub send_failures: u64,
+    /// 因发送队列已满丢弃的消息
+    pub dropped_messages: u64,
+    /// 因超出速率限制被丢弃的消息，按限制关闭连接时也计入触发关闭的那条
+    pub rate_limited: u64,
+    pub ping_timeouts: u64,
+}
+
+/// 当前指标
+pub async fn snapshot() -> WsMetrics {
+    let (active_connections, active_clients, max_connections_per_client) = {
+        let manager = CONN_MGR.lock().await;
+        let max = manager
+            .connections_per_client()
+            .into_values()
+            .max()
+            .unwrap_or(0);
+        (
+            manager.connection_count() as u64,
+            manager.client_count() as u64,
+            max as u64,
+        )
+    };
+    let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+    WsMetrics {
+        active_connections,
+        active_clients,
+        max_connections_per_client,
+        connections_opened: load(&CONNECTIONS_OPENED),
+        connections_closed: load(&CONNECTIONS_CLOSED),
+        connections_rejected: load(&CONNECTIONS_REJECTED),
+        messages_in: load(&MESSAGES_IN),
+        messages_out: load(&MESSAGES_OUT),
+        send_failures: load(&SEND_FAILURES),
+        dropped_messages: dropped_messages(),
+        rate_limited: load(&RATE_LIMITED),
+        ping_timeouts: load(&PING_TIMEOUTS),
+    }
+}
+
+impl WsMetrics {
+    /// 以 Prometheus 文本格式输出，指标名以 `rivus_ws_` 开头，可直接作为 `/metrics` 的响应体
+    pub fn to_prometheus(&self) -> String {
+        let metrics = [
+            (
+                "active_connections",
+                "gauge",
+                "Active WebSocket connections",
+                self.active_connections,
+            ),
+            (
+                "active_clients",
+                "gauge",
+                "Clients with at least one connection",
+                self.active_clients,
+            ),
+            (
+                "max_connections_per_client",
+                "gauge",
+                "Largest number of connections held by one client",
+                self.max_connections_per_client,
+            ),
+            (
+                "connections_opened_total",
+                "counter",
+                "Accepted connections",
+                self.connections_opened,
+            ),
+            (
+                "connections_closed_total",
+                "counter",
+                "Closed connections",
+                self.connections_closed,
+            ),
+            (
+                "connections_rejected_total",
+                "counter",
+                "Connections rejected by connection limits",
+                self.connections_rejected,
+            ),
+            (
+                "messages_in_total",
+                "counter",
+                "Text and binary messages received",
+                self.messages_in,
+            ),
+            (
+                "messages_out_total",
+                "counter",
+                "Text and binary messages written to clients",
+                self.messages_out,
+            ),
+            (
+                "send_failures_total",
+                "counter",
+                "Failed sends to closed or broken connections",
+                self.send_failures,
+            ),
+            (
+                "dropped_messages_total",
+                "counter",
+                "Messages dropped because a send queue was full",
+                self.dropped_messages,
+            ),
+            (
+                "rate_limited_total",
+                "counter",
+                "Inbound messages rejected by per-connection rate limits",
+                self.rate_limited,
+            ),
+            (
+                "ping_timeouts_total",
+                "counter",
+                "Connections closed by ping timeout",
+                self.ping_timeouts,
+            ),
+        ];
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(out, "# HELP rivus_ws_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE rivus_ws_{} {}", name, kind);
+            let _ = writeln!(out, "rivus_ws_{} {}", name, value);
+        }
+        out
+    }
+}
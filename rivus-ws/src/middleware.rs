@@ -0,0 +1,69 @@
+use axum::body::Bytes;
+use axum::extract::ws::Utf8Bytes;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+/// 客户端发来的文本或二进制消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inbound {
+    Text(Utf8Bytes),
+    Binary(Bytes),
+}
+
+/// 在消息处理器之前执行的中间件，用于鉴权刷新、限流、tracing span、指标等横切逻辑
+///
+/// 调用 `next.run(cli_id, message)` 交给后续中间件与处理器，不调用则丢弃该消息；可在调用前替换消息。
+/// 闭包 `Fn(u64, Inbound, Next) -> BoxFuture<'static, ()>` 自动实现该 trait：
+///
+/// ```ignore
+/// let handlers = WsHandlers::new()
+///     .with_middleware(|cli_id, message, next: Next| {
+///         let span = tracing::info_span!("ws_message", cli_id);
+///         next.run(cli_id, message).instrument(span).boxed()
+///     })
+///     .on_text(handle_text);
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    fn call(&self, cli_id: u64, message: Inbound, next: Next) -> BoxFuture<'static, ()>;
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(u64, Inbound, Next) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+{
+    fn call(&self, cli_id: u64, message: Inbound, next: Next) -> BoxFuture<'static, ()> {
+        self(cli_id, message, next)
+    }
+}
+
+/// 中间件链的终点，按消息类型分发到处理器
+pub(crate) type Endpoint = Arc<dyn Fn(u64, Inbound) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// 中间件链中剩余的中间件与处理器
+#[derive(Clone)]
+pub struct Next {
+    chain: Arc<[Arc<dyn Middleware>]>,
+    index: usize,
+    endpoint: Endpoint,
+}
+
+impl Next {
+    pub(crate) fn new(chain: Arc<[Arc<dyn Middleware>]>, endpoint: Endpoint) -> Self {
+        Self {
+            chain,
+            index: 0,
+            endpoint,
+        }
+    }
+
+    /// 执行下一个中间件，已是最后一个时执行消息处理器
+    pub fn run(mut self, cli_id: u64, message: Inbound) -> BoxFuture<'static, ()> {
+        match self.chain.get(self.index).cloned() {
+            Some(middleware) => {
+                self.index += 1;
+                middleware.call(cli_id, message, self)
+            }
+            None => (self.endpoint)(cli_id, message),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::conn_mgr::CONN_MGR;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use tokio::sync::broadcast;
+
+/// 未被订阅者及时读取时保留的在线状态事件数
+const EVENT_CAPACITY: usize = 1024;
+
+static EVENTS: LazyLock<broadcast::Sender<PresenceEvent>> =
+    LazyLock::new(|| broadcast::channel(EVENT_CAPACITY).0);
+
+/// 客户端的在线状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    /// 建立了第一个连接
+    Online,
+    /// 最后一个连接已断开
+    Offline,
+}
+
+/// 客户端在线状态变化
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PresenceEvent {
+    pub cli_id: u64,
+    pub status: PresenceStatus,
+    pub at: SystemTime,
+}
+
+pub(crate) fn notify(cli_id: u64, status: PresenceStatus, at: SystemTime) {
+    // 没有订阅者时发送失败，忽略
+    let _ = EVENTS.send(PresenceEvent { cli_id, status, at });
+}
+
+/// 客户端是否在本节点上有活动连接
+pub async fn is_online(cli_id: u64) -> bool {
+    CONN_MGR.lock().await.is_online(cli_id)
+}
+
+/// 本节点上所有在线的客户端
+pub async fn online_clients() -> Vec<u64> {
+    CONN_MGR.lock().await.online_clients()
+}
+
+/// 客户端最后一个连接断开的时间；在线或从未连接时返回 `None`
+pub async fn last_seen(cli_id: u64) -> Option<SystemTime> {
+    CONN_MGR.lock().await.last_seen(cli_id)
+}
+
+/// 订阅本节点的在线状态变化；订阅者读取过慢时会收到 `RecvError::Lagged` 并丢失最早的事件
+pub fn subscribe() -> broadcast::Receiver<PresenceEvent> {
+    EVENTS.subscribe()
+}
+
+/// 在线状态变化时调用 `f`，可在其中通知关注该客户端的其他客户端。事件按发生顺序逐个处理
+///
+/// ```ignore
+/// presence::on_change(|event| Box::pin(async move {
+///     let body = serde_json::to_string(&event).unwrap();
+///     let _ = send_to_group(&format!("friends:{}", event.cli_id), body).await;
+/// }));
+/// ```
+pub fn on_change<F>(f: F)
+where
+    F: Fn(PresenceEvent) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+{
+    let mut events = subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => f(event).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Presence callback lagged, events dropped");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,80 @@
+use std::time::Instant;
+
+/// 超出消息速率限制时关闭连接的状态码（1008 Policy Violation）
+pub const RATE_LIMIT_CLOSE_CODE: u16 = 1008;
+
+/// 超出消息速率限制时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    /// 丢弃超出限制的消息
+    #[default]
+    Drop,
+    /// 以 [`RATE_LIMIT_CLOSE_CODE`] 关闭连接
+    Close,
+}
+
+/// 每个连接接收文本与二进制消息的速率限制（令牌桶）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// 每秒允许的消息数
+    pub per_second: u32,
+    /// 允许的突发消息数，即令牌桶容量（默认等于 `per_second`）
+    pub burst: u32,
+    pub policy: RateLimitPolicy,
+}
+
+impl RateLimit {
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            burst: per_second,
+            policy: RateLimitPolicy::default(),
+        }
+    }
+
+    /// 设置突发消息数
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// 设置超出限制时的处理策略
+    pub fn with_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+// 令牌桶：以 per_second 的速率补充令牌，最多 burst 个，每条消息消耗一个
+pub(crate) struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: &RateLimit) -> Self {
+        let capacity = f64::from(limit.burst.max(1));
+        Self {
+            rate: f64::from(limit.per_second),
+            capacity,
+            tokens: capacity,
+            updated: Instant::now(),
+        }
+    }
+
+    // 取一个令牌，没有可用令牌时返回 false
+    pub(crate) fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
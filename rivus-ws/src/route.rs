@@ -0,0 +1,123 @@
+use crate::auth::{handle_authenticated_connection, AuthContext, Authenticator};
+use crate::config::WsConfig;
+use crate::ws_handler::{handle_connection, WsHandlers};
+use axum::body::Bytes;
+use axum::extract::ws::{Utf8Bytes, WebSocket, WebSocketUpgrade};
+use axum::http::{HeaderMap, Uri};
+use axum::routing::get;
+use axum::Router;
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 未设置认证时分配给连接的匿名 cli_id 的起始值，与业务 ID 区分
+pub const ANONYMOUS_ID_START: u64 = 1 << 63;
+
+static NEXT_ANONYMOUS_ID: AtomicU64 = AtomicU64::new(ANONYMOUS_ID_START);
+
+/// 创建 WebSocket 路由，在 `path` 上完成协议升级后处理连接
+///
+/// ```ignore
+/// let app = Router::new()
+///     .merge(rivus_ws::route("/ws").auth(token_auth).on_message(handle_text).on_close(handle_close).into_router());
+/// ```
+pub fn route(path: impl Into<String>) -> WsRoute {
+    WsRoute {
+        path: path.into(),
+        authenticator: None,
+        handlers: WsHandlers::new(),
+        config: WsConfig::default(),
+    }
+}
+
+/// WebSocket 路由构建器，见 [`route`]
+pub struct WsRoute {
+    path: String,
+    authenticator: Option<Arc<dyn Authenticator>>,
+    handlers: WsHandlers,
+    config: WsConfig,
+}
+
+impl WsRoute {
+    /// 设置连接认证；未设置时每个连接分配一个从 [`ANONYMOUS_ID_START`] 开始递增的 cli_id
+    pub fn auth(mut self, authenticator: impl Authenticator + 'static) -> Self {
+        self.authenticator = Some(Arc::new(authenticator));
+        self
+    }
+
+    /// 设置文本消息处理器
+    pub fn on_message<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64, Utf8Bytes) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.handlers = self.handlers.on_text(f);
+        self
+    }
+
+    /// 设置二进制消息处理器
+    pub fn on_binary<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64, Bytes) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.handlers = self.handlers.on_binary(f);
+        self
+    }
+
+    /// 设置连接关闭处理器
+    pub fn on_close<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.handlers = self.handlers.on_close(f);
+        self
+    }
+
+    /// 替换全部处理器，用于 `on_json`、`on_decoded`、中间件等
+    pub fn with_handlers(mut self, handlers: WsHandlers) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    pub fn with_config(mut self, config: WsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 生成只包含该路由的 `Router`，可 `merge` 到应用的路由中
+    pub fn into_router<S>(self) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let path = self.path.clone();
+        let route = Arc::new(self);
+        Router::new().route(
+            &path,
+            get(
+                move |ws: WebSocketUpgrade, uri: Uri, headers: HeaderMap| async move {
+                    ws.on_upgrade(move |socket| route.serve(socket, uri, headers))
+                },
+            ),
+        )
+    }
+
+    async fn serve(self: Arc<Self>, socket: WebSocket, uri: Uri, headers: HeaderMap) {
+        let (handlers, config) = (self.handlers.clone(), self.config.clone());
+        match &self.authenticator {
+            Some(authenticator) => {
+                let ctx = AuthContext::new(uri, headers);
+                handle_authenticated_connection(
+                    socket,
+                    ctx,
+                    authenticator.as_ref(),
+                    handlers,
+                    config,
+                )
+                .await
+            }
+            None => {
+                let cli_id = NEXT_ANONYMOUS_ID.fetch_add(1, Ordering::Relaxed);
+                handle_connection(socket, cli_id, handlers, config).await
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use axum::extract::ws::{CloseFrame, Message};
+use futures::task::AtomicWaker;
+use futures::Stream;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// 因发送队列已满而丢弃的消息总数
+static DROPPED_MESSAGES: AtomicU64 = AtomicU64::new(0);
+
+/// 所有连接因发送队列已满而丢弃的消息总数
+pub fn dropped_messages() -> u64 {
+    DROPPED_MESSAGES.load(Ordering::Relaxed)
+}
+
+/// 发送队列已满时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// 丢弃队列中最早的消息，保留新消息
+    DropOldest,
+    /// 丢弃新消息
+    DropNew,
+    /// 清空队列并断开连接（慢消费者）
+    #[default]
+    Disconnect,
+}
+
+/// 慢消费者被断开时的关闭状态码（1008 Policy Violation）
+pub const OVERFLOW_CLOSE_CODE: u16 = 1008;
+
+/// [`QueueSender::push`] 失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushError {
+    /// 队列已关闭（连接已断开，或因队列已满被断开）
+    Closed,
+    /// 队列已满，按 [`OverflowPolicy::DropNew`] 丢弃了该消息
+    Dropped,
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("send queue closed"),
+            Self::Dropped => f.write_str("send queue full, message dropped"),
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
+struct State {
+    queue: VecDeque<Message>,
+    senders: usize,
+    closed: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    waker: AtomicWaker,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 创建容量为 `capacity` 的连接发送队列
+///
+/// 与 `mpsc` 不同，写入从不等待：队列已满时按 `policy` 处理，避免一个慢连接拖住广播。
+pub fn channel(capacity: usize, policy: OverflowPolicy) -> (QueueSender, QueueReceiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            senders: 1,
+            closed: false,
+        }),
+        waker: AtomicWaker::new(),
+        capacity: capacity.max(1),
+        policy,
+    });
+    (
+        QueueSender {
+            shared: shared.clone(),
+        },
+        QueueReceiver { shared },
+    )
+}
+
+/// 发送队列的写入端，可克隆；所有写入端释放后接收端结束
+pub struct QueueSender {
+    shared: Arc<Shared>,
+}
+
+impl QueueSender {
+    /// 写入消息，不等待
+    pub fn push(&self, message: Message) -> Result<(), PushError> {
+        let mut state = self.shared.lock();
+        if state.closed {
+            return Err(PushError::Closed);
+        }
+        if state.queue.len() >= self.shared.capacity {
+            DROPPED_MESSAGES.fetch_add(1, Ordering::Relaxed);
+            match self.shared.policy {
+                OverflowPolicy::DropOldest => {
+                    state.queue.pop_front();
+                }
+                OverflowPolicy::DropNew => return Err(PushError::Dropped),
+                OverflowPolicy::Disconnect => {
+                    let frame = CloseFrame {
+                        code: OVERFLOW_CLOSE_CODE,
+                        reason: "send queue overflow".into(),
+                    };
+                    Self::close_locked(&mut state, frame);
+                    drop(state);
+                    self.shared.waker.wake();
+                    return Err(PushError::Closed);
+                }
+            }
+        }
+        state.queue.push_back(message);
+        drop(state);
+        self.shared.waker.wake();
+        Ok(())
+    }
+
+    /// 清空队列，只保留关闭帧并关闭队列：接收端发出关闭帧后结束，之后的写入返回 [`PushError::Closed`]
+    pub fn close(&self, frame: CloseFrame) {
+        let mut state = self.shared.lock();
+        if state.closed {
+            return;
+        }
+        Self::close_locked(&mut state, frame);
+        drop(state);
+        self.shared.waker.wake();
+    }
+
+    /// 在待发送的消息之后追加关闭帧并关闭队列（不受容量限制），用于优雅关闭
+    pub fn finish(&self, frame: CloseFrame) {
+        let mut state = self.shared.lock();
+        if state.closed {
+            return;
+        }
+        state.queue.push_back(Message::Close(Some(frame)));
+        state.closed = true;
+        drop(state);
+        self.shared.waker.wake();
+    }
+
+    fn close_locked(state: &mut State, frame: CloseFrame) {
+        state.queue.clear();
+        state.queue.push_back(Message::Close(Some(frame)));
+        state.closed = true;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().closed
+    }
+}
+
+impl Clone for QueueSender {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for QueueSender {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.waker.wake();
+        }
+    }
+}
+
+/// 发送队列的读取端
+pub struct QueueReceiver {
+    shared: Arc<Shared>,
+}
+
+impl Stream for QueueReceiver {
+    type Item = Message;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Message>> {
+        // 先注册再检查，避免错过检查与注册之间的唤醒
+        self.shared.waker.register(cx.waker());
+        let mut state = self.shared.lock();
+        match state.queue.pop_front() {
+            Some(message) => Poll::Ready(Some(message)),
+            None if state.closed || state.senders == 0 => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for QueueReceiver {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.closed = true;
+        state.queue.clear();
+    }
+}
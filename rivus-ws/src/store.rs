@@ -0,0 +1,163 @@
+use axum::extract::ws::Message;
+use futures::future::BoxFuture;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::OwnedMutexGuard;
+
+static STORE: OnceLock<Arc<dyn MessageStore>> = OnceLock::new();
+
+// 每个客户端一把顺序锁，只在开启离线消息时使用，不再使用的锁随最后一个持有者释放
+type ClientLocks = HashMap<u64, Arc<tokio::sync::Mutex<()>>>;
+static CLIENT_LOCKS: LazyLock<Mutex<ClientLocks>> = LazyLock::new(Default::default);
+
+/// 离线消息存储：客户端没有活动连接时暂存发给它的消息，客户端重新连接后按顺序发出
+///
+/// 内置 [`MemoryStore`]；多节点部署可基于 Redis 等共享存储自行实现。
+pub trait MessageStore: Send + Sync + 'static {
+    /// 暂存发给客户端的消息
+    fn push(&self, cli_id: u64, message: Message) -> BoxFuture<'_, anyhow::Result<()>>;
+
+    /// 取出并删除客户端所有未过期的消息，按写入顺序返回
+    fn take(&self, cli_id: u64) -> BoxFuture<'_, anyhow::Result<Vec<Message>>>;
+}
+
+/// 开启离线消息：`send_message`、`send_binary`、`send_json` 等发给没有活动连接的客户端时写入 `store`，
+/// 不再返回客户端不存在的错误。只能开启一次
+///
+/// 广播与分组消息不写入离线存储。不能与集群模式同时开启：客户端可能连接在其他节点上，
+/// 本节点无法判断其是否离线。
+pub fn enable(store: impl MessageStore) -> anyhow::Result<()> {
+    if crate::cluster::node_id().is_some() {
+        return Err(anyhow::anyhow!(
+            "message store cannot be enabled in cluster mode"
+        ));
+    }
+    if STORE.set(Arc::new(store)).is_err() {
+        return Err(anyhow::anyhow!("message store already enabled"));
+    }
+    Ok(())
+}
+
+pub(crate) fn get() -> Option<&'static Arc<dyn MessageStore>> {
+    STORE.get()
+}
+
+/// 客户端顺序锁：连接建立时取出暂存消息与发送时写入暂存互斥，暂存消息先于实时消息发出
+pub(crate) struct ClientLock {
+    cli_id: u64,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+pub(crate) async fn lock_client(cli_id: u64) -> ClientLock {
+    let lock = client_locks().entry(cli_id).or_default().clone();
+    ClientLock {
+        cli_id,
+        guard: Some(lock.lock_owned().await),
+    }
+}
+
+fn client_locks() -> std::sync::MutexGuard<'static, ClientLocks> {
+    CLIENT_LOCKS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Drop for ClientLock {
+    fn drop(&mut self) {
+        let mut locks = client_locks();
+        self.guard.take();
+        // 只剩表中的引用时没有其他持有者或等待者，移除
+        if locks
+            .get(&self.cli_id)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            locks.remove(&self.cli_id);
+        }
+    }
+}
+
+/// 内存中的 [`MessageStore`]，只适用于单节点部署
+///
+/// 每个客户端最多保留 `max_per_client` 条消息，超出时丢弃最早的；超过 `ttl` 的消息被丢弃。
+/// 过期消息在读写该客户端时清理，其他不再连接的客户端每隔 `ttl` 在写入时统一清理一次。
+/// `max_per_client` 不宜超过连接的发送队列容量（`WsConfig::send_queue_capacity`），超出部分在下次连接时才发出。
+pub struct MemoryStore {
+    max_per_client: usize,
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    messages: HashMap<u64, VecDeque<(Instant, Message)>>,
+    last_sweep: Instant,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new(100, Duration::from_secs(300))
+    }
+}
+
+impl MemoryStore {
+    pub fn new(max_per_client: usize, ttl: Duration) -> Self {
+        Self {
+            max_per_client: max_per_client.max(1),
+            ttl,
+            inner: Mutex::new(Inner {
+                messages: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// 暂存的消息数（含已过期但尚未清理的）
+    pub fn len(&self) -> usize {
+        self.lock().messages.values().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // 队列按写入时间排序，从头部丢弃过期消息
+    fn expire(&self, queue: &mut VecDeque<(Instant, Message)>, now: Instant) {
+        while queue
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > self.ttl)
+        {
+            queue.pop_front();
+        }
+    }
+}
+
+impl MessageStore for MemoryStore {
+    fn push(&self, cli_id: u64, message: Message) -> BoxFuture<'_, anyhow::Result<()>> {
+        let now = Instant::now();
+        let mut inner = self.lock();
+        // 定期清理不再连接的客户端，避免长期占用内存
+        if now.duration_since(inner.last_sweep) >= self.ttl {
+            inner.last_sweep = now;
+            inner.messages.retain(|_, queue| {
+                self.expire(queue, now);
+                !queue.is_empty()
+            });
+        }
+        let queue = inner.messages.entry(cli_id).or_default();
+        self.expire(queue, now);
+        if queue.len() >= self.max_per_client {
+            queue.pop_front();
+        }
+        queue.push_back((now, message));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn take(&self, cli_id: u64) -> BoxFuture<'_, anyhow::Result<Vec<Message>>> {
+        let mut queue = self.lock().messages.remove(&cli_id).unwrap_or_default();
+        self.expire(&mut queue, Instant::now());
+        let messages = queue.into_iter().map(|(_, message)| message).collect();
+        Box::pin(async { Ok(messages) })
+    }
+}
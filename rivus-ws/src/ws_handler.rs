@@ -1,53 +1,254 @@
-use crate::conn_mgr::CONN_MGR;
+use crate::codec::Codec;
+use crate::config::WsConfig;
+use crate::conn_mgr::{Metadata, CONN_MGR, LIMIT_CLOSE_CODE};
+use crate::metrics;
+use crate::middleware::{Endpoint, Inbound, Middleware, Next};
+use crate::rate_limit::{RateLimit, RateLimitPolicy, TokenBucket, RATE_LIMIT_CLOSE_CODE};
+use crate::send_queue;
+use crate::store;
 use axum::body::Bytes;
-use axum::extract::ws::{Message, Utf8Bytes, WebSocket};
+use axum::extract::ws::{CloseFrame, Message, Utf8Bytes, WebSocket};
 use futures::channel::mpsc;
 use futures::future::{select, BoxFuture};
 use futures::FutureExt;
 use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::time;
 
-// 定义心跳间隔时间（秒）
-const PING_INTERVAL: u64 = 30;
-// 定义心跳超时时间（秒）
-const PING_TIMEOUT: u64 = 120;
+/// 服务端发送关闭帧后等待客户端回应关闭的最长时间；期间未读取的数据会使连接以 RST 结束，客户端可能收不到关闭帧
+const CLOSE_GRACE: Duration = Duration::from_secs(1);
+
+/// 文本消息处理器
+pub type TextHandler = Arc<dyn Fn(u64, Utf8Bytes) -> BoxFuture<'static, ()> + Send + Sync>;
+/// 二进制消息处理器
+pub type BinaryHandler = Arc<dyn Fn(u64, Bytes) -> BoxFuture<'static, ()> + Send + Sync>;
+/// 连接关闭处理器
+pub type CloseHandler = Arc<dyn Fn(u64) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// 连接的消息与关闭处理器；闭包可捕获应用状态（连接池、配置等）
+///
+/// ```ignore
+/// let db = pool.clone();
+/// let handlers = WsHandlers::new().on_text(move |cli_id, text| {
+///     let db = db.clone();
+///     Box::pin(async move { save(&db, cli_id, text).await })
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct WsHandlers {
+    pub on_text: Option<TextHandler>,
+    pub on_binary: Option<BinaryHandler>,
+    pub on_close: Option<CloseHandler>,
+    /// 按添加顺序执行的中间件，先添加的先执行
+    pub middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl WsHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_text<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64, Utf8Bytes) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.on_text = Some(Arc::new(f));
+        self
+    }
+
+    /// 将文本消息反序列化为 `T`（通常是 `#[serde(tag = "type")]` 的枚举）后交给处理器，替代 [`Self::on_text`]
+    ///
+    /// 无法解析的消息记录告警后丢弃。
+    pub fn on_json<T, F>(self, f: F) -> Self
+    where
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(u64, T) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.on_text(move |cli_id, text| match serde_json::from_str::<T>(text.as_str()) {
+            Ok(message) => f(cli_id, message),
+            Err(e) => {
+                tracing::warn!(cli_id = %cli_id, error = %e, "Invalid JSON message from client");
+                Box::pin(async {})
+            }
+        })
+    }
+
+    /// 以 `codec` 解码文本与二进制消息后交给处理器，替代 [`Self::on_text`] 与 [`Self::on_binary`]
+    ///
+    /// 无法解码的消息记录告警后丢弃。
+    pub fn on_decoded<C, T, F>(self, codec: C, f: F) -> Self
+    where
+        C: Codec,
+        T: DeserializeOwned + Send + 'static,
+        F: Fn(u64, T) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let (text_codec, text_f) = (codec.clone(), f.clone());
+        self.on_text(move |cli_id, text| decode_then(&text_codec, text.as_bytes(), cli_id, text_f.as_ref()))
+            .on_binary(move |cli_id, data| decode_then(&codec, &data, cli_id, f.as_ref()))
+    }
+
+    pub fn on_binary<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64, Bytes) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.on_binary = Some(Arc::new(f));
+        self
+    }
+
+    pub fn on_close<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+    {
+        self.on_close = Some(Arc::new(f));
+        self
+    }
+
+    /// 添加在文本与二进制消息处理器之前执行的中间件，见 [`Middleware`]
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    // 以中间件链包装消息处理器，每个连接构建一次
+    pub(crate) fn chain(&self) -> Next {
+        let (on_text, on_binary) = (self.on_text.clone(), self.on_binary.clone());
+        let endpoint: Endpoint =
+            Arc::new(
+                move |cli_id, message| match (message, &on_text, &on_binary) {
+                    (Inbound::Text(text), Some(f), _) => f(cli_id, text),
+                    (Inbound::Binary(data), _, Some(f)) => f(cli_id, data),
+                    _ => Box::pin(async {}),
+                },
+            );
+        Next::new(self.middleware.clone().into(), endpoint)
+    }
+}
+
+fn decode_then<C, T, F>(codec: &C, data: &[u8], cli_id: u64, f: &F) -> BoxFuture<'static, ()>
+where
+    C: Codec,
+    T: DeserializeOwned,
+    F: Fn(u64, T) -> BoxFuture<'static, ()>,
+{
+    match codec.decode::<T>(data) {
+        Ok(message) => f(cli_id, message),
+        Err(e) => {
+            tracing::warn!(cli_id = %cli_id, error = %e, "Failed to decode message from client");
+            Box::pin(async {})
+        }
+    }
+}
+
+tokio::task_local! {
+    /// 正在处理消息的连接 (cli_id, conn_id)
+    static CURRENT_CONN: (u64, usize);
+}
+
+/// 在消息与关闭处理器中获取当前连接的 `(cli_id, conn_id)`，在处理器之外调用时返回 `None`
+pub fn current_connection() -> Option<(u64, usize)> {
+    CURRENT_CONN.try_with(|c| *c).ok()
+}
+
+/// 在消息处理器中读取当前连接的元数据；关闭处理器被调用时连接已移除，返回 `None`
+pub async fn current_metadata() -> Option<Metadata> {
+    let (cli_id, conn_id) = current_connection()?;
+    CONN_MGR.lock().await.metadata(cli_id, conn_id).cloned()
+}
 
 // 处理 WebSocket 连接
 pub async fn handle_connection(
     socket: WebSocket,
     cli_id: u64,
-    msg_handler: Option<fn(cli_id: u64, text: Utf8Bytes) -> BoxFuture<'static, ()>>,
-    close_handler: Option<fn(cli_id: u64) -> BoxFuture<'static, ()>>,
+    handlers: WsHandlers,
+    config: WsConfig,
+) {
+    handle_connection_with(socket, cli_id, Metadata::new(), handlers, config).await
+}
+
+/// 处理 WebSocket 连接，并为连接附加元数据（设备类型、授权范围等），同 [`handle_connection`]
+///
+/// 元数据可在处理器中通过 [`current_metadata`] 读取，或用于 `broadcast_where` 过滤。
+pub async fn handle_connection_with(
+    socket: WebSocket,
+    cli_id: u64,
+    meta: Metadata,
+    handlers: WsHandlers,
+    config: WsConfig,
 ) {
     let (mut sender, receiver) = socket.split();
-    let (tx, rx) = mpsc::channel(100);
+    let (tx, rx) = send_queue::channel(config.send_queue_capacity, config.overflow_policy);
 
     // 为 ping 任务创建一个单独的通道
     let (ping_tx, ping_rx) = mpsc::channel::<Message>(10);
 
-    // 将发送者添加到管理器并获取连接ID
-    let conn_id = {
-        let mut manager = CONN_MGR.lock().await;
-        manager.add_connection(cli_id, tx)
+    // 开启离线消息时，持有客户端顺序锁，先把暂存的消息写入尚未注册的发送队列再注册连接，
+    // 之后的实时消息不会排到它们前面
+    let client_lock = match store::get() {
+        Some(_) => Some(store::lock_client(cli_id).await),
+        None => None,
     };
+    let stored = take_stored(cli_id).await;
+    let sent = stored.len().min(config.send_queue_capacity.max(1));
+    for message in &stored[..sent] {
+        // 队列刚创建且容量足够，不会溢出
+        let _ = tx.push(message.clone());
+    }
+
+    // 将发送者添加到管理器并获取连接ID，超出连接上限时关闭连接
+    let added = CONN_MGR.lock().await.add_connection_with(cli_id, tx, meta);
+    // 连接被拒绝时放回全部暂存消息，否则放回超出队列容量的部分
+    let unsent = if added.is_ok() {
+        &stored[sent..]
+    } else {
+        &stored[..]
+    };
+    restore_stored(cli_id, unsent).await;
+    drop(client_lock);
+    let conn_id = match added {
+        Ok(conn_id) => conn_id,
+        Err(e) => {
+            metrics::incr(&metrics::CONNECTIONS_REJECTED);
+            tracing::warn!(cli_id = %cli_id, error = %e, "Connection rejected");
+            let frame = CloseFrame {
+                code: LIMIT_CLOSE_CODE,
+                reason: e.to_string().into(),
+            };
+            if let Err(e) = sender.send(Message::Close(Some(frame))).await {
+                tracing::debug!(error = ?e, "Failed to send close frame");
+            }
+            return;
+        }
+    };
+
+    metrics::incr(&metrics::CONNECTIONS_OPENED);
 
     // 最后一次收到客户端消息的时间
     let last_client_activity = Arc::new(Mutex::new(Instant::now()));
 
     // 创建一个合并发送任务，处理来自两个通道的消息
     let sender_task = async move {
-        let (text_rx, ping_rx) = (rx, ping_rx);
-        let mut combined_stream =
-            futures::stream::select(text_rx.map(|text| Message::Text(text.into())), ping_rx);
+        let mut combined_stream = futures::stream::select(rx, ping_rx);
 
         while let Some(message) = combined_stream.next().await {
+            let closing = matches!(message, Message::Close(_));
+            let counted = matches!(message, Message::Text(_) | Message::Binary(_));
             if let Err(e) = sender.send(message).await {
+                metrics::incr(&metrics::SEND_FAILURES);
                 tracing::error!(error = ?e, "Failed to send message to client");
                 break;
             }
+            if counted {
+                metrics::incr(&metrics::MESSAGES_OUT);
+            }
+            // 服务端主动关闭（如连接被挤出），发送关闭帧后等待客户端回应（接收任务随之结束），超时则结束
+            if closing {
+                time::sleep(CLOSE_GRACE).await;
+                break;
+            }
         }
     }
         .boxed();
@@ -55,21 +256,17 @@ pub async fn handle_connection(
     // 创建心跳任务
     let ping_task = create_ping_task(
         cli_id,
-        conn_id,
-        close_handler,
         ping_tx,
         last_client_activity.clone(),
+        &config,
     );
 
     // 创建接收任务
-    let receive_task = create_receive_task(
-        receiver,
-        cli_id.clone(),
-        conn_id,
-        msg_handler,
-        close_handler,
-        last_client_activity,
-    );
+    let receive_task = CURRENT_CONN.scope(
+        (cli_id, conn_id),
+        create_receive_task(receiver, cli_id, conn_id, handlers.chain(), config.rate_limit, last_client_activity),
+    )
+    .boxed();
 
     // 等待所有任务完成（任何一个任务结束都会导致连接关闭）
     let _ = select(
@@ -77,25 +274,73 @@ pub async fn handle_connection(
         receive_task,
     )
         .await;
+
+    // 从连接管理器中移除（被挤出的连接已移除），释放锁后再调用关闭处理器
+    tracing::info!(cli_id = ?cli_id, conn_id = ?conn_id, "Connection closed, cleaning up");
+    CONN_MGR.lock().await.remove_connection(cli_id, conn_id);
+    metrics::incr(&metrics::CONNECTIONS_CLOSED);
+    if let Some(f) = &handlers.on_close {
+        CURRENT_CONN.scope((cli_id, conn_id), f(cli_id)).await;
+    }
+}
+
+// 取出客户端离线期间暂存的消息
+async fn take_stored(cli_id: u64) -> Vec<Message> {
+    let Some(store) = store::get() else {
+        return Vec::new();
+    };
+    match store.take(cli_id).await {
+        Ok(messages) => {
+            tracing::debug!(cli_id = %cli_id, count = messages.len(), "Flushing stored messages");
+            messages
+        }
+        Err(e) => {
+            tracing::error!(cli_id = %cli_id, error = ?e, "Failed to load stored messages");
+            Vec::new()
+        }
+    }
+}
+
+// 把未发出的暂存消息按原顺序放回存储，放回失败的消息丢弃
+async fn restore_stored(cli_id: u64, messages: &[Message]) {
+    let Some(store) = store::get() else {
+        return;
+    };
+    if messages.is_empty() {
+        return;
+    }
+    tracing::warn!(cli_id = %cli_id, count = messages.len(), "Stored messages not sent, restoring");
+    for (i, message) in messages.iter().enumerate() {
+        if let Err(e) = store.push(cli_id, message.clone()).await {
+            let lost = messages.len() - i;
+            tracing::error!(cli_id = %cli_id, lost, error = ?e, "Failed to restore stored messages");
+            return;
+        }
+    }
 }
 
 // 创建心跳任务：定期发送 ping 消息
 fn create_ping_task(
     cli_id: u64,
-    conn_id: usize,
-    close_handler: Option<fn(cli_id: u64) -> BoxFuture<'static, ()>>,
     mut ping_tx: mpsc::Sender<Message>,
     last_client_activity: Arc<Mutex<Instant>>,
+    config: &WsConfig,
 ) -> BoxFuture<'static, ()> {
+    let (ping_interval, ping_timeout) = (config.ping_interval, config.ping_timeout);
     async move {
-        let mut interval = time::interval(Duration::from_secs(PING_INTERVAL));
+        // 间隔为 0 时关闭心跳，也不再检查超时
+        if ping_interval.is_zero() {
+            return futures::future::pending().await;
+        }
+        let mut interval = time::interval(ping_interval);
 
         loop {
             interval.tick().await;
 
             // 检查最后活动时间，如果超过超时时间则断开连接
             let last_activity = *last_client_activity.lock().await;
-            if last_activity.elapsed() > Duration::from_secs(PING_TIMEOUT) {
+            if last_activity.elapsed() > ping_timeout {
+                metrics::incr(&metrics::PING_TIMEOUTS);
                 tracing::warn!(user_id = ?cli_id, "Client ping timeout, closing connection");
                 break;
             }
@@ -108,14 +353,6 @@ fn create_ping_task(
                 break;
             }
         }
-
-        // 心跳超时，从连接管理器中移除
-        tracing::info!(user_id = ?cli_id, conn_id = ?conn_id, "Ping timeout, cleaning up");
-        let mut manager = CONN_MGR.lock().await;
-        manager.remove_connection(cli_id, conn_id);
-        if let Some(f) = close_handler {
-            f(cli_id).await;
-        }
     }
         .boxed()
 }
@@ -125,10 +362,12 @@ fn create_receive_task(
     mut receiver: futures::stream::SplitStream<WebSocket>,
     cli_id: u64,
     conn_id: usize,
-    msg_handler: Option<fn(cli_id: u64, text: Utf8Bytes) -> BoxFuture<'static, ()>>,
-    close_handler: Option<fn(cli_id: u64) -> BoxFuture<'static, ()>>,
+    chain: Next,
+    rate_limit: Option<RateLimit>,
     last_client_activity: Arc<Mutex<Instant>>,
 ) -> BoxFuture<'static, ()> {
+    let mut limiter = rate_limit.map(|limit| (TokenBucket::new(&limit), limit.policy));
+    let mut closing = false;
     async move {
         while let Some(msg) = receiver.next().await {
             // 更新最后活动时间
@@ -137,13 +376,18 @@ fn create_receive_task(
             match msg {
                 Ok(msg) => match msg {
                     Message::Text(text) => {
+                        metrics::incr(&metrics::MESSAGES_IN);
                         tracing::debug!(message = ?text, "Received text message from client");
-                        if let Some(f) = msg_handler {
-                            f(cli_id, text).await;
+                        if admit(&mut limiter, &mut closing, cli_id, conn_id).await {
+                            chain.clone().run(cli_id, Inbound::Text(text)).await;
                         }
                     }
                     Message::Binary(data) => {
-                        tracing::info!(bytes = ?data.len(), "Received binary message from client");
+                        metrics::incr(&metrics::MESSAGES_IN);
+                        tracing::debug!(bytes = ?data.len(), "Received binary message from client");
+                        if admit(&mut limiter, &mut closing, cli_id, conn_id).await {
+                            chain.clone().run(cli_id, Inbound::Binary(data)).await;
+                        }
                     }
                     Message::Close(_) => {
                         tracing::info!(cli_id = ?cli_id, "Client initiated close");
@@ -161,13 +405,44 @@ fn create_receive_task(
             }
         }
 
-        // 客户端断开连接，从连接管理器中移除
-        tracing::info!(cli_id = ?cli_id, conn_id = ?conn_id, "Client disconnected, cleaning up");
-        let mut manager = CONN_MGR.lock().await;
-        manager.remove_connection(cli_id, conn_id);
-        if let Some(f) = close_handler {
-            f(cli_id).await;
-        }
+        tracing::info!(cli_id = ?cli_id, "Client disconnected");
     }
         .boxed()
 }
+
+// 检查速率限制，返回是否处理该消息；按策略关闭连接后，关闭帧发出前收到的消息都丢弃
+async fn admit(
+    limiter: &mut Option<(TokenBucket, RateLimitPolicy)>,
+    closing: &mut bool,
+    cli_id: u64,
+    conn_id: usize,
+) -> bool {
+    if *closing {
+        return false;
+    }
+    let Some((bucket, policy)) = limiter else {
+        return true;
+    };
+    if bucket.try_acquire() {
+        return true;
+    }
+    metrics::incr(&metrics::RATE_LIMITED);
+    match policy {
+        RateLimitPolicy::Drop => {
+            tracing::debug!(cli_id = ?cli_id, "Rate limit exceeded, message dropped");
+        }
+        RateLimitPolicy::Close => {
+            tracing::warn!(cli_id = ?cli_id, conn_id = ?conn_id, "Rate limit exceeded, closing connection");
+            *closing = true;
+            let frame = CloseFrame {
+                code: RATE_LIMIT_CLOSE_CODE,
+                reason: "rate limit exceeded".into(),
+            };
+            CONN_MGR
+                .lock()
+                .await
+                .finish_connection(cli_id, conn_id, frame);
+        }
+    }
+    false
+}
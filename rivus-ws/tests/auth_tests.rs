@@ -0,0 +1,133 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::http::{HeaderMap, Uri};
+use axum::routing::get;
+use axum::Router;
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::auth::{handle_authenticated_connection, AuthContext, AuthRejection, FirstMessageAuth};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{send_message, CONN_MGR};
+use rivus_ws::ws_handler::WsHandlers;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::Message;
+
+// token 即 cli_id
+fn token_auth(ctx: AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> {
+    let token = ctx
+        .query_param("token")
+        .or(ctx.bearer_token())
+        .map(str::to_string);
+    Box::pin(async move {
+        token
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| AuthRejection::new(4001, "invalid token"))
+    })
+}
+
+fn first_message_auth(ctx: AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> {
+    Box::pin(async move {
+        let text = ctx
+            .first_message
+            .ok_or_else(|| AuthRejection::policy("missing token"))?;
+        text.as_str()
+            .strip_prefix("token:")
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| AuthRejection::policy("invalid token"))
+    })
+}
+
+async fn serve() -> SocketAddr {
+    let app = Router::new()
+        .route(
+            "/ws",
+            get(
+                |ws: WebSocketUpgrade, uri: Uri, headers: HeaderMap| async move {
+                    ws.on_upgrade(move |socket| async move {
+                        let ctx = AuthContext::new(uri, headers);
+                        handle_authenticated_connection(
+                            socket,
+                            ctx,
+                            &token_auth,
+                            WsHandlers::new(),
+                            WsConfig::default(),
+                        )
+                        .await
+                    })
+                },
+            ),
+        )
+        .route(
+            "/ws-first",
+            get(
+                |ws: WebSocketUpgrade, uri: Uri, headers: HeaderMap| async move {
+                    ws.on_upgrade(move |socket| async move {
+                        let ctx = AuthContext::new(uri, headers);
+                        let auth = FirstMessageAuth(first_message_auth);
+                        handle_authenticated_connection(
+                            socket,
+                            ctx,
+                            &auth,
+                            WsHandlers::new(),
+                            WsConfig::default(),
+                        )
+                        .await
+                    })
+                },
+            ),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+async fn wait_connected(cli_id: u64) {
+    for _ in 0..100 {
+        if send_message(cli_id, "ready".to_string()).await.is_ok() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("client {} not connected", cli_id);
+}
+
+#[tokio::test]
+async fn test_authentication() {
+    let addr = serve().await;
+
+    let (_client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?foo=1&token=41", addr))
+        .await
+        .unwrap();
+    wait_connected(41).await;
+
+    // 认证失败时以指定状态码关闭
+    let (mut rejected, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?token=abc", addr))
+        .await
+        .unwrap();
+    match rejected.next().await.unwrap().unwrap() {
+        Message::Close(Some(frame)) => {
+            assert_eq!(frame.code, CloseCode::from(4001));
+            assert_eq!(frame.reason.as_str(), "invalid token");
+        }
+        other => panic!("expected close frame, got {:?}", other),
+    }
+
+    let (mut first, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws-first", addr))
+        .await
+        .unwrap();
+    first.send(Message::Text("token:42".into())).await.unwrap();
+    wait_connected(42).await;
+
+    let (mut bad, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws-first", addr))
+        .await
+        .unwrap();
+    bad.send(Message::Text("hello".into())).await.unwrap();
+    match bad.next().await.unwrap().unwrap() {
+        Message::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Policy),
+        other => panic!("expected close frame, got {:?}", other),
+    }
+
+    assert_eq!(CONN_MGR.lock().await.connection_count(), 2);
+}
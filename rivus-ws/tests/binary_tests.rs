@@ -0,0 +1,68 @@
+use axum::body::Bytes;
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{send_binary, CONN_MGR};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+async fn test_binary_messages() {
+    // 处理器通过闭包捕获应用状态
+    let received: Arc<Mutex<Vec<(u64, Bytes)>>> = Arc::default();
+    let state = received.clone();
+    let handlers = WsHandlers::new().on_binary(move |cli_id, data| {
+        let state = state.clone();
+        Box::pin(async move { state.lock().await.push((cli_id, data)) })
+    });
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 7, handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    client
+        .send(Message::Binary(vec![1, 2, 3].into()))
+        .await
+        .unwrap();
+
+    // 等待服务端处理
+    for _ in 0..50 {
+        if !received.lock().await.is_empty() && CONN_MGR.lock().await.connection_count() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(
+        received.lock().await.as_slice(),
+        &[(7, Bytes::from_static(&[1, 2, 3]))]
+    );
+
+    send_binary(7, Bytes::from_static(&[9, 8])).await.unwrap();
+    // 跳过服务端的心跳 ping
+    let reply = tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            match client.next().await.unwrap().unwrap() {
+                Message::Ping(_) => continue,
+                other => break other,
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(reply, Message::Binary(vec![9, 8].into()));
+
+    assert!(send_binary(8, Bytes::new()).await.is_err());
+}
@@ -0,0 +1,26 @@
+use axum::extract::ws::Message;
+use futures::StreamExt;
+use rivus_ws::conn_mgr::{broadcast, CONN_MGR};
+use rivus_ws::send_queue::{self, OverflowPolicy};
+
+#[tokio::test]
+async fn test_broadcast() {
+    let (tx1, mut rx1) = send_queue::channel(10, OverflowPolicy::default());
+    let (tx2, mut rx2) = send_queue::channel(10, OverflowPolicy::default());
+    let (tx3, rx3) = send_queue::channel(10, OverflowPolicy::default());
+    {
+        let mut manager = CONN_MGR.lock().await;
+        manager.add_connection(1, tx1).unwrap();
+        manager.add_connection(1, tx2).unwrap();
+        manager.add_connection(2, tx3).unwrap();
+    }
+    // 接收端已关闭的连接发送失败，随后被移除
+    drop(rx3);
+
+    assert_eq!(broadcast("hello".to_string()).await, 2);
+    assert_eq!(rx1.next().await, Some(Message::Text("hello".into())));
+    assert_eq!(rx2.next().await, Some(Message::Text("hello".into())));
+    assert_eq!(CONN_MGR.lock().await.connection_count(), 2);
+
+    assert_eq!(broadcast("again".to_string()).await, 2);
+}
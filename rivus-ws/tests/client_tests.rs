@@ -0,0 +1,121 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use rivus_ws::client::{ClientConfig, WsClient};
+use rivus_ws::codec::JsonCodec;
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{disconnect, send_message, CONN_MGR};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+async fn wait_for<F: Fn() -> bool>(f: F) {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while !f() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+async fn connection_count() -> usize {
+    CONN_MGR.lock().await.connection_count()
+}
+
+#[tokio::test]
+async fn test_client_reconnects_and_flushes_queue() {
+    // 服务端回显文本消息
+    let server_handlers = WsHandlers::new()
+        .on_text(|cli_id, text| Box::pin(async move { send_message(cli_id, format!("echo:{}", text)).await.unwrap() }));
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 1, server_handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let (connects, closes) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let (on_connect, on_close) = (connects.clone(), closes.clone());
+    let handlers = WsHandlers::new()
+        .on_text(move |id, text| {
+            assert_eq!(id, 9);
+            tx.send(text.to_string()).unwrap();
+            Box::pin(async {})
+        })
+        .on_close(move |_| {
+            on_close.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {})
+        });
+    let config =
+        ClientConfig::new().with_backoff(Duration::from_millis(20), Duration::from_millis(50));
+    let sender = WsClient::new(format!("ws://{}/ws", addr))
+        .with_id(9)
+        .with_handlers(handlers)
+        .with_config(config)
+        .on_connect(move |sender| {
+            let n = on_connect.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                sender
+                    .send_encoded(&serde_json::json!({ "subscribe": n }), &JsonCodec)
+                    .unwrap()
+            })
+        })
+        .spawn();
+
+    // 服务端尚未启动：连接失败后重试，期间的消息保留在队列中
+    sender.send_text("queued").unwrap();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(!sender.is_connected());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut next = async || tokio::time::timeout(Duration::from_secs(2), rx.recv()).await.unwrap().unwrap();
+    // 队列按写入顺序发出
+    assert_eq!(next().await, "echo:queued");
+    assert_eq!(next().await, r#"echo:{"subscribe":0}"#);
+    assert!(sender.is_connected());
+
+    // 服务端断开后自动重连，并重新调用连接处理器
+    disconnect(1, 1012, "restart").await.unwrap();
+    assert_eq!(next().await, r#"echo:{"subscribe":1}"#);
+    assert_eq!(closes.load(Ordering::SeqCst), 1);
+    sender.send_text("again").unwrap();
+    assert_eq!(next().await, "echo:again");
+
+    // 关闭后不再重连
+    sender.close();
+    for _ in 0..400 {
+        if connection_count().await == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(connection_count().await, 0);
+    wait_for(|| !sender.is_connected() && sender.is_stopped()).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(connects.load(Ordering::SeqCst), 2);
+    assert!(sender.send_text("closed").is_err());
+}
+
+#[tokio::test]
+async fn test_client_gives_up_after_max_retries() {
+    // 绑定后立即释放端口，连接总是失败
+    let addr = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let config = ClientConfig::new()
+        .with_backoff(Duration::from_millis(5), Duration::from_millis(10))
+        .with_max_retries(2);
+    let sender = WsClient::new(format!("ws://{}/ws", addr))
+        .with_config(config)
+        .spawn();
+    wait_for(|| sender.is_stopped()).await;
+    assert!(!sender.is_connected());
+}
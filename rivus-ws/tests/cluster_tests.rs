@@ -0,0 +1,150 @@
+use axum::extract::ws::Message;
+use futures::channel::mpsc;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt};
+use rivus_ws::cluster::{self, Backplane, ClusterMessage, Frame, Target};
+use rivus_ws::conn_mgr::{broadcast, send_message, send_to_group, CONN_MGR};
+use rivus_ws::send_queue::{self, OverflowPolicy, QueueReceiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 进程内的 Backplane：发布的消息转发给所有订阅者，并记录下来
+#[derive(Clone, Default)]
+struct MemoryBackplane {
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<Vec<u8>>>>>,
+    published: Arc<Mutex<Vec<ClusterMessage>>>,
+}
+
+impl MemoryBackplane {
+    // 模拟其他节点发布消息
+    fn inject(&self, message: &ClusterMessage) {
+        let payload = message.encode().unwrap();
+        for tx in self.subscribers.lock().unwrap().iter() {
+            let _ = tx.unbounded_send(payload.clone());
+        }
+    }
+}
+
+impl Backplane for MemoryBackplane {
+    fn publish(&self, payload: Vec<u8>) -> BoxFuture<'_, anyhow::Result<()>> {
+        self.published
+            .lock()
+            .unwrap()
+            .push(ClusterMessage::decode(&payload).unwrap());
+        for tx in self.subscribers.lock().unwrap().iter() {
+            let _ = tx.unbounded_send(payload.clone());
+        }
+        Box::pin(async { Ok(()) })
+    }
+
+    fn subscribe(&self) -> BoxFuture<'_, anyhow::Result<BoxStream<'static, Vec<u8>>>> {
+        let (tx, rx) = mpsc::unbounded();
+        self.subscribers.lock().unwrap().push(tx);
+        Box::pin(async move { Ok(rx.boxed()) })
+    }
+}
+
+fn remote(target: Target, text: &str) -> ClusterMessage {
+    ClusterMessage {
+        node: "other-node".into(),
+        target,
+        frame: Frame::Text(text.into()),
+    }
+}
+
+async fn recv(rx: &mut QueueReceiver) -> Option<Message> {
+    tokio::time::timeout(Duration::from_secs(1), rx.next())
+        .await
+        .ok()
+        .flatten()
+}
+
+#[tokio::test]
+async fn test_cluster_mode() {
+    let backplane = MemoryBackplane::default();
+    cluster::enable(backplane.clone()).await.unwrap();
+    assert!(cluster::enable(backplane.clone()).await.is_err());
+    let node_id = cluster::node_id().unwrap().to_string();
+
+    let (tx, mut rx) = send_queue::channel(10, OverflowPolicy::default());
+    {
+        let mut manager = CONN_MGR.lock().await;
+        manager.add_connection(1, tx).unwrap();
+        manager.join_group(1, "room");
+    }
+
+    // 客户端可能连接在其他节点上，发布后即视为成功
+    send_message(99, "to remote".into()).await.unwrap();
+    assert_eq!(send_to_group("remote-room", "x".into()).await.unwrap(), 0);
+    assert_eq!(
+        backplane.published.lock().unwrap()[0],
+        ClusterMessage {
+            node: node_id.clone(),
+            target: Target::Client(99),
+            frame: Frame::Text("to remote".into())
+        }
+    );
+
+    // 本节点发布的消息只在本地投递一次
+    send_message(1, "local".into()).await.unwrap();
+    assert_eq!(recv(&mut rx).await, Some(Message::Text("local".into())));
+
+    backplane.inject(&remote(Target::Client(1), "from client"));
+    assert_eq!(
+        recv(&mut rx).await,
+        Some(Message::Text("from client".into()))
+    );
+    backplane.inject(&remote(Target::Group("room".into()), "from group"));
+    assert_eq!(
+        recv(&mut rx).await,
+        Some(Message::Text("from group".into()))
+    );
+    backplane.inject(&remote(Target::All, "from broadcast"));
+    assert_eq!(
+        recv(&mut rx).await,
+        Some(Message::Text("from broadcast".into()))
+    );
+    // 不在本节点的目标被忽略
+    backplane.inject(&remote(Target::Client(2), "elsewhere"));
+
+    assert_eq!(broadcast("all".into()).await, 1);
+    assert_eq!(recv(&mut rx).await, Some(Message::Text("all".into())));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(rx.next().now_or_never().is_none());
+    assert_eq!(backplane.published.lock().unwrap().len(), 4);
+
+    // 其他节点断开本节点上的客户端
+    backplane.inject(&ClusterMessage {
+        node: "other-node".into(),
+        target: Target::Client(1),
+        frame: Frame::Close {
+            code: 4003,
+            reason: "banned".into(),
+        },
+    });
+    match recv(&mut rx).await {
+        Some(Message::Close(Some(frame))) => assert_eq!((frame.code, frame.reason.as_str()), (4003, "banned")),
+        other => panic!("expected close frame, got {:?}", other),
+    }
+    assert_eq!(CONN_MGR.lock().await.connection_count(), 0);
+}
+
+// 需要本地 Redis：REDIS_URL=redis://127.0.0.1/ cargo test --features redis -- --ignored
+#[cfg(feature = "redis")]
+#[tokio::test]
+#[ignore]
+async fn test_redis_backplane() {
+    let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into());
+    let backplane = cluster::RedisBackplane::connect(&url, "rivus-ws-test")
+        .await
+        .unwrap();
+    let mut stream = backplane.subscribe().await.unwrap();
+    let message = remote(Target::All, "hello");
+    backplane.publish(message.encode().unwrap()).await.unwrap();
+    let payload = tokio::time::timeout(Duration::from_secs(1), stream.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ClusterMessage::decode(&payload).unwrap(), message);
+}
@@ -0,0 +1,99 @@
+use axum::extract::ws::{Message as WsMessage, WebSocketUpgrade};
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::codec::{Codec, JsonCodec, MsgPackCodec};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::send_encoded;
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Tick {
+    Price { symbol: String, price: f64 },
+    Halt { symbol: String },
+}
+
+#[test]
+fn test_codec_round_trip() {
+    let tick = Tick::Price {
+        symbol: "ABC".into(),
+        price: 1.5,
+    };
+
+    let WsMessage::Text(text) = JsonCodec.encode(&tick).unwrap() else {
+        panic!("expected text frame")
+    };
+    assert_eq!(
+        text.as_str(),
+        r#"{"type":"price","symbol":"ABC","price":1.5}"#
+    );
+    assert_eq!(JsonCodec.decode::<Tick>(text.as_bytes()).unwrap(), tick);
+
+    let WsMessage::Binary(data) = MsgPackCodec.encode(&tick).unwrap() else {
+        panic!("expected binary frame")
+    };
+    assert!(data.len() < text.len());
+    assert_eq!(MsgPackCodec.decode::<Tick>(&data).unwrap(), tick);
+    assert!(MsgPackCodec.decode::<Tick>(b"\xc1").is_err());
+}
+
+#[tokio::test]
+async fn test_msgpack_connection() {
+    let handlers = WsHandlers::new().on_decoded(MsgPackCodec, |cli_id, tick: Tick| {
+        Box::pin(async move {
+            let reply = match tick {
+                Tick::Price { symbol, .. } => Tick::Halt { symbol },
+                halt => halt,
+            };
+            send_encoded(cli_id, &reply, &MsgPackCodec).await.unwrap();
+        })
+    });
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 5, handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    // 无法解码的帧被丢弃
+    client
+        .send(Message::Binary(vec![0xc1].into()))
+        .await
+        .unwrap();
+    let tick = Tick::Price {
+        symbol: "ABC".into(),
+        price: 2.0,
+    };
+    client
+        .send(Message::Binary(
+            rmp_serde::to_vec_named(&tick).unwrap().into(),
+        ))
+        .await
+        .unwrap();
+
+    let reply = tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            if let Message::Binary(data) = client.next().await.unwrap().unwrap() {
+                break rmp_serde::from_slice::<Tick>(&data).unwrap();
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        reply,
+        Tick::Halt {
+            symbol: "ABC".into()
+        }
+    );
+}
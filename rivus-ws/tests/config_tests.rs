@@ -0,0 +1,69 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::CONN_MGR;
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_custom_ping_timeout() {
+    let config = WsConfig::new()
+        .with_ping_interval(Duration::from_millis(20))
+        .with_ping_timeout(Duration::from_millis(100));
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 1, WsHandlers::new(), config))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    // 客户端不读取消息，也就不会回复 pong
+    let (_client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    for _ in 0..50 {
+        if CONN_MGR.lock().await.client_connection_count(1) == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(CONN_MGR.lock().await.client_connection_count(1), 1);
+
+    // 超过 ping_timeout 后服务端断开连接
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(CONN_MGR.lock().await.client_connection_count(1), 0);
+}
+
+#[tokio::test]
+async fn test_zero_ping_interval_disables_heartbeat() {
+    let config = WsConfig::new()
+        .with_ping_interval(Duration::ZERO)
+        .with_ping_timeout(Duration::from_millis(50));
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 2, WsHandlers::new(), config))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (_client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    for _ in 0..50 {
+        if CONN_MGR.lock().await.client_connection_count(2) == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    // 不发送心跳，也不因超时断开
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(CONN_MGR.lock().await.client_connection_count(2), 1);
+}
@@ -0,0 +1,80 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::StreamExt;
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{disconnect, disconnect_conn, CONN_MGR};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+type Client =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn next_close(client: &mut Client) -> Option<(u16, String)> {
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while let Some(Ok(msg)) = client.next().await {
+            if let Message::Close(frame) = msg {
+                return frame.map(|f| (u16::from(f.code), f.reason.to_string()));
+            }
+        }
+        None
+    })
+    .await
+    .unwrap()
+}
+
+async fn wait_connections(n: usize) {
+    for _ in 0..100 {
+        if CONN_MGR.lock().await.connection_count() == n {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("expected {} connections", n);
+}
+
+#[tokio::test]
+async fn test_disconnect() {
+    let app = Router::new().route(
+        "/ws/{cli_id}",
+        get(|ws: WebSocketUpgrade, axum::extract::Path(cli_id): axum::extract::Path<u64>| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, cli_id, WsHandlers::new(), WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let connect =
+        |cli_id: u64| tokio_tungstenite::connect_async(format!("ws://{}/ws/{}", addr, cli_id));
+
+    let (mut first, _) = connect(1).await.unwrap();
+    wait_connections(1).await;
+    let (mut second, _) = connect(1).await.unwrap();
+    let (_other, _) = connect(2).await.unwrap();
+    wait_connections(3).await;
+
+    // 按连接 ID 断开最早的连接
+    let conn_id = *CONN_MGR
+        .lock()
+        .await
+        .connection_ids(1)
+        .iter()
+        .min()
+        .unwrap();
+    disconnect_conn(1, conn_id, 4001, "session expired")
+        .await
+        .unwrap();
+    assert_eq!(
+        next_close(&mut first).await,
+        Some((4001, "session expired".into()))
+    );
+    assert!(disconnect_conn(1, conn_id, 4001, "again").await.is_err());
+
+    assert_eq!(disconnect(1, 4003, "banned").await.unwrap(), 1);
+    assert_eq!(next_close(&mut second).await, Some((4003, "banned".into())));
+    assert!(disconnect(1, 4003, "banned").await.is_err());
+
+    wait_connections(1).await;
+    assert_eq!(CONN_MGR.lock().await.connection_ids(2).len(), 1);
+}
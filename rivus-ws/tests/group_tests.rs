@@ -0,0 +1,46 @@
+use axum::extract::ws::Message;
+use futures::{FutureExt, StreamExt};
+use rivus_ws::conn_mgr::{send_to_group, CONN_MGR};
+use rivus_ws::send_queue::{self, OverflowPolicy};
+
+#[tokio::test]
+async fn test_groups() {
+    let (tx1, mut rx1) = send_queue::channel(10, OverflowPolicy::default());
+    let (tx2, mut rx2) = send_queue::channel(10, OverflowPolicy::default());
+    let (tx3, mut rx3) = send_queue::channel(10, OverflowPolicy::default());
+    let (conn1, conn2) = {
+        let mut manager = CONN_MGR.lock().await;
+        let conn1 = manager.add_connection(1, tx1).unwrap();
+        let conn2 = manager.add_connection(2, tx2).unwrap();
+        manager.add_connection(3, tx3).unwrap();
+
+        // 没有活动连接的客户端不能加入分组
+        assert!(!manager.join_group(9, "room"));
+        assert!(manager.join_group(1, "room"));
+        assert!(manager.join_group(2, "room"));
+        assert!(manager.join_group(2, "news"));
+        (conn1, conn2)
+    };
+
+    assert_eq!(
+        send_to_group("room", "hi room".to_string()).await.unwrap(),
+        2
+    );
+    assert_eq!(rx1.next().await, Some(Message::Text("hi room".into())));
+    assert_eq!(rx2.next().await, Some(Message::Text("hi room".into())));
+    assert!(rx3.next().now_or_never().is_none());
+
+    {
+        let mut manager = CONN_MGR.lock().await;
+        assert!(manager.leave_group(1, "room"));
+        assert!(!manager.leave_group(1, "room"));
+        assert_eq!(manager.group_members("room"), vec![2]);
+
+        // 最后一个连接断开后退出所有分组，空分组被清理
+        manager.remove_connection(2, conn2);
+        assert!(manager.client_groups(2).is_empty());
+        assert!(manager.group_members("news").is_empty());
+        manager.remove_connection(1, conn1);
+    }
+    assert!(send_to_group("room", "gone".to_string()).await.is_err());
+}
@@ -0,0 +1,80 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::send_json;
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Inbound {
+    Chat { text: String },
+    Join { room: String },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Outbound<'a> {
+    Echo { text: &'a str },
+    Joined { room: &'a str },
+}
+
+#[tokio::test]
+async fn test_json_envelope() {
+    let handlers = WsHandlers::new().on_json(|cli_id, message: Inbound| {
+        Box::pin(async move {
+            let reply = match &message {
+                Inbound::Chat { text } => Outbound::Echo { text },
+                Inbound::Join { room } => Outbound::Joined { room },
+            };
+            send_json(cli_id, &reply).await.unwrap();
+        })
+    });
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 3, handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    // 无法解析的消息被丢弃，不影响后续消息
+    for text in [
+        "not json",
+        r#"{"type":"unknown"}"#,
+        r#"{"type":"chat","text":"hi"}"#,
+        r#"{"type":"join","room":"lobby"}"#,
+    ] {
+        client.send(Message::Text(text.into())).await.unwrap();
+    }
+
+    let mut replies = Vec::new();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while replies.len() < 2 {
+            if let Message::Text(text) = client.next().await.unwrap().unwrap() {
+                replies.push(serde_json::from_str::<serde_json::Value>(&text).unwrap());
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        replies[0],
+        serde_json::json!({"type": "echo", "text": "hi"})
+    );
+    assert_eq!(
+        replies[1],
+        serde_json::json!({"type": "joined", "room": "lobby"})
+    );
+
+    assert!(send_json(4, &Outbound::Echo { text: "x" }).await.is_err());
+}
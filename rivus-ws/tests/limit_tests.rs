@@ -0,0 +1,150 @@
+use axum::extract::ws::{Message, WebSocketUpgrade};
+use axum::routing::get;
+use axum::Router;
+use futures::{FutureExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{ConnectionLimits, ConnectionManager, LimitExceeded, LimitPolicy, CONN_MGR, LIMIT_CLOSE_CODE};
+use rivus_ws::send_queue::{self, OverflowPolicy, QueueReceiver, QueueSender};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite;
+
+fn channel() -> (QueueSender, QueueReceiver) {
+    send_queue::channel(10, OverflowPolicy::default())
+}
+
+fn assert_closed(rx: &mut QueueReceiver) {
+    match rx.next().now_or_never() {
+        Some(Some(Message::Close(Some(frame)))) => assert_eq!(frame.code, LIMIT_CLOSE_CODE),
+        other => panic!("expected close frame, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_reject_over_limits() {
+    let mut manager = ConnectionManager::new();
+    manager.set_limits(
+        ConnectionLimits::new()
+            .with_max_connections(3)
+            .with_max_per_client(2),
+    );
+
+    manager.add_connection(1, channel().0).unwrap();
+    manager.add_connection(1, channel().0).unwrap();
+    assert_eq!(
+        manager.add_connection(1, channel().0),
+        Err(LimitExceeded::PerClient(2))
+    );
+
+    manager.add_connection(2, channel().0).unwrap();
+    assert_eq!(
+        manager.add_connection(3, channel().0),
+        Err(LimitExceeded::Global(3))
+    );
+    assert_eq!(manager.connection_count(), 3);
+}
+
+#[test]
+fn test_evict_oldest_per_client() {
+    let mut manager = ConnectionManager::new();
+    manager.set_limits(
+        ConnectionLimits::new()
+            .with_max_per_client(2)
+            .with_policy(LimitPolicy::EvictOldest),
+    );
+
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    let (tx3, mut rx3) = channel();
+    manager.add_connection(1, tx1).unwrap();
+    manager.add_connection(1, tx2).unwrap();
+    manager.add_connection(1, tx3).unwrap();
+
+    assert_closed(&mut rx1);
+    // 被挤出的连接只收到关闭帧，发送端随即被丢弃
+    assert_eq!(rx1.next().now_or_never(), Some(None));
+    assert!(rx2.next().now_or_never().is_none());
+    assert!(rx3.next().now_or_never().is_none());
+    assert_eq!(manager.connection_count(), 2);
+}
+
+#[test]
+fn test_evict_oldest_global() {
+    let mut manager = ConnectionManager::new();
+    manager.set_limits(
+        ConnectionLimits::new()
+            .with_max_connections(2)
+            .with_policy(LimitPolicy::EvictOldest),
+    );
+
+    let (tx1, mut rx1) = channel();
+    manager.add_connection(1, tx1).unwrap();
+    assert!(manager.join_group(1, "room"));
+    manager.add_connection(2, channel().0).unwrap();
+    manager.add_connection(3, channel().0).unwrap();
+
+    // 客户端 1 的唯一连接被挤出，随之退出分组
+    assert_closed(&mut rx1);
+    assert_eq!(manager.connection_count(), 2);
+    assert!(manager.group_members("room").is_empty());
+    assert!(manager.client_groups(1).is_empty());
+}
+
+// 读取到关闭帧为止，跳过心跳 ping
+async fn next_close<S>(client: &mut S) -> Option<u16>
+where
+    S: futures::Stream<Item = Result<tungstenite::Message, tungstenite::Error>> + Unpin,
+{
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while let Some(Ok(msg)) = client.next().await {
+            if let tungstenite::Message::Close(frame) = msg {
+                return frame.map(|f| u16::from(f.code));
+            }
+        }
+        None
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_limits_on_live_connections() {
+    CONN_MGR.lock().await.set_limits(
+        ConnectionLimits::new()
+            .with_max_connections(1)
+            .with_max_per_client(1),
+    );
+    let app = Router::new().route(
+        "/ws/{cli_id}",
+        get(|ws: WebSocketUpgrade, axum::extract::Path(cli_id): axum::extract::Path<u64>| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, cli_id, WsHandlers::new(), WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let connect =
+        |cli_id: u64| tokio_tungstenite::connect_async(format!("ws://{}/ws/{}", addr, cli_id));
+
+    let (mut first, _) = connect(1).await.unwrap();
+    for _ in 0..50 {
+        if CONN_MGR.lock().await.connection_count() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    // 拒绝策略：新连接收到关闭帧
+    let (mut rejected, _) = connect(2).await.unwrap();
+    assert_eq!(next_close(&mut rejected).await, Some(LIMIT_CLOSE_CODE));
+
+    // 挤出策略：最早的连接收到关闭帧，新连接保留
+    CONN_MGR.lock().await.set_limits(
+        ConnectionLimits::new()
+            .with_max_per_client(1)
+            .with_policy(LimitPolicy::EvictOldest),
+    );
+    let (_second, _) = connect(1).await.unwrap();
+    assert_eq!(next_close(&mut first).await, Some(LIMIT_CLOSE_CODE));
+    assert_eq!(CONN_MGR.lock().await.connection_count(), 1);
+}
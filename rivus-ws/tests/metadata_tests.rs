@@ -0,0 +1,104 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::Query;
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{broadcast_where, send_message, ConnectionManager, Metadata, CONN_MGR};
+use rivus_ws::send_queue::{self, OverflowPolicy};
+use rivus_ws::ws_handler::{current_connection, current_metadata, handle_connection_with, WsHandlers};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+type Client =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn next_text(client: &mut Client) -> String {
+    tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            if let Message::Text(text) = client.next().await.unwrap().unwrap() {
+                break text.to_string();
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[test]
+fn test_metadata_on_manager() {
+    let mut manager = ConnectionManager::new();
+    let meta = Metadata::from_iter([("device".to_string(), json!("ios"))]);
+    let conn_id = manager
+        .add_connection_with(1, send_queue::channel(4, OverflowPolicy::default()).0, meta)
+        .unwrap();
+
+    assert_eq!(manager.metadata(1, conn_id).unwrap()["device"], "ios");
+    assert!(manager.set_metadata(1, conn_id, "scopes", json!(["read", "write"])));
+    assert_eq!(
+        manager.metadata(1, conn_id).unwrap()["scopes"],
+        json!(["read", "write"])
+    );
+    assert_eq!(
+        manager.remove_metadata(1, conn_id, "device"),
+        Some(json!("ios"))
+    );
+    assert!(!manager.set_metadata(1, conn_id + 1, "device", "web"));
+    assert!(manager.metadata(2, conn_id).is_none());
+}
+
+#[tokio::test]
+async fn test_metadata_in_handlers() {
+    // 回复当前连接的设备类型，并记录订阅的主题
+    let handlers = WsHandlers::new().on_text(|cli_id, text| {
+        Box::pin(async move {
+            let meta = current_metadata().await.unwrap();
+            let (_, conn_id) = current_connection().unwrap();
+            CONN_MGR
+                .lock()
+                .await
+                .set_metadata(cli_id, conn_id, "topic", text.as_str());
+            send_message(
+                cli_id,
+                format!("{}:{}", meta["device"].as_str().unwrap(), text),
+            )
+            .await
+            .unwrap();
+        })
+    });
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade, Query(query): Query<HashMap<String, String>>| async move {
+            let cli_id = query["cli_id"].parse().unwrap();
+            let meta = Metadata::from_iter([("device".to_string(), json!(query["device"]))]);
+            ws.on_upgrade(move |socket| handle_connection_with(socket, cli_id, meta, handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let connect = |cli_id: u64, device: &str| {
+        tokio_tungstenite::connect_async(format!(
+            "ws://{}/ws?cli_id={}&device={}",
+            addr, cli_id, device
+        ))
+    };
+
+    let (mut ios, _) = connect(1, "ios").await.unwrap();
+    let (mut web, _) = connect(2, "web").await.unwrap();
+    ios.send(Message::Text("news".into())).await.unwrap();
+    assert_eq!(next_text(&mut ios).await, "ios:news");
+    web.send(Message::Text("sports".into())).await.unwrap();
+    assert_eq!(next_text(&mut web).await, "web:sports");
+
+    let delivered = broadcast_where("for ios".into(), |_, meta| meta["device"] == "ios").await;
+    assert_eq!(delivered, 1);
+    assert_eq!(next_text(&mut ios).await, "for ios");
+    let delivered = broadcast_where("sports update".into(), |_, meta| meta.get("topic") == Some(&json!("sports"))).await;
+    assert_eq!(delivered, 1);
+    assert_eq!(next_text(&mut web).await, "sports update");
+
+    assert!(current_connection().is_none());
+}
@@ -0,0 +1,95 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{send_message, ConnectionLimits, CONN_MGR};
+use rivus_ws::metrics;
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+async fn wait_until<F: Fn(&metrics::WsMetrics) -> bool>(f: F) -> metrics::WsMetrics {
+    for _ in 0..100 {
+        let m = metrics::snapshot().await;
+        if f(&m) {
+            return m;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("metrics condition not met: {:?}", metrics::snapshot().await)
+}
+
+#[tokio::test]
+async fn test_metrics() {
+    CONN_MGR
+        .lock()
+        .await
+        .set_limits(ConnectionLimits::new().with_max_per_client(2));
+    // 回显文本消息
+    let handlers = WsHandlers::new().on_text(|cli_id, text| {
+        Box::pin(async move {
+            send_message(cli_id, text.to_string()).await.unwrap();
+        })
+    });
+    let config = WsConfig::new()
+        .with_ping_interval(Duration::from_millis(50))
+        .with_ping_timeout(Duration::from_millis(200));
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 1, handlers, config))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut active, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (_silent, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let m = wait_until(|m| m.active_connections == 2).await;
+    assert_eq!(
+        (
+            m.active_clients,
+            m.max_connections_per_client,
+            m.connections_opened
+        ),
+        (1, 2, 2)
+    );
+
+    // 超出单客户端上限
+    let (_rejected, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    wait_until(|m| m.connections_rejected == 1).await;
+
+    active.send(Message::Text("a".into())).await.unwrap();
+    active.send(Message::Binary(vec![1].into())).await.unwrap();
+    // 读取回显，同时自动回复 ping
+    let mut echoed = 0;
+    while echoed < 2 {
+        if let Message::Text(_) = active.next().await.unwrap().unwrap() {
+            echoed += 1;
+            active.send(Message::Text("b".into())).await.unwrap();
+        }
+    }
+    let m = metrics::snapshot().await;
+    assert_eq!(m.messages_in, 3);
+    // 回显发送到客户端 1 的两个连接
+    assert!(m.messages_out >= 3);
+
+    // 持续读取以回复 ping；不读取消息的连接心跳超时
+    tokio::spawn(async move { while let Some(Ok(_)) = active.next().await {} });
+    let m = wait_until(|m| m.ping_timeouts == 1 && m.connections_closed == 1).await;
+    assert_eq!(m.active_connections, 1);
+
+    let text = m.to_prometheus();
+    assert!(
+        text.contains("# TYPE rivus_ws_active_connections gauge\nrivus_ws_active_connections 1\n")
+    );
+    assert!(
+        text.contains(
+            "# TYPE rivus_ws_ping_timeouts_total counter\nrivus_ws_ping_timeouts_total 1\n"
+        )
+    );
+    assert!(text.contains("rivus_ws_connections_rejected_total 1\n"));
+}
@@ -0,0 +1,94 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::{FutureExt, SinkExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{send_binary, send_message};
+use rivus_ws::middleware::{Inbound, Next};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+async fn test_middleware_chain() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let (outer, inner) = (calls.clone(), calls.clone());
+    let handlers = WsHandlers::new()
+        // 先添加的先执行，处理器返回后才返回
+        .with_middleware(move |cli_id, message, next: Next| {
+            let calls = outer.clone();
+            async move {
+                calls.lock().unwrap().push("outer:before");
+                next.run(cli_id, message).await;
+                calls.lock().unwrap().push("outer:after");
+            }
+            .boxed()
+        })
+        .with_middleware(move |cli_id, message, next: Next| {
+            inner.lock().unwrap().push("inner");
+            next.run(cli_id, message)
+        })
+        // 丢弃被拦截的消息，将其余文本消息转为大写
+        .with_middleware(|cli_id, message, next: Next| match message {
+            Inbound::Text(text) if text.as_str() == "blocked" => Box::pin(async {}),
+            Inbound::Text(text) => next.run(cli_id, Inbound::Text(text.to_uppercase().into())),
+            message => next.run(cli_id, message),
+        })
+        .on_text(|cli_id, text| Box::pin(async move { send_message(cli_id, text.to_string()).await.unwrap() }))
+        .on_binary(|cli_id, data| Box::pin(async move { send_binary(cli_id, data).await.unwrap() }));
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 5, handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    client.send(Message::Text("blocked".into())).await.unwrap();
+    client.send(Message::Text("hello".into())).await.unwrap();
+    client
+        .send(Message::Binary(vec![1, 2].into()))
+        .await
+        .unwrap();
+
+    let mut replies = Vec::new();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while replies.len() < 2 {
+            match client.next().await.unwrap().unwrap() {
+                Message::Ping(_) => {}
+                message => replies.push(message),
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        replies,
+        vec![
+            Message::Text("HELLO".into()),
+            Message::Binary(vec![1, 2].into())
+        ]
+    );
+
+    let calls = calls.lock().unwrap().clone();
+    // 第三条消息的 outer:after 可能在回复之后才记录
+    assert_eq!(
+        calls[..8],
+        [
+            "outer:before",
+            "inner",
+            "outer:after",
+            "outer:before",
+            "inner",
+            "outer:after",
+            "outer:before",
+            "inner"
+        ]
+    );
+}
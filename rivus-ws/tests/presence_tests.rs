@@ -0,0 +1,95 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::SinkExt;
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{ConnectionManager, CONN_MGR};
+use rivus_ws::presence::{self, PresenceEvent, PresenceStatus};
+use rivus_ws::send_queue::{self, OverflowPolicy};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+#[test]
+fn test_presence_on_manager() {
+    let mut manager = ConnectionManager::new();
+    let sender = || send_queue::channel(4, OverflowPolicy::default()).0;
+    let first = manager.add_connection(1, sender()).unwrap();
+    let second = manager.add_connection(1, sender()).unwrap();
+    assert!(manager.is_online(1));
+    assert_eq!(manager.online_clients(), vec![1]);
+    assert!(manager.last_seen(1).is_none());
+
+    manager.remove_connection(1, first);
+    assert!(manager.is_online(1));
+    let before = SystemTime::now();
+    manager.remove_connection(1, second);
+    assert!(!manager.is_online(1));
+    assert!(manager.online_clients().is_empty());
+    assert!(manager.last_seen(1).unwrap() >= before);
+
+    // 重新上线后清除最后在线时间
+    manager.add_connection(1, sender()).unwrap();
+    assert!(manager.last_seen(1).is_none());
+    manager.clear();
+    assert!(manager.last_seen(1).is_some());
+}
+
+#[tokio::test]
+async fn test_presence_events() {
+    let (tx, mut rx) = mpsc::unbounded_channel::<PresenceEvent>();
+    presence::on_change(move |event| {
+        let tx = tx.clone();
+        Box::pin(async move {
+            if event.cli_id == 42 {
+                tx.send(event).unwrap();
+            }
+        })
+    });
+
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, 42, WsHandlers::new(), WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    let next_event = async |rx: &mut mpsc::UnboundedReceiver<PresenceEvent>| {
+        tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    };
+
+    // 同一客户端的第二个连接不产生事件
+    let (mut first, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    assert_eq!(next_event(&mut rx).await.status, PresenceStatus::Online);
+    let (mut second, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    for _ in 0..50 {
+        if CONN_MGR.lock().await.client_connection_count(42) == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert!(presence::is_online(42).await);
+    assert!(presence::online_clients().await.contains(&42));
+
+    first.send(Message::Close(None)).await.unwrap();
+    second.send(Message::Close(None)).await.unwrap();
+    let event = next_event(&mut rx).await;
+    assert_eq!(event.status, PresenceStatus::Offline);
+    assert!(!presence::is_online(42).await);
+    assert_eq!(presence::last_seen(42).await, Some(event.at));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), rx.recv())
+            .await
+            .is_err()
+    );
+}
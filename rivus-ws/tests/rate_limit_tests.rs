@@ -0,0 +1,96 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::send_message;
+use rivus_ws::metrics;
+use rivus_ws::rate_limit::{RateLimit, RateLimitPolicy, RATE_LIMIT_CLOSE_CODE};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+type Client =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+// 启动回显服务并连接
+async fn connect(cli_id: u64, limit: RateLimit) -> Client {
+    let handlers = WsHandlers::new()
+        .on_text(|cli_id, text| Box::pin(async move { send_message(cli_id, text.to_string()).await.unwrap() }));
+    let config = WsConfig::new().with_rate_limit(limit);
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, cli_id, handlers, config))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap()
+        .0
+}
+
+async fn send_all(client: &mut Client, texts: &[&str]) {
+    for text in texts {
+        client.send(Message::Text((*text).into())).await.unwrap();
+    }
+}
+
+async fn next_message(client: &mut Client) -> Option<Message> {
+    tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            match client.next().await {
+                Some(Ok(Message::Ping(_))) => {}
+                Some(Ok(message)) => break Some(message),
+                _ => break None,
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_drops_excess_messages() {
+    let mut client = connect(1, RateLimit::new(20).with_burst(3)).await;
+    send_all(&mut client, &["1", "2", "3", "4", "5"]).await;
+    for text in ["1", "2", "3"] {
+        assert_eq!(
+            next_message(&mut client).await,
+            Some(Message::Text(text.into()))
+        );
+    }
+
+    // 令牌按速率补充后恢复处理
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    send_all(&mut client, &["6"]).await;
+    assert_eq!(
+        next_message(&mut client).await,
+        Some(Message::Text("6".into()))
+    );
+    assert!(metrics::snapshot().await.rate_limited >= 2);
+}
+
+#[tokio::test]
+async fn test_rate_limit_closes_connection() {
+    let limit = RateLimit::new(1)
+        .with_burst(2)
+        .with_policy(RateLimitPolicy::Close);
+    let mut client = connect(2, limit).await;
+    send_all(&mut client, &["1", "2", "3", "4"]).await;
+    assert_eq!(
+        next_message(&mut client).await,
+        Some(Message::Text("1".into()))
+    );
+    assert_eq!(
+        next_message(&mut client).await,
+        Some(Message::Text("2".into()))
+    );
+    match next_message(&mut client).await {
+        Some(Message::Close(Some(frame))) => assert_eq!(u16::from(frame.code), RATE_LIMIT_CLOSE_CODE),
+        other => panic!("expected close frame, got {:?}", other),
+    }
+}
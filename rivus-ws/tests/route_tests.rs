@@ -0,0 +1,111 @@
+use axum::Router;
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use rivus_ws::auth::{AuthContext, AuthRejection};
+use rivus_ws::conn_mgr::send_message;
+use rivus_ws::route::ANONYMOUS_ID_START;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+type Client =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+fn token_auth(ctx: AuthContext) -> BoxFuture<'static, Result<u64, AuthRejection>> {
+    let token = ctx.query_param("token").and_then(|t| t.parse().ok());
+    Box::pin(async move { token.ok_or_else(|| AuthRejection::new(4001, "invalid token")) })
+}
+
+fn echo(cli_id: u64, text: axum::extract::ws::Utf8Bytes) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        send_message(cli_id, format!("{}:{}", cli_id, text))
+            .await
+            .unwrap()
+    })
+}
+
+async fn next_message(client: &mut Client) -> Message {
+    tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            match client.next().await.unwrap().unwrap() {
+                Message::Ping(_) => {}
+                message => break message,
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_route_builder() {
+    let closed = Arc::new(AtomicU64::new(0));
+    let on_close = closed.clone();
+    let app: Router = Router::new()
+        .merge(
+            rivus_ws::route("/ws")
+                .auth(token_auth)
+                .on_message(echo)
+                .on_close(move |cli_id| {
+                    on_close.store(cli_id, Ordering::SeqCst);
+                    Box::pin(async {})
+                })
+                .into_router(),
+        )
+        .merge(rivus_ws::route("/public").on_message(echo).into_router());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?token=11", addr))
+        .await
+        .unwrap();
+    client.send(Message::Text("hi".into())).await.unwrap();
+    assert_eq!(
+        next_message(&mut client).await,
+        Message::Text("11:hi".into())
+    );
+    client.send(Message::Close(None)).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while closed.load(Ordering::SeqCst) != 11 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    let (mut rejected, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    match next_message(&mut rejected).await {
+        Message::Close(Some(frame)) => assert_eq!(u16::from(frame.code), 4001),
+        other => panic!("expected close frame, got {:?}", other),
+    }
+
+    // 未设置认证时分配匿名 ID
+    let (mut first, _) = tokio_tungstenite::connect_async(format!("ws://{}/public", addr))
+        .await
+        .unwrap();
+    let (mut second, _) = tokio_tungstenite::connect_async(format!("ws://{}/public", addr))
+        .await
+        .unwrap();
+    first.send(Message::Text("a".into())).await.unwrap();
+    second.send(Message::Text("b".into())).await.unwrap();
+    let parse = |message: Message| -> u64 {
+        message
+            .into_text()
+            .unwrap()
+            .split(':')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+    let (a, b) = (
+        parse(next_message(&mut first).await),
+        parse(next_message(&mut second).await),
+    );
+    assert!(a >= ANONYMOUS_ID_START && b >= ANONYMOUS_ID_START);
+    assert_ne!(a, b);
+}
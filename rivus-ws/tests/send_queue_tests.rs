@@ -0,0 +1,69 @@
+use axum::extract::ws::Message;
+use futures::{FutureExt, StreamExt};
+use rivus_ws::send_queue::{self, dropped_messages, OverflowPolicy, PushError, QueueReceiver, OVERFLOW_CLOSE_CODE};
+
+fn text(s: &str) -> Message {
+    Message::Text(s.into())
+}
+
+fn drain(rx: &mut QueueReceiver) -> Vec<Message> {
+    let mut messages = Vec::new();
+    while let Some(Some(message)) = rx.next().now_or_never() {
+        messages.push(message);
+    }
+    messages
+}
+
+#[test]
+fn test_overflow_policies() {
+    let dropped = dropped_messages();
+
+    let (tx, mut rx) = send_queue::channel(2, OverflowPolicy::DropOldest);
+    for s in ["a", "b", "c"] {
+        tx.push(text(s)).unwrap();
+    }
+    assert_eq!(drain(&mut rx), vec![text("b"), text("c")]);
+
+    let (tx, mut rx) = send_queue::channel(2, OverflowPolicy::DropNew);
+    tx.push(text("a")).unwrap();
+    tx.push(text("b")).unwrap();
+    assert_eq!(tx.push(text("c")), Err(PushError::Dropped));
+    assert_eq!(drain(&mut rx), vec![text("a"), text("b")]);
+
+    // 慢消费者：清空队列，只剩关闭帧，之后接收端结束
+    let (tx, mut rx) = send_queue::channel(2, OverflowPolicy::Disconnect);
+    tx.push(text("a")).unwrap();
+    tx.push(text("b")).unwrap();
+    assert_eq!(tx.push(text("c")), Err(PushError::Closed));
+    assert!(tx.is_closed());
+    match drain(&mut rx).as_slice() {
+        [Message::Close(Some(frame))] => assert_eq!(frame.code, OVERFLOW_CLOSE_CODE),
+        other => panic!("expected close frame, got {:?}", other),
+    }
+    assert_eq!(rx.next().now_or_never(), Some(None));
+    assert_eq!(tx.push(text("d")), Err(PushError::Closed));
+
+    assert_eq!(dropped_messages() - dropped, 3);
+}
+
+#[tokio::test]
+async fn test_receiver_wakes_and_ends() {
+    let (tx, rx) = send_queue::channel(4, OverflowPolicy::default());
+    let tx2 = tx.clone();
+    let reader = tokio::spawn(async move { rx.collect::<Vec<_>>().await });
+
+    tx.push(text("a")).unwrap();
+    tokio::task::yield_now().await;
+    tx2.push(text("b")).unwrap();
+    // 所有写入端释放后接收端结束
+    drop(tx);
+    drop(tx2);
+    assert_eq!(reader.await.unwrap(), vec![text("a"), text("b")]);
+}
+
+#[test]
+fn test_push_after_receiver_dropped() {
+    let (tx, rx) = send_queue::channel(4, OverflowPolicy::default());
+    drop(rx);
+    assert_eq!(tx.push(text("a")), Err(PushError::Closed));
+}
@@ -0,0 +1,82 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::routing::get;
+use axum::Router;
+use futures::StreamExt;
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{send_message, shutdown, CONN_MGR};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+async fn test_shutdown() {
+    let closed = Arc::new(AtomicUsize::new(0));
+    let counter = closed.clone();
+    let handlers = WsHandlers::new().on_close(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async {})
+    });
+    let app = Router::new().route(
+        "/ws/{cli_id}",
+        get(move |ws: WebSocketUpgrade, axum::extract::Path(cli_id): axum::extract::Path<u64>| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, cli_id, handlers, WsConfig::default()))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut clients = Vec::new();
+    for cli_id in [1, 2] {
+        clients.push(
+            tokio_tungstenite::connect_async(format!("ws://{}/ws/{}", addr, cli_id))
+                .await
+                .unwrap()
+                .0,
+        );
+    }
+    for _ in 0..50 {
+        if CONN_MGR.lock().await.connection_count() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    CONN_MGR.lock().await.join_group(1, "room");
+
+    // 关闭前待发送的消息仍会送达
+    send_message(1, "last words".into()).await.unwrap();
+    assert_eq!(shutdown(1012, "service restart").await, 2);
+    assert_eq!(CONN_MGR.lock().await.connection_count(), 0);
+    assert!(CONN_MGR.lock().await.group_members("room").is_empty());
+    // 关闭处理器在连接移除后调用
+    for _ in 0..50 {
+        if closed.load(Ordering::SeqCst) == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(closed.load(Ordering::SeqCst), 2);
+
+    for (i, client) in clients.iter_mut().enumerate() {
+        let mut frames = Vec::new();
+        while let Some(Ok(msg)) = client.next().await {
+            match msg {
+                Message::Ping(_) => continue,
+                Message::Close(frame) => {
+                    let frame = frame.unwrap();
+                    frames.push(format!("close {} {}", u16::from(frame.code), frame.reason));
+                    break;
+                }
+                other => frames.push(other.into_text().unwrap().to_string()),
+            }
+        }
+        let expected: &[&str] = if i == 0 {
+            &["last words", "close 1012 service restart"]
+        } else {
+            &["close 1012 service restart"]
+        };
+        assert_eq!(frames, expected);
+    }
+}
@@ -0,0 +1,107 @@
+use axum::extract::ws::{Message as WsMessage, WebSocketUpgrade};
+use axum::routing::get;
+use axum::Router;
+use futures::StreamExt;
+use rivus_ws::config::WsConfig;
+use rivus_ws::conn_mgr::{send_json, send_message, CONN_MGR};
+use rivus_ws::store::{self, MemoryStore, MessageStore};
+use rivus_ws::ws_handler::{handle_connection, WsHandlers};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+async fn test_memory_store_bound_and_ttl() {
+    let store = MemoryStore::new(2, Duration::from_millis(50));
+    for text in ["1", "2", "3"] {
+        store.push(1, WsMessage::Text(text.into())).await.unwrap();
+    }
+    // 超出上限时丢弃最早的消息
+    assert_eq!(
+        store.take(1).await.unwrap(),
+        vec![WsMessage::Text("2".into()), WsMessage::Text("3".into())]
+    );
+    assert!(store.take(1).await.unwrap().is_empty());
+
+    store.push(2, WsMessage::Text("old".into())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert!(store.take(2).await.unwrap().is_empty());
+
+    // 超过 ttl 后写入时统一清理其他客户端已过期的消息
+    store.push(3, WsMessage::Text("old".into())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    store.push(4, WsMessage::Text("new".into())).await.unwrap();
+    assert_eq!(store.len(), 1);
+}
+
+#[tokio::test]
+async fn test_offline_messages_flushed_on_reconnect() {
+    assert!(send_message(7, "dropped".to_string()).await.is_err());
+    store::enable(MemoryStore::default()).unwrap();
+    assert!(store::enable(MemoryStore::default()).is_err());
+
+    send_message(7, "first".to_string()).await.unwrap();
+    send_json(7, &serde_json::json!({"n": 2})).await.unwrap();
+
+    let addr = serve(7, WsConfig::default()).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    assert_eq!(recv_texts(&mut client, 2).await, ["first", r#"{"n":2}"#]);
+
+    // 在线时直接发送
+    send_message(7, "online".to_string()).await.unwrap();
+    assert_eq!(recv_texts(&mut client, 1).await, ["online"]);
+    assert_eq!(CONN_MGR.lock().await.connection_count(), 1);
+
+    // 超出发送队列容量的暂存消息放回存储，下次连接时发出
+    for text in ["a", "b", "c"] {
+        send_message(8, text.to_string()).await.unwrap();
+    }
+    let addr = serve(8, WsConfig::default().with_send_queue_capacity(2)).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    assert_eq!(recv_texts(&mut client, 2).await, ["a", "b"]);
+    drop(client);
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while CONN_MGR.lock().await.connection_count() > 1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+        .await
+        .unwrap();
+    assert_eq!(recv_texts(&mut client, 1).await, ["c"]);
+}
+
+async fn serve(cli_id: u64, config: WsConfig) -> std::net::SocketAddr {
+    let app = Router::new().route(
+        "/ws",
+        get(move |ws: WebSocketUpgrade| async move {
+            ws.on_upgrade(move |socket| handle_connection(socket, cli_id, WsHandlers::new(), config))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+async fn recv_texts<S>(client: &mut S, count: usize) -> Vec<String>
+where
+    S: futures::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let mut received = Vec::new();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while received.len() < count {
+            if let Message::Text(text) = client.next().await.unwrap().unwrap() {
+                received.push(text.to_string());
+            }
+        }
+    })
+    .await
+    .unwrap();
+    received
+}
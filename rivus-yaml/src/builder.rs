@@ -0,0 +1,133 @@
+//! 多配置源合并构建器
+
+use crate::merge::deep_merge;
+use crate::overrides::{apply_env_overrides, apply_set_overrides, parse_set_args};
+use crate::{load_value_from_file, load_value_from_str, validate, Format, Validatable, VarPolicy, YamlLoaderError};
+use serde::de::DeserializeOwned;
+use serde_yaml::{Mapping, Value};
+use std::path::PathBuf;
+
+/// [`ConfigBuilder::add_env`] 默认使用的环境变量前缀
+pub const DEFAULT_ENV_PREFIX: &str = "APP";
+
+enum Source {
+    File { path: PathBuf, required: bool },
+    Str(String),
+    Env(String),
+    Args(Vec<String>),
+}
+
+/// 按添加顺序合并多个配置源，后添加的优先级更高
+///
+/// 映射深度合并，标量与数组整体覆盖。
+///
+/// ```no_run
+/// # #[derive(serde::Deserialize)] struct AppConfig {}
+/// let config: AppConfig = rivus_yaml::ConfigBuilder::new()
+///     .add_file("config/application.yaml")
+///     .add_optional_file("config/local.yaml")
+///     .add_env()
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct ConfigBuilder {
+    sources: Vec<Source>,
+    policy: VarPolicy,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加必须存在的配置文件，按扩展名识别格式
+    pub fn add_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::File {
+            path: path.into(),
+            required: true,
+        });
+        self
+    }
+
+    /// 添加可选的配置文件，文件不存在时跳过
+    pub fn add_optional_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::File {
+            path: path.into(),
+            required: false,
+        });
+        self
+    }
+
+    /// 添加 YAML 字符串
+    pub fn add_str<S: Into<String>>(mut self, content: S) -> Self {
+        self.sources.push(Source::Str(content.into()));
+        self
+    }
+
+    /// 添加以 [`DEFAULT_ENV_PREFIX`] 为前缀的环境变量，`APP_DATABASE__URL` 覆盖 `database.url`
+    pub fn add_env(self) -> Self {
+        self.add_env_with_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// 添加以 `prefix` 为前缀的环境变量，规则见 [`apply_env_overrides`](crate::apply_env_overrides)
+    pub fn add_env_with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.sources.push(Source::Env(prefix.into()));
+        self
+    }
+
+    /// 添加命令行参数中的 `--set key.path=value` 覆盖项，其他参数会被忽略
+    ///
+    /// 通常传入 `std::env::args().skip(1)`，放在最后添加以获得最高优先级。
+    pub fn add_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sources
+            .push(Source::Args(args.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// 设置变量缺失时的处理策略，默认为 [`VarPolicy::Lenient`]
+    pub fn var_policy(mut self, policy: VarPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 合并所有配置源，返回未反序列化的文档
+    pub fn build_value(&self) -> Result<Value, YamlLoaderError> {
+        let mut merged = Value::Mapping(Mapping::new());
+        for source in &self.sources {
+            match source {
+                Source::File { path, required } => {
+                    if !required && !path.is_file() {
+                        continue;
+                    }
+                    deep_merge(&mut merged, load_value_from_file(path, self.policy)?);
+                }
+                Source::Str(content) => {
+                    deep_merge(
+                        &mut merged,
+                        load_value_from_str(content, Format::Yaml, self.policy)?,
+                    );
+                }
+                Source::Env(prefix) => apply_env_overrides(&mut merged, prefix)?,
+                Source::Args(args) => apply_set_overrides(&mut merged, &parse_set_args(args.iter().cloned())?)?,
+            }
+        }
+        Ok(merged)
+    }
+
+    /// 合并所有配置源并反序列化为目标类型
+    pub fn build<T: DeserializeOwned>(&self) -> Result<T, YamlLoaderError> {
+        Ok(crate::de::from_value(self.build_value()?)?)
+    }
+
+    /// 合并、反序列化后执行 [`Validatable`] 校验
+    pub fn build_validated<T: DeserializeOwned + Validatable>(&self) -> Result<T, YamlLoaderError> {
+        let data: T = self.build()?;
+        validate(&data)?;
+        Ok(data)
+    }
+}
@@ -0,0 +1,90 @@
+//! 从 `serde_yaml::Value` 反序列化，行为与直接解析 YAML 文本保持一致
+//!
+//! 直接解析文本时，`name:`、`port: 8080` 都可以反序列化为 `String`；
+//! 而 `Value` 中它们已经变成了 `Null` / `Number`。合并、覆盖后的配置只能以 `Value`
+//! 形式存在，这里对字符串目标做宽松处理，避免同一份配置因加载方式不同而结果不同。
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+use serde_yaml::{Error, Value};
+
+/// 将 `Value` 反序列化为目标类型
+pub(crate) fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(TextLike(value))
+}
+
+struct TextLike(Value);
+
+impl<'de> IntoDeserializer<'de, Error> for TextLike {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for TextLike {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Sequence(seq) => {
+                let mut access = SeqDeserializer::new(seq.into_iter().map(TextLike));
+                let value = visitor.visit_seq(&mut access)?;
+                access.end()?;
+                Ok(value)
+            }
+            Value::Mapping(map) => {
+                let mut access =
+                    MapDeserializer::new(map.into_iter().map(|(k, v)| (TextLike(k), TextLike(v))));
+                let value = visitor.visit_map(&mut access)?;
+                access.end()?;
+                Ok(value)
+            }
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_str(""),
+            Value::Bool(b) => visitor.visit_string(b.to_string()),
+            Value::Number(n) => visitor.visit_string(n.to_string()),
+            other => other.deserialize_string(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(TextLike(other)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
@@ -0,0 +1,40 @@
+//! 配置文件格式
+
+use crate::YamlLoaderError;
+use serde_yaml::Value;
+use std::path::Path;
+
+/// 配置文件格式，三种格式共用同一套变量替换与合并逻辑
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// 按扩展名识别格式，无法识别时按 YAML 处理
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let ext = path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match ext.as_deref() {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            _ => Format::Yaml,
+        }
+    }
+
+    /// 解析已完成变量替换的文本
+    pub(crate) fn parse(self, content: &str) -> Result<Value, YamlLoaderError> {
+        let value = match self {
+            Format::Yaml => serde_yaml::from_str(content)?,
+            Format::Json => serde_json::from_str(content)?,
+            Format::Toml => toml::from_str(content)?,
+        };
+        Ok(value)
+    }
+}
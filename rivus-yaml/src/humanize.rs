@@ -0,0 +1,259 @@
+//! 人类可读的容量与时长，配合 `#[serde(with = "...")]` 使用
+//!
+//! ```
+//! use serde::Deserialize;
+//! use std::time::Duration;
+//!
+//! #[derive(Deserialize)]
+//! struct LogConfig {
+//!     #[serde(with = "rivus_yaml::humanize::size")]
+//!     max_size: usize,
+//!     #[serde(with = "rivus_yaml::humanize::duration")]
+//!     max_age: Duration,
+//! }
+//!
+//! let config: LogConfig = rivus_yaml::load_from_str("max_size: 10MB\nmax_age: 7d").unwrap();
+//! assert_eq!(config.max_size, 10 * 1024 * 1024);
+//! assert_eq!(config.max_age, Duration::from_secs(7 * 24 * 3600));
+//! ```
+
+use std::time::Duration;
+
+const SIZE_UNITS: &[(&str, u64)] = &[
+    ("TB", 1 << 40),
+    ("GB", 1 << 30),
+    ("MB", 1 << 20),
+    ("KB", 1 << 10),
+    ("B", 1),
+];
+
+const DURATION_UNITS: &[(&str, u64)] = &[
+    ("w", 7 * 24 * 3600 * 1_000_000_000),
+    ("d", 24 * 3600 * 1_000_000_000),
+    ("h", 3600 * 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// 解析容量，如 `512`、`64KB`、`10MB`、`1.5G`，单位按 1024 进制且不区分大小写
+///
+/// `K`/`KB`/`KiB` 均表示 1024 字节，没有单位时按字节计算。
+pub fn parse_size(s: &str) -> Result<usize, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = (&s[..split], s[split..].trim());
+    let number: f64 = number.parse().map_err(|_| format!("invalid size: {s:?}"))?;
+
+    let unit = unit.to_ascii_uppercase();
+    let unit = unit
+        .strip_suffix("IB")
+        .map(|u| format!("{u}B"))
+        .unwrap_or(unit);
+    let multiplier = match unit.as_str() {
+        "" => Some(1),
+        u if u.len() == 1 && u != "B" => lookup(SIZE_UNITS, &format!("{u}B")),
+        u => lookup(SIZE_UNITS, u),
+    }
+    .ok_or_else(|| format!("invalid size unit: {s:?}"))?;
+
+    let bytes = number * multiplier as f64;
+    if bytes > usize::MAX as f64 {
+        return Err(format!("size out of range: {s:?}"));
+    }
+    Ok(bytes as usize)
+}
+
+/// 格式化容量，能整除时使用最大的单位，如 `10MB`
+pub fn format_size(bytes: usize) -> String {
+    let bytes = bytes as u64;
+    SIZE_UNITS
+        .iter()
+        .find(|(_, m)| bytes != 0 && bytes.is_multiple_of(*m))
+        .map_or_else(|| "0B".to_string(), |(u, m)| format!("{}{u}", bytes / m))
+}
+
+/// 解析时长，如 `30s`、`500ms`、`7d`、`1h30m`，没有单位时按秒计算
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total: u128 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let units = rest[digits..]
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .map_or(rest.len(), |i| digits + i);
+        let number: u128 = rest[..digits]
+            .parse()
+            .map_err(|_| format!("invalid duration: {s:?}"))?;
+        let nanos = lookup(DURATION_UNITS, &rest[digits..units].to_ascii_lowercase())
+            .ok_or_else(|| format!("invalid duration unit: {s:?}"))?;
+        total = number
+            .checked_mul(nanos as u128)
+            .and_then(|n| total.checked_add(n))
+            .ok_or_else(|| format!("duration out of range: {s:?}"))?;
+        rest = rest[units..].trim_start();
+    }
+
+    let secs = u64::try_from(total / 1_000_000_000)
+        .map_err(|_| format!("duration out of range: {s:?}"))?;
+    Ok(Duration::new(secs, (total % 1_000_000_000) as u32))
+}
+
+/// 格式化时长，按单位从大到小组合，如 `1h30m`、`1s500ms`
+pub fn format_duration(duration: Duration) -> String {
+    let mut rest = duration.as_nanos();
+    if rest == 0 {
+        return "0s".to_string();
+    }
+    let mut out = String::new();
+    for (unit, nanos) in DURATION_UNITS {
+        let nanos = *nanos as u128;
+        if rest >= nanos {
+            out.push_str(&format!("{}{unit}", rest / nanos));
+            rest %= nanos;
+        }
+    }
+    out
+}
+
+fn lookup(units: &[(&str, u64)], unit: &str) -> Option<u64> {
+    units.iter().find(|(u, _)| *u == unit).map(|(_, m)| *m)
+}
+
+/// `usize` 字节数，可写作整数或 `10MB`
+pub mod size {
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(bytes: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_size(*bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_any(SizeVisitor)
+    }
+
+    struct SizeVisitor;
+
+    impl Visitor<'_> for SizeVisitor {
+        type Value = usize;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a size such as 1024 or \"10MB\"")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<usize, E> {
+            usize::try_from(v).map_err(E::custom)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<usize, E> {
+            usize::try_from(v).map_err(E::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<usize, E> {
+            super::parse_size(v).map_err(E::custom)
+        }
+    }
+}
+
+/// `Option<usize>` 字节数，见 [`size`]
+pub mod size_opt {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<usize>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => super::size::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<usize>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::size")] usize);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|w| w.0))
+    }
+}
+
+/// [`Duration`](std::time::Duration)，可写作整数秒或 `30s`、`1h30m`
+pub mod duration {
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_duration(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserializer.deserialize_any(DurationVisitor)
+    }
+
+    struct DurationVisitor;
+
+    impl Visitor<'_> for DurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a duration such as 30 or \"30s\"")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Duration, E> {
+            Ok(Duration::from_secs(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Duration, E> {
+            u64::try_from(v).map(Duration::from_secs).map_err(E::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Duration, E> {
+            super::parse_duration(v).map_err(E::custom)
+        }
+    }
+}
+
+/// `Option<Duration>`，见 [`duration`]
+pub mod duration_opt {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => super::duration::serialize(duration, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::duration")] Duration);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|w| w.0))
+    }
+}
@@ -0,0 +1,94 @@
+//! `!include` 指令，将其他配置文件嵌入到当前节点
+
+use crate::vars::{replace_vars, VarPolicy};
+use crate::{Format, YamlLoaderError};
+use serde_yaml::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INCLUDE_TAG: &str = "!include";
+
+/// 加载配置文件并递归展开 `!include`
+pub(crate) struct Includer {
+    policy: VarPolicy,
+    /// 当前包含链，用于检测循环
+    stack: Vec<PathBuf>,
+    /// 已加载过的全部文件
+    files: Vec<PathBuf>,
+}
+
+impl Includer {
+    pub(crate) fn new(policy: VarPolicy) -> Self {
+        Self {
+            policy,
+            stack: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// 加载文件，完成变量替换并展开其中的 `!include`
+    pub(crate) fn load_file(
+        &mut self,
+        path: &Path,
+        format: Format,
+    ) -> Result<Value, YamlLoaderError> {
+        let canonical = fs::canonicalize(path)?;
+        if self.stack.contains(&canonical) {
+            return Err(YamlLoaderError::IncludeCycle(canonical));
+        }
+
+        if !self.files.contains(&canonical) {
+            self.files.push(canonical.clone());
+        }
+        let content = fs::read_to_string(&canonical)?;
+        let mut value = format.parse(&replace_vars(&content, self.policy)?)?;
+
+        let base_dir = canonical
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        self.stack.push(canonical);
+        self.resolve(&mut value, &base_dir)?;
+        self.stack.pop();
+        Ok(value)
+    }
+
+    /// 已加载过的全部文件，包括被 `!include` 的文件
+    pub(crate) fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// 展开 `value` 中的 `!include`，相对路径以 `base_dir` 为基准
+    pub(crate) fn resolve(
+        &mut self,
+        value: &mut Value,
+        base_dir: &Path,
+    ) -> Result<(), YamlLoaderError> {
+        match value {
+            Value::Tagged(tagged) if tagged.tag == INCLUDE_TAG => {
+                let Some(target) = tagged.value.as_str() else {
+                    return Err(YamlLoaderError::InvalidInclude(format!(
+                        "{:?}",
+                        tagged.value
+                    )));
+                };
+                let target = base_dir.join(target);
+                let format = Format::from_path(&target);
+                *value = self.load_file(&target, format)?;
+            }
+            Value::Tagged(tagged) => self.resolve(&mut tagged.value, base_dir)?,
+            Value::Sequence(seq) => {
+                for item in seq {
+                    self.resolve(item, base_dir)?;
+                }
+            }
+            Value::Mapping(map) => {
+                for (_, item) in map.iter_mut() {
+                    self.resolve(item, base_dir)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
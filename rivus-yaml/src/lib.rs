@@ -1,12 +1,42 @@
-//! YAML 配置加载器，支持环境变量替换
+//! YAML 配置加载器，支持环境变量替换，同时支持 JSON 与 TOML
 
 use serde::de::DeserializeOwned;
 use std::env;
-use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
-use regex::Regex;
-use dotenvy::dotenv;
+use serde_yaml::Value;
+
+mod builder;
+mod de;
+mod dump;
+mod format;
+pub mod humanize;
+mod include;
+mod merge;
+mod overrides;
+mod profile;
+mod registry;
+mod validate;
+mod vars;
+mod watch;
+
+pub use builder::{ConfigBuilder, DEFAULT_ENV_PREFIX};
+pub use dump::{dump_effective, dump_effective_with, DEFAULT_SECRET_KEYS, MASK};
+pub use format::Format;
+pub use merge::deep_merge;
+pub use overrides::{
+    apply_env_overrides, apply_set_overrides, parse_set_args, set_args_from_env, ENV_KEY_SEPARATOR, SET_ARG,
+};
+pub use profile::{active_profiles, load_with_profile, profile_path, PROFILE_ENV};
+pub use validate::{validate, FieldError, Validatable, ValidationErrors};
+pub use registry::{config, init, init_with, section, try_config};
+pub use vars::VarPolicy;
+pub use watch::{
+    watch_channel, watch_channel_with_interval, watch_file, watch_file_with_interval, ConfigWatcher,
+    DEFAULT_WATCH_INTERVAL,
+};
+
+use include::Includer;
 
 /// YAML 加载器错误
 #[derive(Debug, Error)]
@@ -15,41 +45,149 @@ pub enum YamlLoaderError {
     Io(#[from] std::io::Error),
     #[error("YAML parse error: {0}")]
     YamlParse(#[from] serde_yaml::Error),
+    #[error("JSON parse error: {0}")]
+    JsonParse(#[from] serde_json::Error),
+    #[error("TOML parse error: {0}")]
+    TomlParse(#[from] toml::de::Error),
     #[error("Invalid variable format: {0}")]
     InvalidVariable(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Invalid key path: {0}")]
+    InvalidKeyPath(String),
+    #[error("Missing variable: {0}")]
+    MissingVariable(String),
+    #[error("Variable reference cycle: {0}")]
+    VariableCycle(String),
+    #[error("Config already initialized")]
+    AlreadyInitialized,
+    #[error("Config not initialized")]
+    NotInitialized,
+    #[error("Missing config section: {0}")]
+    MissingSection(String),
+    #[error("Validation failed: {0}")]
+    Validation(ValidationErrors),
+    #[error("Secret file error: {}: {source}", .path.display())]
+    SecretFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Include cycle detected: {}", .0.display())]
+    IncludeCycle(PathBuf),
+    #[error("Invalid include target: {0}")]
+    InvalidInclude(String),
 }
 
-/// 替换 YAML 中的环境变量占位符
-fn replace_vars(yaml_content: &str) -> Result<String, YamlLoaderError> {
-    let _ = dotenv();
+/// 从文件加载配置，按扩展名识别 YAML / JSON / TOML
+///
+/// `!include` 的相对路径以当前文件所在目录为基准。
+pub fn load_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, YamlLoaderError> {
+    let format = Format::from_path(&path);
+    load_from_file_with_format(path, format)
+}
 
-    let re = Regex::new(r"\$\{([A-Z0-9_]+)(?::([^\}]*))?\}").unwrap();
+/// 按指定格式从文件加载配置
+pub fn load_from_file_with_format<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    format: Format,
+) -> Result<T, YamlLoaderError> {
+    let value = Includer::new(VarPolicy::Lenient).load_file(path.as_ref(), format)?;
+    let data = de::from_value(value)?;
+    Ok(data)
+}
 
-    let result = re.replace_all(yaml_content, |caps: &regex::Captures| {
-        let var_name = &caps[1];
-        let default = caps.get(2).map(|m| m.as_str());
+/// 从文件加载配置中的一节，键路径同 [`section`]，如 `load_section_from_file::<DatabaseOptions, _>("app.yaml", "database")`
+pub fn load_section_from_file<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    key: &str,
+) -> Result<T, YamlLoaderError> {
+    let value = load_value_from_file(path, VarPolicy::Lenient)?;
+    let data = de::from_value(registry::lookup(&value, key)?.clone())?;
+    Ok(data)
+}
 
-        match env::var(var_name) {
-            Ok(val) => val,
-            Err(_) => default.unwrap_or("").to_string(),
-        }
-    });
+/// 从文件加载配置并执行 [`Validatable`] 校验，所有字段错误汇总在 [`YamlLoaderError::Validation`] 中
+pub fn load_from_file_validated<T, P>(path: P) -> Result<T, YamlLoaderError>
+where
+    T: DeserializeOwned + Validatable,
+    P: AsRef<Path>,
+{
+    let data: T = load_from_file(path)?;
+    validate(&data)?;
+    Ok(data)
+}
 
-    Ok(result.into_owned())
+/// 从文件加载配置，变量既没有环境变量也没有默认值时返回 [`YamlLoaderError::MissingVariable`]
+pub fn load_from_file_strict<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+) -> Result<T, YamlLoaderError> {
+    let value = load_value_from_file(path, VarPolicy::Strict)?;
+    let data = de::from_value(value)?;
+    Ok(data)
 }
 
-/// 从文件加载 YAML 配置
-pub fn load_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, YamlLoaderError> {
-    let content = fs::read_to_string(path)?;
-    let replaced = replace_vars(&content)?;
-    let data = serde_yaml::from_str(&replaced)?;
+/// 从文件加载配置，并用带 `prefix` 前缀的环境变量覆盖任意配置项
+///
+/// 以 `APP` 为前缀时，`APP_DATABASE__URL` 覆盖 `database.url`，详见 [`apply_env_overrides`]。
+pub fn load_from_file_with_env<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    prefix: &str,
+) -> Result<T, YamlLoaderError> {
+    let mut value = load_value_from_file(path, VarPolicy::Lenient)?;
+    apply_env_overrides(&mut value, prefix)?;
+    let data = de::from_value(value)?;
     Ok(data)
 }
 
-/// 从字符串加载 YAML 配置
+/// 从文件加载配置并完成变量替换，返回未反序列化的文档
+pub(crate) fn load_value_from_file<P: AsRef<Path>>(
+    path: P,
+    policy: VarPolicy,
+) -> Result<Value, YamlLoaderError> {
+    let format = Format::from_path(&path);
+    Includer::new(policy).load_file(path.as_ref(), format)
+}
+
+/// 解析字符串并完成变量替换，`!include` 的相对路径以当前工作目录为基准
+pub(crate) fn load_value_from_str(
+    content: &str,
+    format: Format,
+    policy: VarPolicy,
+) -> Result<Value, YamlLoaderError> {
+    let replaced = vars::replace_vars(content, policy)?;
+    let mut value = format.parse(&replaced)?;
+    Includer::new(policy).resolve(&mut value, &env::current_dir()?)?;
+    Ok(value)
+}
+
+/// 从字符串加载 YAML 配置，`!include` 的相对路径以当前工作目录为基准
 pub fn load_from_str<T: DeserializeOwned>(yaml_content: &str) -> Result<T, YamlLoaderError> {
-    let replaced = replace_vars(yaml_content)?;
-    let data = serde_yaml::from_str(&replaced)?;
+    load_from_str_with_format(yaml_content, Format::Yaml)
+}
+
+/// 按指定格式从字符串加载配置
+pub fn load_from_str_with_format<T: DeserializeOwned>(
+    content: &str,
+    format: Format,
+) -> Result<T, YamlLoaderError> {
+    let value = load_value_from_str(content, format, VarPolicy::Lenient)?;
+    let data = de::from_value(value)?;
+    Ok(data)
+}
+
+/// 从字符串加载 YAML 配置并执行 [`Validatable`] 校验
+pub fn load_from_str_validated<T: DeserializeOwned + Validatable>(
+    yaml_content: &str,
+) -> Result<T, YamlLoaderError> {
+    let data: T = load_from_str(yaml_content)?;
+    validate(&data)?;
+    Ok(data)
+}
+
+/// 从字符串加载 YAML 配置，变量既没有环境变量也没有默认值时返回 [`YamlLoaderError::MissingVariable`]
+pub fn load_from_str_strict<T: DeserializeOwned>(yaml_content: &str) -> Result<T, YamlLoaderError> {
+    let value = load_value_from_str(yaml_content, Format::Yaml, VarPolicy::Strict)?;
+    let data = de::from_value(value)?;
     Ok(data)
 }
 
@@ -0,0 +1,22 @@
+//! YAML 文档深度合并
+
+use serde_yaml::Value;
+
+/// 将 `overlay` 深度合并到 `base`
+///
+/// 映射按键递归合并；标量、数组以及类型不一致的节点由 `overlay` 整体覆盖。
+pub fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base_map), Value::Mapping(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
@@ -0,0 +1,146 @@
+//! 按键路径覆盖配置项：环境变量覆盖与命令行 `--set` 覆盖
+
+use crate::YamlLoaderError;
+use serde_yaml::{Mapping, Value};
+use std::env;
+
+/// 环境变量名中层级之间的分隔符
+pub const ENV_KEY_SEPARATOR: &str = "__";
+
+/// 将 `value` 写入 `root` 中 `path` 指定的位置，途经的非映射标量节点会被替换为映射
+///
+/// 每一级优先精确匹配已有键，其次忽略大小写、并将 `-` 视为 `_` 进行匹配，都不存在时新建；
+/// 遇到数组时按下标访问已有元素，下标越界或不是数字时返回 [`YamlLoaderError::InvalidKeyPath`]，不改动配置。
+pub(crate) fn set_path(
+    root: &mut Value,
+    path: &[String],
+    value: Value,
+) -> Result<(), YamlLoaderError> {
+    let mut current = root;
+    for (depth, key) in path.iter().enumerate() {
+        if let Some(len) = current.as_sequence().map(Vec::len) {
+            let index = key
+                .parse::<usize>()
+                .ok()
+                .filter(|i| *i < len)
+                .ok_or_else(|| {
+                    YamlLoaderError::InvalidKeyPath(format!(
+                        "{}: expected a sequence index below {len}, got `{key}`",
+                        path[..=depth].join(".")
+                    ))
+                })?;
+            current = &mut current.as_sequence_mut().unwrap()[index];
+            continue;
+        }
+        if !current.is_mapping() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        let map = current.as_mapping_mut().unwrap();
+        let key = existing_key(map, key).unwrap_or_else(|| Value::String(key.clone()));
+        current = map.entry(key).or_insert(Value::Null);
+    }
+    *current = value;
+    Ok(())
+}
+
+fn existing_key(map: &Mapping, key: &str) -> Option<Value> {
+    let exact = Value::String(key.to_string());
+    if map.contains_key(&exact) {
+        return Some(exact);
+    }
+    let wanted = normalize(key);
+    map.keys()
+        .find(|k| k.as_str().is_some_and(|s| normalize(s) == wanted))
+        .cloned()
+}
+
+fn normalize(key: &str) -> String {
+    key.to_lowercase().replace('-', "_")
+}
+
+/// 将字符串解析为 YAML 标量，无法解析为标量时按原样作为字符串
+pub(crate) fn parse_scalar(raw: &str) -> Value {
+    if raw.is_empty() {
+        return Value::String(String::new());
+    }
+    match serde_yaml::from_str::<Value>(raw) {
+        Ok(v @ (Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_))) => v,
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+/// 将带前缀的环境变量应用到配置上
+///
+/// 以 `APP` 为前缀时，`APP_DATABASE__URL` 覆盖 `database.url`，`APP_LOG__MAX_SIZE` 覆盖 `log.max_size`。
+/// 值按 YAML 标量解析，`8080`、`true` 会得到数字、布尔值。
+pub fn apply_env_overrides(root: &mut Value, prefix: &str) -> Result<(), YamlLoaderError> {
+    let prefix = format!("{prefix}_");
+    let mut vars: Vec<(String, String)> = env::vars()
+        .filter(|(k, _)| k.starts_with(&prefix) && k.len() > prefix.len())
+        .collect();
+    // 保证覆盖顺序稳定
+    vars.sort();
+
+    for (key, raw) in vars {
+        let path: Vec<String> = key[prefix.len()..]
+            .split(ENV_KEY_SEPARATOR)
+            .map(str::to_lowercase)
+            .collect();
+        if path.iter().any(String::is_empty) {
+            continue;
+        }
+        set_path(root, &path, parse_scalar(&raw))?;
+    }
+    Ok(())
+}
+
+/// 命令行覆盖参数名
+pub const SET_ARG: &str = "--set";
+
+/// 从命令行参数中提取 `--set key.path=value` 或 `--set=key.path=value`，其他参数会被忽略
+pub fn parse_set_args<I, S>(args: I) -> Result<Vec<(String, String)>, YamlLoaderError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut pairs = Vec::new();
+    let mut args = args.into_iter().map(Into::into);
+    while let Some(arg) = args.next() {
+        let assignment = if arg == SET_ARG {
+            args.next()
+                .ok_or_else(|| YamlLoaderError::InvalidArgument(format!("{SET_ARG} requires key=value")))?
+        } else if let Some(rest) = arg.strip_prefix(SET_ARG).and_then(|r| r.strip_prefix('=')) {
+            rest.to_string()
+        } else {
+            continue;
+        };
+        pairs.push(parse_assignment(&assignment)?);
+    }
+    Ok(pairs)
+}
+
+/// 读取当前进程的命令行参数，见 [`parse_set_args`]
+pub fn set_args_from_env() -> Result<Vec<(String, String)>, YamlLoaderError> {
+    parse_set_args(env::args().skip(1))
+}
+
+fn parse_assignment(assignment: &str) -> Result<(String, String), YamlLoaderError> {
+    match assignment.split_once('=') {
+        Some((key, value)) if !key.is_empty() && key.split('.').all(|k| !k.is_empty()) => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(YamlLoaderError::InvalidArgument(assignment.to_string())),
+    }
+}
+
+/// 将 `key.path=value` 覆盖项按顺序写入配置，值按 YAML 标量解析
+pub fn apply_set_overrides(
+    root: &mut Value,
+    overrides: &[(String, String)],
+) -> Result<(), YamlLoaderError> {
+    for (key, raw) in overrides {
+        let path: Vec<String> = key.split('.').map(str::to_string).collect();
+        set_path(root, &path, parse_scalar(raw))?;
+    }
+    Ok(())
+}
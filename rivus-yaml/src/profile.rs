@@ -0,0 +1,66 @@
+//! 基于 profile 的配置覆盖，例如 `application.yaml` + `application-dev.yaml`
+
+use crate::merge::deep_merge;
+use crate::{load_value_from_file, VarPolicy, YamlLoaderError};
+use serde::de::DeserializeOwned;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// 指定激活 profile 的环境变量，多个 profile 以逗号分隔
+pub const PROFILE_ENV: &str = "RIVUS_PROFILE";
+
+/// 读取环境变量中激活的 profile 列表
+pub fn active_profiles() -> Vec<String> {
+    env::var(PROFILE_ENV)
+        .map(|v| split_profiles(&v))
+        .unwrap_or_default()
+}
+
+/// 计算 profile 对应的覆盖文件路径，`config/application.yaml` -> `config/application-dev.yaml`
+pub fn profile_path<P: AsRef<Path>>(path: P, profile: &str) -> PathBuf {
+    let path = path.as_ref();
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let file_name = match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => format!("{stem}-{profile}.{ext}"),
+        None => format!("{stem}-{profile}"),
+    };
+    path.with_file_name(file_name)
+}
+
+/// 加载基础配置，并按顺序深度合并各 profile 的覆盖文件
+///
+/// `profile` 为 `None` 时从环境变量 [`PROFILE_ENV`] 读取；profile 文件不存在时跳过。
+pub fn load_with_profile<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    profile: Option<&str>,
+) -> Result<T, YamlLoaderError> {
+    let path = path.as_ref();
+    let profiles = match profile {
+        Some(p) => split_profiles(p),
+        None => active_profiles(),
+    };
+
+    let mut merged = load_value_from_file(path, VarPolicy::Lenient)?;
+    for profile in &profiles {
+        let overlay_path = profile_path(path, profile);
+        if overlay_path.is_file() {
+            deep_merge(
+                &mut merged,
+                load_value_from_file(&overlay_path, VarPolicy::Lenient)?,
+            );
+        }
+    }
+    Ok(crate::de::from_value(merged)?)
+}
+
+fn split_profiles(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
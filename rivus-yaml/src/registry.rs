@@ -0,0 +1,112 @@
+//! 全局配置注册表，启动时初始化一次，之后在任意模块中读取
+
+use crate::{de, load_value_from_file, ConfigBuilder, VarPolicy, YamlLoaderError};
+use serde::de::DeserializeOwned;
+use serde_yaml::Value;
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+type Shared = &'static (dyn Any + Send + Sync);
+
+struct Registry {
+    config: Shared,
+    /// 合并、替换后的原始文档，用于按节读取
+    raw: Value,
+    sections: RwLock<HashMap<(TypeId, String), Shared>>,
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// 从文件加载配置并注册为全局配置，只能初始化一次
+pub fn init<T, P>(path: P) -> Result<&'static T, YamlLoaderError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+    P: AsRef<Path>,
+{
+    register(load_value_from_file(path, VarPolicy::Lenient)?)
+}
+
+/// 使用 [`ConfigBuilder`] 合并后的配置注册为全局配置，只能初始化一次
+pub fn init_with<T>(builder: &ConfigBuilder) -> Result<&'static T, YamlLoaderError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    register(builder.build_value()?)
+}
+
+fn register<T>(raw: Value) -> Result<&'static T, YamlLoaderError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    if REGISTRY.get().is_some() {
+        return Err(YamlLoaderError::AlreadyInitialized);
+    }
+    let config: &'static T = Box::leak(Box::new(de::from_value::<T>(raw.clone())?));
+    let registry = Registry {
+        config,
+        raw,
+        sections: RwLock::new(HashMap::new()),
+    };
+    REGISTRY
+        .set(registry)
+        .map_err(|_| YamlLoaderError::AlreadyInitialized)?;
+    Ok(config)
+}
+
+/// 读取全局配置，未初始化或类型与 [`init`] 时不一致时返回 `None`
+pub fn try_config<T: Send + Sync + 'static>() -> Option<&'static T> {
+    REGISTRY.get()?.config.downcast_ref::<T>()
+}
+
+/// 读取全局配置
+///
+/// # Panics
+///
+/// 未调用 [`init`] 或类型不一致时 panic。
+pub fn config<T: Send + Sync + 'static>() -> &'static T {
+    let registry = REGISTRY
+        .get()
+        .unwrap_or_else(|| panic!("config {} is not initialized", type_name::<T>()));
+    registry.config.downcast_ref::<T>().unwrap_or_else(|| {
+        panic!(
+            "config was initialized with a different type than {}",
+            type_name::<T>()
+        )
+    })
+}
+
+// 按以 `.` 分隔的键路径查找节点
+pub(crate) fn lookup<'a>(root: &'a Value, key: &str) -> Result<&'a Value, YamlLoaderError> {
+    let mut node = root;
+    for part in key.split('.') {
+        node = node
+            .get(part)
+            .ok_or_else(|| YamlLoaderError::MissingSection(key.to_string()))?;
+    }
+    Ok(node)
+}
+
+/// 按键路径读取配置中的一节，如 `section::<DatabaseConfig>("database")`、`section::<u16>("server.port")`
+///
+/// 反序列化结果会被缓存，同一类型、同一路径只解析一次。
+pub fn section<S>(key: &str) -> Result<&'static S, YamlLoaderError>
+where
+    S: DeserializeOwned + Send + Sync + 'static,
+{
+    let registry = REGISTRY.get().ok_or(YamlLoaderError::NotInitialized)?;
+    let cache_key = (TypeId::of::<S>(), key.to_string());
+
+    if let Some(cached) = registry.sections.read().unwrap().get(&cache_key) {
+        return Ok(cached.downcast_ref::<S>().unwrap());
+    }
+
+    let parsed: S = de::from_value(lookup(&registry.raw, key)?.clone())?;
+
+    let mut sections = registry.sections.write().unwrap();
+    let entry = sections
+        .entry(cache_key)
+        .or_insert_with(|| Box::leak(Box::new(parsed)));
+    Ok(entry.downcast_ref::<S>().unwrap())
+}
@@ -0,0 +1,110 @@
+//! 反序列化后的配置校验
+
+use crate::YamlLoaderError;
+use std::fmt;
+
+/// 配置校验，加载后调用以便启动阶段就发现错误配置
+///
+/// ```
+/// use rivus_yaml::{Validatable, ValidationErrors};
+///
+/// struct Server { port: u16 }
+///
+/// impl Validatable for Server {
+///     fn validate(&self, errors: &mut ValidationErrors) {
+///         if self.port == 0 {
+///             errors.add("port", "must be greater than 0");
+///         }
+///     }
+/// }
+/// ```
+pub trait Validatable {
+    fn validate(&self, errors: &mut ValidationErrors);
+}
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// 字段在配置中的路径，如 `database.url`、`nodes[1].host`
+    pub path: String,
+    pub message: String,
+}
+
+/// 汇总的校验错误，嵌套校验时自动拼接字段路径
+#[derive(Debug, Default)]
+pub struct ValidationErrors {
+    prefix: Vec<String>,
+    errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录当前层级下 `field` 的错误
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        let path = self.path_of(field);
+        self.errors.push(FieldError {
+            path,
+            message: message.into(),
+        });
+    }
+
+    /// 校验子配置，错误路径以 `field` 为前缀
+    pub fn nested<V: Validatable + ?Sized>(&mut self, field: &str, value: &V) {
+        self.prefix.push(field.to_string());
+        value.validate(self);
+        self.prefix.pop();
+    }
+
+    /// 校验数组中的每一项，错误路径形如 `field[0].xxx`
+    pub fn nested_each<'a, V, I>(&mut self, field: &str, items: I)
+    where
+        V: Validatable + 'a,
+        I: IntoIterator<Item = &'a V>,
+    {
+        for (i, item) in items.into_iter().enumerate() {
+            self.nested(&format!("{field}[{i}]"), item);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn errors(&self) -> &[FieldError] {
+        &self.errors
+    }
+
+    fn path_of(&self, field: &str) -> String {
+        let mut parts: Vec<&str> = self.prefix.iter().map(String::as_str).collect();
+        if !field.is_empty() {
+            parts.push(field);
+        }
+        parts.join(".")
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.errors.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}: {}", e.path, e.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// 执行校验，存在错误时返回 [`YamlLoaderError::Validation`]
+pub fn validate<T: Validatable + ?Sized>(config: &T) -> Result<(), YamlLoaderError> {
+    let mut errors = ValidationErrors::new();
+    config.validate(&mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(YamlLoaderError::Validation(errors))
+    }
+}
@@ -0,0 +1,223 @@
+//! 环境变量占位符替换
+//!
+//! 支持的语法：
+//! - `${VAR}`：取环境变量，缺失时按 [`VarPolicy`] 处理
+//! - `${VAR:default}` / `${VAR:-default}`：缺失时使用默认值，默认值中的 `}` 写作 `\}`
+//! - `\${VAR}`：输出字面量 `${VAR}`，不做替换
+//! - 默认值与变量值中可以继续引用其他变量，如 `${DATA_DIR:${HOME}/data}`，循环引用会报错
+//! - `${file:/run/secrets/db_pass}`：读取文件内容（去除首尾空白），可写作 `${file:path:-default}`
+
+use crate::YamlLoaderError;
+use dotenvy::dotenv;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// 变量缺失（既没有环境变量也没有默认值）时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VarPolicy {
+    /// 替换为空字符串
+    #[default]
+    Lenient,
+    /// 返回 [`YamlLoaderError::MissingVariable`]
+    Strict,
+}
+
+/// 占位符的取值来源
+enum Source<'a> {
+    /// 环境变量名
+    Env(&'a str),
+    /// 未展开的文件路径原文
+    File(&'a str),
+}
+
+/// 解析出的占位符
+struct Placeholder<'a> {
+    source: Source<'a>,
+    /// 未展开的默认值原文
+    default: Option<&'a str>,
+    /// 占位符在原文中的字节长度
+    len: usize,
+}
+
+const FILE_PREFIX: &str = "file:";
+
+/// 替换文本中的环境变量占位符
+pub(crate) fn replace_vars(content: &str, policy: VarPolicy) -> Result<String, YamlLoaderError> {
+    let _ = dotenv();
+    Resolver {
+        policy,
+        stack: Vec::new(),
+    }
+    .expand(content, false)
+}
+
+/// 递归展开占位符，默认值与变量值中都可以再引用其他变量
+struct Resolver {
+    policy: VarPolicy,
+    /// 正在展开的变量链，用于检测循环引用
+    stack: Vec<String>,
+}
+
+impl Resolver {
+    /// 展开 `text` 中的占位符，`in_default` 为真时将 `\}` 还原为 `}`
+    fn expand(&mut self, text: &str, in_default: bool) -> Result<String, YamlLoaderError> {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(pos) = rest.find(['$', '\\']) {
+            result.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+
+            if let Some(escaped) = rest.strip_prefix("\\${") {
+                result.push_str("${");
+                rest = escaped;
+            } else if let Some(escaped) = rest.strip_prefix("\\}").filter(|_| in_default) {
+                result.push('}');
+                rest = escaped;
+            } else if let Some(placeholder) = parse_placeholder(rest) {
+                result.push_str(&self.resolve(&placeholder)?);
+                rest = &rest[placeholder.len..];
+            } else {
+                // 不是合法的占位符，原样输出当前字符
+                result.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    fn resolve(&mut self, placeholder: &Placeholder) -> Result<String, YamlLoaderError> {
+        match placeholder.source {
+            Source::Env(name) => self.resolve_env(name, placeholder.default),
+            Source::File(path) => self.resolve_file(path, placeholder.default),
+        }
+    }
+
+    fn resolve_env(
+        &mut self,
+        name: &str,
+        default: Option<&str>,
+    ) -> Result<String, YamlLoaderError> {
+        if self.stack.iter().any(|n| n == name) {
+            let mut chain = self.stack.join(" -> ");
+            chain.push_str(" -> ");
+            chain.push_str(name);
+            return Err(YamlLoaderError::VariableCycle(chain));
+        }
+
+        self.stack.push(name.to_string());
+        let value = match (env::var(name), default) {
+            (Ok(val), _) => self.expand(&val, false),
+            (Err(_), Some(default)) => self.expand(default, true),
+            (Err(_), None) if self.policy == VarPolicy::Strict => {
+                Err(YamlLoaderError::MissingVariable(name.to_string()))
+            }
+            (Err(_), None) => Ok(String::new()),
+        };
+        self.stack.pop();
+        value
+    }
+
+    /// 读取密钥文件，文件不存在且没有默认值时无论何种策略都返回错误
+    fn resolve_file(
+        &mut self,
+        path: &str,
+        default: Option<&str>,
+    ) -> Result<String, YamlLoaderError> {
+        let path = PathBuf::from(self.expand(path, true)?);
+        match (fs::read_to_string(&path), default) {
+            (Ok(content), _) => Ok(content.trim().to_string()),
+            (Err(_), Some(default)) => self.expand(default, true),
+            (Err(source), None) => Err(YamlLoaderError::SecretFile { path, source }),
+        }
+    }
+}
+
+/// 从 `input` 开头解析占位符：
+/// `${NAME}`、`${NAME:default}`、`${NAME:-default}`、`${file:path}`、`${file:path:-default}`
+fn parse_placeholder(input: &str) -> Option<Placeholder<'_>> {
+    let body = input.strip_prefix("${")?;
+
+    if let Some(file_src) = body.strip_prefix(FILE_PREFIX) {
+        let scan = scan_body(file_src)?;
+        let (path, default) = match scan.default_sep {
+            Some(sep) => (&file_src[..sep], Some(&file_src[sep + 2..scan.close])),
+            None => (&file_src[..scan.close], None),
+        };
+        if path.is_empty() {
+            return None;
+        }
+        let len = 2 + FILE_PREFIX.len() + scan.close + 1;
+        return Some(Placeholder {
+            source: Source::File(path),
+            default,
+            len,
+        });
+    }
+
+    let name_len = body
+        .bytes()
+        .take_while(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || *b == b'_')
+        .count();
+    if name_len == 0 {
+        return None;
+    }
+    let source = Source::Env(&body[..name_len]);
+    let after_name = &body[name_len..];
+
+    if after_name.starts_with('}') {
+        return Some(Placeholder {
+            source,
+            default: None,
+            len: 2 + name_len + 1,
+        });
+    }
+
+    let default_src = after_name
+        .strip_prefix(":-")
+        .or_else(|| after_name.strip_prefix(':'))?;
+    let prefix_len = after_name.len() - default_src.len();
+    let close = scan_body(default_src)?.close;
+    let len = 2 + name_len + prefix_len + close + 1;
+    Some(Placeholder {
+        source,
+        default: Some(&default_src[..close]),
+        len,
+    })
+}
+
+struct Scan {
+    /// 与占位符开头匹配的 `}` 的位置
+    close: usize,
+    /// 第一个不在嵌套占位符中的 `:-` 的位置
+    default_sep: Option<usize>,
+}
+
+/// 扫描占位符内部，跳过转义字符与嵌套的占位符
+fn scan_body(src: &str) -> Option<Scan> {
+    let bytes = src.as_bytes();
+    let mut depth = 0usize;
+    let mut default_sep = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if matches!(bytes.get(i + 1), Some(b'}' | b'$')) => i += 2,
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                depth += 1;
+                i += 2;
+            }
+            b':' if depth == 0 && default_sep.is_none() && bytes.get(i + 1) == Some(&b'-') => {
+                default_sep = Some(i);
+                i += 2;
+            }
+            b'}' if depth > 0 => {
+                depth -= 1;
+                i += 1;
+            }
+            b'}' => return Some(Scan { close: i, default_sep }),
+            _ => i += 1,
+        }
+    }
+    None
+}
@@ -0,0 +1,199 @@
+//! 配置文件监听与热加载
+//!
+//! 以固定间隔轮询配置文件、被 `!include` 的文件以及 `.env` 的修改时间，发生变化时重新加载。
+
+use crate::include::Includer;
+use crate::{de, Format, VarPolicy, YamlLoaderError};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+/// 默认轮询间隔
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 配置监听句柄，调用 [`ConfigWatcher::stop`] 或被 drop 时停止监听
+pub struct ConfigWatcher {
+    stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    /// 停止监听并等待后台线程退出
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// 监听配置文件，文件变化后将重新加载的结果交给 `on_change`
+///
+/// 首次加载失败时直接返回错误；首次加载的结果不会回调。
+pub fn watch_file<T, P, F>(path: P, on_change: F) -> Result<ConfigWatcher, YamlLoaderError>
+where
+    T: DeserializeOwned + Send + 'static,
+    P: AsRef<Path>,
+    F: Fn(Result<T, YamlLoaderError>) + Send + 'static,
+{
+    watch_file_with_interval(path, DEFAULT_WATCH_INTERVAL, on_change)
+}
+
+/// 按指定轮询间隔监听配置文件，见 [`watch_file`]
+pub fn watch_file_with_interval<T, P, F>(
+    path: P,
+    interval: Duration,
+    on_change: F,
+) -> Result<ConfigWatcher, YamlLoaderError>
+where
+    T: DeserializeOwned + Send + 'static,
+    P: AsRef<Path>,
+    F: Fn(Result<T, YamlLoaderError>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let (_, files) = load::<T>(&path)?;
+    Ok(spawn(path, files, interval, on_change))
+}
+
+/// 监听配置文件，通过 `tokio::sync::watch` 通道发布最新配置
+///
+/// 通道初始值为首次加载的结果；之后重新加载失败时保留上一次的配置。
+pub fn watch_channel<T, P>(
+    path: P,
+) -> Result<(watch::Receiver<Arc<T>>, ConfigWatcher), YamlLoaderError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+    P: AsRef<Path>,
+{
+    watch_channel_with_interval(path, DEFAULT_WATCH_INTERVAL)
+}
+
+/// 按指定轮询间隔监听配置文件，见 [`watch_channel`]
+pub fn watch_channel_with_interval<T, P>(
+    path: P,
+    interval: Duration,
+) -> Result<(watch::Receiver<Arc<T>>, ConfigWatcher), YamlLoaderError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref().to_path_buf();
+    let (initial, files) = load::<T>(&path)?;
+    let (tx, rx) = watch::channel(Arc::new(initial));
+    let watcher = spawn(
+        path,
+        files,
+        interval,
+        move |result: Result<T, YamlLoaderError>| {
+            if let Ok(config) = result {
+                let _ = tx.send(Arc::new(config));
+            }
+        },
+    );
+    Ok((rx, watcher))
+}
+
+/// 加载配置，同时返回需要监听的文件列表
+fn load<T: DeserializeOwned>(path: &Path) -> Result<(T, Vec<PathBuf>), YamlLoaderError> {
+    let mut includer = Includer::new(VarPolicy::Lenient);
+    let value = includer.load_file(path, Format::from_path(path))?;
+    let mut files = includer.files().to_vec();
+    if let Some(env_file) = env_file() {
+        files.push(env_file);
+    }
+    Ok((de::from_value(value)?, files))
+}
+
+fn env_file() -> Option<PathBuf> {
+    dotenvy::dotenv().ok()
+}
+
+fn spawn<T, F>(
+    path: PathBuf,
+    files: Vec<PathBuf>,
+    interval: Duration,
+    on_change: F,
+) -> ConfigWatcher
+where
+    T: DeserializeOwned + Send + 'static,
+    F: Fn(Result<T, YamlLoaderError>) + Send + 'static,
+{
+    let stopped = Arc::new(AtomicBool::new(false));
+    let flag = stopped.clone();
+    // 在启动线程前记录文件状态，避免遗漏启动期间发生的修改
+    let mut snapshot = Snapshot::take(files);
+    let handle = thread::spawn(move || {
+        while !flag.load(Ordering::SeqCst) {
+            thread::sleep(interval);
+            if flag.load(Ordering::SeqCst) || !snapshot.changed() {
+                continue;
+            }
+
+            // .env 的改动需要覆盖进程中已有的环境变量才能生效
+            if let Some(env_file) = env_file() {
+                let _ = dotenvy::from_path_override(env_file);
+            }
+            match load::<T>(&path) {
+                Ok((config, files)) => {
+                    snapshot = Snapshot::take(files);
+                    on_change(Ok(config));
+                }
+                Err(e) => {
+                    snapshot.refresh();
+                    on_change(Err(e));
+                }
+            }
+        }
+    });
+    ConfigWatcher {
+        stopped,
+        handle: Some(handle),
+    }
+}
+
+/// 被监听文件的修改时间与大小
+struct Snapshot {
+    entries: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl Snapshot {
+    fn take(files: Vec<PathBuf>) -> Self {
+        let entries = files.into_iter().map(|f| {
+            let stamp = stamp(&f);
+            (f, stamp)
+        });
+        Self {
+            entries: entries.collect(),
+        }
+    }
+
+    fn changed(&self) -> bool {
+        self.entries.iter().any(|(f, s)| stamp(f) != *s)
+    }
+
+    fn refresh(&mut self) {
+        for (f, s) in &mut self.entries {
+            *s = stamp(f);
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
@@ -0,0 +1,100 @@
+use rivus_yaml::ConfigBuilder;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    name: String,
+    database: Database,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Database {
+    url: String,
+    max_connections: u32,
+}
+
+#[test]
+fn test_later_file_overrides_earlier() {
+    let dir = tempdir().unwrap();
+    let base = dir.path().join("base.yaml");
+    let local = dir.path().join("local.yaml");
+    fs::write(
+        &base,
+        "name: demo\ndatabase:\n  url: mysql://base\n  max_connections: 5\n",
+    )
+    .unwrap();
+    fs::write(&local, "database:\n  url: mysql://local\n").unwrap();
+
+    let config: AppConfig = ConfigBuilder::new()
+        .add_file(&base)
+        .add_file(&local)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.name, "demo");
+    assert_eq!(config.database.url, "mysql://local");
+    assert_eq!(config.database.max_connections, 5);
+}
+
+#[test]
+fn test_missing_required_file_fails() {
+    let result = ConfigBuilder::new()
+        .add_file("nonexistent_builder.yaml")
+        .build::<AppConfig>();
+    assert!(matches!(result, Err(rivus_yaml::YamlLoaderError::Io(_))));
+}
+
+#[test]
+fn test_optional_file_and_str_source() {
+    let config: AppConfig = ConfigBuilder::new()
+        .add_str("name: demo\ndatabase:\n  url: \"sqlite::memory:\"\n  max_connections: 1\n")
+        .add_optional_file("nonexistent_builder.yaml")
+        .add_str("name: ${BUILDER_TEST_NAME:renamed}")
+        .build()
+        .unwrap();
+
+    assert_eq!(config.name, "renamed");
+    assert_eq!(config.database.url, "sqlite::memory:");
+}
+
+#[test]
+fn test_env_source_has_highest_precedence() {
+    unsafe {
+        env::set_var("APP_DATABASE__MAX_CONNECTIONS", "32");
+    }
+
+    let config: AppConfig = ConfigBuilder::new()
+        .add_str("name: demo\ndatabase:\n  url: mysql://base\n  max_connections: 5\n")
+        .add_env()
+        .build()
+        .unwrap();
+
+    unsafe {
+        env::remove_var("APP_DATABASE__MAX_CONNECTIONS");
+    }
+    assert_eq!(config.database.max_connections, 32);
+    assert_eq!(config.database.url, "mysql://base");
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Credentials {
+    user: String,
+    password: String,
+    token: Option<String>,
+}
+
+#[test]
+fn test_merged_scalars_deserialize_like_text() {
+    // 与直接解析文本一致：空值、数字可以反序列化为 String
+    let config: Credentials = ConfigBuilder::new()
+        .add_str("user: ${BUILDER_TEST_MISSING_USER}\npassword: 123456\ntoken:\n")
+        .build()
+        .unwrap();
+
+    assert_eq!(config.user, "");
+    assert_eq!(config.password, "123456");
+    assert_eq!(config.token, None);
+}
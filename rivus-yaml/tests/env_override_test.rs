@@ -0,0 +1,75 @@
+use rivus_yaml::{apply_env_overrides, load_from_file_with_env, ConfigBuilder};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    database: Database,
+    #[serde(rename = "log-level")]
+    log_level: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Database {
+    url: String,
+    #[serde(rename = "maxConnections")]
+    max_connections: u32,
+    enabled: bool,
+}
+
+const YAML: &str = r#"
+database:
+  url: mysql://localhost
+  maxConnections: 5
+  enabled: false
+log-level: info
+"#;
+
+#[test]
+fn test_env_overrides_nested_keys() {
+    unsafe {
+        env::set_var("OVR1_DATABASE__URL", "mysql://prod");
+        env::set_var("OVR1_DATABASE__ENABLED", "true");
+    }
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    fs::write(&path, YAML).unwrap();
+    let config: AppConfig = load_from_file_with_env(&path, "OVR1").unwrap();
+
+    assert_eq!(config.database.url, "mysql://prod");
+    assert!(config.database.enabled);
+    assert_eq!(config.database.max_connections, 5);
+}
+
+#[test]
+fn test_env_overrides_match_existing_key_style() {
+    unsafe {
+        env::set_var("OVR2_DATABASE__MAXCONNECTIONS", "20");
+        env::set_var("OVR2_LOG_LEVEL", "debug");
+    }
+
+    let config: AppConfig = ConfigBuilder::new()
+        .add_str(YAML)
+        .add_env_with_prefix("OVR2")
+        .build()
+        .unwrap();
+
+    assert_eq!(config.database.max_connections, 20);
+    assert_eq!(config.log_level, "debug");
+}
+
+#[test]
+fn test_env_overrides_create_missing_keys() {
+    unsafe {
+        env::set_var("OVR3_CACHE__REDIS__URL", "redis://localhost");
+    }
+
+    let mut value: serde_yaml::Value = serde_yaml::from_str("name: demo").unwrap();
+    apply_env_overrides(&mut value, "OVR3").unwrap();
+
+    assert_eq!(value["cache"]["redis"]["url"], "redis://localhost");
+    assert_eq!(value["name"], "demo");
+}
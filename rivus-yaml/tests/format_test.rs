@@ -0,0 +1,99 @@
+use rivus_yaml::{load_from_file, load_from_file_with_format, load_from_str_with_format, ConfigBuilder, Format};
+use serde::Deserialize;
+use std::fs;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    name: String,
+    server: Server,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(Format::from_path("app.json"), Format::Json);
+    assert_eq!(Format::from_path("app.TOML"), Format::Toml);
+    assert_eq!(Format::from_path("app.yml"), Format::Yaml);
+    assert_eq!(Format::from_path("app"), Format::Yaml);
+}
+
+#[test]
+fn test_load_json_file_with_vars() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.json");
+    fs::write(
+        &path,
+        r#"{"name": "${FORMAT_TEST_JSON_NAME:json-app}", "server": {"host": "localhost", "port": 8080}}"#,
+    )
+    .unwrap();
+
+    let config: AppConfig = load_from_file(&path).unwrap();
+
+    assert_eq!(config.name, "json-app");
+    assert_eq!(config.server.port, 8080);
+}
+
+#[test]
+fn test_load_toml_file_with_vars() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.toml");
+    fs::write(
+        &path,
+        "name = \"${FORMAT_TEST_TOML_NAME:toml-app}\"\n\n[server]\nhost = \"0.0.0.0\"\nport = 9000\n",
+    )
+    .unwrap();
+
+    let config: AppConfig = load_from_file(&path).unwrap();
+
+    assert_eq!(config.name, "toml-app");
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 9000);
+}
+
+#[test]
+fn test_explicit_format_overrides_extension() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.conf");
+    fs::write(&path, "name = \"conf\"\n[server]\nhost = \"h\"\nport = 1\n").unwrap();
+
+    let config: AppConfig = load_from_file_with_format(&path, Format::Toml).unwrap();
+
+    assert_eq!(config.name, "conf");
+}
+
+#[test]
+fn test_invalid_json_reports_json_error() {
+    let result = load_from_str_with_format::<AppConfig>("{\"name\": ", Format::Json);
+    assert!(matches!(
+        result,
+        Err(rivus_yaml::YamlLoaderError::JsonParse(_))
+    ));
+}
+
+#[test]
+fn test_mixed_formats_in_builder() {
+    let dir = tempdir().unwrap();
+    let yaml = dir.path().join("base.yaml");
+    let toml = dir.path().join("override.toml");
+    fs::write(
+        &yaml,
+        "name: base\nserver:\n  host: localhost\n  port: 8080\n",
+    )
+    .unwrap();
+    fs::write(&toml, "[server]\nport = 80\n").unwrap();
+
+    let config: AppConfig = ConfigBuilder::new()
+        .add_file(&yaml)
+        .add_file(&toml)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.name, "base");
+    assert_eq!(config.server.port, 80);
+}
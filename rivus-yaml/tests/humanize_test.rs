@@ -0,0 +1,74 @@
+use rivus_yaml::humanize::{format_duration, format_size, parse_duration, parse_size};
+use rivus_yaml::{dump_effective, load_from_str};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[test]
+fn test_parse_size() {
+    assert_eq!(parse_size("512").unwrap(), 512);
+    assert_eq!(parse_size("512B").unwrap(), 512);
+    assert_eq!(parse_size("64kb").unwrap(), 64 * 1024);
+    assert_eq!(parse_size("10MB").unwrap(), 10 * 1024 * 1024);
+    assert_eq!(parse_size("10 MiB").unwrap(), 10 * 1024 * 1024);
+    assert_eq!(parse_size("1.5G").unwrap(), 3 * 512 * 1024 * 1024);
+    assert!(parse_size("10XB").is_err());
+    assert!(parse_size("MB").is_err());
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+    assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+    assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+    assert_eq!(
+        parse_duration("7d").unwrap(),
+        Duration::from_secs(7 * 86400)
+    );
+    assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+    assert_eq!(parse_duration("1m 30s").unwrap(), Duration::from_secs(90));
+    assert!(parse_duration("10y").is_err());
+    assert!(parse_duration("").is_err());
+    // 溢出时返回错误而不是 panic
+    assert!(parse_duration(&format!("{}d", u128::MAX)).is_err());
+    assert!(parse_duration(&format!("{}ns{}ns", u128::MAX, u128::MAX)).is_err());
+}
+
+#[test]
+fn test_format_round_trip() {
+    assert_eq!(format_size(10 * 1024 * 1024), "10MB");
+    assert_eq!(format_size(1500), "1500B");
+    assert_eq!(format_duration(Duration::from_secs(5400)), "1h30m");
+    assert_eq!(format_duration(Duration::from_millis(1500)), "1s500ms");
+    assert_eq!(
+        parse_duration(&format_duration(Duration::from_millis(90_061_001))).unwrap(),
+        Duration::from_millis(90_061_001)
+    );
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Limits {
+    #[serde(with = "rivus_yaml::humanize::size")]
+    max_size: usize,
+    #[serde(with = "rivus_yaml::humanize::duration")]
+    timeout: Duration,
+    #[serde(default, with = "rivus_yaml::humanize::size_opt")]
+    buffer: Option<usize>,
+    #[serde(default, with = "rivus_yaml::humanize::duration_opt")]
+    idle: Option<Duration>,
+}
+
+#[test]
+fn test_serde_helpers() {
+    let limits: Limits =
+        load_from_str("max_size: ${HUMANIZE_MAX_SIZE:10MB}\ntimeout: 30\nbuffer: 4KB\n").unwrap();
+
+    assert_eq!(limits.max_size, 10 * 1024 * 1024);
+    assert_eq!(limits.timeout, Duration::from_secs(30));
+    assert_eq!(limits.buffer, Some(4096));
+    assert_eq!(limits.idle, None);
+
+    let dumped = dump_effective(&limits).unwrap();
+    assert!(dumped.contains("max_size: 10MB"));
+    assert!(dumped.contains("timeout: 30s"));
+    assert_eq!(load_from_str::<Limits>(&dumped).unwrap(), limits);
+}
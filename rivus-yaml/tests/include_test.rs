@@ -0,0 +1,97 @@
+use rivus_yaml::{load_from_file, YamlLoaderError};
+use serde::Deserialize;
+use std::fs;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    name: String,
+    database: Database,
+    log: Log,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Database {
+    url: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Log {
+    level: String,
+}
+
+#[test]
+fn test_include_relative_to_including_file() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("conf")).unwrap();
+    fs::write(
+        dir.path().join("conf/app.yaml"),
+        "name: demo\ndatabase: !include db.yaml\nlog: !include parts/log.yaml\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("conf/db.yaml"),
+        "url: ${INCLUDE_TEST_DB_URL:mysql://localhost}\n",
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("conf/parts")).unwrap();
+    fs::write(
+        dir.path().join("conf/parts/log.yaml"),
+        "level: !include ../level.yaml\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("conf/level.yaml"), "info\n").unwrap();
+
+    let config: AppConfig = load_from_file(dir.path().join("conf/app.yaml")).unwrap();
+
+    assert_eq!(config.name, "demo");
+    assert_eq!(config.database.url, "mysql://localhost");
+    assert_eq!(config.log.level, "info");
+}
+
+#[test]
+fn test_same_file_included_twice_is_not_a_cycle() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.yaml"),
+        "a: !include part.yaml\nb: !include part.yaml\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("part.yaml"), "level: debug\n").unwrap();
+
+    let value: serde_yaml::Value = load_from_file(dir.path().join("app.yaml")).unwrap();
+
+    assert_eq!(value["a"]["level"], "debug");
+    assert_eq!(value["b"]["level"], "debug");
+}
+
+#[test]
+fn test_include_cycle_detected() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.yaml"), "b: !include b.yaml\n").unwrap();
+    fs::write(dir.path().join("b.yaml"), "a: !include a.yaml\n").unwrap();
+
+    let result = load_from_file::<serde_yaml::Value, _>(dir.path().join("a.yaml"));
+
+    assert!(matches!(result, Err(YamlLoaderError::IncludeCycle(_))));
+}
+
+#[test]
+fn test_include_missing_file() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("app.yaml"), "db: !include missing.yaml\n").unwrap();
+
+    let result = load_from_file::<serde_yaml::Value, _>(dir.path().join("app.yaml"));
+
+    assert!(matches!(result, Err(YamlLoaderError::Io(_))));
+}
+
+#[test]
+fn test_include_requires_string_path() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("app.yaml"), "db: !include [a.yaml]\n").unwrap();
+
+    let result = load_from_file::<serde_yaml::Value, _>(dir.path().join("app.yaml"));
+
+    assert!(matches!(result, Err(YamlLoaderError::InvalidInclude(_))));
+}
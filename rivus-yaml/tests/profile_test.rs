@@ -0,0 +1,112 @@
+use rivus_yaml::{deep_merge, load_with_profile, profile_path};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    name: String,
+    server: Server,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+fn write_base(dir: &Path) {
+    fs::write(
+        dir.join("application.yaml"),
+        r#"
+name: demo
+server:
+  host: 127.0.0.1
+  port: 8080
+tags: [a, b]
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_profile_path() {
+    assert_eq!(
+        profile_path("config/application.yaml", "dev"),
+        Path::new("config/application-dev.yaml")
+    );
+    assert_eq!(
+        profile_path("application", "prod"),
+        Path::new("application-prod")
+    );
+}
+
+#[test]
+fn test_profile_overlay_deep_merge() {
+    let dir = tempdir().unwrap();
+    write_base(dir.path());
+    fs::write(
+        dir.path().join("application-prod.yaml"),
+        r#"
+server:
+  port: 80
+tags: [prod]
+"#,
+    )
+    .unwrap();
+
+    let config: AppConfig =
+        load_with_profile(dir.path().join("application.yaml"), Some("prod")).unwrap();
+
+    // 映射深度合并，标量与数组整体覆盖
+    assert_eq!(config.name, "demo");
+    assert_eq!(config.server.host, "127.0.0.1");
+    assert_eq!(config.server.port, 80);
+    assert_eq!(config.tags, vec!["prod"]);
+}
+
+#[test]
+fn test_multiple_profiles_in_order() {
+    let dir = tempdir().unwrap();
+    write_base(dir.path());
+    fs::write(
+        dir.path().join("application-dev.yaml"),
+        "server:\n  port: 3000\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("application-local.yaml"),
+        "server:\n  host: 0.0.0.0\n",
+    )
+    .unwrap();
+
+    let config: AppConfig =
+        load_with_profile(dir.path().join("application.yaml"), Some("dev, local")).unwrap();
+
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 3000);
+}
+
+#[test]
+fn test_missing_profile_file_is_skipped() {
+    let dir = tempdir().unwrap();
+    write_base(dir.path());
+
+    let config: AppConfig =
+        load_with_profile(dir.path().join("application.yaml"), Some("test")).unwrap();
+
+    assert_eq!(config.server.port, 8080);
+}
+
+#[test]
+fn test_deep_merge_values() {
+    let mut base: serde_yaml::Value = serde_yaml::from_str("a: {b: 1, c: 2}\nd: [1, 2]").unwrap();
+    let overlay: serde_yaml::Value = serde_yaml::from_str("a: {c: 3, e: 4}\nd: [9]").unwrap();
+    deep_merge(&mut base, overlay);
+
+    let expected: serde_yaml::Value =
+        serde_yaml::from_str("a: {b: 1, c: 3, e: 4}\nd: [9]").unwrap();
+    assert_eq!(base, expected);
+}
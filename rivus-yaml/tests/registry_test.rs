@@ -0,0 +1,59 @@
+use rivus_yaml::{config, init, section, try_config, YamlLoaderError};
+use serde::Deserialize;
+use std::fs;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize)]
+struct AppConfig {
+    name: String,
+    database: DatabaseConfig,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct DatabaseConfig {
+    url: String,
+    max_connections: u32,
+}
+
+// 全局配置在进程内只能初始化一次，所以集中在一个测试中验证
+#[test]
+fn test_global_registry() {
+    assert!(try_config::<AppConfig>().is_none());
+    assert!(matches!(
+        section::<DatabaseConfig>("database"),
+        Err(YamlLoaderError::NotInitialized)
+    ));
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    fs::write(
+        &path,
+        "name: demo\ndatabase:\n  url: mysql://localhost\n  max_connections: 5\n",
+    )
+    .unwrap();
+
+    let initialized = init::<AppConfig, _>(&path).unwrap();
+    assert_eq!(initialized.name, "demo");
+    assert!(matches!(
+        init::<AppConfig, _>(&path),
+        Err(YamlLoaderError::AlreadyInitialized)
+    ));
+
+    assert_eq!(config::<AppConfig>().database.max_connections, 5);
+    assert!(std::ptr::eq(config::<AppConfig>(), initialized));
+    // 类型不一致时 try_config 返回 None，config 才 panic
+    assert!(try_config::<DatabaseConfig>().is_none());
+    assert!(std::panic::catch_unwind(config::<DatabaseConfig>).is_err());
+
+    let db = section::<DatabaseConfig>("database").unwrap();
+    assert_eq!(db.url, "mysql://localhost");
+    assert!(std::ptr::eq(
+        db,
+        section::<DatabaseConfig>("database").unwrap()
+    ));
+    assert_eq!(*section::<u32>("database.max_connections").unwrap(), 5);
+    assert!(matches!(
+        section::<String>("cache.url"),
+        Err(YamlLoaderError::MissingSection(_))
+    ));
+}
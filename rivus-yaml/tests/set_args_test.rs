@@ -0,0 +1,119 @@
+use rivus_yaml::{apply_set_overrides, parse_set_args, ConfigBuilder, YamlLoaderError};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AppConfig {
+    name: String,
+    server: Server,
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Server {
+    port: u16,
+    debug: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Node {
+    host: String,
+}
+
+const YAML: &str = r#"
+name: demo
+server:
+  port: 8080
+  debug: false
+nodes:
+  - host: a
+  - host: b
+"#;
+
+#[test]
+fn test_parse_set_args_ignores_other_args() {
+    let pairs = parse_set_args([
+        "--verbose",
+        "--set",
+        "server.port=9000",
+        "--set=name=x=y",
+        "run",
+    ])
+    .unwrap();
+
+    assert_eq!(
+        pairs,
+        vec![
+            ("server.port".to_string(), "9000".to_string()),
+            ("name".to_string(), "x=y".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_set_args_invalid() {
+    assert!(matches!(
+        parse_set_args(["--set"]),
+        Err(YamlLoaderError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        parse_set_args(["--set", "novalue"]),
+        Err(YamlLoaderError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        parse_set_args(["--set", "a..b=1"]),
+        Err(YamlLoaderError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn test_builder_args_override() {
+    let config: AppConfig = ConfigBuilder::new()
+        .add_str(YAML)
+        .add_args([
+            "--set",
+            "server.port=9000",
+            "--set",
+            "server.debug=true",
+            "--set",
+            "nodes.1.host=c",
+        ])
+        .build()
+        .unwrap();
+
+    assert_eq!(config.name, "demo");
+    assert_eq!(config.server.port, 9000);
+    assert!(config.server.debug);
+    assert_eq!(config.nodes[0].host, "a");
+    assert_eq!(config.nodes[1].host, "c");
+}
+
+#[test]
+fn test_apply_set_overrides_creates_keys() {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(YAML).unwrap();
+    apply_set_overrides(&mut value, &[("cache.ttl".to_string(), "30".to_string())]).unwrap();
+
+    assert_eq!(value["cache"]["ttl"], 30);
+}
+
+#[test]
+fn test_apply_set_overrides_rejects_invalid_index() {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(YAML).unwrap();
+    let original = value.clone();
+    for key in ["nodes.5.host", "nodes.first.host"] {
+        let err =
+            apply_set_overrides(&mut value, &[(key.to_string(), "x".to_string())]).unwrap_err();
+        assert!(
+            matches!(err, YamlLoaderError::InvalidKeyPath(_)),
+            "{key}: {err}"
+        );
+    }
+    // 数组未被替换为映射
+    assert_eq!(value, original);
+
+    let err = ConfigBuilder::new()
+        .add_str(YAML)
+        .add_args(["--set", "nodes.2.host=x"])
+        .build_value()
+        .unwrap_err();
+    assert!(matches!(err, YamlLoaderError::InvalidKeyPath(_)));
+}
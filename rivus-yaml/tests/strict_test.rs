@@ -0,0 +1,73 @@
+use rivus_yaml::{load_from_file_strict, load_from_str_strict, ConfigBuilder, VarPolicy, YamlLoaderError};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    address: String,
+}
+
+#[test]
+fn test_strict_missing_variable() {
+    unsafe {
+        env::remove_var("STRICT_MISSING_NAME");
+    }
+
+    let result =
+        load_from_str_strict::<Config>("name: ${STRICT_MISSING_NAME}\naddress: Shanghai\n");
+
+    match result {
+        Err(YamlLoaderError::MissingVariable(name)) => assert_eq!(name, "STRICT_MISSING_NAME"),
+        other => panic!("Expected MissingVariable error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_strict_allows_defaults_and_set_vars() {
+    unsafe {
+        env::set_var("STRICT_SET_NAME", "Alice");
+    }
+
+    let config: Config =
+        load_from_str_strict("name: ${STRICT_SET_NAME}\naddress: ${STRICT_UNSET_ADDRESS:}\n")
+            .unwrap();
+
+    assert_eq!(config.name, "Alice");
+    assert_eq!(config.address, "");
+}
+
+#[test]
+fn test_strict_applies_to_included_files() {
+    unsafe {
+        env::remove_var("STRICT_INCLUDED_VAR");
+    }
+
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("app.yaml"),
+        "name: demo\naddress: !include addr.yaml\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("addr.yaml"), "${STRICT_INCLUDED_VAR}\n").unwrap();
+
+    let result = load_from_file_strict::<Config, _>(dir.path().join("app.yaml"));
+
+    assert!(matches!(result, Err(YamlLoaderError::MissingVariable(_))));
+}
+
+#[test]
+fn test_builder_strict_policy() {
+    unsafe {
+        env::remove_var("STRICT_BUILDER_VAR");
+    }
+
+    let result = ConfigBuilder::new()
+        .var_policy(VarPolicy::Strict)
+        .add_str("name: ${STRICT_BUILDER_VAR}\naddress: x\n")
+        .build::<Config>();
+
+    assert!(matches!(result, Err(YamlLoaderError::MissingVariable(_))));
+}
@@ -0,0 +1,97 @@
+use rivus_yaml::{load_from_str_validated, ConfigBuilder, Validatable, ValidationErrors, YamlLoaderError};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct AppConfig {
+    name: String,
+    database: Database,
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    url: String,
+    max_connections: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Node {
+    host: String,
+}
+
+impl Validatable for AppConfig {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if self.name.is_empty() {
+            errors.add("name", "must not be empty");
+        }
+        errors.nested("database", &self.database);
+        errors.nested_each("nodes", &self.nodes);
+    }
+}
+
+impl Validatable for Database {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if !self.url.contains("://") {
+            errors.add("url", "must be a connection url");
+        }
+        if self.max_connections == 0 {
+            errors.add("max_connections", "must be greater than 0");
+        }
+    }
+}
+
+impl Validatable for Node {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if self.host.is_empty() {
+            errors.add("host", "must not be empty");
+        }
+    }
+}
+
+#[test]
+fn test_valid_config_passes() {
+    let config: AppConfig = load_from_str_validated(
+        "name: demo\ndatabase:\n  url: mysql://localhost\n  max_connections: 5\nnodes:\n  - host: a\n",
+    )
+    .unwrap();
+
+    assert_eq!(config.name, "demo");
+}
+
+#[test]
+fn test_errors_are_aggregated_with_paths() {
+    let result = load_from_str_validated::<AppConfig>(
+        "name: ''\ndatabase:\n  url: localhost\n  max_connections: 0\nnodes:\n  - host: a\n  - host: ''\n",
+    );
+
+    let Err(YamlLoaderError::Validation(errors)) = result else {
+        panic!("Expected validation error");
+    };
+    let paths: Vec<&str> = errors.errors().iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "name",
+            "database.url",
+            "database.max_connections",
+            "nodes[1].host"
+        ]
+    );
+    assert!(
+        errors
+            .to_string()
+            .contains("database.url: must be a connection url")
+    );
+}
+
+#[test]
+fn test_builder_build_validated() {
+    let result = ConfigBuilder::new()
+        .add_str(
+            "name: demo\ndatabase:\n  url: mysql://localhost\n  max_connections: 5\nnodes: []\n",
+        )
+        .add_str("database:\n  max_connections: 0\n")
+        .build_validated::<AppConfig>();
+
+    assert!(matches!(result, Err(YamlLoaderError::Validation(_))));
+}
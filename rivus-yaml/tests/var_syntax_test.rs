@@ -0,0 +1,148 @@
+use rivus_yaml::load_from_str;
+use std::collections::BTreeMap;
+use std::env;
+
+fn load(yaml: &str) -> BTreeMap<String, String> {
+    load_from_str(yaml).unwrap()
+}
+
+#[test]
+fn test_bash_style_default() {
+    unsafe {
+        env::remove_var("SYNTAX_BASH_MISSING");
+    }
+
+    let config = load("a: ${SYNTAX_BASH_MISSING:-fallback}\nb: ${SYNTAX_BASH_MISSING:plain}\n");
+
+    assert_eq!(config["a"], "fallback");
+    assert_eq!(config["b"], "plain");
+}
+
+#[test]
+fn test_bash_style_default_with_set_var() {
+    unsafe {
+        env::set_var("SYNTAX_BASH_SET", "value");
+    }
+
+    let config = load("a: ${SYNTAX_BASH_SET:-fallback}\n");
+
+    assert_eq!(config["a"], "value");
+}
+
+#[test]
+fn test_escaped_brace_in_default() {
+    unsafe {
+        env::remove_var("SYNTAX_BRACE_MISSING");
+    }
+
+    let config = load("a: '${SYNTAX_BRACE_MISSING:-{x\\}}'\n");
+
+    assert_eq!(config["a"], "{x}");
+}
+
+#[test]
+fn test_escaped_placeholder_is_literal() {
+    unsafe {
+        env::set_var("SYNTAX_LITERAL", "replaced");
+    }
+
+    let config =
+        load("a: '\\${SYNTAX_LITERAL}'\nb: '\\${SYNTAX_LITERAL:-x} and ${SYNTAX_LITERAL}'\n");
+
+    assert_eq!(config["a"], "${SYNTAX_LITERAL}");
+    assert_eq!(config["b"], "${SYNTAX_LITERAL:-x} and replaced");
+}
+
+#[test]
+fn test_non_placeholder_text_is_untouched() {
+    let config = load("a: 'cost $5 \\d+ ${lower} ${UNCLOSED'\n");
+
+    assert_eq!(config["a"], "cost $5 \\d+ ${lower} ${UNCLOSED");
+}
+
+#[test]
+fn test_nested_reference_in_default() {
+    unsafe {
+        env::remove_var("SYNTAX_DATA_DIR");
+        env::set_var("SYNTAX_HOME", "/home/app");
+    }
+
+    let config = load(
+        "a: ${SYNTAX_DATA_DIR:${SYNTAX_HOME}/data}\nb: ${SYNTAX_DATA_DIR:-${SYNTAX_NOPE:-/tmp}/x}\n",
+    );
+
+    assert_eq!(config["a"], "/home/app/data");
+    assert_eq!(config["b"], "/tmp/x");
+}
+
+#[test]
+fn test_env_value_referencing_other_var() {
+    unsafe {
+        env::set_var("SYNTAX_REF_HOST", "db.local");
+        env::set_var("SYNTAX_REF_URL", "mysql://${SYNTAX_REF_HOST}:3306");
+    }
+
+    let config = load("url: ${SYNTAX_REF_URL}\n");
+
+    assert_eq!(config["url"], "mysql://db.local:3306");
+}
+
+#[test]
+fn test_reference_cycle_detected() {
+    unsafe {
+        env::set_var("SYNTAX_CYCLE_A", "${SYNTAX_CYCLE_B}");
+        env::set_var("SYNTAX_CYCLE_B", "x-${SYNTAX_CYCLE_A}");
+    }
+
+    let result = load_from_str::<BTreeMap<String, String>>("a: ${SYNTAX_CYCLE_A}\n");
+
+    match result {
+        Err(rivus_yaml::YamlLoaderError::VariableCycle(chain)) => {
+            assert_eq!(chain, "SYNTAX_CYCLE_A -> SYNTAX_CYCLE_B -> SYNTAX_CYCLE_A");
+        }
+        other => panic!("Expected VariableCycle error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_same_var_used_twice_is_not_a_cycle() {
+    unsafe {
+        env::set_var("SYNTAX_TWICE", "v");
+    }
+
+    let config = load("a: ${SYNTAX_MISSING_TWICE:${SYNTAX_TWICE}-${SYNTAX_TWICE}}\n");
+
+    assert_eq!(config["a"], "v-v");
+}
+
+#[test]
+fn test_secret_file_substitution() {
+    let dir = tempfile::tempdir().unwrap();
+    let secret = dir.path().join("db_pass");
+    std::fs::write(&secret, "s3cr3t\n").unwrap();
+    unsafe {
+        env::set_var("SYNTAX_SECRETS_DIR", dir.path());
+    }
+
+    let yaml = format!(
+        "a: ${{file:{}}}\nb: ${{file:${{SYNTAX_SECRETS_DIR}}/db_pass}}\nc: ${{file:{}/missing:-fallback}}\n",
+        secret.display(),
+        dir.path().display()
+    );
+    let config = load(&yaml);
+
+    assert_eq!(config["a"], "s3cr3t");
+    assert_eq!(config["b"], "s3cr3t");
+    assert_eq!(config["c"], "fallback");
+}
+
+#[test]
+fn test_missing_secret_file_is_an_error() {
+    let result =
+        load_from_str::<BTreeMap<String, String>>("a: ${file:/nonexistent/rivus/secret}\n");
+
+    assert!(matches!(
+        result,
+        Err(rivus_yaml::YamlLoaderError::SecretFile { .. })
+    ));
+}
@@ -0,0 +1,97 @@
+use rivus_yaml::{watch_channel_with_interval, watch_file_with_interval};
+use serde::Deserialize;
+use std::fs;
+use std::sync::mpsc;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    port: u16,
+}
+
+const INTERVAL: Duration = Duration::from_millis(50);
+
+#[test]
+fn test_watch_file_reloads_on_change() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    fs::write(&path, "name: a\nport: 1\n").unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let watcher = watch_file_with_interval(&path, INTERVAL, move |result: Result<Config, _>| {
+        tx.send(result).unwrap();
+    })
+    .unwrap();
+
+    fs::write(&path, "name: changed\nport: 2\n").unwrap();
+    let config = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "changed".into(),
+            port: 2
+        }
+    );
+
+    // 修改后解析失败会把错误交给回调
+    fs::write(&path, "name: [broken\n").unwrap();
+    assert!(rx.recv_timeout(Duration::from_secs(5)).unwrap().is_err());
+
+    watcher.stop();
+}
+
+#[test]
+fn test_watch_file_tracks_included_files() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    let part = dir.path().join("port.yaml");
+    fs::write(&path, "name: a\nport: !include port.yaml\n").unwrap();
+    fs::write(&part, "1\n").unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let _watcher = watch_file_with_interval(&path, INTERVAL, move |result: Result<Config, _>| {
+        tx.send(result).unwrap();
+    })
+    .unwrap();
+
+    fs::write(&part, "8080\n").unwrap();
+    let config = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+    assert_eq!(config.port, 8080);
+}
+
+#[test]
+fn test_watch_file_initial_error() {
+    let result = watch_file_with_interval(
+        "nonexistent_watch.yaml",
+        INTERVAL,
+        |_: Result<Config, _>| {},
+    );
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_watch_channel_publishes_latest_config() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    fs::write(&path, "name: a\nport: 1\n").unwrap();
+
+    let (mut rx, _watcher) = watch_channel_with_interval::<Config, _>(&path, INTERVAL).unwrap();
+    assert_eq!(rx.borrow().name, "a");
+
+    fs::write(&path, "name: bb\nport: 22\n").unwrap();
+    tokio::time::timeout(Duration::from_secs(5), rx.changed())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let config = rx.borrow().clone();
+    assert_eq!(
+        *config,
+        Config {
+            name: "bb".into(),
+            port: 22
+        }
+    );
+}